@@ -4,13 +4,15 @@
 DROP FUNCTION IF EXISTS cleanup_expired_tokens;
 DROP FUNCTION IF EXISTS cleanup_old_audit_logs;
 DROP FUNCTION IF EXISTS cleanup_old_login_attempts;
-DROP FUNCTION IF EXISTS update_updated_at_column;
 
 -- Drop triggers
 DROP TRIGGER IF EXISTS update_users_updated_at ON users;
 DROP TRIGGER IF EXISTS update_security_questions_updated_at ON security_questions;
 DROP TRIGGER IF EXISTS update_user_security_questions_updated_at ON user_security_questions;
 
+-- Only once nothing triggers it
+DROP FUNCTION IF EXISTS update_updated_at_column;
+
 -- Drop GIN indexes
 DROP INDEX IF EXISTS idx_audit_logs_metadata_gin;
 DROP INDEX IF EXISTS idx_audit_logs_old_values_gin;
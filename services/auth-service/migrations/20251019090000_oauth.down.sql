@@ -0,0 +1,16 @@
+-- Revert OAuth2 authorization server tables
+
+DROP TRIGGER IF EXISTS update_oauth_clients_updated_at ON oauth_clients;
+
+DROP INDEX IF EXISTS idx_oauth_consents_user_id;
+DROP INDEX IF EXISTS idx_oauth_tokens_expires_at;
+DROP INDEX IF EXISTS idx_oauth_tokens_user_client;
+DROP INDEX IF EXISTS idx_oauth_tokens_token_hash;
+DROP INDEX IF EXISTS idx_oauth_authorization_codes_expires_at;
+DROP INDEX IF EXISTS idx_oauth_authorization_codes_code_hash;
+DROP INDEX IF EXISTS idx_oauth_clients_owner;
+
+DROP TABLE IF EXISTS oauth_consents;
+DROP TABLE IF EXISTS oauth_tokens;
+DROP TABLE IF EXISTS oauth_authorization_codes;
+DROP TABLE IF EXISTS oauth_clients;
@@ -0,0 +1,70 @@
+-- OAuth2 authorization server for partner integrations
+
+-- Registered third-party clients
+CREATE TABLE oauth_clients (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    owner_user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    name VARCHAR(255) NOT NULL,
+    client_secret_hash VARCHAR(255),
+    redirect_uris TEXT[] DEFAULT '{}' NOT NULL,
+    grant_types TEXT[] DEFAULT '{}' NOT NULL,
+    scopes TEXT[] DEFAULT '{}' NOT NULL,
+    is_confidential BOOLEAN DEFAULT TRUE NOT NULL,
+    is_active BOOLEAN DEFAULT TRUE NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT (NOW() AT TIME ZONE 'UTC') NOT NULL,
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT (NOW() AT TIME ZONE 'UTC') NOT NULL
+);
+
+-- Short-lived authorization codes (authorization-code grant)
+CREATE TABLE oauth_authorization_codes (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    code_hash VARCHAR(64) NOT NULL,
+    client_id UUID NOT NULL REFERENCES oauth_clients(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    redirect_uri TEXT NOT NULL,
+    scopes TEXT[] DEFAULT '{}' NOT NULL,
+    code_challenge VARCHAR(128),
+    code_challenge_method VARCHAR(10),
+    expires_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    is_used BOOLEAN DEFAULT FALSE NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT (NOW() AT TIME ZONE 'UTC') NOT NULL
+);
+
+-- Issued access and refresh tokens, used for introspection and revocation
+CREATE TABLE oauth_tokens (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    token_hash VARCHAR(64) NOT NULL,
+    token_type VARCHAR(20) NOT NULL,
+    client_id UUID NOT NULL REFERENCES oauth_clients(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    scopes TEXT[] DEFAULT '{}' NOT NULL,
+    expires_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    is_revoked BOOLEAN DEFAULT FALSE NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT (NOW() AT TIME ZONE 'UTC') NOT NULL,
+    revoked_at TIMESTAMP WITH TIME ZONE
+);
+
+ALTER TABLE oauth_tokens ADD CONSTRAINT oauth_tokens_type_check
+CHECK (token_type IN ('access', 'refresh'));
+
+-- Scopes a user has granted to a client
+CREATE TABLE oauth_consents (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    client_id UUID NOT NULL REFERENCES oauth_clients(id) ON DELETE CASCADE,
+    scopes TEXT[] DEFAULT '{}' NOT NULL,
+    granted_at TIMESTAMP WITH TIME ZONE DEFAULT (NOW() AT TIME ZONE 'UTC') NOT NULL,
+    revoked_at TIMESTAMP WITH TIME ZONE,
+    UNIQUE(user_id, client_id)
+);
+
+CREATE INDEX idx_oauth_clients_owner ON oauth_clients(owner_user_id) WHERE is_active = true;
+CREATE UNIQUE INDEX idx_oauth_authorization_codes_code_hash ON oauth_authorization_codes(code_hash);
+CREATE INDEX idx_oauth_authorization_codes_expires_at ON oauth_authorization_codes(expires_at) WHERE is_used = false;
+CREATE UNIQUE INDEX idx_oauth_tokens_token_hash ON oauth_tokens(token_hash);
+CREATE INDEX idx_oauth_tokens_user_client ON oauth_tokens(user_id, client_id) WHERE is_revoked = false;
+CREATE INDEX idx_oauth_tokens_expires_at ON oauth_tokens(expires_at) WHERE is_revoked = false;
+CREATE INDEX idx_oauth_consents_user_id ON oauth_consents(user_id) WHERE revoked_at IS NULL;
+
+CREATE TRIGGER update_oauth_clients_updated_at BEFORE UPDATE ON oauth_clients
+    FOR EACH ROW EXECUTE FUNCTION update_updated_at_column();
@@ -0,0 +1,6 @@
+-- Revert scoped API keys
+
+DROP INDEX IF EXISTS idx_api_keys_user_id;
+DROP INDEX IF EXISTS idx_api_keys_prefix;
+
+DROP TABLE IF EXISTS api_keys;
@@ -0,0 +1,15 @@
+-- Revert multi-tenant organisations
+
+DROP TRIGGER IF EXISTS update_organisation_memberships_updated_at ON organisation_memberships;
+DROP TRIGGER IF EXISTS update_organisations_updated_at ON organisations;
+
+DROP INDEX IF EXISTS idx_api_keys_organisation_id;
+DROP INDEX IF EXISTS idx_organisation_invitations_org_pending;
+DROP INDEX IF EXISTS idx_organisation_invitations_token_hash;
+DROP INDEX IF EXISTS idx_organisation_memberships_user_id;
+
+ALTER TABLE api_keys DROP COLUMN IF EXISTS organisation_id;
+
+DROP TABLE IF EXISTS organisation_invitations;
+DROP TABLE IF EXISTS organisation_memberships;
+DROP TABLE IF EXISTS organisations;
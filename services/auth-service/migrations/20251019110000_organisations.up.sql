@@ -0,0 +1,58 @@
+-- Multi-tenant organisations (property management companies, agencies)
+
+CREATE TABLE organisations (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    name VARCHAR(255) NOT NULL,
+    slug VARCHAR(100) NOT NULL UNIQUE,
+    created_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    is_active BOOLEAN DEFAULT TRUE NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT (NOW() AT TIME ZONE 'UTC') NOT NULL,
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT (NOW() AT TIME ZONE 'UTC') NOT NULL
+);
+
+-- A user's role inside an organisation
+CREATE TABLE organisation_memberships (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    organisation_id UUID NOT NULL REFERENCES organisations(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    role VARCHAR(20) NOT NULL,
+    last_active_at TIMESTAMP WITH TIME ZONE,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT (NOW() AT TIME ZONE 'UTC') NOT NULL,
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT (NOW() AT TIME ZONE 'UTC') NOT NULL,
+    UNIQUE(organisation_id, user_id)
+);
+
+ALTER TABLE organisation_memberships ADD CONSTRAINT organisation_memberships_role_check
+CHECK (role IN ('owner', 'admin', 'manager', 'member'));
+
+-- Pending email invitations; the emailed token is signed and only its hash is stored
+CREATE TABLE organisation_invitations (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    organisation_id UUID NOT NULL REFERENCES organisations(id) ON DELETE CASCADE,
+    email VARCHAR(255) NOT NULL,
+    role VARCHAR(20) NOT NULL,
+    token_hash VARCHAR(64) NOT NULL,
+    invited_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    expires_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    accepted_at TIMESTAMP WITH TIME ZONE,
+    revoked_at TIMESTAMP WITH TIME ZONE,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT (NOW() AT TIME ZONE 'UTC') NOT NULL
+);
+
+ALTER TABLE organisation_invitations ADD CONSTRAINT organisation_invitations_role_check
+CHECK (role IN ('admin', 'manager', 'member'));
+
+-- API keys may act on behalf of an organisation
+ALTER TABLE api_keys ADD COLUMN organisation_id UUID REFERENCES organisations(id) ON DELETE CASCADE;
+
+CREATE INDEX idx_organisation_memberships_user_id ON organisation_memberships(user_id);
+CREATE UNIQUE INDEX idx_organisation_invitations_token_hash ON organisation_invitations(token_hash);
+CREATE INDEX idx_organisation_invitations_org_pending ON organisation_invitations(organisation_id)
+    WHERE accepted_at IS NULL AND revoked_at IS NULL;
+CREATE INDEX idx_api_keys_organisation_id ON api_keys(organisation_id) WHERE revoked_at IS NULL;
+
+CREATE TRIGGER update_organisations_updated_at BEFORE UPDATE ON organisations
+    FOR EACH ROW EXECUTE FUNCTION update_updated_at_column();
+
+CREATE TRIGGER update_organisation_memberships_updated_at BEFORE UPDATE ON organisation_memberships
+    FOR EACH ROW EXECUTE FUNCTION update_updated_at_column();
@@ -0,0 +1,2 @@
+DROP INDEX IF EXISTS idx_audit_logs_impersonated_by;
+DROP TABLE IF EXISTS audit_logs_default;
@@ -0,0 +1,7 @@
+-- The initial partitions only cover 2025; catch everything else so audit
+-- writes never fail on a missing partition
+CREATE TABLE IF NOT EXISTS audit_logs_default PARTITION OF audit_logs DEFAULT;
+
+-- Impersonated requests are looked up by the staff member who made them
+CREATE INDEX idx_audit_logs_impersonated_by ON audit_logs ((metadata->>'impersonated_by'))
+    WHERE metadata ? 'impersonated_by';
@@ -0,0 +1,2 @@
+DROP TRIGGER IF EXISTS update_user_totp_factors_updated_at ON user_totp_factors;
+DROP TABLE IF EXISTS user_totp_factors;
@@ -0,0 +1,12 @@
+-- Authenticator app (TOTP) factors, one per user
+CREATE TABLE user_totp_factors (
+    user_id UUID PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    secret VARCHAR(64) NOT NULL,
+    confirmed_at TIMESTAMP WITH TIME ZONE,
+    last_used_step BIGINT, -- rejects replay of a code within its window
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT (NOW() AT TIME ZONE 'UTC') NOT NULL,
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT (NOW() AT TIME ZONE 'UTC') NOT NULL
+);
+
+CREATE TRIGGER update_user_totp_factors_updated_at BEFORE UPDATE ON user_totp_factors
+    FOR EACH ROW EXECUTE FUNCTION update_updated_at_column();
@@ -0,0 +1,11 @@
+-- Processed-events ledger for consumers whose side effects are database
+-- writes (shared::utils::messaging::inbox::PostgresInbox). The row is written
+-- in the same transaction as the side effects.
+CREATE TABLE inbox (
+    consumer VARCHAR(255) NOT NULL,
+    event_id UUID NOT NULL,
+    processed_at TIMESTAMP WITH TIME ZONE DEFAULT NOW() NOT NULL,
+    PRIMARY KEY (consumer, event_id)
+);
+
+CREATE INDEX idx_inbox_processed_at ON inbox(processed_at);
@@ -0,0 +1,8 @@
+-- Runtime settings and feature flags (shared::features::settings::RuntimeSettings).
+-- Only overrides are stored; a missing key means the default in code.
+CREATE TABLE runtime_settings (
+    key VARCHAR(255) PRIMARY KEY,
+    value JSONB NOT NULL,
+    updated_by UUID,
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT NOW() NOT NULL
+);
@@ -0,0 +1,3 @@
+-- Like audit_logs, the initial partitions only cover 2025; catch everything
+-- else so logins never fail on a missing partition
+CREATE TABLE IF NOT EXISTS login_attempts_default PARTITION OF login_attempts DEFAULT;
@@ -0,0 +1,4 @@
+DROP INDEX IF EXISTS idx_outbox_unpublished_aggregate;
+DROP INDEX IF EXISTS idx_outbox_unpublished;
+CREATE INDEX idx_outbox_unpublished ON outbox(sequence) WHERE published_at IS NULL;
+ALTER TABLE outbox DROP COLUMN IF EXISTS failed_at;
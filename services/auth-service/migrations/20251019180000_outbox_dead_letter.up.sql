@@ -0,0 +1,9 @@
+-- Events the relay gave up on after `max_attempts`. They stop blocking the
+-- rest of their aggregate and wait for an operator.
+ALTER TABLE outbox ADD COLUMN failed_at TIMESTAMP WITH TIME ZONE;
+
+DROP INDEX idx_outbox_unpublished;
+CREATE INDEX idx_outbox_unpublished ON outbox(sequence) WHERE published_at IS NULL AND failed_at IS NULL;
+-- The relay looks for earlier events of the same aggregate still waiting
+CREATE INDEX idx_outbox_unpublished_aggregate ON outbox(aggregate_type, aggregate_id, sequence)
+    WHERE published_at IS NULL AND failed_at IS NULL;
@@ -0,0 +1,226 @@
+use crate::domain::entities::api_key::ApiKey;
+use crate::domain::repositories::api_key_repository::ApiKeyRepository;
+use crate::domain::repositories::organisation_repository::OrganisationMembershipRepository;
+use crate::domain::repositories::user_repository::UserRepository;
+use crate::domain::services::api_key_domain_service::ApiKeyDomainService;
+use crate::domain::services::organisation_domain_service::OrganisationDomainService;
+use async_trait::async_trait;
+use shared::utils::clock;
+use shared::config::jwt_config::JwtConfig;
+use shared::entities::dtos::auth::api_key::{ApiKeyResponse, CreateApiKeyRequest, CreateApiKeyResponse};
+use shared::entities::enums::UserRole;
+use shared::features::errors::{SuccessResponse, SystemError, SystemResult};
+use shared::features::helper::jwt_helper::JwtHelper;
+use shared::features::security::auth::ApiKeyValidator;
+use shared::features::security::jwt::JwtClaims;
+use std::sync::Arc;
+use uuid::Uuid;
+
+pub struct ApiKeyUseCase {
+    api_key_repo: Arc<dyn ApiKeyRepository>,
+    user_repo: Arc<dyn UserRepository>,
+    membership_repo: Arc<dyn OrganisationMembershipRepository>,
+    jwt_config: JwtConfig,
+}
+
+impl ApiKeyUseCase {
+    pub fn new(
+        api_key_repo: Arc<dyn ApiKeyRepository>,
+        user_repo: Arc<dyn UserRepository>,
+        membership_repo: Arc<dyn OrganisationMembershipRepository>,
+        jwt_config: JwtConfig,
+    ) -> Self {
+        Self {
+            api_key_repo,
+            user_repo,
+            membership_repo,
+            jwt_config,
+        }
+    }
+
+    pub async fn create_key(
+        &self,
+        caller: &JwtClaims,
+        request: CreateApiKeyRequest,
+    ) -> SystemResult<(CreateApiKeyResponse, SuccessResponse)> {
+        Self::ensure_first_party(caller)?;
+
+        if request.name.trim().is_empty() {
+            return Err(SystemError::ValidationError("API key name is required".to_string()));
+        }
+
+        if request.expires_at.is_some_and(|expiry| expiry <= clock::now()) {
+            return Err(SystemError::ValidationError("expires_at must be in the future".to_string()));
+        }
+
+        ApiKeyDomainService::validate_permissions(&caller.role, &request.permissions)?;
+
+        let allowed_ips = request.allowed_ips.unwrap_or_default();
+        ApiKeyDomainService::validate_allowed_ips(&allowed_ips)?;
+
+        // Organisation keys act on behalf of the organisation, so only its
+        // owners and admins may issue them
+        if let Some(organisation_id) = request.organisation_id {
+            let membership = self
+                .membership_repo
+                .find(organisation_id, caller.sub)
+                .await?
+                .ok_or_else(|| SystemError::NotFound(format!("Membership of organisation {}", organisation_id)))?;
+            OrganisationDomainService::ensure_can_manage(&membership)?;
+        }
+
+        let generated = ApiKeyDomainService::generate_key();
+        let api_key = ApiKey::new(
+            caller.sub,
+            request.organisation_id,
+            request.name.trim().to_string(),
+            generated.prefix,
+            generated.secret_hash,
+            request.permissions,
+            allowed_ips,
+            request.expires_at,
+        );
+        let api_key = self.api_key_repo.create(&api_key).await?;
+
+        let response = CreateApiKeyResponse {
+            id: api_key.id,
+            key: generated.key,
+            organisation_id: api_key.organisation_id,
+            prefix: api_key.prefix,
+            name: api_key.name,
+            permissions: api_key.permissions,
+            allowed_ips: api_key.allowed_ips,
+            expires_at: api_key.expires_at,
+        };
+        Ok((response, SuccessResponse::Created))
+    }
+
+    pub async fn list_keys(&self, caller: &JwtClaims) -> SystemResult<(Vec<ApiKeyResponse>, SuccessResponse)> {
+        Self::ensure_first_party(caller)?;
+
+        let keys = self.api_key_repo.find_by_user(caller.sub).await?;
+        let response = keys.into_iter().map(Self::to_response).collect();
+
+        Ok((response, SuccessResponse::Fetched))
+    }
+
+    // Owners revoke their own keys; administrators can revoke any key
+    pub async fn revoke_key(&self, caller: &JwtClaims, key_id: Uuid) -> SystemResult<SuccessResponse> {
+        Self::ensure_first_party(caller)?;
+
+        let is_admin = matches!(caller.role, UserRole::Admin | UserRole::SuperAdmin);
+        let api_key = self
+            .api_key_repo
+            .find_by_id(key_id)
+            .await?
+            .filter(|k| k.revoked_at.is_none() && (is_admin || k.user_id == caller.sub))
+            .ok_or_else(|| SystemError::NotFound(format!("API key {}", key_id)))?;
+
+        self.api_key_repo.revoke(api_key.id).await?;
+        log::info!("API key {} revoked by user {}", api_key.prefix, caller.sub);
+
+        Ok(SuccessResponse::Deleted)
+    }
+
+    pub async fn authenticate(&self, key: &str, ip_address: Option<&str>) -> SystemResult<JwtClaims> {
+        let (prefix, secret) = ApiKeyDomainService::parse_key(key).ok_or(SystemError::InvalidToken)?;
+
+        let api_key = self
+            .api_key_repo
+            .find_by_prefix(prefix)
+            .await?
+            .filter(|k| ApiKeyDomainService::verify_secret(k, secret))
+            .ok_or(SystemError::InvalidToken)?;
+
+        if !api_key.is_valid() {
+            return Err(SystemError::InvalidToken);
+        }
+
+        if !ApiKeyDomainService::is_ip_allowed(&api_key, ip_address) {
+            log::warn!(
+                "API key {} used from disallowed address {}",
+                api_key.prefix,
+                ip_address.unwrap_or("unknown")
+            );
+            return Err(SystemError::PermissionDenied(
+                "API key is not allowed from this address".to_string(),
+            ));
+        }
+
+        let user = self
+            .user_repo
+            .find_by_id(&api_key.user_id)
+            .await?
+            .ok_or(SystemError::InvalidToken)?;
+        user.can_login()?;
+
+        if api_key.needs_usage_update(ip_address) {
+            // Usage tracking must never fail the request itself
+            if let Err(e) = self
+                .api_key_repo
+                .record_usage(api_key.id, clock::now(), ip_address)
+                .await
+            {
+                log::warn!("Failed to record usage for API key {}: {}", api_key.prefix, e);
+            }
+        }
+
+        let permissions = ApiKeyDomainService::effective_permissions(&user.role, api_key.permissions);
+        let claims = JwtClaims::new(
+            user.id,
+            user.email,
+            user.role,
+            permissions,
+            self.jwt_config.issuer.clone(),
+            self.jwt_config.audience.clone(),
+            JwtHelper::generate_jti(),
+            self.jwt_config.access_token_expiry as usize,
+        )
+        .with_api_key_id(api_key.id);
+
+        // A key outlives its organisation context if the owner leaves
+        match api_key.organisation_id {
+            Some(organisation_id) => {
+                let membership = self
+                    .membership_repo
+                    .find(organisation_id, api_key.user_id)
+                    .await?
+                    .ok_or(SystemError::InvalidToken)?;
+                Ok(claims.with_organisation(organisation_id, membership.role))
+            }
+            None => Ok(claims),
+        }
+    }
+
+    fn to_response(api_key: ApiKey) -> ApiKeyResponse {
+        ApiKeyResponse {
+            id: api_key.id,
+            organisation_id: api_key.organisation_id,
+            prefix: api_key.prefix,
+            name: api_key.name,
+            permissions: api_key.permissions,
+            allowed_ips: api_key.allowed_ips,
+            expires_at: api_key.expires_at,
+            last_used_at: api_key.last_used_at,
+            last_used_ip: api_key.last_used_ip,
+            created_at: api_key.created_at,
+        }
+    }
+
+    // Keys cannot mint or revoke other keys
+    fn ensure_first_party(caller: &JwtClaims) -> SystemResult<()> {
+        if caller.is_delegated() {
+            return Err(SystemError::PermissionDenied(
+                "API keys can only be managed with a user session".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl ApiKeyValidator for ApiKeyUseCase {
+    async fn validate(&self, api_key: &str, ip_address: Option<&str>) -> SystemResult<JwtClaims> {
+        self.authenticate(api_key, ip_address).await
+    }
+}
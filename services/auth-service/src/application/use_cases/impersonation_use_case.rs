@@ -0,0 +1,143 @@
+use crate::domain::entities::audit_log::{audit_actions, resource_types, AuditLog};
+use crate::domain::repositories::audit_log_repository::AuditLogRepository;
+use crate::domain::repositories::organisation_repository::OrganisationMembershipRepository;
+use crate::domain::repositories::user_repository::UserRepository;
+use crate::domain::services::impersonation_domain_service::{
+    ImpersonationDomainService, IMPERSONATION_TOKEN_TTL_SECONDS,
+};
+use async_trait::async_trait;
+use chrono::Duration;
+use serde_json::json;
+use shared::utils::clock;
+use shared::config::jwt_config::JwtConfig;
+use shared::entities::dtos::auth::impersonation::{ImpersonateRequest, ImpersonationResponse};
+use shared::features::errors::{SuccessResponse, SystemError, SystemResult};
+use shared::features::helper::jwt_helper::JwtHelper;
+use shared::features::security::auth::{ImpersonatedRequest, ImpersonationAuditor};
+use shared::features::security::jwt::{ActorClaims, JwtClaims};
+use std::sync::Arc;
+
+pub struct ImpersonationUseCase {
+    user_repo: Arc<dyn UserRepository>,
+    membership_repo: Arc<dyn OrganisationMembershipRepository>,
+    audit_log_repo: Arc<dyn AuditLogRepository>,
+    jwt_config: JwtConfig,
+}
+
+impl ImpersonationUseCase {
+    pub fn new(
+        user_repo: Arc<dyn UserRepository>,
+        membership_repo: Arc<dyn OrganisationMembershipRepository>,
+        audit_log_repo: Arc<dyn AuditLogRepository>,
+        jwt_config: JwtConfig,
+    ) -> Self {
+        Self {
+            user_repo,
+            membership_repo,
+            audit_log_repo,
+            jwt_config,
+        }
+    }
+
+    // Issues a short-lived access token for the target user carrying an `act`
+    // claim with the administrator. No refresh token is issued.
+    pub async fn start(
+        &self,
+        caller: &JwtClaims,
+        request: ImpersonateRequest,
+        ip_address: Option<String>,
+        user_agent: Option<String>,
+    ) -> SystemResult<(ImpersonationResponse, SuccessResponse)> {
+        ImpersonationDomainService::validate_reason(&request.reason)?;
+
+        let target = self
+            .user_repo
+            .find_by_id(&request.user_id)
+            .await?
+            .ok_or_else(|| SystemError::UserNotFound(request.user_id.to_string()))?;
+
+        ImpersonationDomainService::ensure_can_impersonate(caller, &target)?;
+
+        let expires_in = IMPERSONATION_TOKEN_TTL_SECONDS.min(self.jwt_config.access_token_expiry as i64);
+        let mut claims = JwtClaims::new(
+            target.id,
+            target.email.clone(),
+            target.role.clone(),
+            vec![],
+            self.jwt_config.issuer.clone(),
+            self.jwt_config.audience.clone(),
+            JwtHelper::generate_jti(),
+            expires_in as usize,
+        )
+        .with_actor(ActorClaims {
+            sub: caller.sub,
+            email: caller.email.clone(),
+        });
+        if let Some(membership) = self.membership_repo.find_default_for_user(target.id).await? {
+            claims = claims.with_organisation(membership.organisation_id, membership.role);
+        }
+        let access_token = JwtHelper::sign_claims(&claims, &self.jwt_config.secret)?;
+
+        let mut audit_log = AuditLog::new(
+            Some(caller.sub),
+            audit_actions::IMPERSONATION_STARTED.to_string(),
+            Some(resource_types::USER.to_string()),
+            Some(target.id),
+        )
+        .with_context(ImpersonationDomainService::normalise_ip(ip_address.as_deref()), user_agent);
+        audit_log.add_metadata_field("reason", json!(request.reason.trim()));
+        audit_log.add_metadata_field("token_id", json!(claims.jti));
+        self.audit_log_repo.create(&audit_log).await?;
+
+        log::warn!("User {} started impersonating user {}", caller.sub, target.id);
+
+        let response = ImpersonationResponse {
+            access_token,
+            expires_in,
+            expires_at: clock::now() + Duration::seconds(expires_in),
+            user_id: target.id,
+            email: target.email,
+            role: target.role,
+        };
+        Ok((response, SuccessResponse::Created))
+    }
+}
+
+// Every request made with an impersonation token lands in audit_logs under the
+// impersonated user, with the real actor in the metadata.
+#[async_trait]
+impl ImpersonationAuditor for ImpersonationUseCase {
+    async fn record(&self, claims: &JwtClaims, request: &ImpersonatedRequest) {
+        let Some(actor) = &claims.act else {
+            return;
+        };
+
+        let mut audit_log = AuditLog::new(
+            Some(claims.sub),
+            audit_actions::IMPERSONATED_REQUEST.to_string(),
+            None,
+            None,
+        )
+        .with_context(
+            ImpersonationDomainService::normalise_ip(request.ip_address.as_deref()),
+            request.user_agent.clone(),
+        );
+        audit_log.add_metadata_field("impersonated_by", json!(actor.sub));
+        audit_log.add_metadata_field("impersonator_email", json!(actor.email));
+        audit_log.add_metadata_field("token_id", json!(claims.jti));
+        audit_log.add_metadata_field("method", json!(request.method));
+        audit_log.add_metadata_field("path", json!(request.path));
+        audit_log.add_metadata_field("status", json!(request.status));
+
+        // Auditing must never change the outcome of the request itself
+        if let Err(e) = self.audit_log_repo.create(&audit_log).await {
+            log::error!(
+                "Failed to audit impersonated request {} {} by {}: {}",
+                request.method,
+                request.path,
+                actor.sub,
+                e
+            );
+        }
+    }
+}
@@ -1,26 +1,33 @@
-use crate::cache::auth_cache::AuthCacheService;
+use crate::cache::auth_cache::AuthCache;
 use crate::domain::repositories::login_attempt_repository::LoginAttemptRepository;
+use crate::domain::repositories::organisation_repository::OrganisationMembershipRepository;
 use crate::domain::repositories::refresh_token_repository::RefreshTokenRepository;
 use crate::domain::repositories::user_repository::UserRepository;
+use crate::infrastructure::metrics::{record_lockout, record_login};
+use crate::infrastructure::settings::{LOGIN_LOCKOUT_MINUTES, LOGIN_MAX_FAILED_ATTEMPTS};
 use crate::domain::{
     entities::{login_attempt::LoginAttempt, refresh_token::RefreshToken, user::User},
     services::auth_domain_service,
 };
 use std::sync::Arc;
 use uuid::Uuid;
+use shared::utils::clock;
 use shared::entities::dtos::auth::auth::{LoginRequest, LoginResponse};
 use shared::features::errors::{SuccessResponse, SystemError, SystemResult};
+use shared::config::jwt_config::JwtConfig;
 use shared::features::helper::jwt_helper::JwtHelper;
-use shared::features::helper::password_helper::PasswordHelper;
+use shared::features::helper::token_helper::TokenHelper;
+use shared::features::security::jwt::{amr, JwtClaims};
+use shared::features::settings::RuntimeSettings;
 
 pub struct LoginUseCase {
     user_repo: Arc<dyn UserRepository>,
     refresh_token_repo: Arc<dyn RefreshTokenRepository>,
     login_attempt_repo: Arc<dyn LoginAttemptRepository>,
-    cache_service: AuthCacheService,
-    jwt_secret: String,
-    max_login_attempts: i32,
-    lockout_duration_minutes: i64,
+    membership_repo: Arc<dyn OrganisationMembershipRepository>,
+    cache_service: Arc<dyn AuthCache>,
+    jwt_config: JwtConfig,
+    settings: Arc<RuntimeSettings>,
 }
 
 impl LoginUseCase {
@@ -28,19 +35,19 @@ impl LoginUseCase {
         user_repo: Arc<dyn UserRepository>,
         refresh_token_repo: Arc<dyn RefreshTokenRepository>,
         login_attempt_repo: Arc<dyn LoginAttemptRepository>,
-        cache_service: AuthCacheService,
-        jwt_secret: String,
-        max_login_attempts: i32,
-        lockout_duration_minutes: i64,
+        membership_repo: Arc<dyn OrganisationMembershipRepository>,
+        cache_service: Arc<dyn AuthCache>,
+        jwt_config: JwtConfig,
+        settings: Arc<RuntimeSettings>,
     ) -> Self {
         Self {
             user_repo,
             refresh_token_repo,
             login_attempt_repo,
+            membership_repo,
             cache_service,
-            jwt_secret,
-            max_login_attempts,
-            lockout_duration_minutes,
+            jwt_config,
+            settings,
         }
     }
 
@@ -60,7 +67,10 @@ impl LoginUseCase {
             .as_ref()
             .find_by_email(request.identifier.as_ref())
             .await?
-            .ok_or(SystemError::InvalidCredentials)?;
+            .ok_or_else(|| {
+                record_login(false);
+                SystemError::InvalidCredentials
+            })?;
 
         // Validate credentials
         let login_result = auth_domain_service::AuthDomainService::validate_login_credentials(
@@ -90,7 +100,13 @@ impl LoginUseCase {
 
         // Handle failed login
         if let Err(e) = login_result {
-            user.increment_failed_attempts(self.max_login_attempts, self.lockout_duration_minutes);
+            record_login(false);
+            if user.increment_failed_attempts(
+                self.settings.get(&LOGIN_MAX_FAILED_ATTEMPTS),
+                self.settings.get(&LOGIN_LOCKOUT_MINUTES),
+            ) {
+                record_lockout();
+            }
             self.user_repo.update(&user).await?;
             return Err(e);
         }
@@ -106,11 +122,12 @@ impl LoginUseCase {
         self.cache_service
             .cache_user_session(user.id, &access_token)
             .await?;
+        record_login(true);
 
         let response = LoginResponse {
             access_token,
             refresh_token,
-            expires_in: 3600, // 1 hour
+            expires_in: self.jwt_config.access_token_expiry as i64,
             // user_info: UserInfo {
             //     id: updated_user.id,
             //     email: updated_user.email,
@@ -124,7 +141,7 @@ impl LoginUseCase {
     }
 
     async fn check_rate_limiting(&self, identifier: &str, ip_address: &str) -> SystemResult<()> {
-        let since = chrono::Utc::now() - chrono::Duration::minutes(15);
+        let since = clock::now() - chrono::Duration::minutes(15);
 
         let identifier_attempts = self
             .login_attempt_repo
@@ -150,25 +167,27 @@ impl LoginUseCase {
         user: &User,
         ip_address: String,
     ) -> SystemResult<(String, String)> {
-        // Generate access security
-        let access_token =
-            JwtHelper::generate_access_token(
-                user.id,
-                user.email.clone(),
-                user.role.clone(),
-                vec![ip_address.clone()],
-                &self.jwt_secret,
-                1,
-                "",
-                "",
-                Uuid::new_v4(),
-                )
-                .map_err(|e| SystemError::InternalError(e.to_string()))?;
+        // Generate access security, starting in the organisation the user last worked in
+        let mut claims = JwtClaims::new(
+            user.id,
+            user.email.clone(),
+            user.role.clone(),
+            vec![ip_address.clone()],
+            self.jwt_config.issuer.clone(),
+            self.jwt_config.audience.clone(),
+            Uuid::new_v4(),
+            self.jwt_config.access_token_expiry as usize,
+        )
+        .with_authentication(amr::PASSWORD);
+        if let Some(membership) = self.membership_repo.find_default_for_user(user.id).await? {
+            claims = claims.with_organisation(membership.organisation_id, membership.role);
+        }
+        let access_token = JwtHelper::sign_claims(&claims, &self.jwt_config.secret)
+            .map_err(|e| SystemError::InternalError(e.to_string()))?;
 
         // Generate refresh security
         let refresh_token_value = JwtHelper::generate_secure_token();
-        let refresh_token_hash = PasswordHelper::hash_string(&refresh_token_value)
-            .map_err(|e| SystemError::InternalError(e.to_string()))?;
+        let refresh_token_hash = TokenHelper::hash_token(&refresh_token_value);
 
         let refresh_token_entity = RefreshToken::new(
             user.id,
@@ -177,7 +196,7 @@ impl LoginUseCase {
             Some(ip_address),
             None,
             None,
-            chrono::Utc::now() + chrono::Duration::days(30),
+            clock::now() + chrono::Duration::days(30),
         );
 
         self.refresh_token_repo
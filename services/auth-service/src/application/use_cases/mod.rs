@@ -1,11 +1,21 @@
+pub mod api_key_use_case;
+pub mod impersonation_use_case;
 pub mod login_use_case;
+pub mod oauth_use_case;
+pub mod organisation_use_case;
 pub mod otp_use_case;
 pub mod password_reset_use_case;
 pub mod refresh_token_use_case;
 pub mod security_question_use_case;
+pub mod step_up_use_case;
 
+pub use api_key_use_case::*;
+pub use impersonation_use_case::*;
 pub use login_use_case::*;
+pub use oauth_use_case::*;
+pub use organisation_use_case::*;
 pub use otp_use_case::*;
 pub use password_reset_use_case::*;
 pub use refresh_token_use_case::*;
 pub use security_question_use_case::*;
+pub use step_up_use_case::*;
@@ -0,0 +1,639 @@
+use crate::cache::auth_cache::AuthCache;
+use crate::domain::entities::blacklisted_token::TokenType;
+use crate::domain::entities::oauth_authorization_code::OAuthAuthorizationCode;
+use crate::domain::entities::oauth_client::{grant_types, OAuthClient};
+use crate::domain::entities::oauth_consent::OAuthConsent;
+use crate::domain::entities::oauth_token::OAuthToken;
+use crate::domain::entities::user::User;
+use crate::domain::repositories::oauth_repository::{
+    OAuthAuthorizationCodeRepository, OAuthClientRepository, OAuthConsentRepository,
+    OAuthTokenRepository,
+};
+use crate::domain::repositories::user_repository::UserRepository;
+use crate::domain::services::oauth_domain_service::OAuthDomainService;
+use crate::infrastructure::metrics::record_token_refresh;
+use chrono::Duration;
+use shared::utils::clock;
+use shared::config::jwt_config::JwtConfig;
+use shared::entities::dtos::auth::oauth::{
+    AuthorizeRequest, AuthorizeResponse, IntrospectRequest, IntrospectResponse,
+    OAuthConsentResponse, RegisterOAuthClientRequest, RegisterOAuthClientResponse,
+    RevokeTokenRequest, TokenRequest, TokenResponse,
+};
+use shared::entities::enums::UserRole;
+use shared::features::errors::{SuccessResponse, SystemError, SystemResult};
+use shared::features::helper::jwt_helper::JwtHelper;
+use shared::features::helper::password_helper::PasswordHelper;
+use shared::features::helper::token_helper::TokenHelper;
+use shared::features::security::jwt::JwtClaims;
+use std::sync::Arc;
+use uuid::Uuid;
+
+const AUTHORIZATION_CODE_TTL_MINUTES: i64 = 10;
+const REFRESH_TOKEN_TTL_DAYS: i64 = 30;
+
+// Roles allowed to register OAuth clients for their integrations
+const CLIENT_OWNER_ROLES: &[UserRole] = &[
+    UserRole::PropertyManager,
+    UserRole::Landlord,
+    UserRole::Admin,
+    UserRole::SuperAdmin,
+];
+
+// Credentials presented by a client, either through HTTP Basic or the form body
+#[derive(Debug, Clone)]
+pub struct ClientCredentials {
+    pub client_id: String,
+    pub client_secret: Option<String>,
+}
+
+pub struct OAuthUseCase {
+    client_repo: Arc<dyn OAuthClientRepository>,
+    code_repo: Arc<dyn OAuthAuthorizationCodeRepository>,
+    token_repo: Arc<dyn OAuthTokenRepository>,
+    consent_repo: Arc<dyn OAuthConsentRepository>,
+    user_repo: Arc<dyn UserRepository>,
+    cache_service: Arc<dyn AuthCache>,
+    jwt_config: JwtConfig,
+}
+
+impl OAuthUseCase {
+    pub fn new(
+        client_repo: Arc<dyn OAuthClientRepository>,
+        code_repo: Arc<dyn OAuthAuthorizationCodeRepository>,
+        token_repo: Arc<dyn OAuthTokenRepository>,
+        consent_repo: Arc<dyn OAuthConsentRepository>,
+        user_repo: Arc<dyn UserRepository>,
+        cache_service: Arc<dyn AuthCache>,
+        jwt_config: JwtConfig,
+    ) -> Self {
+        Self {
+            client_repo,
+            code_repo,
+            token_repo,
+            consent_repo,
+            user_repo,
+            cache_service,
+            jwt_config,
+        }
+    }
+
+    pub async fn register_client(
+        &self,
+        caller: &JwtClaims,
+        request: RegisterOAuthClientRequest,
+    ) -> SystemResult<(RegisterOAuthClientResponse, SuccessResponse)> {
+        Self::ensure_first_party(caller)?;
+
+        if !CLIENT_OWNER_ROLES.contains(&caller.role) {
+            return Err(SystemError::PermissionDenied(
+                "Only property managers, landlords and administrators can register OAuth clients"
+                    .to_string(),
+            ));
+        }
+
+        if request.name.trim().is_empty() {
+            return Err(SystemError::ValidationError("Client name is required".to_string()));
+        }
+
+        let is_confidential = request.is_confidential.unwrap_or(true);
+        OAuthDomainService::validate_client_registration(
+            &caller.role,
+            &request.redirect_uris,
+            &request.grant_types,
+            &request.scopes,
+            is_confidential,
+        )?;
+
+        let (client_secret, client_secret_hash) = if is_confidential {
+            let secret = JwtHelper::generate_secure_token();
+            let hash = PasswordHelper::hash_string(&secret)?;
+            (Some(secret), Some(hash))
+        } else {
+            (None, None)
+        };
+
+        let client = OAuthClient::new(
+            caller.sub,
+            request.name.trim().to_string(),
+            client_secret_hash,
+            request.redirect_uris,
+            request.grant_types,
+            request.scopes,
+        );
+        let client = self.client_repo.create(&client).await?;
+
+        let response = RegisterOAuthClientResponse {
+            client_id: client.id,
+            client_secret,
+            name: client.name,
+            redirect_uris: client.redirect_uris,
+            grant_types: client.grant_types,
+            scopes: client.scopes,
+        };
+        Ok((response, SuccessResponse::Created))
+    }
+
+    pub async fn list_clients(
+        &self,
+        caller: &JwtClaims,
+    ) -> SystemResult<(Vec<RegisterOAuthClientResponse>, SuccessResponse)> {
+        Self::ensure_first_party(caller)?;
+
+        let clients = self.client_repo.find_by_owner(caller.sub).await?;
+        let response = clients
+            .into_iter()
+            .map(|client| RegisterOAuthClientResponse {
+                client_id: client.id,
+                client_secret: None,
+                name: client.name,
+                redirect_uris: client.redirect_uris,
+                grant_types: client.grant_types,
+                scopes: client.scopes,
+            })
+            .collect();
+
+        Ok((response, SuccessResponse::Fetched))
+    }
+
+    // A deactivated client can no longer authenticate, so its refresh tokens stop
+    // working; issued access tokens simply run out their short lifetime.
+    pub async fn deactivate_client(&self, caller: &JwtClaims, client_id: Uuid) -> SystemResult<SuccessResponse> {
+        Self::ensure_first_party(caller)?;
+
+        let mut client = self
+            .client_repo
+            .find_by_id(client_id)
+            .await?
+            .filter(|c| c.owner_user_id == caller.sub)
+            .ok_or_else(|| SystemError::NotFound(format!("OAuth client {}", client_id)))?;
+
+        client.deactivate();
+        self.client_repo.update(&client).await?;
+
+        Ok(SuccessResponse::Deleted)
+    }
+
+    // The authenticated user approving this request is the consent decision, so
+    // the grant is recorded before the code is handed back to the client.
+    pub async fn authorize(
+        &self,
+        caller: &JwtClaims,
+        request: AuthorizeRequest,
+    ) -> SystemResult<(AuthorizeResponse, SuccessResponse)> {
+        Self::ensure_first_party(caller)?;
+
+        if request.response_type != "code" {
+            return Err(SystemError::UnsupportedGrantType(request.response_type));
+        }
+
+        let client = self.find_active_client(request.client_id).await?;
+
+        if !client.allows_grant_type(grant_types::AUTHORIZATION_CODE) {
+            return Err(SystemError::UnsupportedGrantType(
+                grant_types::AUTHORIZATION_CODE.to_string(),
+            ));
+        }
+
+        if !client.allows_redirect_uri(&request.redirect_uri) {
+            return Err(SystemError::ValidationError("Unregistered redirect_uri".to_string()));
+        }
+
+        // Public clients cannot keep a secret, so PKCE is mandatory for them
+        if !client.is_confidential && request.code_challenge.is_none() {
+            return Err(SystemError::ValidationError(
+                "code_challenge is required for public clients".to_string(),
+            ));
+        }
+        OAuthDomainService::validate_code_challenge_method(request.code_challenge_method.as_deref())?;
+
+        let user = self.find_user(caller.sub).await?;
+        user.can_login()?;
+
+        let scopes = OAuthDomainService::resolve_scopes(
+            &client,
+            &user.role,
+            OAuthDomainService::parse_scopes(request.scope.as_deref()),
+        )?;
+
+        self.consent_repo
+            .upsert(&OAuthConsent::new(user.id, client.id, scopes.clone()))
+            .await?;
+
+        let code = JwtHelper::generate_secure_token();
+        let code_challenge_method = request
+            .code_challenge
+            .as_ref()
+            .map(|_| request.code_challenge_method.unwrap_or_else(|| "plain".to_string()));
+
+        let authorization_code = OAuthAuthorizationCode::new(
+            TokenHelper::hash_token(&code),
+            client.id,
+            user.id,
+            request.redirect_uri.clone(),
+            scopes,
+            request.code_challenge,
+            code_challenge_method,
+            clock::now() + Duration::minutes(AUTHORIZATION_CODE_TTL_MINUTES),
+        );
+        self.code_repo.create(&authorization_code).await?;
+
+        let mut params = vec![("code", code.clone())];
+        if let Some(state) = &request.state {
+            params.push(("state", state.clone()));
+        }
+        let query = serde_urlencoded::to_string(&params)
+            .map_err(|e| SystemError::InternalError(e.to_string()))?;
+        let separator = if request.redirect_uri.contains('?') { '&' } else { '?' };
+
+        let response = AuthorizeResponse {
+            redirect_uri: format!("{}{}{}", request.redirect_uri, separator, query),
+            code,
+            state: request.state,
+        };
+        Ok((response, SuccessResponse::Ok))
+    }
+
+    pub async fn exchange_token(
+        &self,
+        credentials: ClientCredentials,
+        request: TokenRequest,
+    ) -> SystemResult<TokenResponse> {
+        let client = self.authenticate_client(&credentials).await?;
+
+        if !client.allows_grant_type(&request.grant_type) {
+            return Err(SystemError::UnsupportedGrantType(request.grant_type));
+        }
+
+        match request.grant_type.as_str() {
+            grant_types::CLIENT_CREDENTIALS => self.client_credentials_grant(&client, request).await,
+            grant_types::AUTHORIZATION_CODE => self.authorization_code_grant(&client, request).await,
+            grant_types::REFRESH_TOKEN => {
+                let result = self.refresh_token_grant(&client, request).await;
+                record_token_refresh("oauth", result.is_ok());
+                result
+            }
+            _ => Err(SystemError::UnsupportedGrantType(request.grant_type)),
+        }
+    }
+
+    pub async fn introspect(
+        &self,
+        credentials: ClientCredentials,
+        request: IntrospectRequest,
+    ) -> SystemResult<IntrospectResponse> {
+        let client = self.authenticate_client(&credentials).await?;
+
+        let token = match self
+            .token_repo
+            .find_by_token_hash(&TokenHelper::hash_token(&request.token))
+            .await?
+        {
+            Some(token) => token,
+            None => return Ok(IntrospectResponse::default()),
+        };
+
+        // Clients may only introspect their own tokens (RFC 7662 section 4)
+        if token.client_id != client.id || !token.is_active() {
+            return Ok(IntrospectResponse::default());
+        }
+
+        let username = self.user_repo.find_by_id(&token.user_id).await?.map(|u| u.email);
+
+        Ok(IntrospectResponse {
+            active: true,
+            scope: Some(OAuthDomainService::format_scopes(&token.scopes)),
+            client_id: Some(client.id.to_string()),
+            username,
+            token_type: Some(match token.token_type {
+                TokenType::Refresh => "refresh_token".to_string(),
+                _ => "Bearer".to_string(),
+            }),
+            exp: Some(token.expires_at.timestamp()),
+            iat: Some(token.created_at.timestamp()),
+            sub: Some(token.user_id.to_string()),
+            aud: Some(self.jwt_config.audience.clone()),
+            iss: Some(self.jwt_config.issuer.clone()),
+            jti: Some(token.id.to_string()),
+        })
+    }
+
+    // Unknown or foreign tokens are ignored so the endpoint does not leak token
+    // validity (RFC 7009 section 2.2).
+    pub async fn revoke(
+        &self,
+        credentials: ClientCredentials,
+        request: RevokeTokenRequest,
+    ) -> SystemResult<()> {
+        let client = self.authenticate_client(&credentials).await?;
+
+        let token = self
+            .token_repo
+            .find_by_token_hash(&TokenHelper::hash_token(&request.token))
+            .await?;
+
+        if let Some(mut token) = token {
+            if token.client_id == client.id && !token.is_revoked {
+                if token.token_type == TokenType::Access {
+                    let remaining = (token.expires_at - clock::now()).num_seconds();
+                    if remaining > 0 {
+                        self.cache_service.blacklist_token(&request.token, remaining).await?;
+                    }
+                }
+                token.revoke();
+                self.token_repo.update(&token).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    pub async fn list_consents(
+        &self,
+        caller: &JwtClaims,
+    ) -> SystemResult<(Vec<OAuthConsentResponse>, SuccessResponse)> {
+        Self::ensure_first_party(caller)?;
+
+        let consents = self.consent_repo.find_active_by_user(caller.sub).await?;
+        let mut response = Vec::with_capacity(consents.len());
+
+        for consent in consents {
+            let client_name = self
+                .client_repo
+                .find_by_id(consent.client_id)
+                .await?
+                .map(|c| c.name)
+                .unwrap_or_default();
+
+            response.push(OAuthConsentResponse {
+                client_id: consent.client_id,
+                client_name,
+                scopes: consent.scopes,
+                granted_at: consent.granted_at,
+            });
+        }
+
+        Ok((response, SuccessResponse::Ok))
+    }
+
+    // Revoking consent also kills every token the client holds for this user.
+    pub async fn revoke_consent(&self, caller: &JwtClaims, client_id: Uuid) -> SystemResult<SuccessResponse> {
+        Self::ensure_first_party(caller)?;
+
+        let mut consent = self
+            .consent_repo
+            .find_by_user_and_client(caller.sub, client_id)
+            .await?
+            .filter(|c| c.is_active())
+            .ok_or_else(|| SystemError::NotFound("No active consent for this client".to_string()))?;
+
+        consent.revoke();
+        self.consent_repo.update(&consent).await?;
+
+        let revoked = self.token_repo.revoke_for_user_and_client(caller.sub, client_id).await?;
+        log::info!(
+            "Consent for client {} revoked by user {}, {} tokens revoked",
+            client_id,
+            caller.sub,
+            revoked
+        );
+
+        Ok(SuccessResponse::Ok)
+    }
+
+    async fn client_credentials_grant(
+        &self,
+        client: &OAuthClient,
+        request: TokenRequest,
+    ) -> SystemResult<TokenResponse> {
+        if !client.is_confidential {
+            return Err(SystemError::InvalidClient);
+        }
+
+        // The client acts as its owner, limited to the granted scopes
+        let owner = self.find_user(client.owner_user_id).await?;
+        owner.can_login()?;
+
+        let scopes = OAuthDomainService::resolve_scopes(
+            client,
+            &owner.role,
+            OAuthDomainService::parse_scopes(request.scope.as_deref()),
+        )?;
+
+        // No refresh token for client credentials (RFC 6749 section 4.4.3)
+        self.issue_tokens(client, &owner, scopes, false).await
+    }
+
+    async fn authorization_code_grant(
+        &self,
+        client: &OAuthClient,
+        request: TokenRequest,
+    ) -> SystemResult<TokenResponse> {
+        let code = request
+            .code
+            .ok_or_else(|| SystemError::ValidationError("code is required".to_string()))?;
+
+        let authorization_code = self
+            .code_repo
+            .find_by_code_hash(&TokenHelper::hash_token(&code))
+            .await?
+            .ok_or_else(|| SystemError::InvalidGrant("Unknown authorization code".to_string()))?;
+
+        if authorization_code.client_id != client.id {
+            return Err(SystemError::InvalidGrant("Authorization code was issued to another client".to_string()));
+        }
+
+        if authorization_code.is_used {
+            return self.reject_replayed_code(client, &authorization_code).await;
+        }
+
+        if !authorization_code.is_valid() {
+            return Err(SystemError::InvalidGrant("Authorization code expired".to_string()));
+        }
+
+        if request.redirect_uri.as_deref() != Some(authorization_code.redirect_uri.as_str()) {
+            return Err(SystemError::InvalidGrant("redirect_uri does not match".to_string()));
+        }
+
+        OAuthDomainService::verify_pkce(&authorization_code, request.code_verifier.as_deref())?;
+
+        // Spent atomically, so a concurrent exchange of the same code loses here
+        if self.code_repo.consume(authorization_code.id).await?.is_none() {
+            return self.reject_replayed_code(client, &authorization_code).await;
+        }
+
+        let user = self.find_user(authorization_code.user_id).await?;
+        user.can_login()?;
+
+        let issue_refresh = client.allows_grant_type(grant_types::REFRESH_TOKEN);
+        self.issue_tokens(client, &user, authorization_code.scopes, issue_refresh).await
+    }
+
+    async fn refresh_token_grant(
+        &self,
+        client: &OAuthClient,
+        request: TokenRequest,
+    ) -> SystemResult<TokenResponse> {
+        let refresh_token = request
+            .refresh_token
+            .ok_or_else(|| SystemError::ValidationError("refresh_token is required".to_string()))?;
+
+        let stored = self
+            .token_repo
+            .find_by_token_hash(&TokenHelper::hash_token(&refresh_token))
+            .await?
+            .filter(|t| t.token_type == TokenType::Refresh && t.client_id == client.id)
+            .ok_or_else(|| SystemError::InvalidGrant("Unknown refresh token".to_string()))?;
+
+        if !stored.is_active() {
+            return Err(SystemError::InvalidGrant("Refresh token is no longer valid".to_string()));
+        }
+
+        // The user may have withdrawn consent since the token was issued
+        let consent = self
+            .consent_repo
+            .find_by_user_and_client(stored.user_id, client.id)
+            .await?;
+        if !consent.is_some_and(|c| c.covers(&stored.scopes)) {
+            return Err(SystemError::InvalidGrant("Consent has been revoked".to_string()));
+        }
+
+        // A refresh may narrow the original scope but never widen it
+        let requested = OAuthDomainService::parse_scopes(request.scope.as_deref());
+        let scopes = if requested.is_empty() {
+            stored.scopes.clone()
+        } else if let Some(scope) = requested.iter().find(|s| !stored.scopes.contains(s)) {
+            return Err(SystemError::InvalidScope(scope.clone()));
+        } else {
+            requested
+        };
+
+        // Rotate: the presented refresh token is single use, even when
+        // presented twice at once
+        if self.token_repo.consume(stored.id).await?.is_none() {
+            return Err(SystemError::InvalidGrant("Refresh token is no longer valid".to_string()));
+        }
+
+        let user = self.find_user(stored.user_id).await?;
+        user.can_login()?;
+
+        self.issue_tokens(client, &user, scopes, true).await
+    }
+
+    // A replayed code suggests it leaked; kill everything it produced
+    async fn reject_replayed_code(
+        &self,
+        client: &OAuthClient,
+        code: &OAuthAuthorizationCode,
+    ) -> SystemResult<TokenResponse> {
+        self.token_repo.revoke_for_user_and_client(code.user_id, client.id).await?;
+        Err(SystemError::InvalidGrant("Authorization code already used".to_string()))
+    }
+
+    async fn issue_tokens(
+        &self,
+        client: &OAuthClient,
+        user: &User,
+        scopes: Vec<String>,
+        issue_refresh: bool,
+    ) -> SystemResult<TokenResponse> {
+        // The user's role may have changed since the scopes were granted
+        let scopes = OAuthDomainService::limit_to_role(&user.role, scopes);
+        if scopes.is_empty() {
+            return Err(SystemError::InvalidGrant(
+                "None of the granted scopes are available to this user any more".to_string(),
+            ));
+        }
+
+        let expires_in = self.jwt_config.access_token_expiry as i64;
+        let jti = JwtHelper::generate_jti();
+
+        let claims = JwtClaims::new(
+            user.id,
+            user.email.clone(),
+            user.role.clone(),
+            scopes.clone(),
+            self.jwt_config.issuer.clone(),
+            self.jwt_config.audience.clone(),
+            jti,
+            expires_in as usize,
+        )
+        .with_client_id(client.id.to_string());
+        let access_token = JwtHelper::sign_claims(&claims, &self.jwt_config.secret)?;
+
+        self.token_repo
+            .create(&OAuthToken::new(
+                jti,
+                TokenHelper::hash_token(&access_token),
+                TokenType::Access,
+                client.id,
+                user.id,
+                scopes.clone(),
+                clock::now() + Duration::seconds(expires_in),
+            ))
+            .await?;
+
+        let refresh_token = if issue_refresh {
+            let value = JwtHelper::generate_secure_token();
+            self.token_repo
+                .create(&OAuthToken::new(
+                    Uuid::new_v4(),
+                    TokenHelper::hash_token(&value),
+                    TokenType::Refresh,
+                    client.id,
+                    user.id,
+                    scopes.clone(),
+                    clock::now() + Duration::days(REFRESH_TOKEN_TTL_DAYS),
+                ))
+                .await?;
+            Some(value)
+        } else {
+            None
+        };
+
+        Ok(TokenResponse {
+            access_token,
+            token_type: "Bearer".to_string(),
+            expires_in,
+            refresh_token,
+            scope: OAuthDomainService::format_scopes(&scopes),
+        })
+    }
+
+    async fn authenticate_client(&self, credentials: &ClientCredentials) -> SystemResult<OAuthClient> {
+        let client_id = Uuid::parse_str(&credentials.client_id).map_err(|_| SystemError::InvalidClient)?;
+        let client = self
+            .client_repo
+            .find_by_id(client_id)
+            .await?
+            .ok_or(SystemError::InvalidClient)?;
+
+        OAuthDomainService::authenticate_client(&client, credentials.client_secret.as_deref())?;
+        Ok(client)
+    }
+
+    async fn find_active_client(&self, client_id: Uuid) -> SystemResult<OAuthClient> {
+        self.client_repo
+            .find_by_id(client_id)
+            .await?
+            .filter(|c| c.is_active)
+            .ok_or(SystemError::InvalidClient)
+    }
+
+    async fn find_user(&self, user_id: Uuid) -> SystemResult<User> {
+        self.user_repo
+            .find_by_id(&user_id)
+            .await?
+            .ok_or_else(|| SystemError::UserNotFound(user_id.to_string()))
+    }
+
+    // Delegated credentials must not be used to manage delegation itself
+    fn ensure_first_party(caller: &JwtClaims) -> SystemResult<()> {
+        if caller.is_delegated() {
+            return Err(SystemError::PermissionDenied(
+                "OAuth access tokens and API keys cannot manage OAuth clients or consents".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,445 @@
+use crate::domain::entities::organisation::Organisation;
+use crate::domain::entities::organisation_invitation::OrganisationInvitation;
+use crate::domain::entities::organisation_membership::OrganisationMembership;
+use crate::domain::repositories::organisation_repository::{
+    OrganisationInvitationRepository, OrganisationMembershipRepository, OrganisationRepository,
+};
+use crate::domain::repositories::user_repository::UserRepository;
+use crate::domain::services::organisation_domain_service::OrganisationDomainService;
+use crate::infrastructure::messaging::notification_publisher::NotificationPublisher;
+use chrono::Duration;
+use shared::utils::clock;
+use shared::config::jwt_config::JwtConfig;
+use shared::entities::dtos::auth::organisation::{
+    AcceptInvitationRequest, CreateOrganisationRequest, InvitationResponse, InviteMemberRequest,
+    OrganisationMemberResponse, OrganisationResponse, SwitchOrganisationRequest,
+    SwitchOrganisationResponse, UpdateMemberRoleRequest,
+};
+use shared::entities::enums::OrganisationRole;
+use shared::features::errors::{SuccessResponse, SystemError, SystemResult};
+use shared::features::helper::jwt_helper::JwtHelper;
+use shared::features::helper::token_helper::TokenHelper;
+use shared::features::security::jwt::JwtClaims;
+use shared::features::validation;
+use std::sync::Arc;
+use uuid::Uuid;
+
+const INVITATION_TTL_DAYS: i64 = 7;
+
+pub struct OrganisationUseCase {
+    organisation_repo: Arc<dyn OrganisationRepository>,
+    membership_repo: Arc<dyn OrganisationMembershipRepository>,
+    invitation_repo: Arc<dyn OrganisationInvitationRepository>,
+    user_repo: Arc<dyn UserRepository>,
+    jwt_config: JwtConfig,
+}
+
+impl OrganisationUseCase {
+    pub fn new(
+        organisation_repo: Arc<dyn OrganisationRepository>,
+        membership_repo: Arc<dyn OrganisationMembershipRepository>,
+        invitation_repo: Arc<dyn OrganisationInvitationRepository>,
+        user_repo: Arc<dyn UserRepository>,
+        jwt_config: JwtConfig,
+    ) -> Self {
+        Self {
+            organisation_repo,
+            membership_repo,
+            invitation_repo,
+            user_repo,
+            jwt_config,
+        }
+    }
+
+    pub async fn create_organisation(
+        &self,
+        caller: &JwtClaims,
+        request: CreateOrganisationRequest,
+    ) -> SystemResult<(OrganisationResponse, SuccessResponse)> {
+        Self::ensure_first_party(caller)?;
+
+        let name = request.name.trim().to_string();
+        let slug = OrganisationDomainService::slugify(&name);
+        if slug.is_empty() {
+            return Err(SystemError::ValidationError("Organisation name is required".to_string()));
+        }
+
+        if self.organisation_repo.exists_by_slug(&slug).await? {
+            return Err(SystemError::ValidationError(format!(
+                "An organisation named {} already exists",
+                name
+            )));
+        }
+
+        let organisation = Organisation::new(name, slug, caller.sub);
+        let owner = OrganisationMembership::new(organisation.id, caller.sub, OrganisationRole::Owner);
+        let organisation = self.organisation_repo.create_with_owner(&organisation, &owner).await?;
+
+        let response = OrganisationResponse {
+            id: organisation.id,
+            name: organisation.name,
+            slug: organisation.slug,
+            role: owner.role,
+            created_at: organisation.created_at,
+        };
+        Ok((response, SuccessResponse::Created))
+    }
+
+    pub async fn list_organisations(
+        &self,
+        caller: &JwtClaims,
+    ) -> SystemResult<(Vec<OrganisationResponse>, SuccessResponse)> {
+        let organisations = self.organisation_repo.find_by_member(caller.sub).await?;
+        let memberships = self.membership_repo.find_by_user(caller.sub).await?;
+
+        let response = organisations
+            .into_iter()
+            .filter_map(|organisation| {
+                let membership = memberships.iter().find(|m| m.organisation_id == organisation.id)?;
+                Some(OrganisationResponse {
+                    id: organisation.id,
+                    name: organisation.name,
+                    slug: organisation.slug,
+                    role: membership.role.clone(),
+                    created_at: organisation.created_at,
+                })
+            })
+            .collect();
+
+        Ok((response, SuccessResponse::Fetched))
+    }
+
+    pub async fn list_members(
+        &self,
+        caller: &JwtClaims,
+        organisation_id: Uuid,
+    ) -> SystemResult<(Vec<OrganisationMemberResponse>, SuccessResponse)> {
+        self.require_membership(organisation_id, caller.sub).await?;
+
+        let memberships = self.membership_repo.find_by_organisation(organisation_id).await?;
+        let mut response = Vec::with_capacity(memberships.len());
+
+        for membership in memberships {
+            let email = self
+                .user_repo
+                .find_by_id(&membership.user_id)
+                .await?
+                .map(|u| u.email)
+                .unwrap_or_default();
+
+            response.push(OrganisationMemberResponse {
+                user_id: membership.user_id,
+                email,
+                role: membership.role,
+                joined_at: membership.created_at,
+            });
+        }
+
+        Ok((response, SuccessResponse::Fetched))
+    }
+
+    pub async fn update_member_role(
+        &self,
+        caller: &JwtClaims,
+        organisation_id: Uuid,
+        user_id: Uuid,
+        request: UpdateMemberRoleRequest,
+    ) -> SystemResult<SuccessResponse> {
+        Self::ensure_first_party(caller)?;
+
+        let actor = self.require_membership(organisation_id, caller.sub).await?;
+        let mut target = self.require_membership(organisation_id, user_id).await?;
+
+        // Both the new role and the current one must be the actor's to give,
+        // so admins cannot change peers or anyone above them
+        OrganisationDomainService::ensure_can_assign(&actor, &request.role)?;
+        OrganisationDomainService::ensure_can_assign(&actor, &target.role)?;
+        if target.role == OrganisationRole::Owner {
+            self.ensure_not_last_owner(organisation_id).await?;
+        }
+
+        target.change_role(request.role);
+        self.membership_repo.update(&target).await?;
+
+        Ok(SuccessResponse::Updated)
+    }
+
+    // Members may leave on their own; removing someone else needs owner/admin
+    pub async fn remove_member(
+        &self,
+        caller: &JwtClaims,
+        organisation_id: Uuid,
+        user_id: Uuid,
+    ) -> SystemResult<SuccessResponse> {
+        Self::ensure_first_party(caller)?;
+
+        let target = self.require_membership(organisation_id, user_id).await?;
+
+        if user_id != caller.sub {
+            let actor = self.require_membership(organisation_id, caller.sub).await?;
+            OrganisationDomainService::ensure_can_assign(&actor, &target.role)?;
+        }
+
+        if target.role == OrganisationRole::Owner {
+            self.ensure_not_last_owner(organisation_id).await?;
+        }
+
+        self.membership_repo.delete(target.id).await?;
+
+        Ok(SuccessResponse::Deleted)
+    }
+
+    pub async fn invite_member(
+        &self,
+        caller: &JwtClaims,
+        organisation_id: Uuid,
+        request: InviteMemberRequest,
+    ) -> SystemResult<(InvitationResponse, SuccessResponse)> {
+        Self::ensure_first_party(caller)?;
+
+        let actor = self.require_membership(organisation_id, caller.sub).await?;
+        if request.role == OrganisationRole::Owner {
+            return Err(SystemError::ValidationError(
+                "Ownership cannot be granted by invitation".to_string(),
+            ));
+        }
+        OrganisationDomainService::ensure_can_assign(&actor, &request.role)?;
+
+        let email = request.email.trim().to_lowercase();
+        if validation::email(&email).is_err() {
+            return Err(SystemError::InvalidEmail(email));
+        }
+
+        let organisation = self.require_organisation(organisation_id).await?;
+
+        if let Some(user) = self.user_repo.find_by_email(&email).await? {
+            if self.membership_repo.find(organisation_id, user.id).await?.is_some() {
+                return Err(SystemError::ValidationError(format!(
+                    "{} is already a member of {}",
+                    email, organisation.name
+                )));
+            }
+        }
+
+        let invitation_id = Uuid::new_v4();
+        let expires_at = clock::now() + Duration::days(INVITATION_TTL_DAYS);
+        let token = OrganisationDomainService::sign_invitation(
+            invitation_id,
+            organisation_id,
+            &email,
+            expires_at,
+            &self.jwt_config.issuer,
+            &self.jwt_config.secret,
+        )?;
+
+        let invitation = OrganisationInvitation::new(
+            invitation_id,
+            organisation_id,
+            email,
+            request.role,
+            TokenHelper::hash_token(&token),
+            caller.sub,
+            expires_at,
+        );
+        let event = NotificationPublisher::organisation_invitation_event(
+            organisation_id,
+            &invitation.email,
+            &organisation.name,
+            &token,
+        )?;
+        let invitation = self.invitation_repo.create_with_event(&invitation, &event).await?;
+
+        Ok((Self::to_invitation_response(invitation), SuccessResponse::Created))
+    }
+
+    pub async fn list_invitations(
+        &self,
+        caller: &JwtClaims,
+        organisation_id: Uuid,
+    ) -> SystemResult<(Vec<InvitationResponse>, SuccessResponse)> {
+        let actor = self.require_membership(organisation_id, caller.sub).await?;
+        OrganisationDomainService::ensure_can_manage(&actor)?;
+
+        let invitations = self.invitation_repo.find_pending_by_organisation(organisation_id).await?;
+        let response = invitations.into_iter().map(Self::to_invitation_response).collect();
+
+        Ok((response, SuccessResponse::Fetched))
+    }
+
+    pub async fn revoke_invitation(
+        &self,
+        caller: &JwtClaims,
+        organisation_id: Uuid,
+        invitation_id: Uuid,
+    ) -> SystemResult<SuccessResponse> {
+        Self::ensure_first_party(caller)?;
+
+        let actor = self.require_membership(organisation_id, caller.sub).await?;
+        OrganisationDomainService::ensure_can_manage(&actor)?;
+
+        let mut invitation = self
+            .invitation_repo
+            .find_by_id(invitation_id)
+            .await?
+            .filter(|i| i.organisation_id == organisation_id && i.is_pending())
+            .ok_or_else(|| SystemError::NotFound(format!("Invitation {}", invitation_id)))?;
+
+        invitation.revoke();
+        self.invitation_repo.update(&invitation).await?;
+
+        Ok(SuccessResponse::Deleted)
+    }
+
+    // The signature proves the token came from us; the stored hash makes it
+    // single use and lets admins revoke it before it expires.
+    pub async fn accept_invitation(
+        &self,
+        caller: &JwtClaims,
+        request: AcceptInvitationRequest,
+    ) -> SystemResult<(OrganisationResponse, SuccessResponse)> {
+        Self::ensure_first_party(caller)?;
+
+        let claims = OrganisationDomainService::verify_invitation(
+            &request.token,
+            &self.jwt_config.issuer,
+            &self.jwt_config.secret,
+        )?;
+
+        let mut invitation = self
+            .invitation_repo
+            .find_by_id(claims.sub)
+            .await?
+            .filter(|i| i.token_hash == TokenHelper::hash_token(&request.token))
+            .ok_or(SystemError::InvalidToken)?;
+
+        if !invitation.is_pending() {
+            return Err(SystemError::InvalidToken);
+        }
+
+        if !invitation.email.eq_ignore_ascii_case(&caller.email) {
+            return Err(SystemError::PermissionDenied(
+                "This invitation was sent to a different email address".to_string(),
+            ));
+        }
+
+        let organisation = self.require_organisation(invitation.organisation_id).await?;
+
+        let membership = match self.membership_repo.find(organisation.id, caller.sub).await? {
+            Some(existing) => existing,
+            None => {
+                self.membership_repo
+                    .create(&OrganisationMembership::new(
+                        organisation.id,
+                        caller.sub,
+                        invitation.role.clone(),
+                    ))
+                    .await?
+            }
+        };
+
+        invitation.accept();
+        self.invitation_repo.update(&invitation).await?;
+
+        let response = OrganisationResponse {
+            id: organisation.id,
+            name: organisation.name,
+            slug: organisation.slug,
+            role: membership.role,
+            created_at: organisation.created_at,
+        };
+        Ok((response, SuccessResponse::Ok))
+    }
+
+    // Issues an access token scoped to the chosen organisation. The choice is
+    // remembered so later logins and refreshes start in the same context.
+    pub async fn switch_organisation(
+        &self,
+        caller: &JwtClaims,
+        request: SwitchOrganisationRequest,
+    ) -> SystemResult<(SwitchOrganisationResponse, SuccessResponse)> {
+        Self::ensure_first_party(caller)?;
+
+        let mut membership = self.require_membership(request.organisation_id, caller.sub).await?;
+        let organisation = self.require_organisation(request.organisation_id).await?;
+        if !organisation.is_active {
+            return Err(SystemError::PermissionDenied("Organisation is inactive".to_string()));
+        }
+
+        let user = self
+            .user_repo
+            .find_by_id(&caller.sub)
+            .await?
+            .ok_or_else(|| SystemError::UserNotFound(caller.sub.to_string()))?;
+        user.can_login()?;
+
+        membership.mark_active();
+        let membership = self.membership_repo.update(&membership).await?;
+
+        let expires_in = self.jwt_config.access_token_expiry as i64;
+        let mut claims = JwtClaims::new(
+            user.id,
+            user.email,
+            user.role,
+            caller.permissions.clone(),
+            self.jwt_config.issuer.clone(),
+            self.jwt_config.audience.clone(),
+            JwtHelper::generate_jti(),
+            expires_in as usize,
+        )
+        .with_organisation(membership.organisation_id, membership.role.clone());
+        // Switching context is not a fresh authentication
+        claims.auth_time = caller.auth_time;
+        claims.amr = caller.amr.clone();
+        let access_token = JwtHelper::sign_claims(&claims, &self.jwt_config.secret)?;
+
+        let response = SwitchOrganisationResponse {
+            access_token,
+            expires_in,
+            organisation_id: membership.organisation_id,
+            role: membership.role,
+        };
+        Ok((response, SuccessResponse::Ok))
+    }
+
+    async fn require_membership(&self, organisation_id: Uuid, user_id: Uuid) -> SystemResult<OrganisationMembership> {
+        self.membership_repo
+            .find(organisation_id, user_id)
+            .await?
+            .ok_or_else(|| SystemError::NotFound(format!("Membership of organisation {}", organisation_id)))
+    }
+
+    async fn require_organisation(&self, organisation_id: Uuid) -> SystemResult<Organisation> {
+        self.organisation_repo
+            .find_by_id(organisation_id)
+            .await?
+            .ok_or_else(|| SystemError::NotFound(format!("Organisation {}", organisation_id)))
+    }
+
+    async fn ensure_not_last_owner(&self, organisation_id: Uuid) -> SystemResult<()> {
+        if self.membership_repo.count_owners(organisation_id).await? <= 1 {
+            return Err(SystemError::ValidationError(
+                "An organisation must keep at least one owner".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    fn to_invitation_response(invitation: OrganisationInvitation) -> InvitationResponse {
+        InvitationResponse {
+            id: invitation.id,
+            organisation_id: invitation.organisation_id,
+            email: invitation.email,
+            role: invitation.role,
+            expires_at: invitation.expires_at,
+        }
+    }
+
+    fn ensure_first_party(caller: &JwtClaims) -> SystemResult<()> {
+        if caller.is_delegated() {
+            return Err(SystemError::PermissionDenied(
+                "Organisations can only be managed with a user session".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
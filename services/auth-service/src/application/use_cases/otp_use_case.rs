@@ -1,31 +1,38 @@
-use crate::cache::otp_cache::OtpCacheService;
+use crate::cache::otp_cache::OtpCache;
 use crate::domain::repositories::user_repository::UserRepository;
 use crate::infrastructure::messaging::notification_publisher::NotificationPublisher;
+use crate::infrastructure::metrics::record_otp_sent;
+use crate::infrastructure::settings::SMS_OTP;
 use shared::features::errors::{SuccessResponse, SystemError, SystemResult};
 use std::sync::Arc;
 use log::{info, debug, warn, error};
 use shared::entities::dtos::auth::otp::{SendOtpRequest, VerifyOtpRequest};
 use shared::entities::enums::IdentifierType;
 use shared::features::helper::otp_helper::OtpHelper;
+use shared::features::settings::{FlagContext, RuntimeSettings};
+use shared::features::validation;
 
 pub struct OtpUseCase {
     user_repo: Arc<dyn UserRepository>,
-    otp_cache: OtpCacheService,
+    otp_cache: Arc<dyn OtpCache>,
     notification_publisher: Arc<NotificationPublisher>, // Temporarily disabled
+    settings: Arc<RuntimeSettings>,
     otp_expiry_minutes: i64,
 }
 
 impl OtpUseCase {
     pub fn new(
         user_repo: Arc<dyn UserRepository>,
-        otp_cache: OtpCacheService,
+        otp_cache: Arc<dyn OtpCache>,
         notification_publisher: Arc<NotificationPublisher>,
         otp_config: shared::config::otp_config::OtpConfig,
+        settings: Arc<RuntimeSettings>,
     ) -> Self {
         Self {
             user_repo,
             otp_cache,
             notification_publisher, // Temporarily disabled
+            settings,
             otp_expiry_minutes: otp_config.expiry_seconds as i64 / 60,
         }
     }
@@ -33,6 +40,16 @@ impl OtpUseCase {
     pub async fn send_otp(&self, request: SendOtpRequest) -> SystemResult<SuccessResponse> {
         info!("Received OTP request for identifier: {} [{:?}]", request.identifier, request.identifier_type);
 
+        // SMS can be switched off at runtime, e.g. during toll fraud
+        if matches!(request.identifier_type, IdentifierType::Phone)
+            && !self.settings.is_enabled(&SMS_OTP, FlagContext::subject(request.identifier.as_ref()))
+        {
+            warn!("SMS OTP is switched off for identifier: {}", request.identifier);
+            return Err(SystemError::ExternalServiceError(
+                "SMS verification codes are temporarily unavailable".to_string(),
+            ));
+        }
+
         // Check rate limiting
         debug!("Checking OTP rate limit for identifier: {}", request.identifier);
         if let Err(e) = self.otp_cache.check_otp_rate_limit(request.identifier.as_ref()).await {
@@ -61,7 +78,7 @@ impl OtpUseCase {
 
         // Generate OTP
         let otp_code = OtpHelper::generate_otp(6);
-        info!("Generated OTP for {}: {}", request.identifier, otp_code);
+        info!("Generated OTP for {}", request.identifier);
 
         // Store OTP in cache
         debug!("Storing OTP in cache for identifier: {}", request.identifier);
@@ -78,6 +95,10 @@ impl OtpUseCase {
             return Err(e);
         }
         info!("OTP notification sent successfully to identifier: {}", request.identifier);
+        record_otp_sent(match request.identifier_type {
+            IdentifierType::Email => "email",
+            IdentifierType::Phone => "sms",
+        });
 
         Ok(SuccessResponse::Ok)
     }
@@ -113,18 +134,14 @@ impl OtpUseCase {
         Ok(SuccessResponse::Ok)
     }
 
+    // Same rules `SendOtpRequest` is validated with at the edge
     fn validate_email_format(&self, email: &str) -> SystemResult<()> {
-        if !email.contains('@') || !email.contains('.') {
-            return Err(SystemError::InternalError("Invalid email format".to_string()));
-        }
-        Ok(())
+        validation::email(email).map_err(|_| SystemError::InvalidEmail(email.to_string()))
     }
 
+    // E.164 only; local and `+`-less numbers are rejected (see README)
     fn validate_phone_format(&self, phone: &str) -> SystemResult<()> {
-        if phone.len() < 10 || !phone.chars().all(|c| c.is_ascii_digit() || c == '+') {
-            return Err(SystemError::InternalError("Invalid phone format".to_string()));
-        }
-        Ok(())
+        validation::e164_phone(phone).map_err(|_| SystemError::InvalidPhone(phone.to_string()))
     }
 
     async fn send_otp_notification(
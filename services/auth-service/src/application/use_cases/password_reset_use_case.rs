@@ -1,19 +1,21 @@
 use crate::domain::entities::password_reset_token::PasswordResetToken;
 use crate::domain::repositories::password_reset_repository::PasswordResetRepository;
+use crate::domain::repositories::refresh_token_repository::RefreshTokenRepository;
 use crate::domain::repositories::user_repository::UserRepository;
 use crate::domain::services::auth_domain_service::AuthDomainService;
 use crate::infrastructure::messaging::notification_publisher::NotificationPublisher;
+use shared::utils::clock;
 use shared::features::errors::{SuccessResponse, SystemError, SystemResult};
 use std::sync::Arc;
 use uuid::Uuid;
 use shared::entities::dtos::auth::password::{PasswordResetConfirmRequest, PasswordResetRequest};
 use shared::features::helper::otp_helper::OtpHelper;
-use shared::features::helper::password_helper::PasswordHelper;
+use shared::features::helper::token_helper::TokenHelper;
 
 pub struct PasswordResetUseCase {
     user_repo: Arc<dyn UserRepository>,
-    password_reset_repo: Arc<dyn PasswordResetRepository>, // Temporarily disabled
-    notification_publisher: Arc<NotificationPublisher>,    // Temporarily disabled
+    password_reset_repo: Arc<dyn PasswordResetRepository>,
+    refresh_token_repo: Arc<dyn RefreshTokenRepository>,
     jwt_secret: String,
 }
 
@@ -21,13 +23,13 @@ impl PasswordResetUseCase {
     pub fn new(
         user_repo: Arc<dyn UserRepository>,
         password_reset_repo: Arc<dyn PasswordResetRepository>,
-        notification_publisher: Arc<NotificationPublisher>,
+        refresh_token_repo: Arc<dyn RefreshTokenRepository>,
         jwt_secret: String,
     ) -> Self {
         Self {
             user_repo,
             password_reset_repo,
-            notification_publisher,
+            refresh_token_repo,
             jwt_secret,
         }
     }
@@ -40,33 +42,28 @@ impl PasswordResetUseCase {
             .find_by_email(request.identifier.as_ref())
             .await
         {
-            Ok(user) => user.unwrap(),
-            Err(_) => return Err(SystemError::UserNotFound(request.identifier.clone())),
+            Ok(Some(user)) => user,
+            _ => return Err(SystemError::UserNotFound(request.identifier.clone())),
         };
         
         // Generate reset security
         let reset_token = OtpHelper::generate_reset_token();
-        let token_hash = PasswordHelper::hash_string(reset_token.as_ref())
-            .map_err(|e| SystemError::InternalError(e.to_string()))?;
+        let token_hash = TokenHelper::hash_token(reset_token.as_ref());
 
         // Store reset security
         let password_reset_token = PasswordResetToken::new(
             user.id,
             token_hash,
-            chrono::Utc::now() + chrono::Duration::hours(1), // change later,
+            clock::now() + chrono::Duration::hours(1), // change later,
         );
 
+        // Store the security and queue the reset email atomically
+        let event = NotificationPublisher::password_reset_event(user.id, &user.email, &reset_token)?;
         self.password_reset_repo
             .as_ref()
-            .create(&password_reset_token)
+            .create_with_event(&password_reset_token, &event)
             .await?;
 
-        // Send reset email
-        self.notification_publisher
-            .as_ref()
-            .send_password_reset_email(&user.email, &reset_token)
-            .await.expect("TODO: panic message");
-
         Ok(SuccessResponse::Ok)
     }
 
@@ -78,8 +75,7 @@ impl PasswordResetUseCase {
         AuthDomainService::validate_new_password(request.new_password.as_ref())?;
 
         // Hash the provided security
-        let token_hash = PasswordHelper::hash_string(request.token.as_ref())
-            .map_err(|e| SystemError::InternalError(e.to_string()))?;
+        let token_hash = TokenHelper::hash_token(request.token.as_ref());
 
         // Find and validate reset security
         let mut reset_token = self
@@ -99,7 +95,8 @@ impl PasswordResetUseCase {
             .find_by_id(&reset_token.user_id)
             .await
             {
-                Ok(user) => user.unwrap(),
+                Ok(Some(user)) => user,
+                Ok(None) => return Err(SystemError::UserNotFound(reset_token.user_id.to_string())),
                 Err(e) => return Err(SystemError::UserNotFound(e.to_string())),
             };
 
@@ -110,29 +107,20 @@ impl PasswordResetUseCase {
         user.password_hash = new_password_hash;
         user.failed_login_attempts = 0;
         user.locked_until = None;
-        user.updated_at = chrono::Utc::now();
-
-        self.user_repo.update(&user).await?;
+        user.updated_at = clock::now();
 
-        // Mark reset security as used
+        // Store the password, use up the token and queue the confirmation email together
         reset_token.mark_as_used();
-        self.password_reset_repo.update(&reset_token).await?;
+        let event = NotificationPublisher::password_changed_event(user.id, &user.email)?;
+        self.password_reset_repo.complete_with_event(&reset_token, &user, &event).await?;
 
         // Revoke all refresh tokens for this user
         self.revoke_all_user_sessions(user.id).await?;
 
-        // Send confirmation email
-        let _ = self.notification_publisher
-            .as_ref()
-            .send_password_changed_confirmation(&user.email)
-            .await;
-
         Ok(SuccessResponse::Ok)
     }
 
     async fn revoke_all_user_sessions(&self, user_id: Uuid) -> SystemResult<()> {
-        // This would typically revoke all refresh tokens
-        // Implementation depends on having refresh security repository
-        Ok(())
+        self.refresh_token_repo.revoke_all_for_user(user_id).await
     }
 }
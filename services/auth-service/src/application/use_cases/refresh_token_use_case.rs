@@ -1,41 +1,54 @@
-use crate::cache::auth_cache::AuthCacheService;
+use crate::cache::auth_cache::AuthCache;
 use crate::domain::entities::refresh_token::RefreshToken;
+use crate::domain::repositories::organisation_repository::OrganisationMembershipRepository;
 use crate::domain::repositories::refresh_token_repository::RefreshTokenRepository;
 use crate::domain::repositories::user_repository::UserRepository;
+use crate::infrastructure::metrics::record_token_refresh;
+use shared::utils::clock;
 use shared::features::errors::{SuccessResponse, SystemError, SystemResult};
 use std::sync::Arc;
 use uuid::Uuid;
 use shared::entities::dtos::auth::auth::LoginResponse;
 use shared::entities::dtos::auth::token::RefreshTokenRequest;
+use shared::config::jwt_config::JwtConfig;
 use shared::features::helper::jwt_helper::JwtHelper;
-use shared::features::helper::password_helper::PasswordHelper;
+use shared::features::helper::token_helper::TokenHelper;
+use shared::features::security::jwt::JwtClaims;
 
 pub struct RefreshTokenUseCase {
     user_repo: Arc<dyn UserRepository>,
     refresh_token_repo: Arc<dyn RefreshTokenRepository>,
-    cache_service: AuthCacheService,
-    jwt_secret: String,
+    membership_repo: Arc<dyn OrganisationMembershipRepository>,
+    cache_service: Arc<dyn AuthCache>,
+    jwt_config: JwtConfig,
 }
 
 impl RefreshTokenUseCase {
     pub fn new(
         user_repo: Arc<dyn UserRepository>,
         refresh_token_repo: Arc<dyn RefreshTokenRepository>,
-        cache_service: AuthCacheService,
-        jwt_secret: String,
+        membership_repo: Arc<dyn OrganisationMembershipRepository>,
+        cache_service: Arc<dyn AuthCache>,
+        jwt_config: JwtConfig,
     ) -> Self {
         Self {
             user_repo,
             refresh_token_repo,
+            membership_repo,
             cache_service,
-            jwt_secret,
+            jwt_config,
         }
     }
 
     pub async fn execute(&self, request: RefreshTokenRequest) -> SystemResult<(LoginResponse, SuccessResponse)> {
+        let result = self.rotate(request).await;
+        record_token_refresh("session", result.is_ok());
+        result
+    }
+
+    async fn rotate(&self, request: RefreshTokenRequest) -> SystemResult<(LoginResponse, SuccessResponse)> {
         // Hash the provided refresh security
-        let token_hash = PasswordHelper::hash_string(request.refresh_token.as_ref())
-            .map_err(|e| SystemError::InternalError(e.to_string()))?;
+        let token_hash = TokenHelper::hash_token(request.refresh_token.as_ref());
 
         // Find the refresh security
         let mut refresh_token = self
@@ -68,24 +81,25 @@ impl RefreshTokenUseCase {
         refresh_token.revoke();
         self.refresh_token_repo.update(&refresh_token).await?;
 
-        // Generate new tokens
-        let new_access_token =
-            JwtHelper::generate_access_token(
-                user.id,
-                user.email.clone(),
-                user.role.clone(),
-                vec![],
-                &self.jwt_secret,
-                1,
-                "",
-                "",
-                Uuid::new_v4(),
-            )
-                .map_err(|e| SystemError::InternalError(e.to_string()))?;
+        // Generate new tokens in the user's active organisation
+        let mut claims = JwtClaims::new(
+            user.id,
+            user.email.clone(),
+            user.role.clone(),
+            vec![],
+            self.jwt_config.issuer.clone(),
+            self.jwt_config.audience.clone(),
+            Uuid::new_v4(),
+            self.jwt_config.access_token_expiry as usize,
+        );
+        if let Some(membership) = self.membership_repo.find_default_for_user(user.id).await? {
+            claims = claims.with_organisation(membership.organisation_id, membership.role);
+        }
+        let new_access_token = JwtHelper::sign_claims(&claims, &self.jwt_config.secret)
+            .map_err(|e| SystemError::InternalError(e.to_string()))?;
 
         let new_refresh_token_value = JwtHelper::generate_secure_token();
-        let new_refresh_token_hash = PasswordHelper::hash_string(&new_refresh_token_value)
-            .map_err(|e| SystemError::InternalError(e.to_string()))?;
+        let new_refresh_token_hash = TokenHelper::hash_token(&new_refresh_token_value);
 
         let new_refresh_token = RefreshToken::new(
             user.id,
@@ -94,7 +108,7 @@ impl RefreshTokenUseCase {
             None,
             None,
             None,
-            chrono::Utc::now() + chrono::Duration::days(30)
+            clock::now() + chrono::Duration::days(30)
         );
 
         self.refresh_token_repo.as_ref().create(&new_refresh_token).await?;
@@ -107,7 +121,7 @@ impl RefreshTokenUseCase {
         let response = LoginResponse {
             access_token: new_access_token,
             refresh_token: new_refresh_token_value,
-            expires_in: 3600,
+            expires_in: self.jwt_config.access_token_expiry as i64,
         };
         Ok((response, SuccessResponse::Ok))
     }
@@ -57,7 +57,7 @@ impl SecurityQuestionUseCase {
             self.security_question_repo
                 .find_by_id(*question_id)
                 .await?
-                .ok_or_else(|| SystemError::InternalError("Invalid question ID".to_string()))?;
+                .ok_or_else(|| SystemError::ValidationError(format!("Unknown security question {}", question_id)))?;
         }
 
         // Delete existing security questions for user
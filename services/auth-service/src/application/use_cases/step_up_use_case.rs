@@ -0,0 +1,213 @@
+use crate::cache::otp_cache::OtpCache;
+use crate::domain::entities::user::User;
+use crate::domain::entities::user_totp::UserTotp;
+use crate::domain::repositories::totp_repository::TotpRepository;
+use crate::domain::repositories::user_repository::UserRepository;
+use crate::domain::services::auth_domain_service::AuthDomainService;
+use crate::infrastructure::metrics::record_lockout;
+use crate::infrastructure::settings::{LOGIN_LOCKOUT_MINUTES, LOGIN_MAX_FAILED_ATTEMPTS};
+use shared::utils::clock;
+use shared::config::jwt_config::JwtConfig;
+use shared::config::step_up_config::StepUpConfig;
+use shared::entities::dtos::auth::step_up::{
+    ConfirmTotpRequest, ReauthenticateRequest, ReauthenticateResponse, ReauthenticationMethod,
+    TotpEnrollmentResponse,
+};
+use shared::features::errors::{SuccessResponse, SystemError, SystemResult};
+use shared::features::helper::jwt_helper::JwtHelper;
+use shared::features::helper::totp_helper::TotpHelper;
+use shared::features::security::jwt::{amr, JwtClaims};
+use shared::features::settings::RuntimeSettings;
+use std::sync::Arc;
+
+const TOTP_ISSUER: &str = "Borough";
+
+pub struct StepUpUseCase {
+    user_repo: Arc<dyn UserRepository>,
+    totp_repo: Arc<dyn TotpRepository>,
+    otp_cache: Arc<dyn OtpCache>,
+    jwt_config: JwtConfig,
+    step_up_config: StepUpConfig,
+    settings: Arc<RuntimeSettings>,
+}
+
+impl StepUpUseCase {
+    pub fn new(
+        user_repo: Arc<dyn UserRepository>,
+        totp_repo: Arc<dyn TotpRepository>,
+        otp_cache: Arc<dyn OtpCache>,
+        jwt_config: JwtConfig,
+        step_up_config: StepUpConfig,
+        settings: Arc<RuntimeSettings>,
+    ) -> Self {
+        Self {
+            user_repo,
+            totp_repo,
+            otp_cache,
+            jwt_config,
+            step_up_config,
+            settings,
+        }
+    }
+
+    // Starts (or restarts) authenticator app enrollment. The factor is only
+    // used for re-authentication once a code has been confirmed.
+    pub async fn enroll_totp(&self, caller: &JwtClaims) -> SystemResult<(TotpEnrollmentResponse, SuccessResponse)> {
+        Self::ensure_first_party(caller)?;
+
+        let user = self.load_user(caller).await?;
+        let secret = TotpHelper::generate_secret();
+        self.totp_repo.upsert(&UserTotp::new(user.id, secret.clone())).await?;
+
+        let response = TotpEnrollmentResponse {
+            otpauth_url: TotpHelper::provisioning_uri(TOTP_ISSUER, &user.email, &secret),
+            secret,
+        };
+        Ok((response, SuccessResponse::Created))
+    }
+
+    pub async fn confirm_totp(&self, caller: &JwtClaims, request: ConfirmTotpRequest) -> SystemResult<SuccessResponse> {
+        Self::ensure_first_party(caller)?;
+
+        let mut totp = self
+            .totp_repo
+            .find_by_user(caller.sub)
+            .await?
+            .ok_or_else(|| SystemError::NotFound("Authenticator enrollment".to_string()))?;
+
+        Self::verify_totp(&mut totp, &request.code)?;
+        totp.confirm();
+        self.totp_repo.update(&totp).await?;
+
+        Ok(SuccessResponse::Updated)
+    }
+
+    // Proves the caller's identity again and issues a short-lived token whose
+    // `auth_time` satisfies `RecentlyAuthenticated`.
+    pub async fn reauthenticate(
+        &self,
+        caller: &JwtClaims,
+        request: ReauthenticateRequest,
+    ) -> SystemResult<(ReauthenticateResponse, SuccessResponse)> {
+        Self::ensure_first_party(caller)?;
+
+        let mut user = self.load_user(caller).await?;
+        // A locked account cannot keep guessing with another factor
+        user.can_login()?;
+
+        let method = match self.verify_factor(&user, request).await {
+            Ok(method) => method,
+            // Wrong passwords and codes share the login lockout, per user
+            Err(e @ (SystemError::InvalidCredentials | SystemError::InvalidOtp(_))) => {
+                if user.increment_failed_attempts(
+                    self.settings.get(&LOGIN_MAX_FAILED_ATTEMPTS),
+                    self.settings.get(&LOGIN_LOCKOUT_MINUTES),
+                ) {
+                    record_lockout();
+                }
+                self.user_repo.update(&user).await?;
+                return Err(e);
+            }
+            Err(e) => return Err(e),
+        };
+        if user.failed_login_attempts > 0 {
+            user.reset_failed_attempts();
+            self.user_repo.update(&user).await?;
+        }
+
+        let expires_in = self.step_up_config.max_age_seconds.min(self.jwt_config.access_token_expiry) as i64;
+        let mut claims = JwtClaims::new(
+            user.id,
+            user.email,
+            user.role,
+            caller.permissions.clone(),
+            self.jwt_config.issuer.clone(),
+            self.jwt_config.audience.clone(),
+            JwtHelper::generate_jti(),
+            expires_in as usize,
+        )
+        .with_authentication(method);
+        claims.organisation_id = caller.organisation_id;
+        claims.organisation_role = caller.organisation_role.clone();
+        let access_token = JwtHelper::sign_claims(&claims, &self.jwt_config.secret)?;
+
+        log::info!("User {} re-authenticated with {}", caller.sub, method);
+
+        let response = ReauthenticateResponse {
+            access_token,
+            expires_in,
+            auth_time: claims.auth_time.unwrap_or_default() as i64,
+        };
+        Ok((response, SuccessResponse::Ok))
+    }
+
+    async fn verify_factor(&self, user: &User, request: ReauthenticateRequest) -> SystemResult<&'static str> {
+        match request.method {
+            ReauthenticationMethod::Password => {
+                let password = request
+                    .password
+                    .ok_or_else(|| SystemError::ValidationError("password is required".to_string()))?;
+                AuthDomainService::validate_login_credentials(user, &password)?;
+                Ok(amr::PASSWORD)
+            }
+            ReauthenticationMethod::Otp => {
+                let code = Self::require_code(request.code)?;
+                let stored = self
+                    .otp_cache
+                    .get_otp(&user.email)
+                    .await?
+                    .ok_or(SystemError::OtpNotFound)?;
+                if stored != code {
+                    return Err(SystemError::InvalidOtp("Invalid verification code".to_string()));
+                }
+                self.otp_cache.invalidate_otp(&user.email).await?;
+                Ok(amr::OTP)
+            }
+            ReauthenticationMethod::Totp => {
+                let code = Self::require_code(request.code)?;
+                let mut totp = self
+                    .totp_repo
+                    .find_by_user(user.id)
+                    .await?
+                    .filter(|t| t.is_confirmed())
+                    .ok_or_else(|| SystemError::ValidationError("No authenticator app is enrolled".to_string()))?;
+                Self::verify_totp(&mut totp, &code)?;
+                self.totp_repo.update(&totp).await?;
+                Ok(amr::TOTP)
+            }
+        }
+    }
+
+    async fn load_user(&self, caller: &JwtClaims) -> SystemResult<User> {
+        self.user_repo
+            .find_by_id(&caller.sub)
+            .await?
+            .ok_or_else(|| SystemError::UserNotFound(caller.sub.to_string()))
+    }
+
+    fn verify_totp(totp: &mut UserTotp, code: &str) -> SystemResult<()> {
+        let step = TotpHelper::verify(&totp.secret, code, clock::now().timestamp())
+            .ok_or_else(|| SystemError::InvalidOtp("Invalid authenticator code".to_string()))?;
+
+        if !totp.accept_step(step) {
+            return Err(SystemError::InvalidOtp("Authenticator code has already been used".to_string()));
+        }
+        Ok(())
+    }
+
+    fn require_code(code: Option<String>) -> SystemResult<String> {
+        code.map(|c| c.trim().to_string())
+            .filter(|c| !c.is_empty())
+            .ok_or_else(|| SystemError::ValidationError("code is required".to_string()))
+    }
+
+    // Impersonators and delegated clients can never step up on the user's behalf
+    fn ensure_first_party(caller: &JwtClaims) -> SystemResult<()> {
+        if caller.is_delegated() {
+            return Err(SystemError::PermissionDenied(
+                "Re-authentication requires the user's own session".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
@@ -1,6 +1,20 @@
+use async_trait::async_trait;
 use shared::features::errors::{SystemError, SystemResult};
 use uuid::Uuid;
+use shared::features::security::auth::RevokedTokens;
 use shared::utils::caching::CacheService;
+use super::CACHE_KEYS;
+
+// Sessions and revoked tokens; `AuthCacheService` keeps them in Redis
+#[async_trait]
+pub trait AuthCache: Send + Sync {
+    async fn cache_user_session(&self, user_id: Uuid, access_token: &str) -> SystemResult<()>;
+    async fn get_user_from_token(&self, access_token: &str) -> SystemResult<Option<Uuid>>;
+    async fn invalidate_user_session(&self, user_id: Uuid) -> SystemResult<()>;
+    async fn blacklist_token(&self, token: &str, expiry_seconds: i64) -> SystemResult<()>;
+    async fn is_token_blacklisted(&self, token: &str) -> SystemResult<bool>;
+    async fn refresh_session_ttl(&self, user_id: Uuid, ttl_seconds: i64) -> SystemResult<()>;
+}
 
 #[derive(Clone)]
 pub struct AuthCacheService {
@@ -11,10 +25,13 @@ impl AuthCacheService {
     pub fn new(cache_service: CacheService) -> Self {
         Self { cache_service }
     }
+}
 
-    pub async fn cache_user_session(&self, user_id: Uuid, access_token: &str) -> SystemResult<()> {
-        let session_key = format!("session:{}", user_id);
-        let token_key = format!("security:{}", access_token);
+#[async_trait]
+impl AuthCache for AuthCacheService {
+    async fn cache_user_session(&self, user_id: Uuid, access_token: &str) -> SystemResult<()> {
+        let session_key = CACHE_KEYS.key("session", user_id);
+        let token_key = CACHE_KEYS.key("security", access_token);
 
         // Store session mapping (user_id -> access_token)
         self.cache_service
@@ -29,8 +46,8 @@ impl AuthCacheService {
         Ok(())
     }
 
-    pub async fn get_user_from_token(&self, access_token: &str) -> SystemResult<Option<Uuid>> {
-        let token_key = format!("security:{}", access_token);
+    async fn get_user_from_token(&self, access_token: &str) -> SystemResult<Option<Uuid>> {
+        let token_key = CACHE_KEYS.key("security", access_token);
 
         let user_id_str = self
             .cache_service
@@ -48,8 +65,8 @@ impl AuthCacheService {
         }
     }
 
-    pub async fn invalidate_user_session(&self, user_id: Uuid) -> SystemResult<()> {
-        let session_key = format!("session:{}", user_id);
+    async fn invalidate_user_session(&self, user_id: Uuid) -> SystemResult<()> {
+        let session_key = CACHE_KEYS.key("session", user_id);
 
         // Get the access token from the session
         let access_token = self
@@ -62,15 +79,15 @@ impl AuthCacheService {
 
         // Delete the token mapping if it exists
         if let Some(token) = access_token {
-            let token_key = format!("security:{}", token);
+            let token_key = CACHE_KEYS.key("security", token);
             self.cache_service.delete(&token_key).await?;
         }
 
         Ok(())
     }
 
-    pub async fn blacklist_token(&self, token: &str, expiry_seconds: i64) -> SystemResult<()> {
-        let blacklist_key = format!("blacklist:{}", token);
+    async fn blacklist_token(&self, token: &str, expiry_seconds: i64) -> SystemResult<()> {
+        let blacklist_key = CACHE_KEYS.key("blacklist", token);
 
         self.cache_service
             .set(&blacklist_key, "1", Some(expiry_seconds as u64))
@@ -79,14 +96,19 @@ impl AuthCacheService {
         Ok(())
     }
 
-    pub async fn is_token_blacklisted(&self, token: &str) -> SystemResult<bool> {
-        let blacklist_key = format!("blacklist:{}", token);
+    async fn is_token_blacklisted(&self, token: &str) -> SystemResult<bool> {
+        let blacklist_key = CACHE_KEYS.key("blacklist", token);
+        if self.cache_service.exists(&blacklist_key).await? {
+            return Ok(true);
+        }
 
-        self.cache_service.exists(&blacklist_key).await
+        // Tokens revoked before keys were namespaced; safe to drop once the
+        // longest-lived access token issued before then has expired
+        self.cache_service.exists(&format!("blacklist:{}", token)).await
     }
 
-    pub async fn refresh_session_ttl(&self, user_id: Uuid, ttl_seconds: i64) -> SystemResult<()> {
-        let session_key = format!("session:{}", user_id);
+    async fn refresh_session_ttl(&self, user_id: Uuid, ttl_seconds: i64) -> SystemResult<()> {
+        let session_key = CACHE_KEYS.key("session", user_id);
 
         // Check if session exists and reset expiry time
         if self.cache_service.exists(&session_key).await? {
@@ -96,3 +118,11 @@ impl AuthCacheService {
         Ok(())
     }
 }
+
+// What AuthMiddleware consults for every bearer token
+#[async_trait]
+impl RevokedTokens for AuthCacheService {
+    async fn is_revoked(&self, token: &str) -> SystemResult<bool> {
+        self.is_token_blacklisted(token).await
+    }
+}
@@ -2,5 +2,10 @@
 pub mod auth_cache;
 pub mod otp_cache;
 
-pub use auth_cache::AuthCacheService;
-pub use otp_cache::OtpCacheService;
+pub use auth_cache::{AuthCache, AuthCacheService};
+pub use otp_cache::{OtpCache, OtpCacheService};
+
+use shared::utils::caching::CacheKeys;
+
+// Bump the version when a cached value changes shape
+pub const CACHE_KEYS: CacheKeys = CacheKeys::new("auth", 1);
@@ -1,33 +1,51 @@
+use super::CACHE_KEYS;
+use crate::infrastructure::settings::OtpRateLimit;
+use async_trait::async_trait;
 use shared::features::errors::{SystemError, SystemResult};
+use shared::features::settings::RuntimeSettings;
 use shared::utils::caching::CacheService;
+use std::sync::Arc;
+
+// One-time codes and how often they are requested; `OtpCacheService` keeps
+// them in Redis
+#[async_trait]
+pub trait OtpCache: Send + Sync {
+    async fn store_otp(&self, identifier: &str, otp_code: &str, expiry_minutes: i64) -> SystemResult<()>;
+    async fn get_otp(&self, identifier: &str) -> SystemResult<Option<String>>;
+    async fn invalidate_otp(&self, identifier: &str) -> SystemResult<()>;
+    async fn check_otp_rate_limit(&self, identifier: &str) -> SystemResult<()>;
+}
 
 #[derive(Clone)]
 pub struct OtpCacheService {
     cache_service: CacheService,
-    rate_limit_window_minutes: i64,
-    max_otp_requests_per_window: i32,
+    settings: Arc<RuntimeSettings>,
+    rate_limit: OtpRateLimit,
 }
 
 impl OtpCacheService {
     pub fn new(
         cache_service: CacheService,
-        rate_limit_window_minutes: i64,
-        max_otp_requests_per_window: i32,
+        settings: Arc<RuntimeSettings>,
+        rate_limit: OtpRateLimit,
     ) -> Self {
         Self {
             cache_service,
-            rate_limit_window_minutes,
-            max_otp_requests_per_window,
+            settings,
+            rate_limit,
         }
     }
+}
 
-    pub async fn store_otp(
+#[async_trait]
+impl OtpCache for OtpCacheService {
+    async fn store_otp(
         &self,
         identifier: &str,
         otp_code: &str,
         expiry_minutes: i64,
     ) -> SystemResult<()> {
-        let otp_key = format!("otp:{}", identifier);
+        let otp_key = CACHE_KEYS.key("otp", identifier);
         let expiry_seconds = expiry_minutes * 60;
 
         self.cache_service
@@ -37,19 +55,20 @@ impl OtpCacheService {
         Ok(())
     }
 
-    pub async fn get_otp(&self, identifier: &str) -> SystemResult<Option<String>> {
-        let otp_key = format!("otp:{}", identifier);
+    async fn get_otp(&self, identifier: &str) -> SystemResult<Option<String>> {
+        let otp_key = CACHE_KEYS.key("otp", identifier);
         self.cache_service.get::<String>(&otp_key).await
     }
 
-    pub async fn invalidate_otp(&self, identifier: &str) -> SystemResult<()> {
-        let otp_key = format!("otp:{}", identifier);
+    async fn invalidate_otp(&self, identifier: &str) -> SystemResult<()> {
+        let otp_key = CACHE_KEYS.key("otp", identifier);
         self.cache_service.delete(&otp_key).await
     }
 
-    pub async fn check_otp_rate_limit(&self, identifier: &str) -> SystemResult<()> {
-        let rate_limit_key = format!("otp_rate_limit:{}", identifier);
-        let window_seconds = self.rate_limit_window_minutes * 60;
+    async fn check_otp_rate_limit(&self, identifier: &str) -> SystemResult<()> {
+        let rate_limit_key = CACHE_KEYS.key("otp_rate_limit", identifier);
+        let window_seconds = self.settings.get(&self.rate_limit.window_seconds);
+        let max_requests = self.settings.get(&self.rate_limit.max_requests) as i32;
 
         let current_count = self
             .cache_service
@@ -57,7 +76,7 @@ impl OtpCacheService {
             .await?;
 
         match current_count {
-            Some(count) if count >= self.max_otp_requests_per_window => {
+            Some(count) if count >= max_requests => {
                 Err(SystemError::OtpRateLimitExceeded)
             }
             Some(_) => {
@@ -66,7 +85,7 @@ impl OtpCacheService {
             }
             None => {
                 self.cache_service
-                    .set(&rate_limit_key, 1, Some(window_seconds as u64))
+                    .set(&rate_limit_key, 1, Some(window_seconds))
                     .await
             }
         }
@@ -1,7 +1,7 @@
 use crate::config::pipeline::service_setup::UseCases;
 use crate::interface::controllers::{
-    AuthController, OtpController, PasswordController, RefreshTokenController,
-    SecurityQuestionController,
+    ApiKeyController, AuthController, ImpersonationController, OAuthController, OrganisationController, OtpController, PasswordController, RefreshTokenController,
+    SecurityQuestionController, StepUpController,
 };
 use std::sync::Arc;
 
@@ -12,6 +12,11 @@ pub struct Controllers {
     pub password: Arc<PasswordController>,
     pub security_question: Arc<SecurityQuestionController>,
     pub refresh_token: Arc<RefreshTokenController>,
+    pub oauth: Arc<OAuthController>,
+    pub api_key: Arc<ApiKeyController>,
+    pub organisation: Arc<OrganisationController>,
+    pub impersonation: Arc<ImpersonationController>,
+    pub step_up: Arc<StepUpController>,
 }
 
 pub fn build_controllers(use_cases: UseCases) -> Controllers {
@@ -21,5 +26,10 @@ pub fn build_controllers(use_cases: UseCases) -> Controllers {
         password: Arc::new(PasswordController::new(use_cases.password_reset)),
         security_question: Arc::new(SecurityQuestionController::new(use_cases.security_question)),
         refresh_token: Arc::new(RefreshTokenController::new(use_cases.refresh_token)),
+        oauth: Arc::new(OAuthController::new(use_cases.oauth)),
+        api_key: Arc::new(ApiKeyController::new(use_cases.api_key)),
+        organisation: Arc::new(OrganisationController::new(use_cases.organisation)),
+        impersonation: Arc::new(ImpersonationController::new(use_cases.impersonation)),
+        step_up: Arc::new(StepUpController::new(use_cases.step_up)),
     }
 }
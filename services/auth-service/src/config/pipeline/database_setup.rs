@@ -1,9 +1,15 @@
+use crate::infrastructure::config::AppConfig;
+use shared::features::errors::SystemResult;
+use shared::utils::migrations::Migrations;
+use sqlx::migrate::Migrator;
 use sqlx::{Pool, Postgres};
 use std::time::Duration;
 
-pub async fn create_database_pool(
-    config: &crate::infrastructure::config::AppConfig,
-) -> Result<Pool<Postgres>, sqlx::Error> {
+static MIGRATOR: Migrator = sqlx::migrate!("./migrations");
+
+pub static MIGRATIONS: Migrations = Migrations::new("auth-service", &MIGRATOR);
+
+pub async fn create_database_pool(config: &AppConfig) -> Result<Pool<Postgres>, sqlx::Error> {
     sqlx::postgres::PgPoolOptions::new()
         .max_connections(config.database.max_connections)
         .min_connections(config.database.min_connections)
@@ -12,3 +18,11 @@ pub async fn create_database_pool(
         .connect(&config.database.url.as_ref())
         .await
 }
+
+// `auth-service migrate <up|down [version]|status>`
+pub async fn run_migrate_command(config: &AppConfig, args: &[String]) -> SystemResult<()> {
+    let pool = create_database_pool(config).await?;
+    let result = MIGRATIONS.run_command(&pool, args).await;
+    pool.close().await;
+    result
+}
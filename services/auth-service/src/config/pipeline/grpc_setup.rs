@@ -0,0 +1,32 @@
+use crate::infrastructure::config::AppConfig;
+use shared::features::health::HealthRegistry;
+use shared::features::observability::grpc::extract_trace_context;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::broadcast;
+use tonic::service::InterceptorLayer;
+use tonic::transport::Server;
+
+const HEALTH_REPORT_INTERVAL: Duration = Duration::from_secs(10);
+
+pub async fn start_grpc_server(
+    config: &AppConfig,
+    health: Arc<HealthRegistry>,
+    shutdown_tx: &broadcast::Sender<()>,
+) -> Result<(), tonic::transport::Error> {
+    let grpc_addr = format!("{}:{}", config.server.host, config.server.grpc_port)
+        .parse()
+        .expect("Invalid gRPC address");
+
+    log::info!("Starting auth gRPC server on {}", grpc_addr);
+
+    let mut shutdown_rx = shutdown_tx.subscribe();
+    Server::builder()
+        // Calls join the caller's trace; handlers open theirs with `server_span`
+        .layer(InterceptorLayer::new(extract_trace_context))
+        .add_service(health.grpc_service(HEALTH_REPORT_INTERVAL, shutdown_tx.subscribe()))
+        .serve_with_shutdown(grpc_addr, async move {
+            let _ = shutdown_rx.recv().await;
+        })
+        .await
+}
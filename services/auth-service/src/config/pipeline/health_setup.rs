@@ -0,0 +1,18 @@
+use shared::features::health::{HealthRegistry, PostgresCheck, RabbitMqCheck, RedisCheck};
+use shared::utils::messaging::MessageBroker;
+use sqlx::{Pool, Postgres};
+use std::sync::Arc;
+
+// Readiness depends on everything the auth flows touch
+pub fn build_health_registry(
+    db_pool: &Pool<Postgres>,
+    redis_client: &Arc<deadpool_redis::Pool>,
+    broker: &MessageBroker,
+) -> Arc<HealthRegistry> {
+    Arc::new(
+        HealthRegistry::new("auth-service")
+            .with_check(PostgresCheck::new(db_pool.clone()))
+            .with_check(RedisCheck::new(redis_client.clone()))
+            .with_check(RabbitMqCheck::new(broker.clone())),
+    )
+}
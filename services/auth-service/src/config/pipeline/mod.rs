@@ -3,35 +3,116 @@ use crate::config::routing;
 use crate::infrastructure::config::AppConfig;
 use crate::interface::middleware::rate_limiter::RateLimiter;
 use crate::interface::middleware::request_logger::RequestLogger;
+use actix_web::body::MessageBody;
+use actix_web::dev::{ServiceFactory, ServiceRequest, ServiceResponse};
 use actix_web::{middleware, web, App, HttpServer};
+use shared::features::health::HealthRegistry;
+use shared::features::idempotency::{Idempotency, IdempotencyStore};
+use shared::features::metrics::MetricsMiddleware;
+use shared::features::observability::TracingMiddleware;
+use shared::features::problem::problem_details;
+use shared::features::security::auth::{ApiKeyValidator, AuthMiddleware, ImpersonationAuditor, RevokedTokens};
+use shared::features::settings::RuntimeSettings;
+use shared::utils::messaging::MessageBroker;
+use std::sync::Arc;
 use std::time::Duration;
 
 pub mod controller_setup;
 pub mod database_setup;
 pub mod env_setup;
+pub mod grpc_setup;
+pub mod health_setup;
 pub mod redis_setup;
 pub mod service_setup;
+pub mod settings_setup;
 pub mod queue_setup;
 
-pub async fn start_http_server(config: AppConfig, controllers: Controllers) -> std::io::Result<()> {
-    let server_config = config.server.clone();
+// Impersonation sessions may look around but never touch credentials; payment
+// routes are blocked by `AuthMiddleware` in every service
+const IMPERSONATION_BLOCKED_PATHS: &[&str] = &[
+    "/api/v1/auth/password-reset",
+    "/api/v1/auth/security-question",
+    "/api/v1/auth/mfa",
+    "/api/v1/auth/impersonation",
+];
+
+// Routes whose responses carry tokens or secrets; `Idempotency` would keep
+// them in Redis in clear, so retries there simply run again
+const IDEMPOTENCY_EXCLUDED_PATHS: &[&str] = &[
+    "/api/v1/auth/login",
+    "/api/v1/auth/refresh",
+    "/api/v1/auth/reauthenticate",
+    "/api/v1/auth/impersonation",
+    "/api/v1/auth/otp",
+    "/api/v1/auth/mfa",
+    "/api/v1/auth/oauth",
+    "/api/v1/auth/api-keys",
+    "/api/v1/auth/organisations/switch",
+];
+
+// Everything the HTTP app is built from
+#[derive(Clone)]
+pub struct HttpState {
+    pub config: AppConfig,
+    pub controllers: Controllers,
+    pub api_key_validator: Arc<dyn ApiKeyValidator>,
+    pub revoked_tokens: Arc<dyn RevokedTokens>,
+    pub impersonation_auditor: Arc<dyn ImpersonationAuditor>,
+    pub broker: MessageBroker,
+    pub health: Arc<HealthRegistry>,
+    pub settings: Arc<RuntimeSettings>,
+    pub idempotency_store: Arc<dyn IdempotencyStore>,
+}
+
+// One worker's app; also what the integration tests run through `actix_web::test`
+pub fn build_app(
+    state: HttpState,
+) -> App<
+    impl ServiceFactory<
+        ServiceRequest,
+        Config = (),
+        Response = ServiceResponse<impl MessageBody>,
+        Error = actix_web::Error,
+        InitError = (),
+    >,
+> {
+    App::new()
+        // Inside AuthMiddleware, so keys are scoped to the caller
+        .wrap(Idempotency::new(state.idempotency_store.clone()).except_for(IDEMPOTENCY_EXCLUDED_PATHS))
+        .wrap(
+            AuthMiddleware::new(state.config.jwt.clone())
+                .with_api_key_validator(state.api_key_validator.clone())
+                .with_revoked_tokens(state.revoked_tokens.clone())
+                .with_impersonation_auditor(state.impersonation_auditor.clone())
+                .with_trusted_proxies(state.config.server.trusted_proxies())
+                .block_impersonation_for(IMPERSONATION_BLOCKED_PATHS),
+        )
+        .wrap(middleware::Logger::default())
+        .wrap(RequestLogger)
+        .wrap(RateLimiter::new(state.settings.clone()))
+        .wrap(MetricsMiddleware)
+        .wrap(TracingMiddleware)
+        .app_data(web::Data::new(state.controllers.clone()))
+        .app_data(web::Data::new(state.broker.clone()))
+        .app_data(web::Data::from(state.health.clone()))
+        .app_data(web::Data::from(state.settings.clone()))
+        .app_data(state.config.step_up.clone())
+        .configure(problem_details)
+        .configure(routing::configure_services)
+}
+
+pub async fn start_http_server(state: HttpState) -> std::io::Result<()> {
+    let server_config = state.config.server.clone();
     let bind_address = format!("{}:{}", server_config.host, server_config.port);
 
     log::info!("Starting auth service on {}", bind_address);
 
-    HttpServer::new(move || {
-        App::new()
-            .wrap(middleware::Logger::default())
-            .wrap(RequestLogger)
-            .wrap(RateLimiter::new(100, Duration::from_secs(60)))
-            .app_data(web::Data::new(controllers.clone()))
-            .configure(routing::configure_services)
-    })
-    .workers(server_config.workers)
-    .keep_alive(Duration::from_secs(server_config.keep_alive))
-    .client_request_timeout(Duration::new(server_config.client_timeout, 0))
-    .client_disconnect_timeout(Duration::new(server_config.client_shutdown, 0))
-    .bind(&bind_address)?
-    .run()
-    .await
+    HttpServer::new(move || build_app(state.clone()))
+        .workers(server_config.workers)
+        .keep_alive(Duration::from_secs(server_config.keep_alive))
+        .client_request_timeout(Duration::new(server_config.client_timeout, 0))
+        .client_disconnect_timeout(Duration::new(server_config.client_shutdown, 0))
+        .bind(&bind_address)?
+        .run()
+        .await
 }
@@ -1,35 +1,87 @@
+use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::broadcast;
-use shared::events::{ExchangeType, RoutingKey};
-use shared::features::errors::SystemResult;
+use shared::utils::caching::CacheService;
+use shared::utils::messaging::inbox::RedisEventLedger;
+use shared::features::errors::{SystemError, SystemResult};
 use shared::utils::messaging::MessageBroker;
 use crate::infrastructure::config::AppConfig;
 use crate::infrastructure::messaging::notification_consumer::NotificationConsumer;
 use crate::infrastructure::messaging::notification_publisher::NotificationPublisher;
 
-pub async fn setup_messaging(config: &AppConfig) -> SystemResult<(MessageBroker, NotificationPublisher, broadcast::Sender<()>)> {
+pub const NOTIFICATION_QUEUE: &str = "auth_notification_queue";
+const DEFAULT_DLQ_LIMIT: usize = 50;
+// A handler holds its claim on an event for at most 5 minutes; processed
+// event ids are remembered for a day, well past the longest retry backoff
+const EVENT_PROCESSING_TTL_SECONDS: u64 = 300;
+const PROCESSED_EVENT_TTL_SECONDS: u64 = 86_400;
+const QUEUE_METRICS_INTERVAL: Duration = Duration::from_secs(15);
+
+pub async fn setup_messaging(
+    config: &AppConfig,
+    redis_client: Arc<deadpool_redis::Pool>,
+) -> SystemResult<(MessageBroker, NotificationPublisher, broadcast::Sender<()>)> {
     let broker = MessageBroker::new(&config.messaging).await.expect("Wahala Wahala");
-    let publisher = NotificationPublisher::new(broker.clone());
-    let consumer = NotificationConsumer::new(broker.clone());
-
-    // Setup queues
-    let topic_queue = "auth_notification_queue";
-    let routing_keys = [
-        RoutingKey::EmailOtp,
-        RoutingKey::SmsOtp,
-        RoutingKey::EmailPasswordReset,
-        RoutingKey::EmailPasswordChanged,
-        RoutingKey::Broadcast,
-    ];
-    publisher.setup(topic_queue, &routing_keys, ExchangeType::Topic).await?;
-
-    // Setup consumer queue
-    consumer.setup(topic_queue, &routing_keys, ExchangeType::Topic).await?;
-
-    // Spawn consumer task
-    let (shutdown_tx, shutdown_rx) = broadcast::channel(1);
-    tokio::spawn(async move {
-        consumer.consume(topic_queue, shutdown_rx).await.expect("Consumer failed");
-    });
+    let publisher = NotificationPublisher::new(Arc::new(broker.clone()));
+    let ledger = RedisEventLedger::new(
+        CacheService::new(redis_client, config.redis_figure_config.clone()),
+        EVENT_PROCESSING_TTL_SECONDS,
+        PROCESSED_EVENT_TTL_SECONDS,
+    );
+    let consumer = NotificationConsumer::new(broker.clone(), NOTIFICATION_QUEUE, Arc::new(ledger));
+
+    // Setup consumer queues
+    consumer.setup().await?;
+
+    // Spawn a consumer task per event type
+    let (shutdown_tx, _) = broadcast::channel(1);
+    consumer.spawn(&shutdown_tx);
+    broker.spawn_queue_metrics(QUEUE_METRICS_INTERVAL, shutdown_tx.subscribe());
 
     Ok((broker, publisher, shutdown_tx))
-}
\ No newline at end of file
+}
+
+// Admin command for the notification dead-letter queues:
+//   auth-service dlq list [limit]    prints dead letters as JSON lines, leaving them queued
+//   auth-service dlq replay [limit]  moves dead letters back onto their queues
+// The limit applies per queue.
+pub async fn run_dlq_command(config: &AppConfig, args: &[String]) -> SystemResult<()> {
+    let limit = match args.get(1) {
+        Some(limit) => limit
+            .parse()
+            .map_err(|_| SystemError::ValidationError(format!("Invalid limit: {}", limit)))?,
+        None => DEFAULT_DLQ_LIMIT,
+    };
+
+    let broker = MessageBroker::new(&config.messaging).await?;
+    let queues = NotificationConsumer::queue_names(NOTIFICATION_QUEUE);
+    let result = match args.first().map(String::as_str) {
+        Some("list") => list_dead_letters(&broker, &queues, limit).await,
+        Some("replay") => replay_dead_letters(&broker, &queues, limit).await,
+        _ => Err(SystemError::ValidationError(
+            "usage: auth-service dlq <list|replay> [limit]".to_string(),
+        )),
+    };
+
+    broker.close().await?;
+    result
+}
+
+async fn list_dead_letters(broker: &MessageBroker, queues: &[String], limit: usize) -> SystemResult<()> {
+    for queue in queues {
+        for dead_letter in broker.inspect_dead_letters(queue, limit).await? {
+            println!("{}", serde_json::to_string(&dead_letter).unwrap_or_default());
+        }
+    }
+    Ok(())
+}
+
+async fn replay_dead_letters(broker: &MessageBroker, queues: &[String], limit: usize) -> SystemResult<()> {
+    for queue in queues {
+        let replayed = broker.replay_dead_letters(queue, limit).await?;
+        if replayed > 0 {
+            println!("Replayed {} dead letters onto {}", replayed, queue);
+        }
+    }
+    Ok(())
+}
@@ -1,4 +1,9 @@
+use crate::cache::auth_cache::AuthCacheService;
+use crate::cache::CACHE_KEYS;
 use crate::infrastructure::config::AppConfig;
+use shared::features::idempotency::{IdempotencyStore, RedisIdempotencyStore};
+use shared::features::security::auth::RevokedTokens;
+use shared::utils::caching::CacheService;
 use std::sync::Arc;
 use deadpool_redis::{Config, Pool, Runtime};
 
@@ -8,4 +13,15 @@ pub fn create_redis_client(config: &AppConfig) -> Result<Arc<Pool>, deadpool_red
         .create_pool(Some(Runtime::Tokio1))
         .map(Arc::new)?;
     Ok(pool)
-}
\ No newline at end of file
+}
+// Responses kept for `Idempotency-Key` retries
+pub fn build_idempotency_store(config: &AppConfig, redis_client: Arc<Pool>) -> Arc<dyn IdempotencyStore> {
+    let cache = CacheService::new(redis_client, config.redis_figure_config.clone());
+    Arc::new(RedisIdempotencyStore::new(cache, CACHE_KEYS))
+}
+
+// The blacklist OAuth revocation writes to, checked on every bearer request
+pub fn build_revoked_tokens(config: &AppConfig, redis_client: Arc<Pool>) -> Arc<dyn RevokedTokens> {
+    let cache = CacheService::new(redis_client, config.redis_figure_config.clone());
+    Arc::new(AuthCacheService::new(cache))
+}
@@ -1,17 +1,30 @@
-use crate::cache::{AuthCacheService, OtpCacheService};
+use crate::cache::{AuthCache, AuthCacheService, OtpCache, OtpCacheService};
 use crate::infrastructure::config::AppConfig;
+use crate::infrastructure::database::api_key_repository_impl::PostgresApiKeyRepository;
+use crate::infrastructure::database::audit_log_repository_impl::PostgresAuditLogRepository;
 use crate::infrastructure::database::login_attempt_repository_impl::PostgresLoginAttemptRepository;
+use crate::infrastructure::database::oauth_repository_impl::{
+    PostgresOAuthAuthorizationCodeRepository, PostgresOAuthClientRepository,
+    PostgresOAuthConsentRepository, PostgresOAuthTokenRepository,
+};
+use crate::infrastructure::database::organisation_repository_impl::{
+    PostgresOrganisationInvitationRepository, PostgresOrganisationMembershipRepository,
+    PostgresOrganisationRepository,
+};
 use crate::infrastructure::database::password_reset_repository_impl::PostgresPasswordResetRepository;
 use crate::infrastructure::database::refresh_token_repository_impl::PostgresRefreshTokenRepository;
+use crate::infrastructure::database::totp_repository_impl::PostgresTotpRepository;
 use crate::infrastructure::database::security_question_repository_impl::{
     PostgresSecurityQuestionRepository, PostgresUserSecurityQuestionRepository,
 };
 use crate::infrastructure::database::user_repository_impl::PostgresUserRepository;
 use crate::infrastructure::messaging::notification_publisher::NotificationPublisher;
+use crate::infrastructure::settings::OtpRateLimit;
+use shared::features::settings::RuntimeSettings;
 use sqlx::{Pool, Postgres};
 use std::sync::Arc;
 use shared::utils::caching::CacheService;
-use crate::application::use_cases::{LoginUseCase, OtpUseCase, PasswordResetUseCase, RefreshTokenUseCase, SecurityQuestionUseCase};
+use crate::application::use_cases::{ApiKeyUseCase, ImpersonationUseCase, LoginUseCase, OAuthUseCase, OrganisationUseCase, OtpUseCase, PasswordResetUseCase, RefreshTokenUseCase, SecurityQuestionUseCase, StepUpUseCase};
 
 pub struct UseCases {
     pub login: Arc<LoginUseCase>,
@@ -19,6 +32,11 @@ pub struct UseCases {
     pub password_reset: Arc<PasswordResetUseCase>,
     pub security_question: Arc<SecurityQuestionUseCase>,
     pub refresh_token: Arc<RefreshTokenUseCase>,
+    pub oauth: Arc<OAuthUseCase>,
+    pub api_key: Arc<ApiKeyUseCase>,
+    pub organisation: Arc<OrganisationUseCase>,
+    pub impersonation: Arc<ImpersonationUseCase>,
+    pub step_up: Arc<StepUpUseCase>,
 }
 
 pub fn build_use_cases(
@@ -26,6 +44,7 @@ pub fn build_use_cases(
     db_pool: &Pool<Postgres>,
     redis_client: Arc<deadpool_redis::Pool>,
     notification_publisher: &Arc<NotificationPublisher>,
+    settings: &Arc<RuntimeSettings>,
 ) -> UseCases {
     let user_repo = Arc::new(PostgresUserRepository::new(db_pool.clone()));
     let refresh_token_repo = Arc::new(PostgresRefreshTokenRepository::new(db_pool.clone()));
@@ -34,36 +53,47 @@ pub fn build_use_cases(
         Arc::new(PostgresUserSecurityQuestionRepository::new(db_pool.clone()));
     let login_attempt_repo = Arc::new(PostgresLoginAttemptRepository::new(db_pool.clone()));
     let password_reset_repo = Arc::new(PostgresPasswordResetRepository::new(db_pool.clone()));
+    let oauth_client_repo = Arc::new(PostgresOAuthClientRepository::new(db_pool.clone()));
+    let oauth_code_repo = Arc::new(PostgresOAuthAuthorizationCodeRepository::new(db_pool.clone()));
+    let oauth_token_repo = Arc::new(PostgresOAuthTokenRepository::new(db_pool.clone()));
+    let oauth_consent_repo = Arc::new(PostgresOAuthConsentRepository::new(db_pool.clone()));
+    let api_key_repo = Arc::new(PostgresApiKeyRepository::new(db_pool.clone()));
+    let organisation_repo = Arc::new(PostgresOrganisationRepository::new(db_pool.clone()));
+    let membership_repo = Arc::new(PostgresOrganisationMembershipRepository::new(db_pool.clone()));
+    let invitation_repo = Arc::new(PostgresOrganisationInvitationRepository::new(db_pool.clone()));
+    let audit_log_repo = Arc::new(PostgresAuditLogRepository::new(db_pool.clone()));
+    let totp_repo = Arc::new(PostgresTotpRepository::new(db_pool.clone()));
 
     let cache_service = CacheService::new(redis_client.clone(), config.redis_figure_config.clone());
     
-    let auth_cache_service = AuthCacheService::new(cache_service.clone());
-    let otp_cache_service = OtpCacheService::new(
+    let auth_cache_service: Arc<dyn AuthCache> = Arc::new(AuthCacheService::new(cache_service.clone()));
+    let otp_cache_service: Arc<dyn OtpCache> = Arc::new(OtpCacheService::new(
         cache_service.clone(),
-        (config.otp.rate_limit_window / 60) as i64,
-        config.otp.max_requests_per_window as i32,
-    );
+        settings.clone(),
+        OtpRateLimit::from_config(&config.otp),
+    ));
 
     UseCases {
         login: Arc::new(LoginUseCase::new(
             user_repo.clone(),
             refresh_token_repo.clone(),
             login_attempt_repo.clone(),
+            membership_repo.clone(),
             auth_cache_service.clone(),
-            config.jwt.secret.clone(),
-            5,
-            30,
+            config.jwt.clone(),
+            settings.clone(),
         )),
         otp: Arc::new(OtpUseCase::new(
             user_repo.clone(),
             otp_cache_service.clone(),
             notification_publisher.clone(),
             config.otp.clone(),
+            settings.clone(),
         )),
         password_reset: Arc::new(PasswordResetUseCase::new(
             user_repo.clone(),
             password_reset_repo.clone(),
-            notification_publisher.clone(),
+            refresh_token_repo.clone(),
             config.jwt.secret.clone(),
         )),
         security_question: Arc::new(SecurityQuestionUseCase::new(
@@ -73,8 +103,45 @@ pub fn build_use_cases(
         refresh_token: Arc::new(RefreshTokenUseCase::new(
             user_repo.clone(),
             refresh_token_repo.clone(),
+            membership_repo.clone(),
             auth_cache_service.clone(),
-            config.jwt.secret.clone(),
+            config.jwt.clone(),
+        )),
+        oauth: Arc::new(OAuthUseCase::new(
+            oauth_client_repo.clone(),
+            oauth_code_repo.clone(),
+            oauth_token_repo.clone(),
+            oauth_consent_repo.clone(),
+            user_repo.clone(),
+            auth_cache_service.clone(),
+            config.jwt.clone(),
+        )),
+        api_key: Arc::new(ApiKeyUseCase::new(
+            api_key_repo.clone(),
+            user_repo.clone(),
+            membership_repo.clone(),
+            config.jwt.clone(),
+        )),
+        organisation: Arc::new(OrganisationUseCase::new(
+            organisation_repo.clone(),
+            membership_repo.clone(),
+            invitation_repo.clone(),
+            user_repo.clone(),
+            config.jwt.clone(),
+        )),
+        impersonation: Arc::new(ImpersonationUseCase::new(
+            user_repo.clone(),
+            membership_repo.clone(),
+            audit_log_repo.clone(),
+            config.jwt.clone(),
+        )),
+        step_up: Arc::new(StepUpUseCase::new(
+            user_repo.clone(),
+            totp_repo.clone(),
+            otp_cache_service.clone(),
+            config.jwt.clone(),
+            config.step_up.clone(),
+            settings.clone(),
         )),
     }
 }
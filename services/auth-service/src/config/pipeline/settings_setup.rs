@@ -0,0 +1,36 @@
+use crate::infrastructure::config::AppConfig;
+use crate::infrastructure::settings::{
+    OtpRateLimit, HTTP_RATE_LIMIT_MAX_REQUESTS, HTTP_RATE_LIMIT_WINDOW_SECONDS, LOGIN_LOCKOUT_MINUTES,
+    LOGIN_MAX_FAILED_ATTEMPTS, SMS_OTP,
+};
+use shared::features::errors::SystemResult;
+use shared::features::settings::RuntimeSettings;
+use sqlx::{Pool, Postgres};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::broadcast;
+
+// Picks up changes missed while the NOTIFY listener was reconnecting
+const SETTINGS_RESYNC_INTERVAL: Duration = Duration::from_secs(60);
+
+pub async fn build_runtime_settings(
+    config: &AppConfig,
+    db_pool: &Pool<Postgres>,
+    shutdown_tx: &broadcast::Sender<()>,
+) -> SystemResult<Arc<RuntimeSettings>> {
+    let otp_rate_limit = OtpRateLimit::from_config(&config.otp);
+    let settings = Arc::new(
+        RuntimeSettings::new(db_pool.clone())
+            .with_setting(&LOGIN_MAX_FAILED_ATTEMPTS)
+            .with_setting(&LOGIN_LOCKOUT_MINUTES)
+            .with_setting(&HTTP_RATE_LIMIT_MAX_REQUESTS)
+            .with_setting(&HTTP_RATE_LIMIT_WINDOW_SECONDS)
+            .with_setting(&otp_rate_limit.window_seconds)
+            .with_setting(&otp_rate_limit.max_requests)
+            .with_flag(&SMS_OTP),
+    );
+
+    settings.refresh().await?;
+    settings.spawn_listener(SETTINGS_RESYNC_INTERVAL, shutdown_tx.subscribe());
+    Ok(settings)
+}
@@ -1,13 +1,26 @@
 use crate::interface::routes::auth_routes;
 use actix_web::web;
 use actix_web::web::ServiceConfig;
+use shared::features::health::health_routes;
+use shared::features::metrics::metrics_routes;
+use shared::features::settings::routes::settings_routes;
 
 pub fn configure_services(cfg: &mut ServiceConfig) {
+    cfg.service(health_routes());
+    cfg.service(metrics_routes());
+    cfg.service(settings_routes());
     cfg.service(
         web::scope("/api/v1/auth")
             .service(auth_routes::login)
             .service(auth_routes::logout)
             .service(auth_routes::refresh_token)
+            .service(auth_routes::start_impersonation)
+            .service(auth_routes::reauthenticate)
+            .service(
+                web::scope("/mfa/totp")
+                    .service(auth_routes::enroll_totp)
+                    .service(auth_routes::confirm_totp)
+            )
             .service(
                 web::scope("/password-reset")
                     .service(auth_routes::request_password_reset)
@@ -25,5 +38,36 @@ pub fn configure_services(cfg: &mut ServiceConfig) {
                     .service(auth_routes::get_questions)
                     .service(auth_routes::verify_security_answers)
             )
+            .service(
+                web::scope("/oauth")
+                    .service(auth_routes::register_oauth_client)
+                    .service(auth_routes::list_oauth_clients)
+                    .service(auth_routes::deactivate_oauth_client)
+                    .service(auth_routes::oauth_authorize)
+                    .service(auth_routes::oauth_token)
+                    .service(auth_routes::oauth_introspect)
+                    .service(auth_routes::oauth_revoke)
+                    .service(auth_routes::list_oauth_consents)
+                    .service(auth_routes::revoke_oauth_consent)
+            )
+            .service(
+                web::scope("/api-keys")
+                    .service(auth_routes::create_api_key)
+                    .service(auth_routes::list_api_keys)
+                    .service(auth_routes::revoke_api_key)
+            )
+            .service(
+                web::scope("/organisations")
+                    .service(auth_routes::create_organisation)
+                    .service(auth_routes::list_organisations)
+                    .service(auth_routes::switch_organisation)
+                    .service(auth_routes::accept_organisation_invitation)
+                    .service(auth_routes::list_organisation_members)
+                    .service(auth_routes::update_organisation_member_role)
+                    .service(auth_routes::remove_organisation_member)
+                    .service(auth_routes::invite_organisation_member)
+                    .service(auth_routes::list_organisation_invitations)
+                    .service(auth_routes::revoke_organisation_invitation)
+            )
     );
 }
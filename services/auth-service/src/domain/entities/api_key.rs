@@ -0,0 +1,74 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+use shared::utils::clock;
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ApiKey {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub organisation_id: Option<Uuid>,
+    pub name: String,
+    pub prefix: String,
+    pub secret_hash: String,
+    pub permissions: Vec<String>,
+    pub allowed_ips: Vec<String>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub last_used_ip: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub revoked_at: Option<DateTime<Utc>>,
+}
+
+impl ApiKey {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        user_id: Uuid,
+        organisation_id: Option<Uuid>,
+        name: String,
+        prefix: String,
+        secret_hash: String,
+        permissions: Vec<String>,
+        allowed_ips: Vec<String>,
+        expires_at: Option<DateTime<Utc>>,
+    ) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            user_id,
+            organisation_id,
+            name,
+            prefix,
+            secret_hash,
+            permissions,
+            allowed_ips,
+            expires_at,
+            last_used_at: None,
+            last_used_ip: None,
+            created_at: clock::now(),
+            revoked_at: None,
+        }
+    }
+
+    pub fn is_valid(&self) -> bool {
+        if self.revoked_at.is_some() {
+            return false;
+        }
+
+        match self.expires_at {
+            Some(expiry) => clock::now() < expiry,
+            None => true, // Non-expiring key
+        }
+    }
+
+    // Avoid a write on every request; minute granularity is enough for auditing
+    pub fn needs_usage_update(&self, ip_address: Option<&str>) -> bool {
+        match self.last_used_at {
+            Some(last_used) => {
+                clock::now() - last_used > Duration::minutes(1)
+                    || self.last_used_ip.as_deref() != ip_address
+            }
+            None => true,
+        }
+    }
+}
@@ -3,6 +3,7 @@ use serde::{Deserialize, Serialize};
 use serde_json::Value as JsonValue;
 use uuid::Uuid;
 use std::collections::HashMap;
+use shared::utils::clock;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AuditLog {
@@ -37,7 +38,7 @@ impl AuditLog {
             ip_address: None,
             user_agent: None,
             metadata: None,
-            created_at: Utc::now(),
+            created_at: clock::now(),
         }
     }
 
@@ -60,7 +61,7 @@ impl AuditLog {
             ip_address: None,
             user_agent: None,
             metadata: None,
-            created_at: Utc::now(),
+            created_at: clock::now(),
         }
     }
 
@@ -121,6 +122,10 @@ pub mod audit_actions {
     pub const REFRESH_TOKEN_USED: &str = "refresh_token_used";
     pub const SESSION_CREATED: &str = "session_created";
     pub const SESSION_ENDED: &str = "session_ended";
+
+    // Impersonation
+    pub const IMPERSONATION_STARTED: &str = "impersonation_started";
+    pub const IMPERSONATED_REQUEST: &str = "impersonated_request";
 }
 
 // Common resource types
@@ -1,6 +1,7 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
+use shared::utils::clock;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BlacklistedToken {
@@ -78,12 +79,12 @@ impl BlacklistedToken {
             user_id,
             expires_at,
             reason: reason.map(|r| r.to_string()),
-            created_at: Utc::now(),
+            created_at: clock::now(),
         }
     }
 
     pub fn is_expired(&self) -> bool {
-        Utc::now() >= self.expires_at
+        clock::now() >= self.expires_at
     }
 
     pub fn get_reason(&self) -> Option<&str> {
@@ -1,6 +1,7 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
+use shared::utils::clock;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LoginAttempt {
@@ -34,7 +35,7 @@ impl LoginAttempt {
             failure_reason,
             country,
             city,
-            created_at: Utc::now(),
+            created_at: clock::now(),
         }
     }
 }
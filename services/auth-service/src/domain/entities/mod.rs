@@ -7,4 +7,13 @@ pub mod user_security_question;
 pub mod user_session;
 pub mod blacklisted_token;
 pub mod user_permission;
-mod audit_log;
+pub mod audit_log;
+pub mod oauth_client;
+pub mod oauth_authorization_code;
+pub mod oauth_token;
+pub mod oauth_consent;
+pub mod api_key;
+pub mod organisation;
+pub mod organisation_membership;
+pub mod organisation_invitation;
+pub mod user_totp;
@@ -0,0 +1,56 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+use shared::utils::clock;
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct OAuthAuthorizationCode {
+    pub id: Uuid,
+    pub code_hash: String,
+    pub client_id: Uuid,
+    pub user_id: Uuid,
+    pub redirect_uri: String,
+    pub scopes: Vec<String>,
+    pub code_challenge: Option<String>,
+    pub code_challenge_method: Option<String>,
+    pub expires_at: DateTime<Utc>,
+    pub is_used: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+impl OAuthAuthorizationCode {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        code_hash: String,
+        client_id: Uuid,
+        user_id: Uuid,
+        redirect_uri: String,
+        scopes: Vec<String>,
+        code_challenge: Option<String>,
+        code_challenge_method: Option<String>,
+        expires_at: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            code_hash,
+            client_id,
+            user_id,
+            redirect_uri,
+            scopes,
+            code_challenge,
+            code_challenge_method,
+            expires_at,
+            is_used: false,
+            created_at: clock::now(),
+        }
+    }
+
+    pub fn is_valid(&self) -> bool {
+        !self.is_used && clock::now() < self.expires_at
+    }
+
+    pub fn mark_as_used(&mut self) {
+        self.is_used = true;
+    }
+}
@@ -0,0 +1,84 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+use shared::utils::clock;
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct OAuthClient {
+    pub id: Uuid,
+    pub owner_user_id: Uuid,
+    pub name: String,
+    pub client_secret_hash: Option<String>,
+    pub redirect_uris: Vec<String>,
+    pub grant_types: Vec<String>,
+    pub scopes: Vec<String>,
+    pub is_confidential: bool,
+    pub is_active: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl OAuthClient {
+    pub fn new(
+        owner_user_id: Uuid,
+        name: String,
+        client_secret_hash: Option<String>,
+        redirect_uris: Vec<String>,
+        grant_types: Vec<String>,
+        scopes: Vec<String>,
+    ) -> Self {
+        let now = clock::now();
+        Self {
+            id: Uuid::new_v4(),
+            owner_user_id,
+            name,
+            is_confidential: client_secret_hash.is_some(),
+            client_secret_hash,
+            redirect_uris,
+            grant_types,
+            scopes,
+            is_active: true,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    pub fn allows_grant_type(&self, grant_type: &str) -> bool {
+        self.grant_types.iter().any(|g| g == grant_type)
+    }
+
+    pub fn allows_redirect_uri(&self, redirect_uri: &str) -> bool {
+        // Exact match only, as recommended by the OAuth 2.0 security BCP
+        self.redirect_uris.iter().any(|uri| uri == redirect_uri)
+    }
+
+    pub fn deactivate(&mut self) {
+        self.is_active = false;
+        self.updated_at = clock::now();
+    }
+}
+
+pub mod grant_types {
+    pub const AUTHORIZATION_CODE: &str = "authorization_code";
+    pub const CLIENT_CREDENTIALS: &str = "client_credentials";
+    pub const REFRESH_TOKEN: &str = "refresh_token";
+
+    pub const ALL: &[&str] = &[AUTHORIZATION_CODE, CLIENT_CREDENTIALS, REFRESH_TOKEN];
+}
+
+// OAuth scopes are the `permissions` constants themselves, so a token issued to a
+// partner carries exactly the permissions it was granted. Admin and user-management
+// permissions are never delegable.
+pub mod oauth_scopes {
+    use crate::domain::entities::user_permission::permissions;
+
+    pub const GRANTABLE: &[&str] = &[
+        permissions::READ_PROPERTIES,
+        permissions::WRITE_PROPERTIES,
+        permissions::READ_BOOKINGS,
+        permissions::WRITE_BOOKINGS,
+        permissions::CANCEL_BOOKINGS,
+        permissions::READ_TRANSACTIONS,
+    ];
+}
@@ -0,0 +1,40 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+use shared::utils::clock;
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct OAuthConsent {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub client_id: Uuid,
+    pub scopes: Vec<String>,
+    pub granted_at: DateTime<Utc>,
+    pub revoked_at: Option<DateTime<Utc>>,
+}
+
+impl OAuthConsent {
+    pub fn new(user_id: Uuid, client_id: Uuid, scopes: Vec<String>) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            user_id,
+            client_id,
+            scopes,
+            granted_at: clock::now(),
+            revoked_at: None,
+        }
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.revoked_at.is_none()
+    }
+
+    pub fn covers(&self, scopes: &[String]) -> bool {
+        self.is_active() && scopes.iter().all(|s| self.scopes.contains(s))
+    }
+
+    pub fn revoke(&mut self) {
+        self.revoked_at = Some(clock::now());
+    }
+}
@@ -0,0 +1,53 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use crate::domain::entities::blacklisted_token::TokenType;
+use shared::utils::clock;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OAuthToken {
+    pub id: Uuid,
+    pub token_hash: String,
+    pub token_type: TokenType,
+    pub client_id: Uuid,
+    pub user_id: Uuid,
+    pub scopes: Vec<String>,
+    pub expires_at: DateTime<Utc>,
+    pub is_revoked: bool,
+    pub created_at: DateTime<Utc>,
+    pub revoked_at: Option<DateTime<Utc>>,
+}
+
+impl OAuthToken {
+    pub fn new(
+        id: Uuid,
+        token_hash: String,
+        token_type: TokenType,
+        client_id: Uuid,
+        user_id: Uuid,
+        scopes: Vec<String>,
+        expires_at: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            id,
+            token_hash,
+            token_type,
+            client_id,
+            user_id,
+            scopes,
+            expires_at,
+            is_revoked: false,
+            created_at: clock::now(),
+            revoked_at: None,
+        }
+    }
+
+    pub fn is_active(&self) -> bool {
+        !self.is_revoked && clock::now() < self.expires_at
+    }
+
+    pub fn revoke(&mut self) {
+        self.is_revoked = true;
+        self.revoked_at = Some(clock::now());
+    }
+}
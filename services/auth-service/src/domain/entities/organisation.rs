@@ -0,0 +1,31 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+use shared::utils::clock;
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Organisation {
+    pub id: Uuid,
+    pub name: String,
+    pub slug: String,
+    pub created_by: Option<Uuid>,
+    pub is_active: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Organisation {
+    pub fn new(name: String, slug: String, created_by: Uuid) -> Self {
+        let now = clock::now();
+        Self {
+            id: Uuid::new_v4(),
+            name,
+            slug,
+            created_by: Some(created_by),
+            is_active: true,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+}
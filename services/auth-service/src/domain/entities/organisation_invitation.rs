@@ -0,0 +1,56 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use shared::utils::clock;
+use shared::entities::enums::OrganisationRole;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrganisationInvitation {
+    pub id: Uuid,
+    pub organisation_id: Uuid,
+    pub email: String,
+    pub role: OrganisationRole,
+    pub token_hash: String,
+    pub invited_by: Option<Uuid>,
+    pub expires_at: DateTime<Utc>,
+    pub accepted_at: Option<DateTime<Utc>>,
+    pub revoked_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl OrganisationInvitation {
+    pub fn new(
+        id: Uuid,
+        organisation_id: Uuid,
+        email: String,
+        role: OrganisationRole,
+        token_hash: String,
+        invited_by: Uuid,
+        expires_at: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            id,
+            organisation_id,
+            email,
+            role,
+            token_hash,
+            invited_by: Some(invited_by),
+            expires_at,
+            accepted_at: None,
+            revoked_at: None,
+            created_at: clock::now(),
+        }
+    }
+
+    pub fn is_pending(&self) -> bool {
+        self.accepted_at.is_none() && self.revoked_at.is_none() && clock::now() < self.expires_at
+    }
+
+    pub fn accept(&mut self) {
+        self.accepted_at = Some(clock::now());
+    }
+
+    pub fn revoke(&mut self) {
+        self.revoked_at = Some(clock::now());
+    }
+}
@@ -0,0 +1,44 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use shared::utils::clock;
+use shared::entities::enums::OrganisationRole;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrganisationMembership {
+    pub id: Uuid,
+    pub organisation_id: Uuid,
+    pub user_id: Uuid,
+    pub role: OrganisationRole,
+    pub last_active_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl OrganisationMembership {
+    pub fn new(organisation_id: Uuid, user_id: Uuid, role: OrganisationRole) -> Self {
+        let now = clock::now();
+        Self {
+            id: Uuid::new_v4(),
+            organisation_id,
+            user_id,
+            role,
+            last_active_at: None,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    pub fn can_manage_members(&self) -> bool {
+        matches!(self.role, OrganisationRole::Owner | OrganisationRole::Admin)
+    }
+
+    pub fn change_role(&mut self, role: OrganisationRole) {
+        self.role = role;
+        self.updated_at = clock::now();
+    }
+
+    pub fn mark_active(&mut self) {
+        self.last_active_at = Some(clock::now());
+    }
+}
@@ -2,6 +2,7 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 use uuid::Uuid;
+use shared::utils::clock;
 
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct PasswordResetToken {
@@ -22,13 +23,13 @@ impl PasswordResetToken {
             token_hash,
             expires_at,
             is_used: false,
-            created_at: Utc::now(),
+            created_at: clock::now(),
             used_at: None
         }
     }
 
     pub fn is_valid(&self) -> bool {
-        !self.is_used && Utc::now() < self.expires_at
+        !self.is_used && clock::now() < self.expires_at
     }
 
     pub fn mark_as_used(&mut self) {
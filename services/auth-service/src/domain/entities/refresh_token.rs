@@ -1,16 +1,18 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
 use uuid::Uuid;
+use shared::utils::clock;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct RefreshToken {
     pub id: Uuid,
     pub user_id: Uuid,
     pub token_hash: String,
-    device_id: Option<String>,
-    device_name: Option<String>,
-    ip_address: Option<String>,
-    user_agent: Option<String>,
+    pub device_id: Option<String>,
+    pub device_name: Option<String>,
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
     pub expires_at: DateTime<Utc>,
     pub is_revoked: bool,
     pub created_at: DateTime<Utc>,
@@ -37,16 +39,17 @@ impl RefreshToken {
             user_agent,
             expires_at,
             is_revoked: false,
-            created_at: Utc::now(),
+            created_at: clock::now(),
             revoked_at: None,
         }
     }
 
     pub fn is_valid(&self) -> bool {
-        !self.is_revoked && Utc::now() < self.expires_at
+        !self.is_revoked && clock::now() < self.expires_at
     }
 
     pub fn revoke(&mut self) {
         self.is_revoked = true;
+        self.revoked_at = Some(clock::now());
     }
 }
@@ -2,6 +2,7 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 use uuid::Uuid;
+use shared::utils::clock;
 
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct SecurityQuestion {
@@ -20,18 +21,18 @@ impl SecurityQuestion {
             created_by,
             question,
             is_active: true,
-            created_at: Utc::now(),
-            updated_at: Utc::now(),
+            created_at: clock::now(),
+            updated_at: clock::now(),
         }
     }
 
     pub fn deactivate(&mut self) {
         self.is_active = false;
-        self.updated_at = Utc::now();
+        self.updated_at = clock::now();
     }
 
     pub fn update_question(&mut self, new_question: String) {
         self.question = new_question;
-        self.updated_at = Utc::now();
+        self.updated_at = clock::now();
     }
 }
\ No newline at end of file
@@ -1,5 +1,6 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use shared::utils::clock;
 use shared::features::errors::SystemError;
 use shared::features::errors::SystemResult;
 use uuid::Uuid;
@@ -23,7 +24,7 @@ pub struct User {
 
 impl User {
     pub fn new(email: String, password_hash: String, role: UserRole) -> Self {
-        let now = Utc::now();
+        let now = clock::now();
         Self {
             id: Uuid::new_v4(),
             email,
@@ -42,7 +43,7 @@ impl User {
 
     pub fn is_locked(&self) -> bool {
         if let Some(locked_until) = self.locked_until {
-            Utc::now() < locked_until
+            clock::now() < locked_until
         } else {
             false
         }
@@ -64,26 +65,29 @@ impl User {
         Ok(())
     }
 
-    pub fn increment_failed_attempts(&mut self, max_attempts: i32, lockout_duration_minutes: i64) {
+    // Returns whether this attempt locked an unlocked account
+    pub fn increment_failed_attempts(&mut self, max_attempts: i32, lockout_duration_minutes: i64) -> bool {
+        let was_locked = self.is_locked();
         self.failed_login_attempts += 1;
 
         if self.failed_login_attempts >= max_attempts {
             self.locked_until =
-                Some(Utc::now() + chrono::Duration::minutes(lockout_duration_minutes));
+                Some(clock::now() + chrono::Duration::minutes(lockout_duration_minutes));
         }
 
-        self.updated_at = Utc::now();
+        self.updated_at = clock::now();
+        !was_locked && self.is_locked()
     }
 
     pub fn reset_failed_attempts(&mut self) {
         self.failed_login_attempts = 0;
         self.locked_until = None;
-        self.last_login_at = Some(Utc::now());
-        self.updated_at = Utc::now();
+        self.last_login_at = Some(clock::now());
+        self.updated_at = clock::now();
     }
 
     pub fn verify_account(&mut self) {
         self.is_verified = true;
-        self.updated_at = Utc::now();
+        self.updated_at = clock::now();
     }
 }
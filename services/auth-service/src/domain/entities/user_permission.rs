@@ -1,6 +1,7 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
+use shared::utils::clock;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UserPermission {
@@ -24,21 +25,21 @@ impl UserPermission {
             user_id,
             permission,
             granted_by,
-            granted_at: Utc::now(),
+            granted_at: clock::now(),
             expires_at,
         }
     }
 
     pub fn is_valid(&self) -> bool {
         match self.expires_at {
-            Some(expiry) => Utc::now() < expiry,
+            Some(expiry) => clock::now() < expiry,
             None => true, // Permanent permission
         }
     }
 
     pub fn is_expired(&self) -> bool {
         match self.expires_at {
-            Some(expiry) => Utc::now() >= expiry,
+            Some(expiry) => clock::now() >= expiry,
             None => false,
         }
     }
@@ -50,6 +51,8 @@ impl UserPermission {
 
 // Common permission constants
 pub mod permissions {
+    use shared::entities::enums::UserRole;
+
     // Property permissions
     pub const READ_PROPERTIES: &str = "read:properties";
     pub const WRITE_PROPERTIES: &str = "write:properties";
@@ -78,6 +81,75 @@ pub mod permissions {
     pub const ADMIN_ACCESS: &str = "admin:access";
     pub const SYSTEM_ADMIN: &str = "system:admin";
     pub const AUDIT_LOGS: &str = "read:audit_logs";
+
+    pub const ALL: &[&str] = &[
+        READ_PROPERTIES,
+        WRITE_PROPERTIES,
+        DELETE_PROPERTIES,
+        MANAGE_PROPERTIES,
+        READ_BOOKINGS,
+        WRITE_BOOKINGS,
+        CANCEL_BOOKINGS,
+        MANAGE_BOOKINGS,
+        READ_TRANSACTIONS,
+        PROCESS_PAYMENTS,
+        REFUND_PAYMENTS,
+        MANAGE_TRANSACTIONS,
+        READ_USERS,
+        WRITE_USERS,
+        DELETE_USERS,
+        MANAGE_USERS,
+        ADMIN_ACCESS,
+        SYSTEM_ADMIN,
+        AUDIT_LOGS,
+    ];
+
+    // Permissions only administrators may hand out to credentials they create
+    pub const ADMIN_ONLY: &[&str] = &[
+        READ_USERS,
+        WRITE_USERS,
+        DELETE_USERS,
+        MANAGE_USERS,
+        ADMIN_ACCESS,
+        SYSTEM_ADMIN,
+        AUDIT_LOGS,
+    ];
+
+    // What each platform role may do; credentials a user issues can carry no more
+    pub fn for_role(role: &UserRole) -> &'static [&'static str] {
+        match role {
+            UserRole::SuperAdmin | UserRole::Admin => ALL,
+            UserRole::PropertyManager => &[
+                READ_PROPERTIES,
+                WRITE_PROPERTIES,
+                DELETE_PROPERTIES,
+                MANAGE_PROPERTIES,
+                READ_BOOKINGS,
+                WRITE_BOOKINGS,
+                CANCEL_BOOKINGS,
+                MANAGE_BOOKINGS,
+                READ_TRANSACTIONS,
+            ],
+            UserRole::Landlord => &[
+                READ_PROPERTIES,
+                WRITE_PROPERTIES,
+                DELETE_PROPERTIES,
+                READ_BOOKINGS,
+                MANAGE_BOOKINGS,
+                READ_TRANSACTIONS,
+            ],
+            UserRole::Tenant => &[
+                READ_PROPERTIES,
+                READ_BOOKINGS,
+                WRITE_BOOKINGS,
+                CANCEL_BOOKINGS,
+                READ_TRANSACTIONS,
+                PROCESS_PAYMENTS,
+            ],
+            UserRole::Maintenance => &[READ_PROPERTIES, READ_BOOKINGS],
+            UserRole::Guest => &[READ_PROPERTIES],
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
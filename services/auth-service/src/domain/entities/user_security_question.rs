@@ -2,6 +2,7 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 use uuid::Uuid;
+use shared::utils::clock;
 
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct UserSecurityQuestion {
@@ -15,7 +16,7 @@ pub struct UserSecurityQuestion {
 
 impl UserSecurityQuestion {
     pub fn new(user_id: Uuid, question_id: Uuid, answer_hash: String) -> Self {
-        let now = Utc::now();
+        let now = clock::now();
         Self {
             id: Uuid::new_v4(),
             user_id,
@@ -1,6 +1,7 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
+use shared::utils::clock;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UserSession {
@@ -27,7 +28,7 @@ impl UserSession {
         ip_address: Option<String>,
         user_agent: Option<String>,
     ) -> Self {
-        let now = Utc::now();
+        let now = clock::now();
         Self {
             id: Uuid::new_v4(),
             user_id,
@@ -44,24 +45,24 @@ impl UserSession {
     }
 
     pub fn is_valid(&self) -> bool {
-        self.is_active && Utc::now() < self.expires_at
+        self.is_active && clock::now() < self.expires_at
     }
 
     pub fn is_expired(&self) -> bool {
-        Utc::now() >= self.expires_at
+        clock::now() >= self.expires_at
     }
 
     pub fn update_activity(&mut self) {
-        self.last_activity_at = Utc::now();
+        self.last_activity_at = clock::now();
     }
 
     pub fn deactivate(&mut self) {
         self.is_active = false;
-        self.last_activity_at = Utc::now();
+        self.last_activity_at = clock::now();
     }
 
     pub fn extend_expiry(&mut self, new_expires_at: DateTime<Utc>) {
         self.expires_at = new_expires_at;
-        self.last_activity_at = Utc::now();
+        self.last_activity_at = clock::now();
     }
 }
\ No newline at end of file
@@ -0,0 +1,48 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+use shared::utils::clock;
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct UserTotp {
+    pub user_id: Uuid,
+    pub secret: String,
+    pub confirmed_at: Option<DateTime<Utc>>,
+    pub last_used_step: Option<i64>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl UserTotp {
+    pub fn new(user_id: Uuid, secret: String) -> Self {
+        let now = clock::now();
+        Self {
+            user_id,
+            secret,
+            confirmed_at: None,
+            last_used_step: None,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    pub fn is_confirmed(&self) -> bool {
+        self.confirmed_at.is_some()
+    }
+
+    // A code is single use: anything at or before the last accepted step is a replay
+    pub fn accept_step(&mut self, step: i64) -> bool {
+        if self.last_used_step.is_some_and(|last| step <= last) {
+            return false;
+        }
+        self.last_used_step = Some(step);
+        self.updated_at = clock::now();
+        true
+    }
+
+    pub fn confirm(&mut self) {
+        self.confirmed_at = Some(clock::now());
+        self.updated_at = clock::now();
+    }
+}
@@ -0,0 +1,20 @@
+use crate::domain::entities::api_key::ApiKey;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use shared::features::errors::SystemResult;
+use uuid::Uuid;
+
+#[async_trait]
+pub trait ApiKeyRepository: Send + Sync {
+    async fn create(&self, api_key: &ApiKey) -> SystemResult<ApiKey>;
+    async fn find_by_id(&self, id: Uuid) -> SystemResult<Option<ApiKey>>;
+    async fn find_by_prefix(&self, prefix: &str) -> SystemResult<Option<ApiKey>>;
+    async fn find_by_user(&self, user_id: Uuid) -> SystemResult<Vec<ApiKey>>;
+    async fn revoke(&self, id: Uuid) -> SystemResult<()>;
+    async fn record_usage(
+        &self,
+        id: Uuid,
+        used_at: DateTime<Utc>,
+        ip_address: Option<&str>,
+    ) -> SystemResult<()>;
+}
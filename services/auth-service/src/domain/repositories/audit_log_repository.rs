@@ -0,0 +1,8 @@
+use crate::domain::entities::audit_log::AuditLog;
+use async_trait::async_trait;
+use shared::features::errors::SystemResult;
+
+#[async_trait]
+pub trait AuditLogRepository: Send + Sync {
+    async fn create(&self, audit_log: &AuditLog) -> SystemResult<()>;
+}
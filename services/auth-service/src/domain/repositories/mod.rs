@@ -3,3 +3,8 @@ pub mod password_reset_repository;
 pub mod refresh_token_repository;
 pub mod security_repository;
 pub mod user_repository;
+pub mod oauth_repository;
+pub mod api_key_repository;
+pub mod organisation_repository;
+pub mod audit_log_repository;
+pub mod totp_repository;
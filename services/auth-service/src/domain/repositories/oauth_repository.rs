@@ -0,0 +1,49 @@
+use crate::domain::entities::oauth_authorization_code::OAuthAuthorizationCode;
+use crate::domain::entities::oauth_client::OAuthClient;
+use crate::domain::entities::oauth_consent::OAuthConsent;
+use crate::domain::entities::oauth_token::OAuthToken;
+use async_trait::async_trait;
+use shared::features::errors::SystemResult;
+use uuid::Uuid;
+
+#[async_trait]
+pub trait OAuthClientRepository: Send + Sync {
+    async fn create(&self, client: &OAuthClient) -> SystemResult<OAuthClient>;
+    async fn find_by_id(&self, id: Uuid) -> SystemResult<Option<OAuthClient>>;
+    async fn find_by_owner(&self, owner_user_id: Uuid) -> SystemResult<Vec<OAuthClient>>;
+    async fn update(&self, client: &OAuthClient) -> SystemResult<OAuthClient>;
+}
+
+#[async_trait]
+pub trait OAuthAuthorizationCodeRepository: Send + Sync {
+    async fn create(&self, code: &OAuthAuthorizationCode) -> SystemResult<OAuthAuthorizationCode>;
+    async fn find_by_code_hash(&self, code_hash: &str) -> SystemResult<Option<OAuthAuthorizationCode>>;
+    // Marks the code used and returns it, or None if it already was; of two
+    // concurrent exchanges of one code only the first gets it back
+    async fn consume(&self, id: Uuid) -> SystemResult<Option<OAuthAuthorizationCode>>;
+    async fn cleanup_expired(&self) -> SystemResult<u64>;
+}
+
+#[async_trait]
+pub trait OAuthTokenRepository: Send + Sync {
+    async fn create(&self, token: &OAuthToken) -> SystemResult<OAuthToken>;
+    async fn find_by_token_hash(&self, token_hash: &str) -> SystemResult<Option<OAuthToken>>;
+    async fn update(&self, token: &OAuthToken) -> SystemResult<OAuthToken>;
+    // Revokes the token and returns it, or None if it was already revoked;
+    // spends a refresh token exactly once
+    async fn consume(&self, id: Uuid) -> SystemResult<Option<OAuthToken>>;
+    async fn revoke_for_user_and_client(&self, user_id: Uuid, client_id: Uuid) -> SystemResult<u64>;
+    async fn cleanup_expired(&self) -> SystemResult<u64>;
+}
+
+#[async_trait]
+pub trait OAuthConsentRepository: Send + Sync {
+    async fn upsert(&self, consent: &OAuthConsent) -> SystemResult<OAuthConsent>;
+    async fn find_by_user_and_client(
+        &self,
+        user_id: Uuid,
+        client_id: Uuid,
+    ) -> SystemResult<Option<OAuthConsent>>;
+    async fn find_active_by_user(&self, user_id: Uuid) -> SystemResult<Vec<OAuthConsent>>;
+    async fn update(&self, consent: &OAuthConsent) -> SystemResult<OAuthConsent>;
+}
@@ -0,0 +1,46 @@
+use crate::domain::entities::organisation::Organisation;
+use crate::domain::entities::organisation_invitation::OrganisationInvitation;
+use crate::domain::entities::organisation_membership::OrganisationMembership;
+use async_trait::async_trait;
+use shared::features::errors::SystemResult;
+use shared::utils::messaging::outbox::OutboxEvent;
+use uuid::Uuid;
+
+#[async_trait]
+pub trait OrganisationRepository: Send + Sync {
+    // Creates the organisation and its first owner in one transaction
+    async fn create_with_owner(
+        &self,
+        organisation: &Organisation,
+        owner: &OrganisationMembership,
+    ) -> SystemResult<Organisation>;
+    async fn find_by_id(&self, id: Uuid) -> SystemResult<Option<Organisation>>;
+    async fn exists_by_slug(&self, slug: &str) -> SystemResult<bool>;
+    async fn find_by_member(&self, user_id: Uuid) -> SystemResult<Vec<Organisation>>;
+}
+
+#[async_trait]
+pub trait OrganisationMembershipRepository: Send + Sync {
+    async fn create(&self, membership: &OrganisationMembership) -> SystemResult<OrganisationMembership>;
+    async fn find(&self, organisation_id: Uuid, user_id: Uuid) -> SystemResult<Option<OrganisationMembership>>;
+    async fn find_by_user(&self, user_id: Uuid) -> SystemResult<Vec<OrganisationMembership>>;
+    async fn find_by_organisation(&self, organisation_id: Uuid) -> SystemResult<Vec<OrganisationMembership>>;
+    // Most recently used membership, falling back to the oldest one
+    async fn find_default_for_user(&self, user_id: Uuid) -> SystemResult<Option<OrganisationMembership>>;
+    async fn update(&self, membership: &OrganisationMembership) -> SystemResult<OrganisationMembership>;
+    async fn delete(&self, id: Uuid) -> SystemResult<()>;
+    async fn count_owners(&self, organisation_id: Uuid) -> SystemResult<i64>;
+}
+
+#[async_trait]
+pub trait OrganisationInvitationRepository: Send + Sync {
+    // Persists the invitation and queues the invitation email in one transaction
+    async fn create_with_event(
+        &self,
+        invitation: &OrganisationInvitation,
+        event: &OutboxEvent,
+    ) -> SystemResult<OrganisationInvitation>;
+    async fn find_by_id(&self, id: Uuid) -> SystemResult<Option<OrganisationInvitation>>;
+    async fn find_pending_by_organisation(&self, organisation_id: Uuid) -> SystemResult<Vec<OrganisationInvitation>>;
+    async fn update(&self, invitation: &OrganisationInvitation) -> SystemResult<OrganisationInvitation>;
+}
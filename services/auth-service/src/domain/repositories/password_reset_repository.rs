@@ -1,13 +1,23 @@
 use crate::domain::entities::password_reset_token::PasswordResetToken;
+use crate::domain::entities::user::User;
 use async_trait::async_trait;
 use shared::features::errors::SystemResult;
+use shared::utils::messaging::outbox::OutboxEvent;
 use uuid::Uuid;
 
 #[async_trait]
 pub trait PasswordResetRepository: Send + Sync {
-    async fn create(&self, token: &PasswordResetToken) -> SystemResult<PasswordResetToken>;
+    // Persists the token and queues its notification in one transaction
+    async fn create_with_event(&self, token: &PasswordResetToken, event: &OutboxEvent) -> SystemResult<PasswordResetToken>;
     async fn find_by_token_hash(&self, token_hash: &str) -> SystemResult<Option<PasswordResetToken>>;
-    async fn update(&self, token: &PasswordResetToken) -> SystemResult<PasswordResetToken>;
+    // Stores the user's new password, marks the token used and queues the
+    // confirmation in one transaction, so a failure leaves the link usable
+    async fn complete_with_event(
+        &self,
+        token: &PasswordResetToken,
+        user: &User,
+        event: &OutboxEvent,
+    ) -> SystemResult<PasswordResetToken>;
     async fn cleanup_expired(&self) -> SystemResult<u64>;
     async fn revoke_all_for_user(&self, user_id: Uuid) -> SystemResult<()>;
 }
@@ -0,0 +1,12 @@
+use crate::domain::entities::user_totp::UserTotp;
+use async_trait::async_trait;
+use shared::features::errors::SystemResult;
+use uuid::Uuid;
+
+#[async_trait]
+pub trait TotpRepository: Send + Sync {
+    // Replaces any existing factor for the user
+    async fn upsert(&self, totp: &UserTotp) -> SystemResult<UserTotp>;
+    async fn find_by_user(&self, user_id: Uuid) -> SystemResult<Option<UserTotp>>;
+    async fn update(&self, totp: &UserTotp) -> SystemResult<UserTotp>;
+}
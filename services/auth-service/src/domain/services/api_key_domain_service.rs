@@ -0,0 +1,150 @@
+use crate::domain::entities::api_key::ApiKey;
+use crate::domain::entities::user_permission::permissions;
+use shared::entities::enums::UserRole;
+use shared::features::errors::{SystemError, SystemResult};
+use shared::features::helper::jwt_helper::JwtHelper;
+use shared::features::helper::token_helper::TokenHelper;
+use std::net::IpAddr;
+
+// Keys look like `bk_<prefix>_<secret>`. The prefix is stored in clear for
+// lookup and display; only a SHA-256 digest of the secret is persisted.
+const KEY_MARKER: &str = "bk";
+const PREFIX_LENGTH: usize = 12;
+
+pub struct GeneratedApiKey {
+    pub key: String,
+    pub prefix: String,
+    pub secret_hash: String,
+}
+
+pub struct ApiKeyDomainService;
+
+impl ApiKeyDomainService {
+    pub fn generate_key() -> GeneratedApiKey {
+        let prefix = JwtHelper::generate_secure_token()[..PREFIX_LENGTH].to_string();
+        let secret = JwtHelper::generate_secure_token();
+
+        GeneratedApiKey {
+            key: format!("{}_{}_{}", KEY_MARKER, prefix, secret),
+            secret_hash: TokenHelper::hash_token(&secret),
+            prefix,
+        }
+    }
+
+    // Returns (prefix, secret) for a well-formed key
+    pub fn parse_key(key: &str) -> Option<(&str, &str)> {
+        let rest = key.strip_prefix(KEY_MARKER)?.strip_prefix('_')?;
+        let (prefix, secret) = rest.split_once('_')?;
+
+        if prefix.len() != PREFIX_LENGTH || secret.is_empty() {
+            return None;
+        }
+
+        Some((prefix, secret))
+    }
+
+    pub fn verify_secret(api_key: &ApiKey, secret: &str) -> bool {
+        TokenHelper::verify_token(secret, &api_key.secret_hash)
+    }
+
+    pub fn validate_permissions(role: &UserRole, requested: &[String]) -> SystemResult<()> {
+        if requested.is_empty() {
+            return Err(SystemError::ValidationError(
+                "An API key needs at least one permission".to_string(),
+            ));
+        }
+
+        let granted = permissions::for_role(role);
+
+        for permission in requested {
+            if !permissions::ALL.contains(&permission.as_str()) {
+                return Err(SystemError::ValidationError(format!(
+                    "Unknown permission: {}",
+                    permission
+                )));
+            }
+
+            if !granted.contains(&permission.as_str()) {
+                let reason = if permissions::ADMIN_ONLY.contains(&permission.as_str()) {
+                    format!("Only administrators can issue keys with {}", permission)
+                } else {
+                    format!("The {} role cannot issue keys with {}", role, permission)
+                };
+                return Err(SystemError::PermissionDenied(reason));
+            }
+        }
+
+        Ok(())
+    }
+
+    // The owner's role is checked again on every use: a key keeps no
+    // permission its owner has lost since it was issued
+    pub fn effective_permissions(role: &UserRole, permissions: Vec<String>) -> Vec<String> {
+        let granted = permissions::for_role(role);
+        permissions
+            .into_iter()
+            .filter(|permission| granted.contains(&permission.as_str()))
+            .collect()
+    }
+
+    pub fn validate_allowed_ips(allowed_ips: &[String]) -> SystemResult<()> {
+        for entry in allowed_ips {
+            if parse_network(entry).is_none() {
+                return Err(SystemError::ValidationError(format!(
+                    "Invalid IP address or CIDR range: {}",
+                    entry
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    // An empty allowlist means the key can be used from anywhere
+    pub fn is_ip_allowed(api_key: &ApiKey, ip_address: Option<&str>) -> bool {
+        if api_key.allowed_ips.is_empty() {
+            return true;
+        }
+
+        let ip = match ip_address.and_then(|ip| ip.parse::<IpAddr>().ok()) {
+            Some(ip) => ip,
+            None => return false,
+        };
+
+        api_key
+            .allowed_ips
+            .iter()
+            .filter_map(|entry| parse_network(entry))
+            .any(|(network, prefix_len)| network_contains(network, prefix_len, ip))
+    }
+}
+
+fn parse_network(entry: &str) -> Option<(IpAddr, u8)> {
+    let (address, prefix_len) = match entry.split_once('/') {
+        Some((address, prefix_len)) => (address, Some(prefix_len.parse::<u8>().ok()?)),
+        None => (entry, None),
+    };
+
+    let address = address.trim().parse::<IpAddr>().ok()?;
+    let max_len = if address.is_ipv4() { 32 } else { 128 };
+    let prefix_len = prefix_len.unwrap_or(max_len);
+
+    if prefix_len > max_len {
+        return None;
+    }
+
+    Some((address, prefix_len))
+}
+
+fn network_contains(network: IpAddr, prefix_len: u8, ip: IpAddr) -> bool {
+    match (network, ip) {
+        (IpAddr::V4(network), IpAddr::V4(ip)) => {
+            let mask = u32::MAX.checked_shl(32 - prefix_len as u32).unwrap_or(0);
+            u32::from(network) & mask == u32::from(ip) & mask
+        }
+        (IpAddr::V6(network), IpAddr::V6(ip)) => {
+            let mask = u128::MAX.checked_shl(128 - prefix_len as u32).unwrap_or(0);
+            u128::from(network) & mask == u128::from(ip) & mask
+        }
+        _ => false,
+    }
+}
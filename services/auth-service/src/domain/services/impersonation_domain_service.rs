@@ -0,0 +1,65 @@
+use crate::domain::entities::user::User;
+use shared::entities::enums::UserRole;
+use shared::features::errors::{SystemError, SystemResult};
+use shared::features::security::jwt::JwtClaims;
+use std::net::{IpAddr, SocketAddr};
+
+// Impersonation tokens are deliberately short-lived and never refreshed
+pub const IMPERSONATION_TOKEN_TTL_SECONDS: i64 = 15 * 60;
+
+pub struct ImpersonationDomainService;
+
+impl ImpersonationDomainService {
+    pub fn ensure_can_impersonate(actor: &JwtClaims, target: &User) -> SystemResult<()> {
+        if actor.is_delegated() {
+            return Err(SystemError::PermissionDenied(
+                "Impersonation requires an administrator's own session".to_string(),
+            ));
+        }
+
+        if !Self::is_staff(&actor.role) {
+            return Err(SystemError::PermissionDenied(
+                "Only administrators can impersonate users".to_string(),
+            ));
+        }
+
+        if target.id == actor.sub {
+            return Err(SystemError::ValidationError("You cannot impersonate yourself".to_string()));
+        }
+
+        if Self::is_staff(&target.role) {
+            return Err(SystemError::PermissionDenied(
+                "Administrators cannot be impersonated".to_string(),
+            ));
+        }
+
+        if !target.is_active {
+            return Err(SystemError::AccountInactive);
+        }
+
+        Ok(())
+    }
+
+    pub fn validate_reason(reason: &str) -> SystemResult<()> {
+        if reason.trim().len() < 10 {
+            return Err(SystemError::ValidationError(
+                "A reason of at least 10 characters is required to impersonate a user".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    // `realip_remote_addr` may carry a port; audit_logs.ip_address is INET
+    pub fn normalise_ip(ip_address: Option<&str>) -> Option<String> {
+        let ip = ip_address?;
+        ip.parse::<SocketAddr>()
+            .map(|addr| addr.ip())
+            .or_else(|_| ip.parse::<IpAddr>())
+            .map(|addr| addr.to_string())
+            .ok()
+    }
+
+    fn is_staff(role: &UserRole) -> bool {
+        matches!(role, UserRole::Admin | UserRole::SuperAdmin)
+    }
+}
@@ -1,2 +1,6 @@
 pub mod auth_domain_service;
 pub mod security_domain_service;
+pub mod oauth_domain_service;
+pub mod api_key_domain_service;
+pub mod organisation_domain_service;
+pub mod impersonation_domain_service;
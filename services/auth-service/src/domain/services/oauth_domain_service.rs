@@ -0,0 +1,178 @@
+use crate::domain::entities::oauth_authorization_code::OAuthAuthorizationCode;
+use crate::domain::entities::oauth_client::{grant_types, oauth_scopes, OAuthClient};
+use crate::domain::entities::user_permission::permissions;
+use shared::entities::enums::UserRole;
+use shared::features::errors::{SystemError, SystemResult};
+use shared::features::helper::password_helper::PasswordHelper;
+use shared::features::helper::token_helper::TokenHelper;
+
+pub struct OAuthDomainService;
+
+impl OAuthDomainService {
+    pub fn parse_scopes(scope: Option<&str>) -> Vec<String> {
+        let mut scopes: Vec<String> = Vec::new();
+        for s in scope.unwrap_or_default().split_whitespace() {
+            if !scopes.iter().any(|existing| existing == s) {
+                scopes.push(s.to_string());
+            }
+        }
+        scopes
+    }
+
+    pub fn format_scopes(scopes: &[String]) -> String {
+        scopes.join(" ")
+    }
+
+    pub fn validate_client_registration(
+        owner_role: &UserRole,
+        redirect_uris: &[String],
+        requested_grant_types: &[String],
+        scopes: &[String],
+        is_confidential: bool,
+    ) -> SystemResult<()> {
+        if requested_grant_types.is_empty() {
+            return Err(SystemError::ValidationError(
+                "At least one grant type is required".to_string(),
+            ));
+        }
+
+        for grant_type in requested_grant_types {
+            if !grant_types::ALL.contains(&grant_type.as_str()) {
+                return Err(SystemError::UnsupportedGrantType(grant_type.clone()));
+            }
+        }
+
+        if !is_confidential
+            && requested_grant_types
+                .iter()
+                .any(|g| g == grant_types::CLIENT_CREDENTIALS)
+        {
+            return Err(SystemError::ValidationError(
+                "Public clients cannot use the client_credentials grant".to_string(),
+            ));
+        }
+
+        if scopes.is_empty() {
+            return Err(SystemError::InvalidScope("At least one scope is required".to_string()));
+        }
+
+        for scope in scopes {
+            if !oauth_scopes::GRANTABLE.contains(&scope.as_str()) {
+                return Err(SystemError::InvalidScope(scope.clone()));
+            }
+            // Client credentials tokens act as the owner, so they can carry no more
+            if !permissions::for_role(owner_role).contains(&scope.as_str()) {
+                return Err(SystemError::PermissionDenied(format!(
+                    "The {} role cannot register clients with {}",
+                    owner_role, scope
+                )));
+            }
+        }
+
+        let uses_redirects = requested_grant_types
+            .iter()
+            .any(|g| g == grant_types::AUTHORIZATION_CODE);
+
+        if uses_redirects && redirect_uris.is_empty() {
+            return Err(SystemError::ValidationError(
+                "At least one redirect URI is required for the authorization_code grant".to_string(),
+            ));
+        }
+
+        for uri in redirect_uris {
+            let is_loopback = uri.starts_with("http://localhost") || uri.starts_with("http://127.0.0.1");
+            if !(uri.starts_with("https://") || is_loopback) || uri.contains('#') {
+                return Err(SystemError::ValidationError(format!(
+                    "Redirect URI must use https and must not contain a fragment: {}",
+                    uri
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
+    // Scopes become the token's permissions, so they are limited to what the
+    // resource owner's role may do as well as to the client's registration.
+    // An empty request means "everything the client is registered for".
+    pub fn resolve_scopes(
+        client: &OAuthClient,
+        owner_role: &UserRole,
+        requested: Vec<String>,
+    ) -> SystemResult<Vec<String>> {
+        if requested.is_empty() {
+            let scopes = Self::limit_to_role(owner_role, client.scopes.clone());
+            if scopes.is_empty() {
+                return Err(SystemError::InvalidScope(Self::format_scopes(&client.scopes)));
+            }
+            return Ok(scopes);
+        }
+
+        let allowed = permissions::for_role(owner_role);
+        if let Some(scope) = requested
+            .iter()
+            .find(|s| !client.scopes.contains(s) || !allowed.contains(&s.as_str()))
+        {
+            return Err(SystemError::InvalidScope(scope.clone()));
+        }
+
+        Ok(requested)
+    }
+
+    // Drops scopes the role no longer carries, e.g. after a downgrade
+    pub fn limit_to_role(role: &UserRole, mut scopes: Vec<String>) -> Vec<String> {
+        let allowed = permissions::for_role(role);
+        scopes.retain(|s| allowed.contains(&s.as_str()));
+        scopes
+    }
+
+    pub fn authenticate_client(client: &OAuthClient, client_secret: Option<&str>) -> SystemResult<()> {
+        if !client.is_active {
+            return Err(SystemError::InvalidClient);
+        }
+
+        match (&client.client_secret_hash, client_secret) {
+            (Some(hash), Some(secret)) => {
+                if PasswordHelper::verify_hashed_string(secret, hash)? {
+                    Ok(())
+                } else {
+                    Err(SystemError::InvalidClient)
+                }
+            }
+            (Some(_), None) => Err(SystemError::InvalidClient),
+            // Public clients are identified, not authenticated; PKCE protects their codes
+            (None, _) => Ok(()),
+        }
+    }
+
+    pub fn validate_code_challenge_method(method: Option<&str>) -> SystemResult<()> {
+        match method {
+            None | Some("S256") | Some("plain") => Ok(()),
+            Some(other) => Err(SystemError::ValidationError(format!(
+                "Unsupported code_challenge_method: {}",
+                other
+            ))),
+        }
+    }
+
+    pub fn verify_pkce(code: &OAuthAuthorizationCode, code_verifier: Option<&str>) -> SystemResult<()> {
+        let challenge = match &code.code_challenge {
+            Some(challenge) => challenge,
+            None => return Ok(()),
+        };
+
+        let verifier = code_verifier
+            .ok_or_else(|| SystemError::InvalidGrant("code_verifier is required".to_string()))?;
+
+        let computed = match code.code_challenge_method.as_deref() {
+            Some("S256") => TokenHelper::pkce_s256_challenge(verifier),
+            _ => verifier.to_string(),
+        };
+
+        if &computed != challenge {
+            return Err(SystemError::InvalidGrant("code_verifier does not match".to_string()));
+        }
+
+        Ok(())
+    }
+}
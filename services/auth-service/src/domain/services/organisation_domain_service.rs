@@ -0,0 +1,97 @@
+use crate::domain::entities::organisation_membership::OrganisationMembership;
+use chrono::{DateTime, Utc};
+use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, Validation};
+use serde::{Deserialize, Serialize};
+use shared::entities::enums::OrganisationRole;
+use shared::features::errors::{SystemError, SystemResult};
+use uuid::Uuid;
+
+// Audience used for invitation tokens so they can never pass as access tokens
+const INVITATION_AUDIENCE: &str = "organisation-invitation";
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct InvitationClaims {
+    pub sub: Uuid, // invitation id
+    pub org: Uuid,
+    pub email: String,
+    pub iss: String,
+    pub aud: String,
+    pub exp: usize,
+}
+
+pub struct OrganisationDomainService;
+
+impl OrganisationDomainService {
+    pub fn slugify(name: &str) -> String {
+        let mut slug = String::with_capacity(name.len());
+        for c in name.trim().to_lowercase().chars() {
+            if c.is_ascii_alphanumeric() {
+                slug.push(c);
+            } else if !slug.ends_with('-') && !slug.is_empty() {
+                slug.push('-');
+            }
+        }
+        slug.trim_end_matches('-').chars().take(80).collect()
+    }
+
+    pub fn ensure_can_manage(membership: &OrganisationMembership) -> SystemResult<()> {
+        if membership.can_manage_members() {
+            Ok(())
+        } else {
+            Err(SystemError::PermissionDenied(
+                "Only organisation owners and admins can manage members".to_string(),
+            ))
+        }
+    }
+
+    // Admins manage everyone below them; only owners can grant or touch ownership
+    // and the admin role.
+    pub fn ensure_can_assign(actor: &OrganisationMembership, role: &OrganisationRole) -> SystemResult<()> {
+        Self::ensure_can_manage(actor)?;
+
+        if actor.role != OrganisationRole::Owner
+            && matches!(role, OrganisationRole::Owner | OrganisationRole::Admin)
+        {
+            return Err(SystemError::PermissionDenied(format!(
+                "Only owners can assign the {} role",
+                role
+            )));
+        }
+
+        Ok(())
+    }
+
+    pub fn sign_invitation(
+        invitation_id: Uuid,
+        organisation_id: Uuid,
+        email: &str,
+        expires_at: DateTime<Utc>,
+        issuer: &str,
+        secret: &str,
+    ) -> SystemResult<String> {
+        let claims = InvitationClaims {
+            sub: invitation_id,
+            org: organisation_id,
+            email: email.to_string(),
+            iss: issuer.to_string(),
+            aud: INVITATION_AUDIENCE.to_string(),
+            exp: expires_at.timestamp() as usize,
+        };
+
+        encode(&Header::default(), &claims, &EncodingKey::from_secret(secret.as_ref()))
+            .map_err(|e| SystemError::TokenError(e.to_string()))
+    }
+
+    pub fn verify_invitation(token: &str, issuer: &str, secret: &str) -> SystemResult<InvitationClaims> {
+        let mut validation = Validation::default();
+        validation.set_issuer(&[issuer]);
+        validation.set_audience(&[INVITATION_AUDIENCE]);
+
+        decode::<InvitationClaims>(token, &DecodingKey::from_secret(secret.as_ref()), &validation)
+            .map(|data| data.claims)
+            .map_err(|e| match e.kind() {
+                jsonwebtoken::errors::ErrorKind::ExpiredSignature => SystemError::TokenExpired,
+                _ => SystemError::InvalidToken,
+            })
+    }
+}
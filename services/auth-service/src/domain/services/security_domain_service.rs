@@ -1,4 +1,4 @@
-use shared::features::errors::{SystemError, SystemResult};
+use shared::features::errors::{FieldError, SystemError, SystemResult};
 use uuid::Uuid;
 use shared::features::helper::security_question_helper::SecurityHelper;
 use crate::domain::entities::user_security_question::UserSecurityQuestion;
@@ -41,46 +41,51 @@ impl SecurityDomainService {
             .map_err(|e| SystemError::InternalError(e.to_string()))
     }
 
+    // Reports every problem at once, with the offending field
     pub fn validate_security_question_setup(
         questions_answers: &[(Uuid, String)],
     ) -> SystemResult<()> {
+        let mut errors = Vec::new();
+
         if questions_answers.len() < 2 {
-            return Err(SystemError::InternalError(
-                "At least 2 security questions required".to_string(),
-            ));
+            errors.push(FieldError::new("questions", "length", "at least 2 security questions required"));
         }
 
         if questions_answers.len() > 5 {
-            return Err(SystemError::InternalError(
-                "Maximum 5 security questions allowed".to_string(),
-            ));
+            errors.push(FieldError::new("questions", "length", "maximum 5 security questions allowed"));
         }
 
-        // Check for duplicate questions
         let mut question_ids = std::collections::HashSet::new();
-        for (question_id, _) in questions_answers {
+        for (index, (question_id, answer)) in questions_answers.iter().enumerate() {
             if !question_ids.insert(*question_id) {
-                return Err(SystemError::InternalError(
-                    "Duplicate security questions not allowed".to_string(),
+                errors.push(FieldError::new(
+                    format!("questions[{}].question_id", index),
+                    "duplicate",
+                    "duplicate security questions not allowed",
                 ));
             }
-        }
 
-        // Validate answer length
-        for (_, answer) in questions_answers {
             if answer.trim().len() < 2 {
-                return Err(SystemError::InternalError(
-                    "Security question answers must be at least 2 characters".to_string(),
+                errors.push(FieldError::new(
+                    format!("questions[{}].answer", index),
+                    "length",
+                    "must be at least 2 characters",
                 ));
             }
 
             if answer.len() > 200 {
-                return Err(SystemError::InternalError(
-                    "Security question answers must not exceed 200 characters".to_string(),
+                errors.push(FieldError::new(
+                    format!("questions[{}].answer", index),
+                    "length",
+                    "must not exceed 200 characters",
                 ));
             }
         }
 
-        Ok(())
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(SystemError::InvalidFields(errors))
+        }
     }
 }
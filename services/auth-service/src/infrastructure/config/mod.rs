@@ -1,6 +1,8 @@
 use serde::{Deserialize, Serialize};
+use shared::config::loader::ConfigLoader;
 use shared::config::{database_config, jwt_config, messaging_config, otp_config, redis_config, server_config};
 use shared::config::redis_config::RedisFigureConfig;
+use shared::config::step_up_config::StepUpConfig;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AppConfig {
@@ -10,19 +12,22 @@ pub struct AppConfig {
     pub otp: otp_config::OtpConfig,
     pub server: server_config::ServerConfig,
     pub messaging: messaging_config::MessagingConfig,
-    pub redis_figure_config: RedisFigureConfig
+    pub redis_figure_config: RedisFigureConfig,
+    pub step_up: StepUpConfig,
 }
 
 impl AppConfig {
-    pub fn from_env() -> Self {
-        Self {
-            database: database_config::DatabaseConfig::from_env(),
-            redis: redis_config::RedisConfig::from_env(),
-            jwt: jwt_config::JwtConfig::from_env(),
-            otp: otp_config::OtpConfig::from_env(),
-            server: server_config::ServerConfig::from_env(),
-            messaging: messaging_config::MessagingConfig::from_env(),
-            redis_figure_config: RedisFigureConfig::from_env()
-        }
+    pub fn loader() -> ConfigLoader {
+        ConfigLoader::new("auth-service")
+            .section::<database_config::DatabaseConfig>("database")
+            .section::<redis_config::RedisConfig>("redis")
+            .section::<jwt_config::JwtConfig>("jwt")
+            .section::<otp_config::OtpConfig>("otp")
+            .section::<server_config::ServerConfig>("server")
+            .section::<messaging_config::MessagingConfig>("messaging")
+            .section::<RedisFigureConfig>("redis_figure_config")
+            .section::<StepUpConfig>("step_up")
+            .with_default("server.port", 8001)
+            .with_default("server.grpc_port", 9001)
     }
 }
@@ -0,0 +1,157 @@
+use crate::domain::entities::api_key::ApiKey;
+use crate::domain::repositories::api_key_repository::ApiKeyRepository;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use shared::features::errors::SystemResult;
+use sqlx::{Pool, Postgres};
+use uuid::Uuid;
+
+pub struct PostgresApiKeyRepository {
+    pool: Pool<Postgres>,
+}
+
+impl PostgresApiKeyRepository {
+    pub fn new(pool: Pool<Postgres>) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl ApiKeyRepository for PostgresApiKeyRepository {
+    #[tracing::instrument(name = "PostgresApiKeyRepository::create", skip_all, fields(db.system = "postgresql"))]
+    async fn create(&self, api_key: &ApiKey) -> SystemResult<ApiKey> {
+        log::info!("create() called with api_key: {}", api_key.id);
+
+        let row = sqlx::query_as::<_, ApiKey>(
+            r#"
+            INSERT INTO api_keys (
+            id,
+            user_id,
+            organisation_id,
+            name,
+            prefix,
+            secret_hash,
+            permissions,
+            allowed_ips,
+            expires_at,
+            created_at
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+            RETURNING id, user_id, organisation_id, name, prefix, secret_hash, permissions, allowed_ips, expires_at,
+                last_used_at, last_used_ip, created_at, revoked_at
+            "#
+        )
+        .bind(api_key.id)
+        .bind(api_key.user_id)
+        .bind(api_key.organisation_id)
+        .bind(&api_key.name)
+        .bind(&api_key.prefix)
+        .bind(&api_key.secret_hash)
+        .bind(&api_key.permissions)
+        .bind(&api_key.allowed_ips)
+        .bind(api_key.expires_at)
+        .bind(api_key.created_at)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(row)
+    }
+
+    #[tracing::instrument(name = "PostgresApiKeyRepository::find_by_id", skip_all, fields(db.system = "postgresql"))]
+    async fn find_by_id(&self, id: Uuid) -> SystemResult<Option<ApiKey>> {
+        log::info!("find_by_id() called with id: {}", id);
+
+        let row = sqlx::query_as::<_, ApiKey>(
+            r#"
+            SELECT id, user_id, organisation_id, name, prefix, secret_hash, permissions, allowed_ips, expires_at,
+                last_used_at, last_used_ip, created_at, revoked_at
+            FROM api_keys
+            WHERE id = $1
+            "#
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row)
+    }
+
+    #[tracing::instrument(name = "PostgresApiKeyRepository::find_by_prefix", skip_all, fields(db.system = "postgresql"))]
+    async fn find_by_prefix(&self, prefix: &str) -> SystemResult<Option<ApiKey>> {
+        log::info!("find_by_prefix() called with prefix: {}", prefix);
+
+        let row = sqlx::query_as::<_, ApiKey>(
+            r#"
+            SELECT id, user_id, organisation_id, name, prefix, secret_hash, permissions, allowed_ips, expires_at,
+                last_used_at, last_used_ip, created_at, revoked_at
+            FROM api_keys
+            WHERE prefix = $1
+            "#
+        )
+        .bind(prefix)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row)
+    }
+
+    #[tracing::instrument(name = "PostgresApiKeyRepository::find_by_user", skip_all, fields(db.system = "postgresql"))]
+    async fn find_by_user(&self, user_id: Uuid) -> SystemResult<Vec<ApiKey>> {
+        log::info!("find_by_user() called with user_id: {}", user_id);
+
+        let rows = sqlx::query_as::<_, ApiKey>(
+            r#"
+            SELECT id, user_id, organisation_id, name, prefix, secret_hash, permissions, allowed_ips, expires_at,
+                last_used_at, last_used_ip, created_at, revoked_at
+            FROM api_keys
+            WHERE user_id = $1 AND revoked_at IS NULL
+            ORDER BY created_at DESC
+            "#
+        )
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows)
+    }
+
+    #[tracing::instrument(name = "PostgresApiKeyRepository::revoke", skip_all, fields(db.system = "postgresql"))]
+    async fn revoke(&self, id: Uuid) -> SystemResult<()> {
+        log::info!("revoke() called with id: {}", id);
+
+        sqlx::query(
+            r#"
+            UPDATE api_keys
+            SET revoked_at = NOW()
+            WHERE id = $1 AND revoked_at IS NULL
+            "#
+        )
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(name = "PostgresApiKeyRepository::record_usage", skip_all, fields(db.system = "postgresql"))]
+    async fn record_usage(
+        &self,
+        id: Uuid,
+        used_at: DateTime<Utc>,
+        ip_address: Option<&str>,
+    ) -> SystemResult<()> {
+        sqlx::query(
+            r#"
+            UPDATE api_keys
+            SET last_used_at = $1, last_used_ip = $2
+            WHERE id = $3
+            "#
+        )
+        .bind(used_at)
+        .bind(ip_address)
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,56 @@
+use crate::domain::entities::audit_log::AuditLog;
+use crate::domain::repositories::audit_log_repository::AuditLogRepository;
+use async_trait::async_trait;
+use shared::features::errors::SystemResult;
+use sqlx::{Pool, Postgres};
+
+pub struct PostgresAuditLogRepository {
+    pool: Pool<Postgres>,
+}
+
+impl PostgresAuditLogRepository {
+    pub fn new(pool: Pool<Postgres>) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl AuditLogRepository for PostgresAuditLogRepository {
+    #[tracing::instrument(name = "PostgresAuditLogRepository::create", skip_all, fields(db.system = "postgresql"))]
+    async fn create(&self, audit_log: &AuditLog) -> SystemResult<()> {
+        log::info!("create() called with action: {}", audit_log.action);
+
+        sqlx::query(
+            r#"
+            INSERT INTO audit_logs (
+            id,
+            user_id,
+            action,
+            resource_type,
+            resource_id,
+            old_values,
+            new_values,
+            ip_address,
+            user_agent,
+            metadata,
+            created_at
+            ) VALUES ($1, $2, $3, $4, $5, $6::jsonb, $7::jsonb, $8::inet, $9, $10::jsonb, $11)
+            "#
+        )
+        .bind(audit_log.id)
+        .bind(audit_log.user_id)
+        .bind(&audit_log.action)
+        .bind(&audit_log.resource_type)
+        .bind(audit_log.resource_id)
+        .bind(audit_log.old_values.as_ref().map(|v| v.to_string()))
+        .bind(audit_log.new_values.as_ref().map(|v| v.to_string()))
+        .bind(&audit_log.ip_address)
+        .bind(&audit_log.user_agent)
+        .bind(audit_log.metadata.as_ref().map(|v| v.to_string()))
+        .bind(audit_log.created_at)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+}
@@ -17,8 +17,9 @@ impl PostgresLoginAttemptRepository {
 
 #[async_trait]
 impl LoginAttemptRepository for PostgresLoginAttemptRepository {
+    #[tracing::instrument(name = "PostgresLoginAttemptRepository::create", skip_all, fields(db.system = "postgresql"))]
     async fn create(&self, attempt: &LoginAttempt) -> SystemResult<LoginAttempt> {
-        log::info!("create() called with attempt: {:?}", attempt);
+        log::info!("create() called with attempt: {}", attempt.id);
 
         let row = sqlx::query(
             r#"
@@ -32,8 +33,8 @@ impl LoginAttemptRepository for PostgresLoginAttemptRepository {
             country,
             city,
             created_at
-            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
-            RETURNING id, identifier, ip_address, user_agent, is_successful, failure_reason, country, city, created_at
+            ) VALUES ($1, $2, $3::inet, $4, $5, $6, $7, $8, $9)
+            RETURNING id, identifier, host(ip_address) AS ip_address, user_agent, is_successful, failure_reason, country, city, created_at
             "#
         )
         .bind(&attempt.id)
@@ -64,6 +65,7 @@ impl LoginAttemptRepository for PostgresLoginAttemptRepository {
         Ok(row)
     }
 
+    #[tracing::instrument(name = "PostgresLoginAttemptRepository::get_recent_attempts", skip_all, fields(db.system = "postgresql"))]
     async fn get_recent_attempts(
         &self,
         identifier: &str,
@@ -77,7 +79,7 @@ impl LoginAttemptRepository for PostgresLoginAttemptRepository {
 
         let rows = sqlx::query(
             r#"
-            SELECT id, identifier, ip_address, user_agent, is_successful, failure_reason, country, city, created_at
+            SELECT id, identifier, host(ip_address) AS ip_address, user_agent, is_successful, failure_reason, country, city, created_at
             FROM login_attempts
             WHERE identifier = $1 AND created_at >= $2
             ORDER BY created_at DESC
@@ -107,6 +109,7 @@ impl LoginAttemptRepository for PostgresLoginAttemptRepository {
         Ok(attempts)
     }
 
+    #[tracing::instrument(name = "PostgresLoginAttemptRepository::get_failed_attempts_count", skip_all, fields(db.system = "postgresql"))]
     async fn get_failed_attempts_count(
         &self,
         identifier: &str,
@@ -137,6 +140,7 @@ impl LoginAttemptRepository for PostgresLoginAttemptRepository {
         Ok(count)
     }
 
+    #[tracing::instrument(name = "PostgresLoginAttemptRepository::get_attempts_by_ip", skip_all, fields(db.system = "postgresql"))]
     async fn get_attempts_by_ip(
         &self,
         ip_address: &str,
@@ -150,9 +154,9 @@ impl LoginAttemptRepository for PostgresLoginAttemptRepository {
 
         let rows = sqlx::query(
             r#"
-            SELECT id, identifier, ip_address, user_agent, is_successful, failure_reason, country, city, created_at
+            SELECT id, identifier, host(ip_address) AS ip_address, user_agent, is_successful, failure_reason, country, city, created_at
             FROM login_attempts
-            WHERE ip_address = $1 AND created_at >= $2
+            WHERE ip_address = $1::inet AND created_at >= $2
             ORDER BY created_at DESC
             "#
         )
@@ -180,6 +184,7 @@ impl LoginAttemptRepository for PostgresLoginAttemptRepository {
         Ok(attempts)
     }
 
+    #[tracing::instrument(name = "PostgresLoginAttemptRepository::count_failed_attempts_by_ip", skip_all, fields(db.system = "postgresql"))]
     async fn count_failed_attempts_by_ip(&self, ip: &str, since: DateTime<Utc>) -> SystemResult<i64> {
         log::info!(
             "count_failed_attempts_by_ip() called with ip: {}, since: {}",
@@ -212,6 +217,7 @@ impl LoginAttemptRepository for PostgresLoginAttemptRepository {
         Ok(count)
     }
 
+    #[tracing::instrument(name = "PostgresLoginAttemptRepository::cleanup_old_attempts", skip_all, fields(db.system = "postgresql"))]
     async fn cleanup_old_attempts(&self, before: DateTime<Utc>) -> SystemResult<u64> {
         log::info!("cleanup_old_attempts() called with before: {}", before);
         
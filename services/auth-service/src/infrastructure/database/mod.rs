@@ -3,3 +3,8 @@ pub mod password_reset_repository_impl;
 pub mod refresh_token_repository_impl;
 pub mod security_question_repository_impl;
 pub mod user_repository_impl;
+pub mod oauth_repository_impl;
+pub mod api_key_repository_impl;
+pub mod organisation_repository_impl;
+pub mod audit_log_repository_impl;
+pub mod totp_repository_impl;
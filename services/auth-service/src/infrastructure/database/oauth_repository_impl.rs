@@ -0,0 +1,516 @@
+use crate::domain::entities::blacklisted_token::TokenType;
+use crate::domain::entities::oauth_authorization_code::OAuthAuthorizationCode;
+use crate::domain::entities::oauth_client::OAuthClient;
+use crate::domain::entities::oauth_consent::OAuthConsent;
+use crate::domain::entities::oauth_token::OAuthToken;
+use crate::domain::repositories::oauth_repository::{
+    OAuthAuthorizationCodeRepository, OAuthClientRepository, OAuthConsentRepository,
+    OAuthTokenRepository,
+};
+use async_trait::async_trait;
+use shared::features::errors::{SystemError, SystemResult};
+use sqlx::postgres::PgRow;
+use sqlx::{Pool, Postgres, Row};
+use uuid::Uuid;
+
+pub struct PostgresOAuthClientRepository {
+    pool: Pool<Postgres>,
+}
+
+impl PostgresOAuthClientRepository {
+    pub fn new(pool: Pool<Postgres>) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl OAuthClientRepository for PostgresOAuthClientRepository {
+    #[tracing::instrument(name = "PostgresOAuthClientRepository::create", skip_all, fields(db.system = "postgresql"))]
+    async fn create(&self, client: &OAuthClient) -> SystemResult<OAuthClient> {
+        log::info!("create() called with client: {}", client.id);
+
+        let row = sqlx::query_as::<_, OAuthClient>(
+            r#"
+            INSERT INTO oauth_clients (
+            id,
+            owner_user_id,
+            name,
+            client_secret_hash,
+            redirect_uris,
+            grant_types,
+            scopes,
+            is_confidential,
+            is_active,
+            created_at,
+            updated_at
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+            RETURNING id, owner_user_id, name, client_secret_hash, redirect_uris, grant_types, scopes,
+                is_confidential, is_active, created_at, updated_at
+            "#
+        )
+        .bind(client.id)
+        .bind(client.owner_user_id)
+        .bind(&client.name)
+        .bind(&client.client_secret_hash)
+        .bind(&client.redirect_uris)
+        .bind(&client.grant_types)
+        .bind(&client.scopes)
+        .bind(client.is_confidential)
+        .bind(client.is_active)
+        .bind(client.created_at)
+        .bind(client.updated_at)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(row)
+    }
+
+    #[tracing::instrument(name = "PostgresOAuthClientRepository::find_by_id", skip_all, fields(db.system = "postgresql"))]
+    async fn find_by_id(&self, id: Uuid) -> SystemResult<Option<OAuthClient>> {
+        log::info!("find_by_id() called with id: {}", id);
+
+        let row = sqlx::query_as::<_, OAuthClient>(
+            r#"
+            SELECT id, owner_user_id, name, client_secret_hash, redirect_uris, grant_types, scopes,
+                is_confidential, is_active, created_at, updated_at
+            FROM oauth_clients
+            WHERE id = $1
+            "#
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row)
+    }
+
+    #[tracing::instrument(name = "PostgresOAuthClientRepository::find_by_owner", skip_all, fields(db.system = "postgresql"))]
+    async fn find_by_owner(&self, owner_user_id: Uuid) -> SystemResult<Vec<OAuthClient>> {
+        log::info!("find_by_owner() called with owner_user_id: {}", owner_user_id);
+
+        let rows = sqlx::query_as::<_, OAuthClient>(
+            r#"
+            SELECT id, owner_user_id, name, client_secret_hash, redirect_uris, grant_types, scopes,
+                is_confidential, is_active, created_at, updated_at
+            FROM oauth_clients
+            WHERE owner_user_id = $1 AND is_active = true
+            ORDER BY created_at DESC
+            "#
+        )
+        .bind(owner_user_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows)
+    }
+
+    #[tracing::instrument(name = "PostgresOAuthClientRepository::update", skip_all, fields(db.system = "postgresql"))]
+    async fn update(&self, client: &OAuthClient) -> SystemResult<OAuthClient> {
+        log::info!("update() called with client: {}", client.id);
+
+        let row = sqlx::query_as::<_, OAuthClient>(
+            r#"
+            UPDATE oauth_clients
+            SET
+            name = $1,
+            redirect_uris = $2,
+            grant_types = $3,
+            scopes = $4,
+            is_active = $5
+            WHERE id = $6
+            RETURNING id, owner_user_id, name, client_secret_hash, redirect_uris, grant_types, scopes,
+                is_confidential, is_active, created_at, updated_at
+            "#
+        )
+        .bind(&client.name)
+        .bind(&client.redirect_uris)
+        .bind(&client.grant_types)
+        .bind(&client.scopes)
+        .bind(client.is_active)
+        .bind(client.id)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(row)
+    }
+}
+
+pub struct PostgresOAuthAuthorizationCodeRepository {
+    pool: Pool<Postgres>,
+}
+
+impl PostgresOAuthAuthorizationCodeRepository {
+    pub fn new(pool: Pool<Postgres>) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl OAuthAuthorizationCodeRepository for PostgresOAuthAuthorizationCodeRepository {
+    #[tracing::instrument(name = "PostgresOAuthAuthorizationCodeRepository::create", skip_all, fields(db.system = "postgresql"))]
+    async fn create(&self, code: &OAuthAuthorizationCode) -> SystemResult<OAuthAuthorizationCode> {
+        log::info!("create() called for client: {}", code.client_id);
+
+        let row = sqlx::query_as::<_, OAuthAuthorizationCode>(
+            r#"
+            INSERT INTO oauth_authorization_codes (
+            id,
+            code_hash,
+            client_id,
+            user_id,
+            redirect_uri,
+            scopes,
+            code_challenge,
+            code_challenge_method,
+            expires_at,
+            is_used,
+            created_at
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+            RETURNING id, code_hash, client_id, user_id, redirect_uri, scopes, code_challenge,
+                code_challenge_method, expires_at, is_used, created_at
+            "#
+        )
+        .bind(code.id)
+        .bind(&code.code_hash)
+        .bind(code.client_id)
+        .bind(code.user_id)
+        .bind(&code.redirect_uri)
+        .bind(&code.scopes)
+        .bind(&code.code_challenge)
+        .bind(&code.code_challenge_method)
+        .bind(code.expires_at)
+        .bind(code.is_used)
+        .bind(code.created_at)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(row)
+    }
+
+    #[tracing::instrument(name = "PostgresOAuthAuthorizationCodeRepository::find_by_code_hash", skip_all, fields(db.system = "postgresql"))]
+    async fn find_by_code_hash(&self, code_hash: &str) -> SystemResult<Option<OAuthAuthorizationCode>> {
+        log::info!("find_by_code_hash() called");
+
+        let row = sqlx::query_as::<_, OAuthAuthorizationCode>(
+            r#"
+            SELECT id, code_hash, client_id, user_id, redirect_uri, scopes, code_challenge,
+                code_challenge_method, expires_at, is_used, created_at
+            FROM oauth_authorization_codes
+            WHERE code_hash = $1
+            "#
+        )
+        .bind(code_hash)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row)
+    }
+
+    #[tracing::instrument(name = "PostgresOAuthAuthorizationCodeRepository::consume", skip_all, fields(db.system = "postgresql"))]
+    async fn consume(&self, id: Uuid) -> SystemResult<Option<OAuthAuthorizationCode>> {
+        log::info!("consume() called with code: {}", id);
+
+        let row = sqlx::query_as::<_, OAuthAuthorizationCode>(
+            r#"
+            UPDATE oauth_authorization_codes
+            SET is_used = TRUE
+            WHERE id = $1 AND is_used = FALSE
+            RETURNING id, code_hash, client_id, user_id, redirect_uri, scopes, code_challenge,
+                code_challenge_method, expires_at, is_used, created_at
+            "#
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row)
+    }
+
+    #[tracing::instrument(name = "PostgresOAuthAuthorizationCodeRepository::cleanup_expired", skip_all, fields(db.system = "postgresql"))]
+    async fn cleanup_expired(&self) -> SystemResult<u64> {
+        log::info!("cleanup_expired() called");
+
+        let result = sqlx::query(
+            r#"
+            DELETE FROM oauth_authorization_codes
+            WHERE expires_at < NOW() OR is_used = TRUE
+            "#
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+}
+
+pub struct PostgresOAuthTokenRepository {
+    pool: Pool<Postgres>,
+}
+
+impl PostgresOAuthTokenRepository {
+    pub fn new(pool: Pool<Postgres>) -> Self {
+        Self { pool }
+    }
+
+    fn map_row(row: PgRow) -> SystemResult<OAuthToken> {
+        Ok(OAuthToken {
+            id: row.get("id"),
+            token_hash: row.get("token_hash"),
+            token_type: row
+                .get::<String, _>("token_type")
+                .parse::<TokenType>()
+                .map_err(SystemError::ValidationError)?,
+            client_id: row.get("client_id"),
+            user_id: row.get("user_id"),
+            scopes: row.get("scopes"),
+            expires_at: row.get("expires_at"),
+            is_revoked: row.get("is_revoked"),
+            created_at: row.get("created_at"),
+            revoked_at: row.get("revoked_at"),
+        })
+    }
+}
+
+#[async_trait]
+impl OAuthTokenRepository for PostgresOAuthTokenRepository {
+    #[tracing::instrument(name = "PostgresOAuthTokenRepository::create", skip_all, fields(db.system = "postgresql"))]
+    async fn create(&self, token: &OAuthToken) -> SystemResult<OAuthToken> {
+        log::info!("create() called with {} token: {}", token.token_type, token.id);
+
+        sqlx::query(
+            r#"
+            INSERT INTO oauth_tokens (
+            id,
+            token_hash,
+            token_type,
+            client_id,
+            user_id,
+            scopes,
+            expires_at,
+            is_revoked,
+            created_at,
+            revoked_at
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+            "#
+        )
+        .bind(token.id)
+        .bind(&token.token_hash)
+        .bind(token.token_type.to_string())
+        .bind(token.client_id)
+        .bind(token.user_id)
+        .bind(&token.scopes)
+        .bind(token.expires_at)
+        .bind(token.is_revoked)
+        .bind(token.created_at)
+        .bind(token.revoked_at)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(token.clone())
+    }
+
+    #[tracing::instrument(name = "PostgresOAuthTokenRepository::find_by_token_hash", skip_all, fields(db.system = "postgresql"))]
+    async fn find_by_token_hash(&self, token_hash: &str) -> SystemResult<Option<OAuthToken>> {
+        log::info!("find_by_token_hash() called");
+
+        let row = sqlx::query(
+            r#"
+            SELECT id, token_hash, token_type, client_id, user_id, scopes, expires_at,
+                is_revoked, created_at, revoked_at
+            FROM oauth_tokens
+            WHERE token_hash = $1
+            "#
+        )
+        .bind(token_hash)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        row.map(Self::map_row).transpose()
+    }
+
+    #[tracing::instrument(name = "PostgresOAuthTokenRepository::update", skip_all, fields(db.system = "postgresql"))]
+    async fn update(&self, token: &OAuthToken) -> SystemResult<OAuthToken> {
+        log::info!("update() called with token: {}", token.id);
+
+        sqlx::query(
+            r#"
+            UPDATE oauth_tokens
+            SET is_revoked = $1, revoked_at = $2
+            WHERE id = $3
+            "#
+        )
+        .bind(token.is_revoked)
+        .bind(token.revoked_at)
+        .bind(token.id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(token.clone())
+    }
+
+    #[tracing::instrument(name = "PostgresOAuthTokenRepository::consume", skip_all, fields(db.system = "postgresql"))]
+    async fn consume(&self, id: Uuid) -> SystemResult<Option<OAuthToken>> {
+        log::info!("consume() called with token: {}", id);
+
+        let row = sqlx::query(
+            r#"
+            UPDATE oauth_tokens
+            SET is_revoked = TRUE, revoked_at = NOW()
+            WHERE id = $1 AND is_revoked = FALSE
+            RETURNING id, token_hash, token_type, client_id, user_id, scopes, expires_at,
+                is_revoked, created_at, revoked_at
+            "#
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        row.map(Self::map_row).transpose()
+    }
+
+    #[tracing::instrument(name = "PostgresOAuthTokenRepository::revoke_for_user_and_client", skip_all, fields(db.system = "postgresql"))]
+    async fn revoke_for_user_and_client(&self, user_id: Uuid, client_id: Uuid) -> SystemResult<u64> {
+        log::info!(
+            "revoke_for_user_and_client() called with user_id: {}, client_id: {}",
+            user_id,
+            client_id
+        );
+
+        let result = sqlx::query(
+            r#"
+            UPDATE oauth_tokens
+            SET is_revoked = TRUE, revoked_at = NOW()
+            WHERE user_id = $1 AND client_id = $2 AND is_revoked = FALSE
+            "#
+        )
+        .bind(user_id)
+        .bind(client_id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    #[tracing::instrument(name = "PostgresOAuthTokenRepository::cleanup_expired", skip_all, fields(db.system = "postgresql"))]
+    async fn cleanup_expired(&self) -> SystemResult<u64> {
+        log::info!("cleanup_expired() called");
+
+        let result = sqlx::query(
+            r#"
+            DELETE FROM oauth_tokens
+            WHERE expires_at < NOW()
+            "#
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+}
+
+pub struct PostgresOAuthConsentRepository {
+    pool: Pool<Postgres>,
+}
+
+impl PostgresOAuthConsentRepository {
+    pub fn new(pool: Pool<Postgres>) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl OAuthConsentRepository for PostgresOAuthConsentRepository {
+    #[tracing::instrument(name = "PostgresOAuthConsentRepository::upsert", skip_all, fields(db.system = "postgresql"))]
+    async fn upsert(&self, consent: &OAuthConsent) -> SystemResult<OAuthConsent> {
+        log::info!(
+            "upsert() called with user_id: {}, client_id: {}",
+            consent.user_id,
+            consent.client_id
+        );
+
+        let row = sqlx::query_as::<_, OAuthConsent>(
+            r#"
+            INSERT INTO oauth_consents (id, user_id, client_id, scopes, granted_at, revoked_at)
+            VALUES ($1, $2, $3, $4, $5, NULL)
+            ON CONFLICT (user_id, client_id) DO UPDATE
+            SET scopes = EXCLUDED.scopes, granted_at = EXCLUDED.granted_at, revoked_at = NULL
+            RETURNING id, user_id, client_id, scopes, granted_at, revoked_at
+            "#
+        )
+        .bind(consent.id)
+        .bind(consent.user_id)
+        .bind(consent.client_id)
+        .bind(&consent.scopes)
+        .bind(consent.granted_at)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(row)
+    }
+
+    #[tracing::instrument(name = "PostgresOAuthConsentRepository::find_by_user_and_client", skip_all, fields(db.system = "postgresql"))]
+    async fn find_by_user_and_client(
+        &self,
+        user_id: Uuid,
+        client_id: Uuid,
+    ) -> SystemResult<Option<OAuthConsent>> {
+        log::info!(
+            "find_by_user_and_client() called with user_id: {}, client_id: {}",
+            user_id,
+            client_id
+        );
+
+        let row = sqlx::query_as::<_, OAuthConsent>(
+            r#"
+            SELECT id, user_id, client_id, scopes, granted_at, revoked_at
+            FROM oauth_consents
+            WHERE user_id = $1 AND client_id = $2
+            "#
+        )
+        .bind(user_id)
+        .bind(client_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row)
+    }
+
+    #[tracing::instrument(name = "PostgresOAuthConsentRepository::find_active_by_user", skip_all, fields(db.system = "postgresql"))]
+    async fn find_active_by_user(&self, user_id: Uuid) -> SystemResult<Vec<OAuthConsent>> {
+        log::info!("find_active_by_user() called with user_id: {}", user_id);
+
+        let rows = sqlx::query_as::<_, OAuthConsent>(
+            r#"
+            SELECT id, user_id, client_id, scopes, granted_at, revoked_at
+            FROM oauth_consents
+            WHERE user_id = $1 AND revoked_at IS NULL
+            ORDER BY granted_at DESC
+            "#
+        )
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows)
+    }
+
+    #[tracing::instrument(name = "PostgresOAuthConsentRepository::update", skip_all, fields(db.system = "postgresql"))]
+    async fn update(&self, consent: &OAuthConsent) -> SystemResult<OAuthConsent> {
+        log::info!("update() called with consent: {}", consent.id);
+
+        sqlx::query(
+            r#"
+            UPDATE oauth_consents
+            SET scopes = $1, revoked_at = $2
+            WHERE id = $3
+            "#
+        )
+        .bind(&consent.scopes)
+        .bind(consent.revoked_at)
+        .bind(consent.id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(consent.clone())
+    }
+}
@@ -0,0 +1,451 @@
+use crate::domain::entities::organisation::Organisation;
+use crate::domain::entities::organisation_invitation::OrganisationInvitation;
+use crate::domain::entities::organisation_membership::OrganisationMembership;
+use crate::domain::repositories::organisation_repository::{
+    OrganisationInvitationRepository, OrganisationMembershipRepository, OrganisationRepository,
+};
+use async_trait::async_trait;
+use shared::entities::enums::OrganisationRole;
+use shared::utils::messaging::outbox::{Outbox, OutboxEvent};
+use shared::features::errors::{SystemError, SystemResult};
+use sqlx::postgres::PgRow;
+use sqlx::{Pool, Postgres, Row};
+use uuid::Uuid;
+
+fn parse_role(row: &PgRow) -> SystemResult<OrganisationRole> {
+    row.get::<String, _>("role")
+        .parse::<OrganisationRole>()
+        .map_err(SystemError::ValidationError)
+}
+
+pub struct PostgresOrganisationRepository {
+    pool: Pool<Postgres>,
+}
+
+impl PostgresOrganisationRepository {
+    pub fn new(pool: Pool<Postgres>) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl OrganisationRepository for PostgresOrganisationRepository {
+    #[tracing::instrument(name = "PostgresOrganisationRepository::create_with_owner", skip_all, fields(db.system = "postgresql"))]
+    async fn create_with_owner(
+        &self,
+        organisation: &Organisation,
+        owner: &OrganisationMembership,
+    ) -> SystemResult<Organisation> {
+        log::info!("create_with_owner() called with organisation: {}", organisation.id);
+
+        let mut tx = self.pool.begin().await?;
+
+        let row = sqlx::query_as::<_, Organisation>(
+            r#"
+            INSERT INTO organisations (
+            id,
+            name,
+            slug,
+            created_by,
+            is_active,
+            created_at,
+            updated_at
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7)
+            RETURNING id, name, slug, created_by, is_active, created_at, updated_at
+            "#
+        )
+        .bind(organisation.id)
+        .bind(&organisation.name)
+        .bind(&organisation.slug)
+        .bind(organisation.created_by)
+        .bind(organisation.is_active)
+        .bind(organisation.created_at)
+        .bind(organisation.updated_at)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO organisation_memberships (id, organisation_id, user_id, role, last_active_at, created_at, updated_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            "#
+        )
+        .bind(owner.id)
+        .bind(owner.organisation_id)
+        .bind(owner.user_id)
+        .bind(owner.role.to_string())
+        .bind(owner.last_active_at)
+        .bind(owner.created_at)
+        .bind(owner.updated_at)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(row)
+    }
+
+    #[tracing::instrument(name = "PostgresOrganisationRepository::find_by_id", skip_all, fields(db.system = "postgresql"))]
+    async fn find_by_id(&self, id: Uuid) -> SystemResult<Option<Organisation>> {
+        log::info!("find_by_id() called with id: {}", id);
+
+        let row = sqlx::query_as::<_, Organisation>(
+            r#"
+            SELECT id, name, slug, created_by, is_active, created_at, updated_at
+            FROM organisations
+            WHERE id = $1
+            "#
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row)
+    }
+
+    #[tracing::instrument(name = "PostgresOrganisationRepository::exists_by_slug", skip_all, fields(db.system = "postgresql"))]
+    async fn exists_by_slug(&self, slug: &str) -> SystemResult<bool> {
+        log::info!("exists_by_slug() called with slug: {}", slug);
+
+        let exists: bool = sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM organisations WHERE slug = $1)")
+            .bind(slug)
+            .fetch_one(&self.pool)
+            .await?;
+
+        Ok(exists)
+    }
+
+    #[tracing::instrument(name = "PostgresOrganisationRepository::find_by_member", skip_all, fields(db.system = "postgresql"))]
+    async fn find_by_member(&self, user_id: Uuid) -> SystemResult<Vec<Organisation>> {
+        log::info!("find_by_member() called with user_id: {}", user_id);
+
+        let rows = sqlx::query_as::<_, Organisation>(
+            r#"
+            SELECT o.id, o.name, o.slug, o.created_by, o.is_active, o.created_at, o.updated_at
+            FROM organisations o
+            JOIN organisation_memberships m ON m.organisation_id = o.id
+            WHERE m.user_id = $1 AND o.is_active = true
+            ORDER BY o.name
+            "#
+        )
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows)
+    }
+}
+
+pub struct PostgresOrganisationMembershipRepository {
+    pool: Pool<Postgres>,
+}
+
+impl PostgresOrganisationMembershipRepository {
+    pub fn new(pool: Pool<Postgres>) -> Self {
+        Self { pool }
+    }
+
+    fn map_row(row: PgRow) -> SystemResult<OrganisationMembership> {
+        Ok(OrganisationMembership {
+            id: row.get("id"),
+            organisation_id: row.get("organisation_id"),
+            user_id: row.get("user_id"),
+            role: parse_role(&row)?,
+            last_active_at: row.get("last_active_at"),
+            created_at: row.get("created_at"),
+            updated_at: row.get("updated_at"),
+        })
+    }
+}
+
+#[async_trait]
+impl OrganisationMembershipRepository for PostgresOrganisationMembershipRepository {
+    #[tracing::instrument(name = "PostgresOrganisationMembershipRepository::create", skip_all, fields(db.system = "postgresql"))]
+    async fn create(&self, membership: &OrganisationMembership) -> SystemResult<OrganisationMembership> {
+        log::info!(
+            "create() called with organisation_id: {}, user_id: {}",
+            membership.organisation_id,
+            membership.user_id
+        );
+
+        let row = sqlx::query(
+            r#"
+            INSERT INTO organisation_memberships (id, organisation_id, user_id, role, last_active_at, created_at, updated_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            RETURNING id, organisation_id, user_id, role, last_active_at, created_at, updated_at
+            "#
+        )
+        .bind(membership.id)
+        .bind(membership.organisation_id)
+        .bind(membership.user_id)
+        .bind(membership.role.to_string())
+        .bind(membership.last_active_at)
+        .bind(membership.created_at)
+        .bind(membership.updated_at)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Self::map_row(row)
+    }
+
+    #[tracing::instrument(name = "PostgresOrganisationMembershipRepository::find", skip_all, fields(db.system = "postgresql"))]
+    async fn find(&self, organisation_id: Uuid, user_id: Uuid) -> SystemResult<Option<OrganisationMembership>> {
+        log::info!(
+            "find() called with organisation_id: {}, user_id: {}",
+            organisation_id,
+            user_id
+        );
+
+        let row = sqlx::query(
+            r#"
+            SELECT id, organisation_id, user_id, role, last_active_at, created_at, updated_at
+            FROM organisation_memberships
+            WHERE organisation_id = $1 AND user_id = $2
+            "#
+        )
+        .bind(organisation_id)
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        row.map(Self::map_row).transpose()
+    }
+
+    #[tracing::instrument(name = "PostgresOrganisationMembershipRepository::find_by_user", skip_all, fields(db.system = "postgresql"))]
+    async fn find_by_user(&self, user_id: Uuid) -> SystemResult<Vec<OrganisationMembership>> {
+        log::info!("find_by_user() called with user_id: {}", user_id);
+
+        let rows = sqlx::query(
+            r#"
+            SELECT id, organisation_id, user_id, role, last_active_at, created_at, updated_at
+            FROM organisation_memberships
+            WHERE user_id = $1
+            ORDER BY created_at
+            "#
+        )
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.into_iter().map(Self::map_row).collect()
+    }
+
+    #[tracing::instrument(name = "PostgresOrganisationMembershipRepository::find_by_organisation", skip_all, fields(db.system = "postgresql"))]
+    async fn find_by_organisation(&self, organisation_id: Uuid) -> SystemResult<Vec<OrganisationMembership>> {
+        log::info!("find_by_organisation() called with organisation_id: {}", organisation_id);
+
+        let rows = sqlx::query(
+            r#"
+            SELECT id, organisation_id, user_id, role, last_active_at, created_at, updated_at
+            FROM organisation_memberships
+            WHERE organisation_id = $1
+            ORDER BY created_at
+            "#
+        )
+        .bind(organisation_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.into_iter().map(Self::map_row).collect()
+    }
+
+    #[tracing::instrument(name = "PostgresOrganisationMembershipRepository::find_default_for_user", skip_all, fields(db.system = "postgresql"))]
+    async fn find_default_for_user(&self, user_id: Uuid) -> SystemResult<Option<OrganisationMembership>> {
+        log::info!("find_default_for_user() called with user_id: {}", user_id);
+
+        let row = sqlx::query(
+            r#"
+            SELECT m.id, m.organisation_id, m.user_id, m.role, m.last_active_at, m.created_at, m.updated_at
+            FROM organisation_memberships m
+            JOIN organisations o ON o.id = m.organisation_id
+            WHERE m.user_id = $1 AND o.is_active = true
+            ORDER BY m.last_active_at DESC NULLS LAST, m.created_at
+            LIMIT 1
+            "#
+        )
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        row.map(Self::map_row).transpose()
+    }
+
+    #[tracing::instrument(name = "PostgresOrganisationMembershipRepository::update", skip_all, fields(db.system = "postgresql"))]
+    async fn update(&self, membership: &OrganisationMembership) -> SystemResult<OrganisationMembership> {
+        log::info!("update() called with membership: {}", membership.id);
+
+        let row = sqlx::query(
+            r#"
+            UPDATE organisation_memberships
+            SET role = $1, last_active_at = $2
+            WHERE id = $3
+            RETURNING id, organisation_id, user_id, role, last_active_at, created_at, updated_at
+            "#
+        )
+        .bind(membership.role.to_string())
+        .bind(membership.last_active_at)
+        .bind(membership.id)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Self::map_row(row)
+    }
+
+    #[tracing::instrument(name = "PostgresOrganisationMembershipRepository::delete", skip_all, fields(db.system = "postgresql"))]
+    async fn delete(&self, id: Uuid) -> SystemResult<()> {
+        log::info!("delete() called with id: {}", id);
+
+        sqlx::query("DELETE FROM organisation_memberships WHERE id = $1")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(name = "PostgresOrganisationMembershipRepository::count_owners", skip_all, fields(db.system = "postgresql"))]
+    async fn count_owners(&self, organisation_id: Uuid) -> SystemResult<i64> {
+        log::info!("count_owners() called with organisation_id: {}", organisation_id);
+
+        let count: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM organisation_memberships WHERE organisation_id = $1 AND role = 'owner'",
+        )
+        .bind(organisation_id)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(count)
+    }
+}
+
+pub struct PostgresOrganisationInvitationRepository {
+    pool: Pool<Postgres>,
+}
+
+impl PostgresOrganisationInvitationRepository {
+    pub fn new(pool: Pool<Postgres>) -> Self {
+        Self { pool }
+    }
+
+    fn map_row(row: PgRow) -> SystemResult<OrganisationInvitation> {
+        Ok(OrganisationInvitation {
+            id: row.get("id"),
+            organisation_id: row.get("organisation_id"),
+            email: row.get("email"),
+            role: parse_role(&row)?,
+            token_hash: row.get("token_hash"),
+            invited_by: row.get("invited_by"),
+            expires_at: row.get("expires_at"),
+            accepted_at: row.get("accepted_at"),
+            revoked_at: row.get("revoked_at"),
+            created_at: row.get("created_at"),
+        })
+    }
+}
+
+#[async_trait]
+impl OrganisationInvitationRepository for PostgresOrganisationInvitationRepository {
+    #[tracing::instrument(name = "PostgresOrganisationInvitationRepository::create_with_event", skip_all, fields(db.system = "postgresql"))]
+    async fn create_with_event(
+        &self,
+        invitation: &OrganisationInvitation,
+        event: &OutboxEvent,
+    ) -> SystemResult<OrganisationInvitation> {
+        log::info!("create_with_event() called with invitation: {}", invitation.id);
+
+        let mut tx = self.pool.begin().await?;
+
+        let row = sqlx::query(
+            r#"
+            INSERT INTO organisation_invitations (
+            id,
+            organisation_id,
+            email,
+            role,
+            token_hash,
+            invited_by,
+            expires_at,
+            created_at
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            RETURNING id, organisation_id, email, role, token_hash, invited_by, expires_at,
+                accepted_at, revoked_at, created_at
+            "#
+        )
+        .bind(invitation.id)
+        .bind(invitation.organisation_id)
+        .bind(&invitation.email)
+        .bind(invitation.role.to_string())
+        .bind(&invitation.token_hash)
+        .bind(invitation.invited_by)
+        .bind(invitation.expires_at)
+        .bind(invitation.created_at)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        Outbox::enqueue(&mut tx, event).await?;
+        tx.commit().await?;
+
+        Self::map_row(row)
+    }
+
+    #[tracing::instrument(name = "PostgresOrganisationInvitationRepository::find_by_id", skip_all, fields(db.system = "postgresql"))]
+    async fn find_by_id(&self, id: Uuid) -> SystemResult<Option<OrganisationInvitation>> {
+        log::info!("find_by_id() called with id: {}", id);
+
+        let row = sqlx::query(
+            r#"
+            SELECT id, organisation_id, email, role, token_hash, invited_by, expires_at,
+                accepted_at, revoked_at, created_at
+            FROM organisation_invitations
+            WHERE id = $1
+            "#
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        row.map(Self::map_row).transpose()
+    }
+
+    #[tracing::instrument(name = "PostgresOrganisationInvitationRepository::find_pending_by_organisation", skip_all, fields(db.system = "postgresql"))]
+    async fn find_pending_by_organisation(&self, organisation_id: Uuid) -> SystemResult<Vec<OrganisationInvitation>> {
+        log::info!("find_pending_by_organisation() called with organisation_id: {}", organisation_id);
+
+        let rows = sqlx::query(
+            r#"
+            SELECT id, organisation_id, email, role, token_hash, invited_by, expires_at,
+                accepted_at, revoked_at, created_at
+            FROM organisation_invitations
+            WHERE organisation_id = $1 AND accepted_at IS NULL AND revoked_at IS NULL AND expires_at > NOW()
+            ORDER BY created_at DESC
+            "#
+        )
+        .bind(organisation_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.into_iter().map(Self::map_row).collect()
+    }
+
+    #[tracing::instrument(name = "PostgresOrganisationInvitationRepository::update", skip_all, fields(db.system = "postgresql"))]
+    async fn update(&self, invitation: &OrganisationInvitation) -> SystemResult<OrganisationInvitation> {
+        log::info!("update() called with invitation: {}", invitation.id);
+
+        let row = sqlx::query(
+            r#"
+            UPDATE organisation_invitations
+            SET accepted_at = $1, revoked_at = $2
+            WHERE id = $3
+            RETURNING id, organisation_id, email, role, token_hash, invited_by, expires_at,
+                accepted_at, revoked_at, created_at
+            "#
+        )
+        .bind(invitation.accepted_at)
+        .bind(invitation.revoked_at)
+        .bind(invitation.id)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Self::map_row(row)
+    }
+}
@@ -1,7 +1,9 @@
 use crate::domain::entities::password_reset_token::PasswordResetToken;
+use crate::domain::entities::user::User;
 use crate::domain::repositories::password_reset_repository::PasswordResetRepository;
 use async_trait::async_trait;
 use shared::features::errors::SystemResult;
+use shared::utils::messaging::outbox::{Outbox, OutboxEvent};
 use sqlx::{Pool, Postgres};
 use uuid::Uuid;
 
@@ -17,8 +19,11 @@ impl PostgresPasswordResetRepository {
 
 #[async_trait]
 impl PasswordResetRepository for PostgresPasswordResetRepository {
-    async fn create(&self, token: &PasswordResetToken) -> SystemResult<PasswordResetToken> {
-        log::info!("create() called with security: {:?}", token);
+    #[tracing::instrument(name = "PostgresPasswordResetRepository::create_with_event", skip_all, fields(db.system = "postgresql"))]
+    async fn create_with_event(&self, token: &PasswordResetToken, event: &OutboxEvent) -> SystemResult<PasswordResetToken> {
+        log::info!("create_with_event() called with user_id: {}", token.user_id);
+
+        let mut tx = self.pool.begin().await?;
 
         let row = sqlx::query_as::<_, PasswordResetToken>(
             r#"
@@ -41,12 +46,16 @@ impl PasswordResetRepository for PostgresPasswordResetRepository {
         .bind(token.is_used)
         .bind(token.created_at)
         .bind(&token.used_at)
-        .fetch_one(&self.pool)
+        .fetch_one(&mut *tx)
         .await?;
 
+        Outbox::enqueue(&mut tx, event).await?;
+        tx.commit().await?;
+
         Ok(row)
     }
 
+    #[tracing::instrument(name = "PostgresPasswordResetRepository::find_by_token_hash", skip_all, fields(db.system = "postgresql"))]
     async fn find_by_token_hash(&self, token_hash: &str) -> SystemResult<Option<PasswordResetToken>> {
         log::info!(
             "find_by_token_hash() called with token_hash: {}",
@@ -68,38 +77,57 @@ impl PasswordResetRepository for PostgresPasswordResetRepository {
         Ok(row)
     }
 
-    async fn update(&self, token: &PasswordResetToken) -> SystemResult<PasswordResetToken> {
-        log::info!("update() called with security: {:?}", token);
+    #[tracing::instrument(name = "PostgresPasswordResetRepository::complete_with_event", skip_all, fields(db.system = "postgresql"))]
+    async fn complete_with_event(
+        &self,
+        token: &PasswordResetToken,
+        user: &User,
+        event: &OutboxEvent,
+    ) -> SystemResult<PasswordResetToken> {
+        log::info!("complete_with_event() called with token: {}", token.id);
+
+        let mut tx = self.pool.begin().await?;
 
         sqlx::query(
+            r#"
+            UPDATE users
+            SET
+            password_hash = $1,
+            failed_login_attempts = $2,
+            locked_until = $3
+            WHERE id = $4
+            "#
+        )
+        .bind(&user.password_hash)
+        .bind(user.failed_login_attempts)
+        .bind(user.locked_until)
+        .bind(user.id)
+        .execute(&mut *tx)
+        .await?;
+
+        let updated_token = sqlx::query_as::<_, PasswordResetToken>(
             r#"
             UPDATE password_reset_tokens
             SET
             is_used = $1,
             used_at = $2
             WHERE id = $3
+            RETURNING id, user_id, token_hash, expires_at, is_used, created_at, used_at
             "#
         )
         .bind(token.is_used)
         .bind(&token.used_at)
         .bind(token.id)
-        .execute(&self.pool)
+        .fetch_one(&mut *tx)
         .await?;
 
-        let updated_token = sqlx::query_as::<_, PasswordResetToken>(
-            r#"
-            SELECT id, user_id, token_hash, expires_at, is_used, created_at, used_at
-            FROM password_reset_tokens
-            WHERE id = $1
-            "#
-        )
-        .bind(token.id)
-        .fetch_one(&self.pool)
-        .await?;
+        Outbox::enqueue(&mut tx, event).await?;
+        tx.commit().await?;
 
         Ok(updated_token)
     }
 
+    #[tracing::instrument(name = "PostgresPasswordResetRepository::cleanup_expired", skip_all, fields(db.system = "postgresql"))]
     async fn cleanup_expired(&self) -> SystemResult<u64> {
         log::info!("cleanup_expired() called");
 
@@ -115,6 +143,7 @@ impl PasswordResetRepository for PostgresPasswordResetRepository {
         Ok(result.rows_affected())
     }
 
+    #[tracing::instrument(name = "PostgresPasswordResetRepository::revoke_all_for_user", skip_all, fields(db.system = "postgresql"))]
     async fn revoke_all_for_user(&self, user_id: Uuid) -> SystemResult<()> {
         log::info!("revoke_all_for_user() called with user_id: {}", user_id);
 
@@ -5,6 +5,10 @@ use shared::features::errors::SystemResult;
 use sqlx::{Pool, Postgres};
 use uuid::Uuid;
 
+// `ip_address` is INET; it goes in as text and comes back without the /32
+const COLUMNS: &str = "id, user_id, token_hash, device_id, device_name, host(ip_address) AS ip_address, \
+                       user_agent, expires_at, is_revoked, created_at, revoked_at";
+
 pub struct PostgresRefreshTokenRepository {
     pool: Pool<Postgres>,
 }
@@ -17,36 +21,126 @@ impl PostgresRefreshTokenRepository {
 
 #[async_trait]
 impl RefreshTokenRepository for PostgresRefreshTokenRepository {
+    #[tracing::instrument(name = "PostgresRefreshTokenRepository::create", skip_all, fields(db.system = "postgresql"))]
     async fn create(&self, token: &RefreshToken) -> SystemResult<RefreshToken> {
-        log::info!("create() called with security: {:?}", token);
-        Ok(token.clone())
+        log::info!("create() called with security: {}", token.id);
+
+        let row = sqlx::query_as::<_, RefreshToken>(&format!(
+            r#"
+            INSERT INTO refresh_tokens (
+            id,
+            user_id,
+            token_hash,
+            device_id,
+            device_name,
+            ip_address,
+            user_agent,
+            expires_at,
+            is_revoked,
+            created_at,
+            revoked_at
+            ) VALUES ($1, $2, $3, $4, $5, $6::inet, $7, $8, $9, $10, $11)
+            RETURNING {}
+            "#,
+            COLUMNS
+        ))
+        .bind(token.id)
+        .bind(token.user_id)
+        .bind(&token.token_hash)
+        .bind(&token.device_id)
+        .bind(&token.device_name)
+        .bind(&token.ip_address)
+        .bind(&token.user_agent)
+        .bind(token.expires_at)
+        .bind(token.is_revoked)
+        .bind(token.created_at)
+        .bind(token.revoked_at)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(row)
     }
 
+    #[tracing::instrument(name = "PostgresRefreshTokenRepository::find_by_token_hash", skip_all, fields(db.system = "postgresql"))]
     async fn find_by_token_hash(&self, token_hash: &str) -> SystemResult<Option<RefreshToken>> {
-        log::info!(
-            "find_by_token_hash() called with token_hash: {}",
-            token_hash
-        );
-        Ok(None)
+        log::info!("find_by_token_hash() called");
+
+        let row = sqlx::query_as::<_, RefreshToken>(&format!(
+            "SELECT {} FROM refresh_tokens WHERE token_hash = $1 LIMIT 1",
+            COLUMNS
+        ))
+        .bind(token_hash)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row)
     }
 
+    #[tracing::instrument(name = "PostgresRefreshTokenRepository::find_by_user_id", skip_all, fields(db.system = "postgresql"))]
     async fn find_by_user_id(&self, user_id: Uuid) -> SystemResult<Vec<RefreshToken>> {
         log::info!("find_by_user_id() called with user_id: {}", user_id);
-        Ok(vec![])
+
+        let rows = sqlx::query_as::<_, RefreshToken>(&format!(
+            "SELECT {} FROM refresh_tokens WHERE user_id = $1 ORDER BY created_at DESC",
+            COLUMNS
+        ))
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows)
     }
 
+    #[tracing::instrument(name = "PostgresRefreshTokenRepository::update", skip_all, fields(db.system = "postgresql"))]
     async fn update(&self, token: &RefreshToken) -> SystemResult<RefreshToken> {
-        log::info!("update() called with security: {:?}", token);
-        Ok(token.clone())
+        log::info!("update() called with security: {}", token.id);
+
+        let row = sqlx::query_as::<_, RefreshToken>(&format!(
+            r#"
+            UPDATE refresh_tokens
+            SET
+            is_revoked = $1,
+            revoked_at = $2
+            WHERE id = $3
+            RETURNING {}
+            "#,
+            COLUMNS
+        ))
+        .bind(token.is_revoked)
+        .bind(token.revoked_at)
+        .bind(token.id)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(row)
     }
 
+    #[tracing::instrument(name = "PostgresRefreshTokenRepository::revoke_all_for_user", skip_all, fields(db.system = "postgresql"))]
     async fn revoke_all_for_user(&self, user_id: Uuid) -> SystemResult<()> {
         log::info!("revoke_all_for_user() called with user_id: {}", user_id);
+
+        sqlx::query(
+            r#"
+            UPDATE refresh_tokens
+            SET is_revoked = true, revoked_at = NOW()
+            WHERE user_id = $1 AND is_revoked = false
+            "#,
+        )
+        .bind(user_id)
+        .execute(&self.pool)
+        .await?;
+
         Ok(())
     }
 
+    #[tracing::instrument(name = "PostgresRefreshTokenRepository::cleanup_expired", skip_all, fields(db.system = "postgresql"))]
     async fn cleanup_expired(&self) -> SystemResult<u64> {
         log::info!("cleanup_expired() called");
-        Ok(0)
+
+        let result = sqlx::query("DELETE FROM refresh_tokens WHERE expires_at < NOW()")
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected())
     }
 }
@@ -20,6 +20,7 @@ impl PostgresSecurityQuestionRepository {
 
 #[async_trait]
 impl SecurityQuestionRepository for PostgresSecurityQuestionRepository {
+    #[tracing::instrument(name = "PostgresSecurityQuestionRepository::get_all_questions", skip_all, fields(db.system = "postgresql"))]
     async fn get_all_questions(&self) -> SystemResult<Vec<SecurityQuestion>> {
         log::info!("get_all_questions() called");
 
@@ -47,6 +48,7 @@ impl SecurityQuestionRepository for PostgresSecurityQuestionRepository {
         Ok(questions)
     }
 
+    #[tracing::instrument(name = "PostgresSecurityQuestionRepository::get_active_questions", skip_all, fields(db.system = "postgresql"))]
     async fn get_active_questions(&self) -> SystemResult<Vec<SecurityQuestion>> {
         log::info!("get_active_questions() called");
 
@@ -75,6 +77,7 @@ impl SecurityQuestionRepository for PostgresSecurityQuestionRepository {
         Ok(questions)
     }
 
+    #[tracing::instrument(name = "PostgresSecurityQuestionRepository::find_by_id", skip_all, fields(db.system = "postgresql"))]
     async fn find_by_id(&self, id: Uuid) -> SystemResult<Option<SecurityQuestion>> {
         log::info!("find_by_id() called with id: {}", id);
 
@@ -112,11 +115,12 @@ impl PostgresUserSecurityQuestionRepository {
 
 #[async_trait]
 impl UserSecurityQuestionRepository for PostgresUserSecurityQuestionRepository {
+    #[tracing::instrument(name = "PostgresUserSecurityQuestionRepository::create", skip_all, fields(db.system = "postgresql"))]
     async fn create(
         &self,
         user_question: &UserSecurityQuestion,
     ) -> SystemResult<UserSecurityQuestion> {
-        log::info!("create() called with user_question: {:?}", user_question);
+        log::info!("create() called with user_id: {}, question_id: {}", user_question.user_id, user_question.question_id);
 
         let row = sqlx::query_as::<_, UserSecurityQuestion>(
             r#"
@@ -146,6 +150,7 @@ impl UserSecurityQuestionRepository for PostgresUserSecurityQuestionRepository {
         Ok(created)
     }
 
+    #[tracing::instrument(name = "PostgresUserSecurityQuestionRepository::find_by_user_id", skip_all, fields(db.system = "postgresql"))]
     async fn find_by_user_id(&self, user_id: Uuid) -> SystemResult<Vec<UserSecurityQuestion>> {
         log::info!("find_by_user_id() called with user_id: {}", user_id);
 
@@ -175,11 +180,12 @@ impl UserSecurityQuestionRepository for PostgresUserSecurityQuestionRepository {
         Ok(user_questions)
     }
 
+    #[tracing::instrument(name = "PostgresUserSecurityQuestionRepository::update", skip_all, fields(db.system = "postgresql"))]
     async fn update(
         &self,
         user_question: &UserSecurityQuestion,
     ) -> SystemResult<UserSecurityQuestion> {
-        log::info!("update() called with user_question: {:?}", user_question);
+        log::info!("update() called with user_question: {}", user_question.id);
         
         let row = sqlx::query_as::<_, UserSecurityQuestion>(
             r#"
@@ -207,6 +213,7 @@ impl UserSecurityQuestionRepository for PostgresUserSecurityQuestionRepository {
         Ok(updated)
     }
 
+    #[tracing::instrument(name = "PostgresUserSecurityQuestionRepository::delete_by_user_id", skip_all, fields(db.system = "postgresql"))]
     async fn delete_by_user_id(&self, user_id: Uuid) -> SystemResult<()> {
         log::info!("delete_by_user_id() called with user_id: {}", user_id);
         
@@ -223,6 +230,7 @@ impl UserSecurityQuestionRepository for PostgresUserSecurityQuestionRepository {
         Ok(())
     }
 
+    #[tracing::instrument(name = "PostgresUserSecurityQuestionRepository::find_by_user_and_question", skip_all, fields(db.system = "postgresql"))]
     async fn find_by_user_and_question(
         &self,
         user_id: Uuid,
@@ -0,0 +1,86 @@
+use crate::domain::entities::user_totp::UserTotp;
+use crate::domain::repositories::totp_repository::TotpRepository;
+use async_trait::async_trait;
+use shared::features::errors::SystemResult;
+use sqlx::{Pool, Postgres};
+use uuid::Uuid;
+
+pub struct PostgresTotpRepository {
+    pool: Pool<Postgres>,
+}
+
+impl PostgresTotpRepository {
+    pub fn new(pool: Pool<Postgres>) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl TotpRepository for PostgresTotpRepository {
+    #[tracing::instrument(name = "PostgresTotpRepository::upsert", skip_all, fields(db.system = "postgresql"))]
+    async fn upsert(&self, totp: &UserTotp) -> SystemResult<UserTotp> {
+        log::info!("upsert() called with user_id: {}", totp.user_id);
+
+        let row = sqlx::query_as::<_, UserTotp>(
+            r#"
+            INSERT INTO user_totp_factors (user_id, secret, confirmed_at, last_used_step, created_at, updated_at)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            ON CONFLICT (user_id) DO UPDATE
+            SET secret = EXCLUDED.secret,
+                confirmed_at = EXCLUDED.confirmed_at,
+                last_used_step = EXCLUDED.last_used_step,
+                created_at = EXCLUDED.created_at
+            RETURNING user_id, secret, confirmed_at, last_used_step, created_at, updated_at
+            "#
+        )
+        .bind(totp.user_id)
+        .bind(&totp.secret)
+        .bind(totp.confirmed_at)
+        .bind(totp.last_used_step)
+        .bind(totp.created_at)
+        .bind(totp.updated_at)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(row)
+    }
+
+    #[tracing::instrument(name = "PostgresTotpRepository::find_by_user", skip_all, fields(db.system = "postgresql"))]
+    async fn find_by_user(&self, user_id: Uuid) -> SystemResult<Option<UserTotp>> {
+        log::info!("find_by_user() called with user_id: {}", user_id);
+
+        let row = sqlx::query_as::<_, UserTotp>(
+            r#"
+            SELECT user_id, secret, confirmed_at, last_used_step, created_at, updated_at
+            FROM user_totp_factors
+            WHERE user_id = $1
+            "#
+        )
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row)
+    }
+
+    #[tracing::instrument(name = "PostgresTotpRepository::update", skip_all, fields(db.system = "postgresql"))]
+    async fn update(&self, totp: &UserTotp) -> SystemResult<UserTotp> {
+        log::info!("update() called with user_id: {}", totp.user_id);
+
+        let row = sqlx::query_as::<_, UserTotp>(
+            r#"
+            UPDATE user_totp_factors
+            SET confirmed_at = $1, last_used_step = $2
+            WHERE user_id = $3
+            RETURNING user_id, secret, confirmed_at, last_used_step, created_at, updated_at
+            "#
+        )
+        .bind(totp.confirmed_at)
+        .bind(totp.last_used_step)
+        .bind(totp.user_id)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(row)
+    }
+}
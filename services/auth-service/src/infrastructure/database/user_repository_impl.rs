@@ -17,15 +17,16 @@ impl PostgresUserRepository {
 
 #[async_trait]
 impl UserRepository for PostgresUserRepository {
+    #[tracing::instrument(name = "PostgresUserRepository::create", skip_all, fields(db.system = "postgresql"))]
     async fn create(&self, user: &User) -> SystemResult<User> {
-        log::info!("create() called with user: {:?}", user);
+        log::info!("create() called with user: {}", user.id);
 
         // Insert the user into the database
         let row = match sqlx::query(
             "INSERT INTO users (
                 id, email, phone_number, password_hash, role,
                 is_verified, is_active, failed_login_attempts, locked_until,
-                last_login, created_at, updated_at
+                last_login_at, created_at, updated_at
             ) VALUES (
                 $1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12
             ) RETURNING id"
@@ -56,6 +57,7 @@ impl UserRepository for PostgresUserRepository {
         self.find_by_id(&id).await?.ok_or(SystemError::DatabaseError("User not found after creation".into()))
     }
 
+    #[tracing::instrument(name = "PostgresUserRepository::find_by_id", skip_all, fields(db.system = "postgresql"))]
     async fn find_by_id(&self, id: &Uuid) -> SystemResult<Option<User>> {
         log::info!("find_by_id() called with id: {}", id);
 
@@ -99,6 +101,7 @@ impl UserRepository for PostgresUserRepository {
         Ok(None)
     }
 
+    #[tracing::instrument(name = "PostgresUserRepository::find_by_email", skip_all, fields(db.system = "postgresql"))]
     async fn find_by_email(&self, email: &str) -> SystemResult<Option<User>> {
         log::info!("find_by_email() called with email: {}", email);
 
@@ -142,6 +145,7 @@ impl UserRepository for PostgresUserRepository {
         Ok(None)
     }
 
+    #[tracing::instrument(name = "PostgresUserRepository::find_by_phone", skip_all, fields(db.system = "postgresql"))]
     async fn find_by_phone(&self, phone: &str) -> SystemResult<Option<User>> {
         log::info!("find_by_phone() called with phone: {}", phone);
 
@@ -178,8 +182,9 @@ impl UserRepository for PostgresUserRepository {
         Ok(None)
     }
 
+    #[tracing::instrument(name = "PostgresUserRepository::update", skip_all, fields(db.system = "postgresql"))]
     async fn update(&self, user: &User) -> SystemResult<User> {
-        log::info!("update() called with user: {:?}", user);
+        log::info!("update() called with user: {}", user.id);
 
         // Update the user in the database
         let result = sqlx::query(
@@ -215,6 +220,7 @@ impl UserRepository for PostgresUserRepository {
         Ok(user.clone())
     }
 
+    #[tracing::instrument(name = "PostgresUserRepository::delete", skip_all, fields(db.system = "postgresql"))]
     async fn delete(&self, id: &Uuid) -> SystemResult<()> {
         log::info!("delete() called with id: {}", id);
 
@@ -234,12 +240,14 @@ impl UserRepository for PostgresUserRepository {
         Ok(())
     }
 
+    #[tracing::instrument(name = "PostgresUserRepository::exists_by_email", skip_all, fields(db.system = "postgresql"))]
     async fn exists_by_email(&self, email: &str) -> SystemResult<bool> {
         log::info!("exists_by_email() called with email: {}", email);
         let row = sqlx::query("SELECT EXISTS(SELECT 1 FROM users WHERE email = $1)").bind(email).fetch_one(&self.pool).await?;
         Ok(row.get::<bool, _>(0))
     }
 
+    #[tracing::instrument(name = "PostgresUserRepository::exists_by_phone", skip_all, fields(db.system = "postgresql"))]
     async fn exists_by_phone(&self, phone: &str) -> SystemResult<bool> {
         log::info!("exists_by_phone() called with phone: {}", phone);
         let row = sqlx::query("SELECT EXISTS(SELECT 1 FROM users WHERE phone_number = $1)").bind(phone).fetch_one(&self.pool).await?;
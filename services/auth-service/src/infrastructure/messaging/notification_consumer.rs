@@ -1,91 +1,135 @@
-use lapin::options::BasicAckOptions;
+use std::future::Future;
+use std::sync::Arc;
 use tokio::sync::broadcast;
-use shared::events::{ExchangeType, RoutingKey};
-use shared::features::errors::{SystemError, SystemResult};
+use shared::events::notification_event::broadcast_event::BroadcastEvent;
+use shared::events::notification_event::email_otp_requested_event::EmailOtpRequestedEvent;
+use shared::events::notification_event::organisation_invitation_event::OrganisationInvitationEvent;
+use shared::events::notification_event::password_changed_event::PasswordChangedEvent;
+use shared::events::notification_event::password_reset_requested_event::PasswordResetRequestedEvent;
+use shared::events::notification_event::sms_otp_requested_event::SmsOtpRequestedEvent;
+use shared::events::{DomainEvent, EventEnvelope};
+use shared::features::errors::SystemResult;
+use shared::utils::messaging::inbox::{deduplicate, ProcessedEventLedger};
+use shared::utils::messaging::retry::MessageError;
 use shared::utils::messaging::MessageBroker;
 
+// Each notification event gets its own queue, `<prefix>.<event type>`, so a
+// failing event type cannot hold up the others. Handlers run at most once
+// per event: redeliveries are skipped using the processed-events ledger.
 pub struct NotificationConsumer {
     broker: MessageBroker,
+    queue_prefix: String,
+    ledger: Arc<dyn ProcessedEventLedger>,
 }
 
 impl NotificationConsumer {
-    pub fn new(broker: MessageBroker) -> Self {
-        Self { broker }
+    pub fn new(broker: MessageBroker, queue_prefix: &str, ledger: Arc<dyn ProcessedEventLedger>) -> Self {
+        Self {
+            broker,
+            queue_prefix: queue_prefix.to_string(),
+            ledger,
+        }
     }
 
-    pub async fn setup(&self, queue_name: &str, routing_keys: &[RoutingKey], exchange: ExchangeType) -> SystemResult<()> {
-        self.broker.setup_queue(queue_name, routing_keys, exchange).await
+    pub fn queue_name<E: DomainEvent>(&self) -> String {
+        Self::queue_name_for::<E>(&self.queue_prefix)
     }
 
-    pub async fn consume(&self, queue_name: &str, shutdown_rx: broadcast::Receiver<()>) -> SystemResult<()> {
+    fn queue_name_for<E: DomainEvent>(queue_prefix: &str) -> String {
+        format!("{}.{}", queue_prefix, E::EVENT_TYPE)
+    }
+
+    pub fn queue_names(queue_prefix: &str) -> Vec<String> {
+        vec![
+            Self::queue_name_for::<EmailOtpRequestedEvent>(queue_prefix),
+            Self::queue_name_for::<SmsOtpRequestedEvent>(queue_prefix),
+            Self::queue_name_for::<PasswordResetRequestedEvent>(queue_prefix),
+            Self::queue_name_for::<PasswordChangedEvent>(queue_prefix),
+            Self::queue_name_for::<OrganisationInvitationEvent>(queue_prefix),
+            Self::queue_name_for::<BroadcastEvent>(queue_prefix),
+        ]
+    }
+
+    // Declares every queue up front so events published before the
+    // subscriptions start are not dropped
+    pub async fn setup(&self) -> SystemResult<()> {
+        self.declare::<EmailOtpRequestedEvent>().await?;
+        self.declare::<SmsOtpRequestedEvent>().await?;
+        self.declare::<PasswordResetRequestedEvent>().await?;
+        self.declare::<PasswordChangedEvent>().await?;
+        self.declare::<OrganisationInvitationEvent>().await?;
+        self.declare::<BroadcastEvent>().await
+    }
+
+    pub fn spawn(&self, shutdown_tx: &broadcast::Sender<()>) {
+        self.spawn_subscription(shutdown_tx, Self::handle_email_otp);
+        self.spawn_subscription(shutdown_tx, Self::handle_sms_otp);
+        self.spawn_subscription(shutdown_tx, Self::handle_password_reset);
+        self.spawn_subscription(shutdown_tx, Self::handle_password_changed);
+        self.spawn_subscription(shutdown_tx, Self::handle_organisation_invitation);
+        self.spawn_subscription(shutdown_tx, Self::handle_broadcast);
+    }
+
+    async fn declare<E: DomainEvent>(&self) -> SystemResult<()> {
         self.broker
-            .consume(queue_name, "notification_consumer", shutdown_rx, |delivery| async move {
-                let payload: serde_json::Value = serde_json::from_slice(&delivery.data)
-                    .map_err(|e| SystemError::MessageBrokerError(e.to_string()))?;
-                let routing_key = delivery.routing_key.as_str();
-                let template = payload.get("template").and_then(|t| t.as_str()).unwrap_or("");
-
-                match (routing_key, template) {
-                    ("notification.email.otp", "otp_verification") => {
-                        if let (Some(email), Some(otp_code)) = (
-                            payload.get("email").and_then(|e| e.as_str()),
-                            payload.get("otp_code").and_then(|o| o.as_str()),
-                        ) {
-                            println!("Processing email OTP for {}: {}", email, otp_code);
-                            // Add email sending logic here
-                        } else {
-                            eprintln!("Invalid email OTP payload: {:?}", payload);
-                        }
-                    }
-                    ("notification.sms.otp", "otp_verification") => {
-                        if let (Some(phone), Some(otp_code)) = (
-                            payload.get("phone").and_then(|p| p.as_str()),
-                            payload.get("otp_code").and_then(|o| o.as_str()),
-                        ) {
-                            println!("Processing SMS OTP for {}: {}", phone, otp_code);
-                            // Add SMS sending logic here
-                        } else {
-                            eprintln!("Invalid SMS OTP payload: {:?}", payload);
-                        }
-                    }
-                    ("notification.email.password_reset", "password_reset") => {
-                        if let (Some(email), Some(reset_token)) = (
-                            payload.get("email").and_then(|e| e.as_str()),
-                            payload.get("reset_token").and_then(|r| r.as_str()),
-                        ) {
-                            println!("Processing password reset for {}: {}", email, reset_token);
-                            // Add email sending logic here
-                        } else {
-                            eprintln!("Invalid password reset payload: {:?}", payload);
-                        }
-                    }
-                    ("notification.email.password_changed", "password_changed") => {
-                        if let Some(email) = payload.get("email").and_then(|e| e.as_str()) {
-                            println!("Processing password changed confirmation for {}", email);
-                            // Add email sending logic here
-                        } else {
-                            eprintln!("Invalid password changed payload: {:?}", payload);
-                        }
-                    }
-                    ("notification.broadcast", "broadcast") => {
-                        if let Some(message) = payload.get("message").and_then(|m| m.as_str()) {
-                            println!("Processing broadcast message: {}", message);
-                            // Add broadcast handling logic here
-                        } else {
-                            eprintln!("Invalid broadcast payload: {:?}", payload);
-                        }
-                    }
-                    _ => {
-                        eprintln!("Unknown routing key or template: {} / {}", routing_key, template);
-                    }
-                }
-
-                delivery
-                    .ack(BasicAckOptions::default())
-                    .await
-                    .map_err(|e| SystemError::MessageBrokerError(e.to_string()))?;
-                Ok(())
-            })
+            .setup_queue(&self.queue_name::<E>(), &[E::ROUTING_KEY], E::EXCHANGE)
             .await
     }
-}
\ No newline at end of file
+
+    fn spawn_subscription<E, F, Fut>(&self, shutdown_tx: &broadcast::Sender<()>, handler: F)
+    where
+        E: DomainEvent,
+        F: Fn(EventEnvelope<E>) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<(), MessageError>> + Send + 'static,
+    {
+        let broker = self.broker.clone();
+        let queue_name = self.queue_name::<E>();
+        let shutdown_rx = shutdown_tx.subscribe();
+        let handler = deduplicate(self.ledger.clone(), &queue_name, handler);
+
+        tokio::spawn(async move {
+            if let Err(e) = broker.subscribe::<E, _, _>(&queue_name, shutdown_rx, handler).await {
+                log::error!("Subscription {} stopped: {}", queue_name, e);
+            }
+        });
+    }
+
+    async fn handle_email_otp(envelope: EventEnvelope<EmailOtpRequestedEvent>) -> Result<(), MessageError> {
+        log::info!("Processing email OTP {}", envelope.event_id);
+        // Add email sending logic here
+        Ok(())
+    }
+
+    async fn handle_sms_otp(envelope: EventEnvelope<SmsOtpRequestedEvent>) -> Result<(), MessageError> {
+        log::info!("Processing SMS OTP {}", envelope.event_id);
+        // Add SMS sending logic here
+        Ok(())
+    }
+
+    async fn handle_password_reset(envelope: EventEnvelope<PasswordResetRequestedEvent>) -> Result<(), MessageError> {
+        log::info!("Processing password reset {}", envelope.event_id);
+        // Add email sending logic here
+        Ok(())
+    }
+
+    async fn handle_password_changed(envelope: EventEnvelope<PasswordChangedEvent>) -> Result<(), MessageError> {
+        log::info!("Processing password changed confirmation {}", envelope.event_id);
+        // Add email sending logic here
+        Ok(())
+    }
+
+    async fn handle_organisation_invitation(
+        envelope: EventEnvelope<OrganisationInvitationEvent>,
+    ) -> Result<(), MessageError> {
+        let event = envelope.payload;
+        log::info!("Processing organisation invitation {} to {}", envelope.event_id, event.organisation_name);
+        // Add email sending logic here
+        Ok(())
+    }
+
+    async fn handle_broadcast(envelope: EventEnvelope<BroadcastEvent>) -> Result<(), MessageError> {
+        log::info!("Processing broadcast message {}", envelope.event_id);
+        // Add broadcast handling logic here
+        Ok(())
+    }
+}
@@ -1,72 +1,87 @@
-use serde_json::json;
-use shared::events::{ExchangeType, RoutingKey};
+use shared::events::notification_event::broadcast_event::BroadcastEvent;
+use shared::events::notification_event::email_otp_requested_event::EmailOtpRequestedEvent;
+use shared::events::notification_event::organisation_invitation_event::OrganisationInvitationEvent;
+use shared::events::notification_event::password_changed_event::PasswordChangedEvent;
+use shared::events::notification_event::password_reset_requested_event::PasswordResetRequestedEvent;
+use shared::events::notification_event::sms_otp_requested_event::SmsOtpRequestedEvent;
+use shared::events::EventEnvelope;
 use shared::features::errors::SystemResult;
-use shared::utils::messaging::MessageBroker;
+use shared::utils::messaging::outbox::OutboxEvent;
+use shared::utils::messaging::Publisher;
+use std::sync::Arc;
+use uuid::Uuid;
+
+pub const SOURCE_SERVICE: &str = "auth-service";
 
 #[derive(Clone)]
 pub struct NotificationPublisher {
-    broker: MessageBroker,
+    broker: Arc<dyn Publisher>,
 }
 
 impl NotificationPublisher {
-    pub fn new(broker: MessageBroker) -> Self {
+    pub fn new(broker: Arc<dyn Publisher>) -> Self {
         Self { broker }
     }
 
-    pub async fn setup(&self, queue_name: &str, routing_keys: &[RoutingKey], exchange: ExchangeType) -> SystemResult<()> {
-        self.broker.setup_queue(queue_name, routing_keys, exchange).await
-    }
-
     pub async fn send_email_otp(&self, email: &str, otp_code: &str) -> SystemResult<()> {
-        let event = json!({
-            "email": email,
-            "otp_code": otp_code,
-            "template": "otp_verification"
-        });
+        let event = EmailOtpRequestedEvent {
+            email: email.to_string(),
+            otp_code: otp_code.to_string(),
+        };
         self.broker
-            .publish(RoutingKey::EmailOtp, event, ExchangeType::Topic)
+            .publish(&EventEnvelope::new(SOURCE_SERVICE, event))
             .await
     }
 
     pub async fn send_sms_otp(&self, phone: &str, otp_code: &str) -> SystemResult<()> {
-        let event = json!({
-            "phone": phone,
-            "otp_code": otp_code,
-            "template": "otp_verification"
-        });
+        let event = SmsOtpRequestedEvent {
+            phone: phone.to_string(),
+            otp_code: otp_code.to_string(),
+        };
         self.broker
-            .publish(RoutingKey::SmsOtp, event, ExchangeType::Topic)
+            .publish(&EventEnvelope::new(SOURCE_SERVICE, event))
             .await
     }
 
-    pub async fn send_password_reset_email(&self, email: &str, reset_token: &str) -> SystemResult<()> {
-        let event = json!({
-            "email": email,
-            "reset_token": reset_token,
-            "template": "password_reset"
-        });
+    pub async fn send_broadcast_notification(&self, message: &str) -> SystemResult<()> {
+        let event = BroadcastEvent {
+            message: message.to_string(),
+        };
         self.broker
-            .publish(RoutingKey::EmailPasswordReset, event, ExchangeType::Topic)
+            .publish(&EventEnvelope::new(SOURCE_SERVICE, event))
             .await
     }
 
-    pub async fn send_password_changed_confirmation(&self, email: &str) -> SystemResult<()> {
-        let event = json!({
-            "email": email,
-            "template": "password_changed"
-        });
-        self.broker
-            .publish(RoutingKey::EmailPasswordChanged, event, ExchangeType::Topic)
-            .await
+    // The events below are not published directly. They are written to the
+    // outbox together with the change that caused them and relayed once the
+    // transaction commits.
+
+    pub fn password_reset_event(user_id: Uuid, email: &str, reset_token: &str) -> SystemResult<OutboxEvent> {
+        let event = PasswordResetRequestedEvent {
+            email: email.to_string(),
+            reset_token: reset_token.to_string(),
+        };
+        OutboxEvent::new("user", user_id, &EventEnvelope::new(SOURCE_SERVICE, event))
     }
 
-    pub async fn send_broadcast_notification(&self, message: &str) -> SystemResult<()> {
-        let event = json!({
-            "message": message,
-            "template": "broadcast"
-        });
-        self.broker
-            .publish(RoutingKey::Broadcast, event, ExchangeType::Fanout)
-            .await
+    pub fn password_changed_event(user_id: Uuid, email: &str) -> SystemResult<OutboxEvent> {
+        let event = PasswordChangedEvent {
+            email: email.to_string(),
+        };
+        OutboxEvent::new("user", user_id, &EventEnvelope::new(SOURCE_SERVICE, event))
+    }
+
+    pub fn organisation_invitation_event(
+        organisation_id: Uuid,
+        email: &str,
+        organisation_name: &str,
+        invitation_token: &str,
+    ) -> SystemResult<OutboxEvent> {
+        let event = OrganisationInvitationEvent {
+            email: email.to_string(),
+            organisation_name: organisation_name.to_string(),
+            invitation_token: invitation_token.to_string(),
+        };
+        OutboxEvent::new("organisation", organisation_id, &EventEnvelope::new(SOURCE_SERVICE, event))
     }
 }
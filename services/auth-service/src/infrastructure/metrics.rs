@@ -0,0 +1,53 @@
+use prometheus::{register_int_counter, register_int_counter_vec, IntCounter, IntCounterVec};
+use std::sync::LazyLock;
+
+// Auth domain counters, served on /metrics with the shared HTTP, pool and
+// broker metrics
+
+static LOGINS_TOTAL: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    register_int_counter_vec!("auth_logins_total", "Password logins, by outcome", &["outcome"])
+        .expect("Failed to register auth_logins_total")
+});
+
+static ACCOUNT_LOCKOUTS_TOTAL: LazyLock<IntCounter> = LazyLock::new(|| {
+    register_int_counter!("auth_account_lockouts_total", "Accounts locked after too many failed attempts")
+        .expect("Failed to register auth_account_lockouts_total")
+});
+
+static OTPS_SENT_TOTAL: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    register_int_counter_vec!("auth_otps_sent_total", "One-time passwords sent, by channel", &["channel"])
+        .expect("Failed to register auth_otps_sent_total")
+});
+
+static TOKEN_REFRESHES_TOTAL: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    register_int_counter_vec!(
+        "auth_token_refreshes_total",
+        "Refresh token exchanges, by flow (session, oauth) and outcome",
+        &["flow", "outcome"]
+    )
+    .expect("Failed to register auth_token_refreshes_total")
+});
+
+fn outcome(succeeded: bool) -> &'static str {
+    if succeeded {
+        "succeeded"
+    } else {
+        "failed"
+    }
+}
+
+pub fn record_login(succeeded: bool) {
+    LOGINS_TOTAL.with_label_values(&[outcome(succeeded)]).inc();
+}
+
+pub fn record_lockout() {
+    ACCOUNT_LOCKOUTS_TOTAL.inc();
+}
+
+pub fn record_otp_sent(channel: &str) {
+    OTPS_SENT_TOTAL.with_label_values(&[channel]).inc();
+}
+
+pub fn record_token_refresh(flow: &str, succeeded: bool) {
+    TOKEN_REFRESHES_TOTAL.with_label_values(&[flow, outcome(succeeded)]).inc();
+}
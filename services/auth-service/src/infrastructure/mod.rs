@@ -4,3 +4,5 @@
 pub mod config;
 pub mod database;
 pub mod messaging;
+pub mod metrics;
+pub mod settings;
@@ -0,0 +1,33 @@
+use shared::config::otp_config::OtpConfig;
+use shared::features::settings::{Flag, Setting};
+
+// Knobs operators can turn without a restart, through PUT /settings/{key}
+
+// A lockout of a minute to a week, after 1 to 100 wrong passwords or codes
+pub const LOGIN_MAX_FAILED_ATTEMPTS: Setting<i32> =
+    Setting::new("auth.login.max_failed_attempts", 5).with_range(1, 100);
+pub const LOGIN_LOCKOUT_MINUTES: Setting<i64> =
+    Setting::new("auth.login.lockout_minutes", 30).with_range(1, 7 * 24 * 60);
+
+// Per client IP, across the whole API
+pub const HTTP_RATE_LIMIT_MAX_REQUESTS: Setting<u32> = Setting::new("http.rate_limit.max_requests", 100);
+pub const HTTP_RATE_LIMIT_WINDOW_SECONDS: Setting<u64> = Setting::new("http.rate_limit.window_seconds", 60);
+
+// Turned off, or down to a share of numbers, when SMS is being abused
+pub const SMS_OTP: Flag = Flag::new("auth.otp.sms", true);
+
+// OTP request limits per identifier; they start from the `[otp]` configuration
+#[derive(Debug, Clone)]
+pub struct OtpRateLimit {
+    pub window_seconds: Setting<u64>,
+    pub max_requests: Setting<u32>,
+}
+
+impl OtpRateLimit {
+    pub fn from_config(config: &OtpConfig) -> Self {
+        Self {
+            window_seconds: Setting::new("auth.otp.rate_limit_window_seconds", config.rate_limit_window),
+            max_requests: Setting::new("auth.otp.max_requests_per_window", config.max_requests_per_window),
+        }
+    }
+}
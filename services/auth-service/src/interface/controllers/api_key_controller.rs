@@ -0,0 +1,44 @@
+use crate::application::use_cases::ApiKeyUseCase;
+use actix_web::{web, HttpResponse, Result};
+use shared::entities::dtos::auth::api_key::CreateApiKeyRequest;
+use shared::features::errors::{map_auth_error_to_response, map_success_to_response};
+use shared::features::security::auth::{AuthenticatedUser, RecentlyAuthenticated};
+use shared::features::validation::ValidatedJson;
+use std::sync::Arc;
+use uuid::Uuid;
+
+pub struct ApiKeyController {
+    api_key_use_case: Arc<ApiKeyUseCase>,
+}
+
+impl ApiKeyController {
+    pub fn new(api_key_use_case: Arc<ApiKeyUseCase>) -> Self {
+        Self { api_key_use_case }
+    }
+
+    // Minting a credential requires a recent login or re-authentication
+    pub async fn create_key(
+        &self,
+        user: RecentlyAuthenticated,
+        req: ValidatedJson<CreateApiKeyRequest>,
+    ) -> Result<HttpResponse> {
+        match self.api_key_use_case.create_key(user.claims(), req.into_inner()).await {
+            Ok((response, success)) => Ok(map_success_to_response(success, Some(response), None)),
+            Err(err) => Ok(map_auth_error_to_response(&err)),
+        }
+    }
+
+    pub async fn list_keys(&self, user: AuthenticatedUser) -> Result<HttpResponse> {
+        match self.api_key_use_case.list_keys(user.claims()).await {
+            Ok((response, success)) => Ok(map_success_to_response(success, Some(response), None)),
+            Err(err) => Ok(map_auth_error_to_response(&err)),
+        }
+    }
+
+    pub async fn revoke_key(&self, user: AuthenticatedUser, path: web::Path<Uuid>) -> Result<HttpResponse> {
+        match self.api_key_use_case.revoke_key(user.claims(), path.into_inner()).await {
+            Ok(success) => Ok(map_success_to_response::<()>(success, None, None)),
+            Err(err) => Ok(map_auth_error_to_response(&err)),
+        }
+    }
+}
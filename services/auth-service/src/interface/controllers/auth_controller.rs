@@ -1,5 +1,6 @@
 use crate::application::use_cases::LoginUseCase;
-use actix_web::{web, HttpResponse, Result};
+use actix_web::{HttpResponse, Result};
+use shared::features::validation::ValidatedJson;
 use std::sync::Arc;
 use shared::entities::dtos::auth::auth::LoginRequest;
 use shared::features::errors::{map_auth_error_to_response, map_success_to_response};
@@ -15,7 +16,7 @@ impl AuthController {
 
     pub async fn login(
         &self,
-        req: web::Json<LoginRequest>,
+        req: ValidatedJson<LoginRequest>,
         http_req: actix_web::HttpRequest,
     ) -> Result<HttpResponse> {
         // Get IP address
@@ -50,11 +51,4 @@ impl AuthController {
             "message": "Logged out successfully"
         })))
     }
-
-    pub async fn refresh_token(&self) -> Result<HttpResponse> {
-        // Implement refresh security logic
-        Ok(HttpResponse::Ok().json(serde_json::json!({
-            "message": "Token refreshed successfully"
-        })))
-    }
 }
@@ -0,0 +1,44 @@
+use crate::application::use_cases::ImpersonationUseCase;
+use actix_web::{HttpRequest, HttpResponse, Result};
+use shared::entities::dtos::auth::impersonation::ImpersonateRequest;
+use shared::features::errors::{map_auth_error_to_response, map_success_to_response};
+use shared::features::security::auth::AuthenticatedUser;
+use shared::features::validation::ValidatedJson;
+use std::sync::Arc;
+
+pub struct ImpersonationController {
+    impersonation_use_case: Arc<ImpersonationUseCase>,
+}
+
+impl ImpersonationController {
+    pub fn new(impersonation_use_case: Arc<ImpersonationUseCase>) -> Self {
+        Self { impersonation_use_case }
+    }
+
+    pub async fn start_impersonation(
+        &self,
+        user: AuthenticatedUser,
+        req: ValidatedJson<ImpersonateRequest>,
+        http_req: HttpRequest,
+    ) -> Result<HttpResponse> {
+        let ip_address = http_req
+            .connection_info()
+            .realip_remote_addr()
+            .map(|s| s.to_string());
+
+        let user_agent = http_req
+            .headers()
+            .get(actix_web::http::header::USER_AGENT)
+            .and_then(|h| h.to_str().ok())
+            .map(|s| s.to_string());
+
+        match self
+            .impersonation_use_case
+            .start(user.claims(), req.into_inner(), ip_address, user_agent)
+            .await
+        {
+            Ok((response, success)) => Ok(map_success_to_response(success, Some(response), None)),
+            Err(err) => Ok(map_auth_error_to_response(&err)),
+        }
+    }
+}
@@ -1,11 +1,21 @@
+pub mod api_key_controller;
 pub mod auth_controller;
+pub mod impersonation_controller;
+pub mod oauth_controller;
+pub mod organisation_controller;
 pub mod otp_controller;
 pub mod password_controller;
 pub mod refresh_token_controller;
 pub mod security_question_controller;
+pub mod step_up_controller;
 
+pub use api_key_controller::ApiKeyController;
 pub use auth_controller::AuthController;
+pub use impersonation_controller::ImpersonationController;
+pub use oauth_controller::OAuthController;
+pub use organisation_controller::OrganisationController;
 pub use otp_controller::OtpController;
 pub use password_controller::PasswordController;
 pub use refresh_token_controller::RefreshTokenController;
 pub use security_question_controller::SecurityQuestionController;
+pub use step_up_controller::StepUpController;
@@ -0,0 +1,182 @@
+use crate::application::use_cases::{ClientCredentials, OAuthUseCase};
+use actix_web::http::header::{AUTHORIZATION, CACHE_CONTROL, WWW_AUTHENTICATE};
+use actix_web::http::StatusCode;
+use actix_web::{web, HttpRequest, HttpResponse, Result};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use shared::entities::dtos::auth::oauth::{
+    AuthorizeRequest, IntrospectRequest, OAuthErrorResponse, RegisterOAuthClientRequest,
+    RevokeTokenRequest, TokenRequest,
+};
+use shared::features::errors::{map_auth_error_to_response, map_success_to_response, SystemError};
+use shared::features::security::auth::AuthenticatedUser;
+use shared::features::validation::ValidatedJson;
+use std::sync::Arc;
+use uuid::Uuid;
+
+pub struct OAuthController {
+    oauth_use_case: Arc<OAuthUseCase>,
+}
+
+impl OAuthController {
+    pub fn new(oauth_use_case: Arc<OAuthUseCase>) -> Self {
+        Self { oauth_use_case }
+    }
+
+    pub async fn register_client(
+        &self,
+        user: AuthenticatedUser,
+        req: ValidatedJson<RegisterOAuthClientRequest>,
+    ) -> Result<HttpResponse> {
+        match self.oauth_use_case.register_client(user.claims(), req.into_inner()).await {
+            Ok((response, success)) => Ok(map_success_to_response(success, Some(response), None)),
+            Err(err) => Ok(map_auth_error_to_response(&err)),
+        }
+    }
+
+    pub async fn list_clients(&self, user: AuthenticatedUser) -> Result<HttpResponse> {
+        match self.oauth_use_case.list_clients(user.claims()).await {
+            Ok((response, success)) => Ok(map_success_to_response(success, Some(response), None)),
+            Err(err) => Ok(map_auth_error_to_response(&err)),
+        }
+    }
+
+    pub async fn deactivate_client(&self, user: AuthenticatedUser, path: web::Path<Uuid>) -> Result<HttpResponse> {
+        match self.oauth_use_case.deactivate_client(user.claims(), path.into_inner()).await {
+            Ok(success) => Ok(map_success_to_response::<()>(success, None, None)),
+            Err(err) => Ok(map_auth_error_to_response(&err)),
+        }
+    }
+
+    pub async fn authorize(
+        &self,
+        user: AuthenticatedUser,
+        req: ValidatedJson<AuthorizeRequest>,
+    ) -> Result<HttpResponse> {
+        match self.oauth_use_case.authorize(user.claims(), req.into_inner()).await {
+            Ok((response, success)) => Ok(map_success_to_response(success, Some(response), None)),
+            Err(err) => Ok(map_auth_error_to_response(&err)),
+        }
+    }
+
+    // The token, introspection and revocation endpoints answer in the bare
+    // RFC 6749 / 7662 / 7009 formats so standard OAuth client libraries work.
+    pub async fn token(&self, http_req: HttpRequest, req: web::Form<TokenRequest>) -> Result<HttpResponse> {
+        let req = req.into_inner();
+        let credentials = match client_credentials(&http_req, req.client_id.clone(), req.client_secret.clone()) {
+            Some(credentials) => credentials,
+            None => return Ok(map_oauth_error_to_response(&SystemError::InvalidClient)),
+        };
+
+        match self.oauth_use_case.exchange_token(credentials, req).await {
+            Ok(response) => Ok(HttpResponse::Ok()
+                .insert_header((CACHE_CONTROL, "no-store"))
+                .json(response)),
+            Err(err) => Ok(map_oauth_error_to_response(&err)),
+        }
+    }
+
+    pub async fn introspect(
+        &self,
+        http_req: HttpRequest,
+        req: web::Form<IntrospectRequest>,
+    ) -> Result<HttpResponse> {
+        let req = req.into_inner();
+        let credentials = match client_credentials(&http_req, req.client_id.clone(), req.client_secret.clone()) {
+            Some(credentials) => credentials,
+            None => return Ok(map_oauth_error_to_response(&SystemError::InvalidClient)),
+        };
+
+        match self.oauth_use_case.introspect(credentials, req).await {
+            Ok(response) => Ok(HttpResponse::Ok()
+                .insert_header((CACHE_CONTROL, "no-store"))
+                .json(response)),
+            Err(err) => Ok(map_oauth_error_to_response(&err)),
+        }
+    }
+
+    pub async fn revoke(&self, http_req: HttpRequest, req: web::Form<RevokeTokenRequest>) -> Result<HttpResponse> {
+        let req = req.into_inner();
+        let credentials = match client_credentials(&http_req, req.client_id.clone(), req.client_secret.clone()) {
+            Some(credentials) => credentials,
+            None => return Ok(map_oauth_error_to_response(&SystemError::InvalidClient)),
+        };
+
+        match self.oauth_use_case.revoke(credentials, req).await {
+            Ok(()) => Ok(HttpResponse::Ok().finish()),
+            Err(err) => Ok(map_oauth_error_to_response(&err)),
+        }
+    }
+
+    pub async fn list_consents(&self, user: AuthenticatedUser) -> Result<HttpResponse> {
+        match self.oauth_use_case.list_consents(user.claims()).await {
+            Ok((response, success)) => Ok(map_success_to_response(success, Some(response), None)),
+            Err(err) => Ok(map_auth_error_to_response(&err)),
+        }
+    }
+
+    pub async fn revoke_consent(&self, user: AuthenticatedUser, path: web::Path<Uuid>) -> Result<HttpResponse> {
+        match self.oauth_use_case.revoke_consent(user.claims(), path.into_inner()).await {
+            Ok(success) => Ok(map_success_to_response::<()>(success, None, None)),
+            Err(err) => Ok(map_auth_error_to_response(&err)),
+        }
+    }
+}
+
+// HTTP Basic takes precedence over form parameters (RFC 6749 section 2.3.1)
+fn client_credentials(
+    http_req: &HttpRequest,
+    form_client_id: Option<String>,
+    form_client_secret: Option<String>,
+) -> Option<ClientCredentials> {
+    let basic = http_req
+        .headers()
+        .get(AUTHORIZATION)
+        .and_then(|h| h.to_str().ok())
+        .and_then(|h| h.strip_prefix("Basic "))
+        .and_then(|encoded| STANDARD.decode(encoded.trim()).ok())
+        .and_then(|decoded| String::from_utf8(decoded).ok())
+        .and_then(|decoded| {
+            decoded
+                .split_once(':')
+                .map(|(id, secret)| (id.to_string(), secret.to_string()))
+        });
+
+    match basic {
+        Some((client_id, client_secret)) => Some(ClientCredentials {
+            client_id,
+            client_secret: Some(client_secret).filter(|s| !s.is_empty()),
+        }),
+        None => form_client_id.map(|client_id| ClientCredentials {
+            client_id,
+            client_secret: form_client_secret,
+        }),
+    }
+}
+
+fn map_oauth_error_to_response(err: &SystemError) -> HttpResponse {
+    let (status, error, description) = match err {
+        SystemError::InvalidClient => (StatusCode::UNAUTHORIZED, "invalid_client", err.to_string()),
+        SystemError::InvalidGrant(msg) => (StatusCode::BAD_REQUEST, "invalid_grant", msg.clone()),
+        SystemError::InvalidScope(msg) => (StatusCode::BAD_REQUEST, "invalid_scope", msg.clone()),
+        SystemError::UnsupportedGrantType(msg) => {
+            (StatusCode::BAD_REQUEST, "unsupported_grant_type", msg.clone())
+        }
+        SystemError::ValidationError(msg) => (StatusCode::BAD_REQUEST, "invalid_request", msg.clone()),
+        SystemError::AccountLocked | SystemError::AccountInactive | SystemError::UserNotFound(_) => {
+            (StatusCode::BAD_REQUEST, "invalid_grant", err.to_string())
+        }
+        _ => return map_auth_error_to_response(err),
+    };
+
+    let mut response = HttpResponse::build(status);
+    response.insert_header((CACHE_CONTROL, "no-store"));
+    if status == StatusCode::UNAUTHORIZED {
+        response.insert_header((WWW_AUTHENTICATE, "Basic realm=\"oauth\""));
+    }
+
+    response.json(OAuthErrorResponse {
+        error: error.to_string(),
+        error_description: Some(description),
+    })
+}
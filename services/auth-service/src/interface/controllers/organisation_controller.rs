@@ -0,0 +1,160 @@
+use crate::application::use_cases::OrganisationUseCase;
+use actix_web::{web, HttpResponse, Result};
+use shared::entities::dtos::auth::organisation::{
+    AcceptInvitationRequest, CreateOrganisationRequest, InviteMemberRequest, SwitchOrganisationRequest,
+    UpdateMemberRoleRequest,
+};
+use shared::features::errors::{map_auth_error_to_response, map_success_to_response};
+use shared::features::security::auth::AuthenticatedUser;
+use shared::features::validation::ValidatedJson;
+use std::sync::Arc;
+use uuid::Uuid;
+
+pub struct OrganisationController {
+    organisation_use_case: Arc<OrganisationUseCase>,
+}
+
+impl OrganisationController {
+    pub fn new(organisation_use_case: Arc<OrganisationUseCase>) -> Self {
+        Self { organisation_use_case }
+    }
+
+    pub async fn create_organisation(
+        &self,
+        user: AuthenticatedUser,
+        req: ValidatedJson<CreateOrganisationRequest>,
+    ) -> Result<HttpResponse> {
+        match self
+            .organisation_use_case
+            .create_organisation(user.claims(), req.into_inner())
+            .await
+        {
+            Ok((response, success)) => Ok(map_success_to_response(success, Some(response), None)),
+            Err(err) => Ok(map_auth_error_to_response(&err)),
+        }
+    }
+
+    pub async fn list_organisations(&self, user: AuthenticatedUser) -> Result<HttpResponse> {
+        match self.organisation_use_case.list_organisations(user.claims()).await {
+            Ok((response, success)) => Ok(map_success_to_response(success, Some(response), None)),
+            Err(err) => Ok(map_auth_error_to_response(&err)),
+        }
+    }
+
+    pub async fn switch_organisation(
+        &self,
+        user: AuthenticatedUser,
+        req: ValidatedJson<SwitchOrganisationRequest>,
+    ) -> Result<HttpResponse> {
+        match self
+            .organisation_use_case
+            .switch_organisation(user.claims(), req.into_inner())
+            .await
+        {
+            Ok((response, success)) => Ok(map_success_to_response(success, Some(response), None)),
+            Err(err) => Ok(map_auth_error_to_response(&err)),
+        }
+    }
+
+    pub async fn accept_invitation(
+        &self,
+        user: AuthenticatedUser,
+        req: ValidatedJson<AcceptInvitationRequest>,
+    ) -> Result<HttpResponse> {
+        match self
+            .organisation_use_case
+            .accept_invitation(user.claims(), req.into_inner())
+            .await
+        {
+            Ok((response, success)) => Ok(map_success_to_response(success, Some(response), None)),
+            Err(err) => Ok(map_auth_error_to_response(&err)),
+        }
+    }
+
+    pub async fn list_members(&self, user: AuthenticatedUser, path: web::Path<Uuid>) -> Result<HttpResponse> {
+        match self
+            .organisation_use_case
+            .list_members(user.claims(), path.into_inner())
+            .await
+        {
+            Ok((response, success)) => Ok(map_success_to_response(success, Some(response), None)),
+            Err(err) => Ok(map_auth_error_to_response(&err)),
+        }
+    }
+
+    pub async fn update_member_role(
+        &self,
+        user: AuthenticatedUser,
+        path: web::Path<(Uuid, Uuid)>,
+        req: ValidatedJson<UpdateMemberRoleRequest>,
+    ) -> Result<HttpResponse> {
+        let (organisation_id, user_id) = path.into_inner();
+        match self
+            .organisation_use_case
+            .update_member_role(user.claims(), organisation_id, user_id, req.into_inner())
+            .await
+        {
+            Ok(success) => Ok(map_success_to_response::<()>(success, None, None)),
+            Err(err) => Ok(map_auth_error_to_response(&err)),
+        }
+    }
+
+    pub async fn remove_member(
+        &self,
+        user: AuthenticatedUser,
+        path: web::Path<(Uuid, Uuid)>,
+    ) -> Result<HttpResponse> {
+        let (organisation_id, user_id) = path.into_inner();
+        match self
+            .organisation_use_case
+            .remove_member(user.claims(), organisation_id, user_id)
+            .await
+        {
+            Ok(success) => Ok(map_success_to_response::<()>(success, None, None)),
+            Err(err) => Ok(map_auth_error_to_response(&err)),
+        }
+    }
+
+    pub async fn invite_member(
+        &self,
+        user: AuthenticatedUser,
+        path: web::Path<Uuid>,
+        req: ValidatedJson<InviteMemberRequest>,
+    ) -> Result<HttpResponse> {
+        match self
+            .organisation_use_case
+            .invite_member(user.claims(), path.into_inner(), req.into_inner())
+            .await
+        {
+            Ok((response, success)) => Ok(map_success_to_response(success, Some(response), None)),
+            Err(err) => Ok(map_auth_error_to_response(&err)),
+        }
+    }
+
+    pub async fn list_invitations(&self, user: AuthenticatedUser, path: web::Path<Uuid>) -> Result<HttpResponse> {
+        match self
+            .organisation_use_case
+            .list_invitations(user.claims(), path.into_inner())
+            .await
+        {
+            Ok((response, success)) => Ok(map_success_to_response(success, Some(response), None)),
+            Err(err) => Ok(map_auth_error_to_response(&err)),
+        }
+    }
+
+    pub async fn revoke_invitation(
+        &self,
+        user: AuthenticatedUser,
+        path: web::Path<(Uuid, Uuid)>,
+    ) -> Result<HttpResponse> {
+        let (organisation_id, invitation_id) = path.into_inner();
+        match self
+            .organisation_use_case
+            .revoke_invitation(user.claims(), organisation_id, invitation_id)
+            .await
+        {
+            Ok(success) => Ok(map_success_to_response::<()>(success, None, None)),
+            Err(err) => Ok(map_auth_error_to_response(&err)),
+        }
+    }
+}
@@ -1,5 +1,6 @@
 use crate::application::use_cases::PasswordResetUseCase;
-use actix_web::{web, HttpResponse, Result};
+use actix_web::{HttpResponse, Result};
+use shared::features::validation::ValidatedJson;
 use std::sync::Arc;
 use shared::entities::dtos::auth::password::{PasswordResetConfirmRequest, PasswordResetRequest};
 use shared::features::errors::map_auth_error_to_response;
@@ -17,7 +18,7 @@ impl PasswordController {
 
     pub async fn request_password_reset(
         &self,
-        req: web::Json<PasswordResetRequest>,
+        req: ValidatedJson<PasswordResetRequest>,
     ) -> Result<HttpResponse> {
         match self
             .password_reset_use_case
@@ -33,7 +34,7 @@ impl PasswordController {
 
     pub async fn confirm_password_reset(
         &self,
-        req: web::Json<PasswordResetConfirmRequest>,
+        req: ValidatedJson<PasswordResetConfirmRequest>,
     ) -> Result<HttpResponse> {
         match self
             .password_reset_use_case
@@ -1,5 +1,6 @@
 use crate::application::use_cases::RefreshTokenUseCase;
-use actix_web::{web, HttpResponse, Result};
+use actix_web::{HttpResponse, Result};
+use shared::features::validation::ValidatedJson;
 use std::sync::Arc;
 use shared::entities::dtos::auth::token::RefreshTokenRequest;
 use shared::features::errors::{map_auth_error_to_response, map_success_to_response};
@@ -17,7 +18,7 @@ impl RefreshTokenController {
 
     pub async fn refresh_access_token(
         &self,
-        req: web::Json<RefreshTokenRequest>,
+        req: ValidatedJson<RefreshTokenRequest>,
     ) -> Result<HttpResponse> {
         match self.refresh_token_use_case.as_ref().execute(req.into_inner()).await {
             Ok(response) => Ok(map_success_to_response(response.1, Some(response.0), None)),
@@ -25,7 +26,7 @@ impl RefreshTokenController {
         }
     }
 
-    // pub async fn revoke_refresh_token(&self, req: web::Json<RefreshTokenRequest>) -> Result<HttpResponse> {
+    // pub async fn revoke_refresh_token(&self, req: ValidatedJson<RefreshTokenRequest>) -> Result<HttpResponse> {
     //     match self.refresh_token_use_case.revoke_refresh_token(req.refresh_token.clone()).await {
     //         Ok(_) => Ok(HttpResponse::Ok().json(serde_json::json!({
     //             "message": "Refresh security revoked successfully"
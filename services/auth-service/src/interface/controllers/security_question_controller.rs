@@ -1,5 +1,6 @@
 use crate::application::use_cases::SecurityQuestionUseCase;
 use actix_web::{web, HttpResponse, Result};
+use shared::features::validation::ValidatedJson;
 use std::sync::Arc;
 use uuid::Uuid;
 use shared::entities::dtos::auth::question::{SetSecurityQuestionsRequest, VerifySecurityQuestionsRequest};
@@ -19,7 +20,7 @@ impl SecurityQuestionController {
     pub async fn create_security_questions(
         &self,
         path: web::Path<Uuid>,
-        req: web::Json<SetSecurityQuestionsRequest>,
+        req: ValidatedJson<SetSecurityQuestionsRequest>,
     ) -> Result<HttpResponse> {
         match self
             .security_question_use_case
@@ -61,7 +62,7 @@ impl SecurityQuestionController {
     pub async fn verify_security_answers(
         &self,
         path: web::Path<Uuid>,
-        req: web::Json<VerifySecurityQuestionsRequest>,
+        req: ValidatedJson<VerifySecurityQuestionsRequest>,
     ) -> Result<HttpResponse> {
         let user_id = path.into_inner();
         let request = req.into_inner();
@@ -0,0 +1,46 @@
+use crate::application::use_cases::StepUpUseCase;
+use actix_web::{HttpResponse, Result};
+use shared::entities::dtos::auth::step_up::{ConfirmTotpRequest, ReauthenticateRequest};
+use shared::features::errors::{map_auth_error_to_response, map_success_to_response};
+use shared::features::security::auth::{AuthenticatedUser, RecentlyAuthenticated};
+use shared::features::validation::ValidatedJson;
+use std::sync::Arc;
+
+pub struct StepUpController {
+    step_up_use_case: Arc<StepUpUseCase>,
+}
+
+impl StepUpController {
+    pub fn new(step_up_use_case: Arc<StepUpUseCase>) -> Self {
+        Self { step_up_use_case }
+    }
+
+    pub async fn reauthenticate(
+        &self,
+        user: AuthenticatedUser,
+        req: ValidatedJson<ReauthenticateRequest>,
+    ) -> Result<HttpResponse> {
+        match self.step_up_use_case.reauthenticate(user.claims(), req.into_inner()).await {
+            Ok((response, success)) => Ok(map_success_to_response(success, Some(response), None)),
+            Err(err) => Ok(map_auth_error_to_response(&err)),
+        }
+    }
+
+    pub async fn enroll_totp(&self, user: RecentlyAuthenticated) -> Result<HttpResponse> {
+        match self.step_up_use_case.enroll_totp(user.claims()).await {
+            Ok((response, success)) => Ok(map_success_to_response(success, Some(response), None)),
+            Err(err) => Ok(map_auth_error_to_response(&err)),
+        }
+    }
+
+    pub async fn confirm_totp(
+        &self,
+        user: AuthenticatedUser,
+        req: ValidatedJson<ConfirmTotpRequest>,
+    ) -> Result<HttpResponse> {
+        match self.step_up_use_case.confirm_totp(user.claims(), req.into_inner()).await {
+            Ok(success) => Ok(map_success_to_response::<()>(success, None, None)),
+            Err(err) => Ok(map_auth_error_to_response(&err)),
+        }
+    }
+}
@@ -1,7 +1,10 @@
 use tonic::{Request, Response, Status};
 use uuid::Uuid;
+use shared::features::errors::SystemError;
 use shared::features::helper::jwt_helper::JwtHelper;
+use shared::features::observability::grpc::server_span;
 use shared::features::security::jwt::JwtClaims;
+use tracing::Instrument;
 // gRPC service definition would be generated from .proto files
 // This is a placeholder for the actual generated code
 
@@ -17,7 +20,7 @@ impl AuthValidationService {
 
     pub async fn validate_token(&self, token: &str) -> Result<JwtClaims, Status> {
         JwtHelper::validate_jwt(token, self.jwt_secret.as_ref())
-            .map_err(|_| Status::unauthenticated("Invalid security"))
+            .map_err(|_| Status::from(SystemError::InvalidToken))
     }
 
     pub async fn validate_user_permission(
@@ -43,9 +46,10 @@ pub async fn validate_auth_token(
     service: &AuthValidationService,
     request: Request<AuthTokenRequest>,
 ) -> Result<Response<AuthTokenResponse>, Status> {
+    let span = server_span(&request, "auth.AuthValidation/ValidateAuthToken");
     let req = request.into_inner();
 
-    match service.validate_token(req.token.as_ref()).await {
+    match service.validate_token(req.token.as_ref()).instrument(span).await {
         Ok(claims) => {
             let response = AuthTokenResponse {
                 valid: true,
@@ -64,12 +68,14 @@ pub async fn check_user_permission(
     service: &AuthValidationService,
     request: Request<PermissionRequest>,
 ) -> Result<Response<PermissionResponse>, Status> {
+    let span = server_span(&request, "auth.AuthValidation/CheckUserPermission");
     let req = request.into_inner();
     let user_id = Uuid::parse_str(req.user_id.as_ref())
-        .map_err(|_| Status::invalid_argument("Invalid user ID format"))?;
+        .map_err(|_| Status::from(SystemError::ValidationError("Invalid user ID format".to_string())))?;
 
     match service
         .validate_user_permission(user_id, req.permission.as_ref())
+        .instrument(span)
         .await
     {
         Ok(has_permission) => {
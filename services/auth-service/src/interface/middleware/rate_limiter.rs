@@ -1,28 +1,31 @@
 use actix_web::{
     body::BoxBody,
     dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform},
-    Error, HttpResponse,
+    Error, ResponseError,
 };
+use crate::infrastructure::settings::{HTTP_RATE_LIMIT_MAX_REQUESTS, HTTP_RATE_LIMIT_WINDOW_SECONDS};
 use futures_util::future::LocalBoxFuture;
+use shared::features::errors::SystemError;
+use shared::features::settings::RuntimeSettings;
 use std::{
     collections::HashMap,
     future::{ready, Ready},
     rc::Rc,
-    sync::Mutex,
+    sync::{Arc, Mutex},
     time::{Duration, Instant},
 };
 
+// Limits are read on every request, so a change made through the settings
+// endpoint applies without a restart
 pub struct RateLimiter {
-    max_requests: u32,
-    window_duration: Duration,
+    settings: Arc<RuntimeSettings>,
     storage: Rc<Mutex<HashMap<String, (u32, Instant)>>>,
 }
 
 impl RateLimiter {
-    pub fn new(max_requests: u32, window_duration: Duration) -> Self {
+    pub fn new(settings: Arc<RuntimeSettings>) -> Self {
         Self {
-            max_requests,
-            window_duration,
+            settings,
             storage: Rc::new(Mutex::new(HashMap::new())),
         }
     }
@@ -43,8 +46,7 @@ where
     fn new_transform(&self, service: S) -> Self::Future {
         ready(Ok(RateLimiterMiddleware {
             service,
-            max_requests: self.max_requests,
-            window_duration: self.window_duration,
+            settings: self.settings.clone(),
             storage: self.storage.clone(),
         }))
     }
@@ -52,8 +54,7 @@ where
 
 pub struct RateLimiterMiddleware<S> {
     service: S,
-    max_requests: u32,
-    window_duration: Duration,
+    settings: Arc<RuntimeSettings>,
     storage: Rc<Mutex<HashMap<String, (u32, Instant)>>>,
 }
 
@@ -76,26 +77,29 @@ where
             .unwrap_or("unknown")
             .to_string();
 
+        let max_requests = self.settings.get(&HTTP_RATE_LIMIT_MAX_REQUESTS);
+        let window_duration = Duration::from_secs(self.settings.get(&HTTP_RATE_LIMIT_WINDOW_SECONDS));
+
         let mut storage = self.storage.lock().unwrap();
         let now = Instant::now();
 
         // Clean up expired entries
-        storage.retain(|_, (_, timestamp)| now.duration_since(*timestamp) < self.window_duration);
+        storage.retain(|_, (_, timestamp)| now.duration_since(*timestamp) < window_duration);
 
         let (count, first_request) = storage.entry(client_ip.clone()).or_insert((0, now));
 
-        if now.duration_since(*first_request) >= self.window_duration {
+        if now.duration_since(*first_request) >= window_duration {
             *count = 1;
             *first_request = now;
         } else {
             *count += 1;
         }
 
-        if *count > self.max_requests {
+        if *count > max_requests {
             drop(storage);
             Box::pin(async move {
-                let response = HttpResponse::TooManyRequests()
-                    .json(serde_json::json!({ "error": "Rate limit exceeded" }))
+                let response = SystemError::RateLimitExceeded("Rate limit exceeded".to_string())
+                    .error_response()
                     .map_into_boxed_body();
 
                 Ok(ServiceResponse::new(req.into_parts().0, response))
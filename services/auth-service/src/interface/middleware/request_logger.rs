@@ -7,7 +7,6 @@ use std::{
     future::{ready, Ready},
     time::Instant,
 };
-use uuid::Uuid;
 
 pub struct RequestLogger;
 
@@ -49,17 +48,16 @@ where
         let method = req.method().to_string();
         let path = req.path().to_string();
         let query_string = req.query_string().to_string();
-        let request_id = Uuid::new_v4().to_string();
         let client_ip = req
             .connection_info()
             .realip_remote_addr()
             .unwrap_or("unknown")
             .to_string();
 
-        // Log incoming request
+        // Log incoming request; the request and trace ids come from the
+        // `TracingMiddleware` span
         log::info!(
-            "Request started - ID: {} | {} {} | IP: {} | Query: {}",
-            request_id,
+            "Request started - {} {} | IP: {} | Query: {}",
             method,
             path,
             client_ip,
@@ -79,16 +77,14 @@ where
             match &result {
                 Ok(response) => {
                     log::info!(
-                        "Request completed - ID: {} | Status: {} | Duration: {:?}",
-                        request_id,
+                        "Request completed - Status: {} | Duration: {:?}",
                         response.status(),
                         duration
                     );
                 }
                 Err(error) => {
                     log::error!(
-                        "Request failed - ID: {} | Error: {:?} | Duration: {:?}",
-                        request_id,
+                        "Request failed - Error: {:?} | Duration: {:?}",
                         error,
                         duration
                     );
@@ -1,11 +1,22 @@
 use crate::config::pipeline::controller_setup::Controllers;
-use crate::interface::controllers::RefreshTokenController;
-use actix_web::{get, post, web};
+use actix_web::{delete, get, post, put, web};
+use shared::entities::dtos::auth::api_key::CreateApiKeyRequest;
 use shared::entities::dtos::auth::auth::LoginRequest;
+use shared::entities::dtos::auth::impersonation::ImpersonateRequest;
+use shared::entities::dtos::auth::oauth::{
+    AuthorizeRequest, IntrospectRequest, RegisterOAuthClientRequest, RevokeTokenRequest, TokenRequest,
+};
+use shared::entities::dtos::auth::organisation::{
+    AcceptInvitationRequest, CreateOrganisationRequest, InviteMemberRequest, SwitchOrganisationRequest,
+    UpdateMemberRoleRequest,
+};
 use shared::entities::dtos::auth::otp::{SendOtpRequest, VerifyOtpRequest};
 use shared::entities::dtos::auth::password::{PasswordResetConfirmRequest, PasswordResetRequest};
+use shared::entities::dtos::auth::step_up::{ConfirmTotpRequest, ReauthenticateRequest};
 use shared::entities::dtos::auth::question::{SetSecurityQuestionsRequest, VerifySecurityQuestionsRequest};
 use shared::entities::dtos::auth::token::RefreshTokenRequest;
+use shared::features::security::auth::{AuthenticatedUser, RecentlyAuthenticated};
+use shared::features::validation::ValidatedJson;
 // pub fn refresh_token_routes() -> Scope {
 //     web::scope("/tokens").route("/refresh", web::post().to(refresh_access_token))
 // }
@@ -14,7 +25,7 @@ use shared::entities::dtos::auth::token::RefreshTokenRequest;
 #[post("/login")]
 pub async fn login(
     controller: web::Data<Controllers>,
-    req: web::Json<LoginRequest>,
+    req: ValidatedJson<LoginRequest>,
     http_req: actix_web::HttpRequest,
 ) -> actix_web::Result<actix_web::HttpResponse> {
     controller.auth.login(req, http_req).await
@@ -30,15 +41,16 @@ pub async fn logout(
 #[post("/refresh")]
 pub async fn refresh_token(
     controller: web::Data<Controllers>,
+    req: ValidatedJson<RefreshTokenRequest>,
 ) -> actix_web::Result<actix_web::HttpResponse> {
-    controller.auth.refresh_token().await
+    controller.refresh_token.refresh_access_token(req).await
 }
 
 // OTP Controller Handlers
 #[post("/send")]
 pub async fn send_otp(
     controller: web::Data<Controllers>,
-    req: web::Json<SendOtpRequest>,
+    req: ValidatedJson<SendOtpRequest>,
 ) -> actix_web::Result<actix_web::HttpResponse> {
     controller.otp.send_otp(req).await
 }
@@ -46,7 +58,7 @@ pub async fn send_otp(
 #[post("/verify")]
 pub async fn verify_otp(
     controller: web::Data<Controllers>,
-    req: web::Json<VerifyOtpRequest>,
+    req: ValidatedJson<VerifyOtpRequest>,
 ) -> actix_web::Result<actix_web::HttpResponse> {
     controller.otp.verify_otp(req).await
 }
@@ -55,7 +67,7 @@ pub async fn verify_otp(
 #[post("/request")]
 pub async fn request_password_reset(
     controller: web::Data<Controllers>,
-    req: web::Json<PasswordResetRequest>,
+    req: ValidatedJson<PasswordResetRequest>,
 ) -> actix_web::Result<actix_web::HttpResponse> {
     controller.password.request_password_reset(req).await
 }
@@ -63,7 +75,7 @@ pub async fn request_password_reset(
 #[post("/confirm")]
 pub async fn confirm_password_reset(
     controller: web::Data<Controllers>,
-    req: web::Json<PasswordResetConfirmRequest>,
+    req: ValidatedJson<PasswordResetConfirmRequest>,
 ) -> actix_web::Result<actix_web::HttpResponse> {
     controller.password.confirm_password_reset(req).await
 }
@@ -73,7 +85,7 @@ pub async fn confirm_password_reset(
 pub async fn create_security_questions(
     controller: web::Data<Controllers>,
     path: web::Path<uuid::Uuid>,
-    req: web::Json<SetSecurityQuestionsRequest>,
+    req: ValidatedJson<SetSecurityQuestionsRequest>,
 ) -> actix_web::Result<actix_web::HttpResponse> {
     controller
         .security_question
@@ -100,7 +112,7 @@ pub async fn get_questions(
 pub async fn verify_security_answers(
     controller: web::Data<Controllers>,
     path: web::Path<uuid::Uuid>,
-    req: web::Json<VerifySecurityQuestionsRequest>,
+    req: ValidatedJson<VerifySecurityQuestionsRequest>,
 ) -> actix_web::Result<actix_web::HttpResponse> {
     controller
         .security_question
@@ -108,10 +120,239 @@ pub async fn verify_security_answers(
         .await
 }
 
-// Refresh Token Controller Handlers
-async fn refresh_access_token(
-    controller: web::Data<RefreshTokenController>,
-    req: web::Json<RefreshTokenRequest>,
+// OAuth Controller Handlers
+#[post("/clients")]
+pub async fn register_oauth_client(
+    controller: web::Data<Controllers>,
+    user: AuthenticatedUser,
+    req: ValidatedJson<RegisterOAuthClientRequest>,
+) -> actix_web::Result<actix_web::HttpResponse> {
+    controller.oauth.register_client(user, req).await
+}
+
+#[get("/clients")]
+pub async fn list_oauth_clients(
+    controller: web::Data<Controllers>,
+    user: AuthenticatedUser,
+) -> actix_web::Result<actix_web::HttpResponse> {
+    controller.oauth.list_clients(user).await
+}
+
+#[delete("/clients/{client_id}")]
+pub async fn deactivate_oauth_client(
+    controller: web::Data<Controllers>,
+    user: AuthenticatedUser,
+    path: web::Path<uuid::Uuid>,
+) -> actix_web::Result<actix_web::HttpResponse> {
+    controller.oauth.deactivate_client(user, path).await
+}
+
+#[post("/authorize")]
+pub async fn oauth_authorize(
+    controller: web::Data<Controllers>,
+    user: AuthenticatedUser,
+    req: ValidatedJson<AuthorizeRequest>,
+) -> actix_web::Result<actix_web::HttpResponse> {
+    controller.oauth.authorize(user, req).await
+}
+
+#[post("/token")]
+pub async fn oauth_token(
+    controller: web::Data<Controllers>,
+    http_req: actix_web::HttpRequest,
+    req: web::Form<TokenRequest>,
+) -> actix_web::Result<actix_web::HttpResponse> {
+    controller.oauth.token(http_req, req).await
+}
+
+#[post("/introspect")]
+pub async fn oauth_introspect(
+    controller: web::Data<Controllers>,
+    http_req: actix_web::HttpRequest,
+    req: web::Form<IntrospectRequest>,
+) -> actix_web::Result<actix_web::HttpResponse> {
+    controller.oauth.introspect(http_req, req).await
+}
+
+#[post("/revoke")]
+pub async fn oauth_revoke(
+    controller: web::Data<Controllers>,
+    http_req: actix_web::HttpRequest,
+    req: web::Form<RevokeTokenRequest>,
+) -> actix_web::Result<actix_web::HttpResponse> {
+    controller.oauth.revoke(http_req, req).await
+}
+
+#[get("/consents")]
+pub async fn list_oauth_consents(
+    controller: web::Data<Controllers>,
+    user: AuthenticatedUser,
+) -> actix_web::Result<actix_web::HttpResponse> {
+    controller.oauth.list_consents(user).await
+}
+
+#[delete("/consents/{client_id}")]
+pub async fn revoke_oauth_consent(
+    controller: web::Data<Controllers>,
+    user: AuthenticatedUser,
+    path: web::Path<uuid::Uuid>,
+) -> actix_web::Result<actix_web::HttpResponse> {
+    controller.oauth.revoke_consent(user, path).await
+}
+
+// API Key Controller Handlers
+#[post("")]
+pub async fn create_api_key(
+    controller: web::Data<Controllers>,
+    user: RecentlyAuthenticated,
+    req: ValidatedJson<CreateApiKeyRequest>,
+) -> actix_web::Result<actix_web::HttpResponse> {
+    controller.api_key.create_key(user, req).await
+}
+
+#[get("")]
+pub async fn list_api_keys(
+    controller: web::Data<Controllers>,
+    user: AuthenticatedUser,
+) -> actix_web::Result<actix_web::HttpResponse> {
+    controller.api_key.list_keys(user).await
+}
+
+#[delete("/{key_id}")]
+pub async fn revoke_api_key(
+    controller: web::Data<Controllers>,
+    user: AuthenticatedUser,
+    path: web::Path<uuid::Uuid>,
+) -> actix_web::Result<actix_web::HttpResponse> {
+    controller.api_key.revoke_key(user, path).await
+}
+
+// Organisation Controller Handlers
+#[post("")]
+pub async fn create_organisation(
+    controller: web::Data<Controllers>,
+    user: AuthenticatedUser,
+    req: ValidatedJson<CreateOrganisationRequest>,
+) -> actix_web::Result<actix_web::HttpResponse> {
+    controller.organisation.create_organisation(user, req).await
+}
+
+#[get("")]
+pub async fn list_organisations(
+    controller: web::Data<Controllers>,
+    user: AuthenticatedUser,
+) -> actix_web::Result<actix_web::HttpResponse> {
+    controller.organisation.list_organisations(user).await
+}
+
+#[post("/switch")]
+pub async fn switch_organisation(
+    controller: web::Data<Controllers>,
+    user: AuthenticatedUser,
+    req: ValidatedJson<SwitchOrganisationRequest>,
+) -> actix_web::Result<actix_web::HttpResponse> {
+    controller.organisation.switch_organisation(user, req).await
+}
+
+#[post("/invitations/accept")]
+pub async fn accept_organisation_invitation(
+    controller: web::Data<Controllers>,
+    user: AuthenticatedUser,
+    req: ValidatedJson<AcceptInvitationRequest>,
+) -> actix_web::Result<actix_web::HttpResponse> {
+    controller.organisation.accept_invitation(user, req).await
+}
+
+#[get("/{organisation_id}/members")]
+pub async fn list_organisation_members(
+    controller: web::Data<Controllers>,
+    user: AuthenticatedUser,
+    path: web::Path<uuid::Uuid>,
+) -> actix_web::Result<actix_web::HttpResponse> {
+    controller.organisation.list_members(user, path).await
+}
+
+#[put("/{organisation_id}/members/{user_id}")]
+pub async fn update_organisation_member_role(
+    controller: web::Data<Controllers>,
+    user: AuthenticatedUser,
+    path: web::Path<(uuid::Uuid, uuid::Uuid)>,
+    req: ValidatedJson<UpdateMemberRoleRequest>,
+) -> actix_web::Result<actix_web::HttpResponse> {
+    controller.organisation.update_member_role(user, path, req).await
+}
+
+#[delete("/{organisation_id}/members/{user_id}")]
+pub async fn remove_organisation_member(
+    controller: web::Data<Controllers>,
+    user: AuthenticatedUser,
+    path: web::Path<(uuid::Uuid, uuid::Uuid)>,
+) -> actix_web::Result<actix_web::HttpResponse> {
+    controller.organisation.remove_member(user, path).await
+}
+
+#[post("/{organisation_id}/invitations")]
+pub async fn invite_organisation_member(
+    controller: web::Data<Controllers>,
+    user: AuthenticatedUser,
+    path: web::Path<uuid::Uuid>,
+    req: ValidatedJson<InviteMemberRequest>,
+) -> actix_web::Result<actix_web::HttpResponse> {
+    controller.organisation.invite_member(user, path, req).await
+}
+
+#[get("/{organisation_id}/invitations")]
+pub async fn list_organisation_invitations(
+    controller: web::Data<Controllers>,
+    user: AuthenticatedUser,
+    path: web::Path<uuid::Uuid>,
+) -> actix_web::Result<actix_web::HttpResponse> {
+    controller.organisation.list_invitations(user, path).await
+}
+
+#[delete("/{organisation_id}/invitations/{invitation_id}")]
+pub async fn revoke_organisation_invitation(
+    controller: web::Data<Controllers>,
+    user: AuthenticatedUser,
+    path: web::Path<(uuid::Uuid, uuid::Uuid)>,
+) -> actix_web::Result<actix_web::HttpResponse> {
+    controller.organisation.revoke_invitation(user, path).await
+}
+
+// Impersonation Controller Handlers
+#[post("/impersonation")]
+pub async fn start_impersonation(
+    controller: web::Data<Controllers>,
+    user: AuthenticatedUser,
+    req: ValidatedJson<ImpersonateRequest>,
+    http_req: actix_web::HttpRequest,
+) -> actix_web::Result<actix_web::HttpResponse> {
+    controller.impersonation.start_impersonation(user, req, http_req).await
+}
+
+// Step-up Controller Handlers
+#[post("/reauthenticate")]
+pub async fn reauthenticate(
+    controller: web::Data<Controllers>,
+    user: AuthenticatedUser,
+    req: ValidatedJson<ReauthenticateRequest>,
+) -> actix_web::Result<actix_web::HttpResponse> {
+    controller.step_up.reauthenticate(user, req).await
+}
+
+#[post("")]
+pub async fn enroll_totp(
+    controller: web::Data<Controllers>,
+    user: RecentlyAuthenticated,
+) -> actix_web::Result<actix_web::HttpResponse> {
+    controller.step_up.enroll_totp(user).await
+}
+
+#[post("/confirm")]
+pub async fn confirm_totp(
+    controller: web::Data<Controllers>,
+    user: AuthenticatedUser,
+    req: ValidatedJson<ConfirmTotpRequest>,
 ) -> actix_web::Result<actix_web::HttpResponse> {
-    controller.refresh_access_token(req).await
+    controller.step_up.confirm_totp(user, req).await
 }
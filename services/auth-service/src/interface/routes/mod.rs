@@ -1,2 +1 @@
 pub mod auth_routes;
-pub mod health_routes;
@@ -0,0 +1,6 @@
+pub mod application;
+pub mod cache;
+pub mod config;
+pub mod domain;
+pub mod infrastructure;
+pub mod interface;
@@ -1,44 +1,107 @@
-mod application;
-mod cache;
-mod config;
-mod domain;
-mod infrastructure;
-mod interface;
-
 use std::sync::Arc;
-use crate::config::pipeline::controller_setup::build_controllers;
-use crate::config::pipeline::{database_setup::create_database_pool, env_setup::load_env};
-use crate::config::pipeline::redis_setup::create_redis_client;
-use crate::config::pipeline::service_setup::build_use_cases;
-use crate::config::pipeline::start_http_server;
-use infrastructure::config::AppConfig;
-use crate::config::pipeline::queue_setup::setup_messaging;
+use auth_service::config::pipeline::controller_setup::build_controllers;
+use auth_service::config::pipeline::database_setup::{create_database_pool, run_migrate_command, MIGRATIONS};
+use auth_service::config::pipeline::env_setup::load_env;
+use auth_service::config::pipeline::redis_setup::{build_idempotency_store, build_revoked_tokens, create_redis_client};
+use auth_service::config::pipeline::service_setup::build_use_cases;
+use auth_service::config::pipeline::{start_http_server, HttpState};
+use auth_service::config::pipeline::grpc_setup::start_grpc_server;
+use auth_service::config::pipeline::health_setup::build_health_registry;
+use auth_service::config::pipeline::settings_setup::build_runtime_settings;
+use auth_service::infrastructure::config::AppConfig;
+use auth_service::config::pipeline::queue_setup::{run_dlq_command, setup_messaging};
+use shared::utils::messaging::outbox::{OutboxRelay, OutboxRelayConfig};
+use shared::config::loader::ConfigArgs;
+use shared::config::tracing_config::TracingConfig;
+use shared::features::errors::SystemError;
+use shared::features::metrics::{watch_db_pool, watch_outbox, watch_redis_pool};
+use shared::features::observability::init_tracing;
 
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
     load_env();
 
-    env_logger::init();
+    let _tracing = init_tracing(&TracingConfig::from_env("auth-service")).expect("Failed to initialise tracing");
 
-    let config = AppConfig::from_env();
+    // Fails fast, listing every invalid setting
+    let (config_args, args) = ConfigArgs::parse(std::env::args().skip(1)).unwrap_or_else(|e| exit_with(e));
+    let loader = AppConfig::loader();
+    let config: AppConfig = loader.load(&config_args).unwrap_or_else(|e| exit_with(e));
+    if config_args.print_config {
+        println!("{}", loader.render(&config));
+        return Ok(());
+    }
+
+    match args.first().map(String::as_str) {
+        Some("dlq") => {
+            return run_dlq_command(&config, &args[1..])
+                .await
+                .map_err(|e| std::io::Error::other(e.to_string()));
+        }
+        Some("migrate") => {
+            return run_migrate_command(&config, &args[1..])
+                .await
+                .map_err(|e| std::io::Error::other(e.to_string()));
+        }
+        _ => {}
+    }
 
     let db_pool = create_database_pool(&config)
         .await
         .expect("Failed to create database connection pool");
 
+    // Also refuses to start if the schema is newer than this binary
+    MIGRATIONS
+        .on_startup(&db_pool, config.database.run_migrations)
+        .await
+        .unwrap_or_else(|e| exit_with(e));
+
     let redis_client = create_redis_client(&config).expect("Failed to create Redis client");
 
-    let (broker, publisher, shutdown_tx) = setup_messaging(&config).await.expect("Failed to setup messaging");
+    watch_db_pool(db_pool.clone());
+    watch_redis_pool(redis_client.clone());
+
+    let (broker, publisher, shutdown_tx) = setup_messaging(&config, redis_client.clone()).await.expect("Failed to setup messaging");
 
-    let use_cases = build_use_cases(&config, &db_pool, redis_client.clone(), &Arc::new(publisher.clone()));
+    // Relays notification events committed to the outbox table
+    let outbox_relay = OutboxRelay::new(db_pool.clone(), Arc::new(broker.clone()), OutboxRelayConfig::default());
+    watch_outbox(outbox_relay.metrics());
+    let outbox_relay = outbox_relay.spawn(shutdown_tx.subscribe());
 
-    let controllers = build_controllers(use_cases);
+    let health = build_health_registry(&db_pool, &redis_client, &broker);
+    let grpc_server = start_grpc_server(&config, health.clone(), &shutdown_tx);
 
-    // Start HTTP server and handle shutdown
+    let settings = build_runtime_settings(&config, &db_pool, &shutdown_tx)
+        .await
+        .expect("Failed to load runtime settings");
+
+    let use_cases = build_use_cases(&config, &db_pool, redis_client.clone(), &Arc::new(publisher.clone()), &settings);
+
+    let api_key_validator = use_cases.api_key.clone();
+    let impersonation_auditor = use_cases.impersonation.clone();
+
+    let state = HttpState {
+        config: config.clone(),
+        controllers: build_controllers(use_cases),
+        api_key_validator,
+        revoked_tokens: build_revoked_tokens(&config, redis_client.clone()),
+        impersonation_auditor,
+        broker: broker.clone(),
+        health,
+        settings,
+        idempotency_store: build_idempotency_store(&config, redis_client.clone()),
+    };
+
+    // Start HTTP and gRPC servers and handle shutdown
     tokio::select! {
-        result = start_http_server(config, controllers) => {
+        result = start_http_server(state) => {
             result?;
         }
+        result = grpc_server => {
+            if let Err(err) = result {
+                log::error!("gRPC server error: {}", err);
+            }
+        }
         _ = tokio::signal::ctrl_c() => {
             log::info!("Received shutdown signal");
         }
@@ -46,6 +109,12 @@ async fn main() -> std::io::Result<()> {
 
     // Send shutdown signal and close broker
     shutdown_tx.send(()).expect("Failed to send shutdown signal");
+    let _ = outbox_relay.await;
     broker.close().await.expect("Failed to close MessageBroker");
     Ok(())
 }
+
+fn exit_with(err: SystemError) -> ! {
+    eprintln!("{}", err);
+    std::process::exit(1);
+}
@@ -0,0 +1,181 @@
+// Login, refresh and password reset through the HTTP API
+mod common;
+
+use actix_web::http::StatusCode;
+use auth_service::cache::auth_cache::{AuthCache, AuthCacheService};
+use auth_service::domain::repositories::refresh_token_repository::RefreshTokenRepository;
+use auth_service::domain::repositories::user_repository::UserRepository;
+use auth_service::infrastructure::database::refresh_token_repository_impl::PostgresRefreshTokenRepository;
+use auth_service::infrastructure::database::user_repository_impl::PostgresUserRepository;
+use common::fixtures::{RefreshTokenFixture, UserFixture, DEFAULT_PASSWORD};
+use common::TestApp;
+use serde_json::{json, Value};
+
+const LOGIN: &str = "/api/v1/auth/login";
+const REFRESH: &str = "/api/v1/auth/refresh";
+const RESET_REQUEST: &str = "/api/v1/auth/password-reset/request";
+const RESET_CONFIRM: &str = "/api/v1/auth/password-reset/confirm";
+const API_KEYS: &str = "/api/v1/auth/api-keys";
+
+async fn login(app: &TestApp, email: &str, password: &str) -> (StatusCode, Value) {
+    app.post(LOGIN, json!({ "identifier": email, "password": password })).await
+}
+
+#[actix_web::test]
+async fn login_issues_tokens_for_valid_credentials() {
+    let Some(app) = TestApp::spawn().await else { return };
+    let user = UserFixture::new().insert(app.db()).await;
+
+    let (status, body) = login(&app, &user.email, DEFAULT_PASSWORD).await;
+
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    assert!(body["data"]["access_token"].as_str().is_some_and(|t| !t.is_empty()));
+    assert!(body["data"]["refresh_token"].as_str().is_some_and(|t| !t.is_empty()));
+    let tokens = PostgresRefreshTokenRepository::new(app.db().clone())
+        .find_by_user_id(user.id)
+        .await
+        .unwrap();
+    assert_eq!(tokens.len(), 1);
+    let successful: i64 =
+        sqlx::query_scalar("SELECT COUNT(*) FROM login_attempts WHERE identifier = $1 AND is_successful")
+            .bind(&user.email)
+            .fetch_one(app.db())
+            .await
+            .unwrap();
+    assert_eq!(successful, 1);
+}
+
+#[actix_web::test]
+async fn login_rejects_a_wrong_password() {
+    let Some(app) = TestApp::spawn().await else { return };
+    let user = UserFixture::new().insert(app.db()).await;
+
+    let (status, _) = login(&app, &user.email, "Wrong-Password-1").await;
+
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    let user = PostgresUserRepository::new(app.db().clone())
+        .find_by_id(&user.id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(user.failed_login_attempts, 1);
+}
+
+#[actix_web::test]
+async fn login_locks_the_account_after_too_many_failures() {
+    let Some(app) = TestApp::spawn().await else { return };
+    // One short of the default limit of 5
+    let user = UserFixture::new().with_failed_attempts(4).insert(app.db()).await;
+
+    let (status, _) = login(&app, &user.email, "Wrong-Password-1").await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    let (status, _) = login(&app, &user.email, DEFAULT_PASSWORD).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+}
+
+#[actix_web::test]
+async fn login_rejects_unverified_accounts() {
+    let Some(app) = TestApp::spawn().await else { return };
+    let user = UserFixture::new().unverified().insert(app.db()).await;
+
+    let (status, _) = login(&app, &user.email, DEFAULT_PASSWORD).await;
+
+    assert_eq!(status, StatusCode::FORBIDDEN);
+}
+
+#[actix_web::test]
+async fn revoked_access_tokens_are_rejected() {
+    let Some(app) = TestApp::spawn().await else { return };
+    let user = UserFixture::new().insert(app.db()).await;
+    let (_, body) = login(&app, &user.email, DEFAULT_PASSWORD).await;
+    let access_token = body["data"]["access_token"].as_str().unwrap();
+
+    let (status, _) = app.get_as(API_KEYS, access_token).await;
+    assert_eq!(status, StatusCode::OK);
+
+    AuthCacheService::new(app.cache()).blacklist_token(access_token, 900).await.unwrap();
+
+    let (status, body) = app.get_as(API_KEYS, access_token).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED, "{}", body);
+}
+
+#[actix_web::test]
+async fn refresh_rotates_the_refresh_token() {
+    let Some(app) = TestApp::spawn().await else { return };
+    let user = UserFixture::new().insert(app.db()).await;
+    let (_, body) = login(&app, &user.email, DEFAULT_PASSWORD).await;
+    let first = body["data"]["refresh_token"].as_str().unwrap().to_string();
+
+    let (status, body) = app.post(REFRESH, json!({ "refresh_token": first })).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    let second = body["data"]["refresh_token"].as_str().unwrap().to_string();
+    assert_ne!(first, second);
+
+    // The rotated-out token is spent
+    let (status, _) = app.post(REFRESH, json!({ "refresh_token": first })).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    let (status, _) = app.post(REFRESH, json!({ "refresh_token": second })).await;
+    assert_eq!(status, StatusCode::OK);
+}
+
+#[actix_web::test]
+async fn refresh_rejects_expired_and_unknown_tokens() {
+    let Some(app) = TestApp::spawn().await else { return };
+    let user = UserFixture::new().insert(app.db()).await;
+    let (_, expired) = RefreshTokenFixture::for_user(user.id).expired().insert(app.db()).await;
+
+    let (status, _) = app.post(REFRESH, json!({ "refresh_token": expired })).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    let (status, _) = app.post(REFRESH, json!({ "refresh_token": "not-a-token" })).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+}
+
+#[actix_web::test]
+async fn password_reset_changes_the_password_and_ends_sessions() {
+    let Some(app) = TestApp::spawn().await else { return };
+    let user = UserFixture::new().insert(app.db()).await;
+    let (_, session) = RefreshTokenFixture::for_user(user.id).insert(app.db()).await;
+
+    let (status, body) = app
+        .post(RESET_REQUEST, json!({ "identifier": user.email, "identifier_type": "Email" }))
+        .await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    let token = app.sent_reset_token(user.id).await.expect("no reset email was queued");
+
+    let new_password = "N3w-Password!";
+    let (status, body) = app
+        .post(RESET_CONFIRM, json!({ "token": token, "new_password": new_password }))
+        .await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+
+    let (status, _) = login(&app, &user.email, DEFAULT_PASSWORD).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    let (status, _) = login(&app, &user.email, new_password).await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, _) = app.post(REFRESH, json!({ "refresh_token": session })).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    // A reset token works once
+    let (status, _) = app
+        .post(RESET_CONFIRM, json!({ "token": token, "new_password": "An0ther-Password!" }))
+        .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[actix_web::test]
+async fn password_reset_rejects_weak_passwords() {
+    let Some(app) = TestApp::spawn().await else { return };
+    let user = UserFixture::new().insert(app.db()).await;
+    app.post(RESET_REQUEST, json!({ "identifier": user.email, "identifier_type": "Email" }))
+        .await;
+    let token = app.sent_reset_token(user.id).await.unwrap();
+
+    let (status, _) = app
+        .post(RESET_CONFIRM, json!({ "token": token, "new_password": "alllowercase" }))
+        .await;
+
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let (status, _) = login(&app, &user.email, DEFAULT_PASSWORD).await;
+    assert_eq!(status, StatusCode::OK);
+}
@@ -0,0 +1,237 @@
+// Builders for the rows the auth flows read. Each starts from a valid,
+// unique default, so a test only spells out what it is about:
+//
+//     let user = UserFixture::new().with_failed_attempts(4).insert(app.db()).await;
+use auth_service::domain::entities::login_attempt::LoginAttempt;
+use auth_service::domain::entities::password_reset_token::PasswordResetToken;
+use auth_service::domain::entities::refresh_token::RefreshToken;
+use auth_service::domain::entities::user::User;
+use auth_service::domain::repositories::login_attempt_repository::LoginAttemptRepository;
+use auth_service::domain::repositories::password_reset_repository::PasswordResetRepository;
+use auth_service::domain::repositories::refresh_token_repository::RefreshTokenRepository;
+use auth_service::domain::repositories::user_repository::UserRepository;
+use auth_service::infrastructure::database::login_attempt_repository_impl::PostgresLoginAttemptRepository;
+use auth_service::infrastructure::database::password_reset_repository_impl::PostgresPasswordResetRepository;
+use auth_service::infrastructure::database::refresh_token_repository_impl::PostgresRefreshTokenRepository;
+use auth_service::infrastructure::database::user_repository_impl::PostgresUserRepository;
+use auth_service::infrastructure::messaging::notification_publisher::NotificationPublisher;
+use chrono::{DateTime, Duration, Utc};
+use shared::entities::enums::UserRole;
+use shared::features::helper::jwt_helper::JwtHelper;
+use shared::features::helper::otp_helper::OtpHelper;
+use shared::features::helper::password_helper::PasswordHelper;
+use shared::features::helper::token_helper::TokenHelper;
+use sqlx::{Pool, Postgres};
+use std::sync::OnceLock;
+use uuid::Uuid;
+
+pub const DEFAULT_PASSWORD: &str = "Correct-Horse-9";
+
+// Hashing is slow in debug builds, so the default password is hashed once
+fn default_password_hash() -> String {
+    static HASH: OnceLock<String> = OnceLock::new();
+    HASH.get_or_init(|| PasswordHelper::hash_string(DEFAULT_PASSWORD).unwrap())
+        .clone()
+}
+
+// A verified, active tenant with `DEFAULT_PASSWORD`
+pub struct UserFixture {
+    user: User,
+}
+
+impl UserFixture {
+    pub fn new() -> Self {
+        let email = format!("user-{}@example.com", Uuid::new_v4().simple());
+        let mut user = User::new(email, default_password_hash(), UserRole::Tenant);
+        user.is_verified = true;
+        Self { user }
+    }
+
+    pub fn with_email(mut self, email: &str) -> Self {
+        self.user.email = email.to_string();
+        self
+    }
+
+    pub fn with_phone(mut self, phone: &str) -> Self {
+        self.user.phone_number = Some(phone.to_string());
+        self
+    }
+
+    pub fn with_password(mut self, password: &str) -> Self {
+        self.user.password_hash = PasswordHelper::hash_string(password).unwrap();
+        self
+    }
+
+    pub fn with_role(mut self, role: UserRole) -> Self {
+        self.user.role = role;
+        self
+    }
+
+    pub fn unverified(mut self) -> Self {
+        self.user.is_verified = false;
+        self
+    }
+
+    pub fn inactive(mut self) -> Self {
+        self.user.is_active = false;
+        self
+    }
+
+    pub fn with_failed_attempts(mut self, attempts: i32) -> Self {
+        self.user.failed_login_attempts = attempts;
+        self
+    }
+
+    pub fn locked_for(mut self, duration: Duration) -> Self {
+        self.user.locked_until = Some(Utc::now() + duration);
+        self
+    }
+
+    pub fn build(self) -> User {
+        self.user
+    }
+
+    pub async fn insert(self, pool: &Pool<Postgres>) -> User {
+        PostgresUserRepository::new(pool.clone())
+            .create(&self.user)
+            .await
+            .expect("Failed to insert user fixture")
+    }
+}
+
+// A live refresh token; `insert` also returns the raw value a client holds
+pub struct RefreshTokenFixture {
+    user_id: Uuid,
+    value: String,
+    expires_at: DateTime<Utc>,
+    revoked: bool,
+}
+
+impl RefreshTokenFixture {
+    pub fn for_user(user_id: Uuid) -> Self {
+        Self {
+            user_id,
+            value: JwtHelper::generate_secure_token(),
+            expires_at: Utc::now() + Duration::days(30),
+            revoked: false,
+        }
+    }
+
+    pub fn expired(mut self) -> Self {
+        self.expires_at = Utc::now() - Duration::minutes(1);
+        self
+    }
+
+    pub fn revoked(mut self) -> Self {
+        self.revoked = true;
+        self
+    }
+
+    pub fn build(self) -> (RefreshToken, String) {
+        let mut token = RefreshToken::new(
+            self.user_id,
+            TokenHelper::hash_token(&self.value),
+            None,
+            None,
+            Some("127.0.0.1".to_string()),
+            None,
+            self.expires_at,
+        );
+        if self.revoked {
+            token.revoke();
+        }
+        (token, self.value)
+    }
+
+    pub async fn insert(self, pool: &Pool<Postgres>) -> (RefreshToken, String) {
+        let (token, value) = self.build();
+        let token = PostgresRefreshTokenRepository::new(pool.clone())
+            .create(&token)
+            .await
+            .expect("Failed to insert refresh token fixture");
+        (token, value)
+    }
+}
+
+// A failed attempt from `127.0.0.1`, made just now
+pub struct LoginAttemptFixture {
+    attempt: LoginAttempt,
+}
+
+impl LoginAttemptFixture {
+    pub fn for_identifier(identifier: &str) -> Self {
+        Self {
+            attempt: LoginAttempt::new(
+                identifier.to_string(),
+                "127.0.0.1".to_string(),
+                None,
+                false,
+                Some("Invalid credentials".to_string()),
+                None,
+                None,
+            ),
+        }
+    }
+
+    pub fn successful(mut self) -> Self {
+        self.attempt.is_successful = true;
+        self.attempt.failure_reason = None;
+        self
+    }
+
+    pub fn with_ip(mut self, ip_address: &str) -> Self {
+        self.attempt.ip_address = ip_address.to_string();
+        self
+    }
+
+    pub fn made_ago(mut self, age: Duration) -> Self {
+        self.attempt.created_at = Utc::now() - age;
+        self
+    }
+
+    pub fn build(self) -> LoginAttempt {
+        self.attempt
+    }
+
+    pub async fn insert(self, pool: &Pool<Postgres>) -> LoginAttempt {
+        PostgresLoginAttemptRepository::new(pool.clone())
+            .create(&self.attempt)
+            .await
+            .expect("Failed to insert login attempt fixture")
+    }
+}
+
+// An unused reset token valid for an hour; `insert` also returns the raw
+// value from the reset email
+pub struct PasswordResetTokenFixture {
+    user_id: Uuid,
+    email: String,
+    value: String,
+    expires_at: DateTime<Utc>,
+}
+
+impl PasswordResetTokenFixture {
+    pub fn for_user(user: &User) -> Self {
+        Self {
+            user_id: user.id,
+            email: user.email.clone(),
+            value: OtpHelper::generate_reset_token(),
+            expires_at: Utc::now() + Duration::hours(1),
+        }
+    }
+
+    pub fn expired(mut self) -> Self {
+        self.expires_at = Utc::now() - Duration::minutes(1);
+        self
+    }
+
+    pub async fn insert(self, pool: &Pool<Postgres>) -> (PasswordResetToken, String) {
+        let token = PasswordResetToken::new(self.user_id, TokenHelper::hash_token(&self.value), self.expires_at);
+        let event = NotificationPublisher::password_reset_event(self.user_id, &self.email, &self.value).unwrap();
+        let token = PostgresPasswordResetRepository::new(pool.clone())
+            .create_with_event(&token, &event)
+            .await
+            .expect("Failed to insert password reset token fixture");
+        (token, self.value)
+    }
+}
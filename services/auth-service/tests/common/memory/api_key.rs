@@ -0,0 +1,71 @@
+use super::{duplicate, lock};
+use async_trait::async_trait;
+use auth_service::domain::entities::api_key::ApiKey;
+use auth_service::domain::repositories::api_key_repository::ApiKeyRepository;
+use chrono::{DateTime, Utc};
+use shared::features::errors::SystemResult;
+use shared::utils::clock;
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use uuid::Uuid;
+
+#[derive(Clone, Default)]
+pub struct InMemoryApiKeyRepository {
+    keys: Arc<Mutex<HashMap<Uuid, ApiKey>>>,
+}
+
+impl InMemoryApiKeyRepository {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get(&self, id: Uuid) -> Option<ApiKey> {
+        lock(&self.keys).get(&id).cloned()
+    }
+}
+
+#[async_trait]
+impl ApiKeyRepository for InMemoryApiKeyRepository {
+    async fn create(&self, api_key: &ApiKey) -> SystemResult<ApiKey> {
+        let mut keys = lock(&self.keys);
+        if keys.values().any(|k| k.prefix == api_key.prefix) {
+            return Err(duplicate("api_keys", &api_key.prefix));
+        }
+        keys.insert(api_key.id, api_key.clone());
+        Ok(api_key.clone())
+    }
+
+    async fn find_by_id(&self, id: Uuid) -> SystemResult<Option<ApiKey>> {
+        Ok(lock(&self.keys).get(&id).cloned())
+    }
+
+    async fn find_by_prefix(&self, prefix: &str) -> SystemResult<Option<ApiKey>> {
+        Ok(lock(&self.keys).values().find(|k| k.prefix == prefix).cloned())
+    }
+
+    async fn find_by_user(&self, user_id: Uuid) -> SystemResult<Vec<ApiKey>> {
+        let mut keys: Vec<_> = lock(&self.keys)
+            .values()
+            .filter(|k| k.user_id == user_id && k.revoked_at.is_none())
+            .cloned()
+            .collect();
+        keys.sort_by_key(|r| Reverse(r.created_at));
+        Ok(keys)
+    }
+
+    async fn revoke(&self, id: Uuid) -> SystemResult<()> {
+        if let Some(key) = lock(&self.keys).get_mut(&id).filter(|k| k.revoked_at.is_none()) {
+            key.revoked_at = Some(clock::now());
+        }
+        Ok(())
+    }
+
+    async fn record_usage(&self, id: Uuid, used_at: DateTime<Utc>, ip_address: Option<&str>) -> SystemResult<()> {
+        if let Some(key) = lock(&self.keys).get_mut(&id) {
+            key.last_used_at = Some(used_at);
+            key.last_used_ip = ip_address.map(str::to_string);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,30 @@
+use super::lock;
+use async_trait::async_trait;
+use auth_service::domain::entities::audit_log::AuditLog;
+use auth_service::domain::repositories::audit_log_repository::AuditLogRepository;
+use shared::features::errors::SystemResult;
+use std::sync::{Arc, Mutex};
+
+#[derive(Clone, Default)]
+pub struct InMemoryAuditLogRepository {
+    logs: Arc<Mutex<Vec<AuditLog>>>,
+}
+
+impl InMemoryAuditLogRepository {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Oldest first
+    pub fn all(&self) -> Vec<AuditLog> {
+        lock(&self.logs).clone()
+    }
+}
+
+#[async_trait]
+impl AuditLogRepository for InMemoryAuditLogRepository {
+    async fn create(&self, audit_log: &AuditLog) -> SystemResult<()> {
+        lock(&self.logs).push(audit_log.clone());
+        Ok(())
+    }
+}
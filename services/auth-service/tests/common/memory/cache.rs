@@ -0,0 +1,160 @@
+use super::lock;
+use async_trait::async_trait;
+use auth_service::cache::{AuthCache, OtpCache};
+use chrono::{DateTime, Duration, Utc};
+use shared::features::errors::{SystemError, SystemResult};
+use shared::utils::clock;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use uuid::Uuid;
+
+// A value with the moment Redis would evict it
+#[derive(Clone)]
+struct Expiring<T> {
+    value: T,
+    expires_at: DateTime<Utc>,
+}
+
+impl<T: Clone> Expiring<T> {
+    fn new(value: T, ttl: Duration) -> Self {
+        Self {
+            value,
+            expires_at: clock::now() + ttl,
+        }
+    }
+
+    fn live(&self) -> Option<T> {
+        (clock::now() < self.expires_at).then(|| self.value.clone())
+    }
+}
+
+#[derive(Default)]
+struct Sessions {
+    by_user: HashMap<Uuid, Expiring<String>>,
+    by_token: HashMap<String, Expiring<Uuid>>,
+    blacklist: HashMap<String, Expiring<()>>,
+}
+
+#[derive(Clone, Default)]
+pub struct InMemoryAuthCache {
+    sessions: Arc<Mutex<Sessions>>,
+}
+
+impl InMemoryAuthCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn session(&self, user_id: Uuid) -> Option<String> {
+        lock(&self.sessions).by_user.get(&user_id).and_then(Expiring::live)
+    }
+}
+
+#[async_trait]
+impl AuthCache for InMemoryAuthCache {
+    async fn cache_user_session(&self, user_id: Uuid, access_token: &str) -> SystemResult<()> {
+        let mut sessions = lock(&self.sessions);
+        let ttl = Duration::seconds(3600);
+        sessions.by_user.insert(user_id, Expiring::new(access_token.to_string(), ttl));
+        sessions.by_token.insert(access_token.to_string(), Expiring::new(user_id, ttl));
+        Ok(())
+    }
+
+    async fn get_user_from_token(&self, access_token: &str) -> SystemResult<Option<Uuid>> {
+        Ok(lock(&self.sessions).by_token.get(access_token).and_then(Expiring::live))
+    }
+
+    async fn invalidate_user_session(&self, user_id: Uuid) -> SystemResult<()> {
+        let mut sessions = lock(&self.sessions);
+        if let Some(session) = sessions.by_user.remove(&user_id) {
+            sessions.by_token.remove(&session.value);
+        }
+        Ok(())
+    }
+
+    async fn blacklist_token(&self, token: &str, expiry_seconds: i64) -> SystemResult<()> {
+        lock(&self.sessions)
+            .blacklist
+            .insert(token.to_string(), Expiring::new((), Duration::seconds(expiry_seconds)));
+        Ok(())
+    }
+
+    async fn is_token_blacklisted(&self, token: &str) -> SystemResult<bool> {
+        Ok(lock(&self.sessions).blacklist.get(token).and_then(Expiring::live).is_some())
+    }
+
+    async fn refresh_session_ttl(&self, user_id: Uuid, ttl_seconds: i64) -> SystemResult<()> {
+        if let Some(session) = lock(&self.sessions).by_user.get_mut(&user_id) {
+            if session.live().is_some() {
+                *session = Expiring::new(session.value.clone(), Duration::seconds(ttl_seconds));
+            }
+        }
+        Ok(())
+    }
+}
+
+// Rate limiting uses a fixed window opened by the first request, as the
+// Redis counter does
+#[derive(Clone)]
+pub struct InMemoryOtpCache {
+    codes: Arc<Mutex<HashMap<String, Expiring<String>>>>,
+    requests: Arc<Mutex<HashMap<String, Expiring<u32>>>>,
+    max_requests: u32,
+    window: Duration,
+}
+
+impl InMemoryOtpCache {
+    pub fn new(max_requests: u32, window: Duration) -> Self {
+        Self {
+            codes: Arc::default(),
+            requests: Arc::default(),
+            max_requests,
+            window,
+        }
+    }
+
+    pub fn code(&self, identifier: &str) -> Option<String> {
+        lock(&self.codes).get(identifier).and_then(Expiring::live)
+    }
+}
+
+impl Default for InMemoryOtpCache {
+    fn default() -> Self {
+        Self::new(5, Duration::hours(1))
+    }
+}
+
+#[async_trait]
+impl OtpCache for InMemoryOtpCache {
+    async fn store_otp(&self, identifier: &str, otp_code: &str, expiry_minutes: i64) -> SystemResult<()> {
+        lock(&self.codes).insert(
+            identifier.to_string(),
+            Expiring::new(otp_code.to_string(), Duration::minutes(expiry_minutes)),
+        );
+        Ok(())
+    }
+
+    async fn get_otp(&self, identifier: &str) -> SystemResult<Option<String>> {
+        Ok(self.code(identifier))
+    }
+
+    async fn invalidate_otp(&self, identifier: &str) -> SystemResult<()> {
+        lock(&self.codes).remove(identifier);
+        Ok(())
+    }
+
+    async fn check_otp_rate_limit(&self, identifier: &str) -> SystemResult<()> {
+        let mut requests = lock(&self.requests);
+        match requests.get_mut(identifier).filter(|r| r.live().is_some()) {
+            Some(count) if count.value >= self.max_requests => Err(SystemError::OtpRateLimitExceeded),
+            Some(count) => {
+                count.value += 1;
+                Ok(())
+            }
+            None => {
+                requests.insert(identifier.to_string(), Expiring::new(1, self.window));
+                Ok(())
+            }
+        }
+    }
+}
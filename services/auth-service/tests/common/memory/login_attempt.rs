@@ -0,0 +1,65 @@
+use super::lock;
+use async_trait::async_trait;
+use auth_service::domain::entities::login_attempt::LoginAttempt;
+use auth_service::domain::repositories::login_attempt_repository::LoginAttemptRepository;
+use chrono::{DateTime, Utc};
+use shared::features::errors::SystemResult;
+use std::cmp::Reverse;
+use std::sync::{Arc, Mutex};
+
+#[derive(Clone, Default)]
+pub struct InMemoryLoginAttemptRepository {
+    attempts: Arc<Mutex<Vec<LoginAttempt>>>,
+}
+
+impl InMemoryLoginAttemptRepository {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn all(&self) -> Vec<LoginAttempt> {
+        lock(&self.attempts).clone()
+    }
+
+    // Newest first, as the queries return them
+    fn matching(&self, filter: impl Fn(&LoginAttempt) -> bool) -> Vec<LoginAttempt> {
+        let mut attempts: Vec<_> = lock(&self.attempts).iter().filter(|a| filter(a)).cloned().collect();
+        attempts.sort_by_key(|r| Reverse(r.created_at));
+        attempts
+    }
+}
+
+#[async_trait]
+impl LoginAttemptRepository for InMemoryLoginAttemptRepository {
+    async fn create(&self, attempt: &LoginAttempt) -> SystemResult<LoginAttempt> {
+        lock(&self.attempts).push(attempt.clone());
+        Ok(attempt.clone())
+    }
+
+    async fn get_recent_attempts(&self, identifier: &str, since: DateTime<Utc>) -> SystemResult<Vec<LoginAttempt>> {
+        Ok(self.matching(|a| a.identifier == identifier && a.created_at >= since))
+    }
+
+    async fn get_failed_attempts_count(&self, identifier: &str, since: DateTime<Utc>) -> SystemResult<i64> {
+        Ok(self
+            .matching(|a| a.identifier == identifier && !a.is_successful && a.created_at >= since)
+            .len() as i64)
+    }
+
+    async fn get_attempts_by_ip(&self, ip_address: &str, since: DateTime<Utc>) -> SystemResult<Vec<LoginAttempt>> {
+        Ok(self.matching(|a| a.ip_address == ip_address && a.created_at >= since))
+    }
+
+    async fn count_failed_attempts_by_ip(&self, ip: &str, since: DateTime<Utc>) -> SystemResult<i64> {
+        Ok(self
+            .matching(|a| a.ip_address == ip && !a.is_successful && a.created_at >= since)
+            .len() as i64)
+    }
+
+    async fn cleanup_old_attempts(&self, before: DateTime<Utc>) -> SystemResult<u64> {
+        let mut attempts = lock(&self.attempts);
+        let count = attempts.len();
+        attempts.retain(|a| a.created_at >= before);
+        Ok((count - attempts.len()) as u64)
+    }
+}
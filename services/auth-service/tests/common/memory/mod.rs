@@ -0,0 +1,53 @@
+// In-memory stand-ins for the Postgres repositories and Redis caches, so use
+// cases can be tested without any infrastructure. Rows live in a `Mutex`ed
+// map; filters and ordering follow the SQL they replace, and expiry is judged
+// against `clock::now()` so a `FakeClock` moves it. Clones share their rows.
+#![allow(unused_imports)] // each test binary uses different fakes
+
+mod api_key;
+mod audit_log;
+mod cache;
+mod login_attempt;
+mod oauth;
+mod organisation;
+mod password_reset;
+mod refresh_token;
+mod security_question;
+mod totp;
+mod user;
+
+pub use api_key::InMemoryApiKeyRepository;
+pub use audit_log::InMemoryAuditLogRepository;
+pub use cache::{InMemoryAuthCache, InMemoryOtpCache};
+pub use login_attempt::InMemoryLoginAttemptRepository;
+pub use oauth::{
+    InMemoryOAuthAuthorizationCodeRepository, InMemoryOAuthClientRepository, InMemoryOAuthConsentRepository,
+    InMemoryOAuthTokenRepository,
+};
+pub use organisation::{
+    InMemoryOrganisationInvitationRepository, InMemoryOrganisationMembershipRepository,
+    InMemoryOrganisationRepository,
+};
+pub use password_reset::InMemoryPasswordResetRepository;
+pub use refresh_token::InMemoryRefreshTokenRepository;
+pub use security_question::{InMemorySecurityQuestionRepository, InMemoryUserSecurityQuestionRepository};
+pub use totp::InMemoryTotpRepository;
+pub use user::InMemoryUserRepository;
+
+use shared::features::errors::SystemError;
+use std::fmt::Display;
+use std::sync::{Mutex, MutexGuard};
+
+// What Postgres reports for a unique violation
+fn duplicate(table: &str, key: impl Display) -> SystemError {
+    SystemError::DatabaseError(format!("duplicate key value violates unique constraint on {} ({})", table, key))
+}
+
+// What sqlx reports when an UPDATE ... RETURNING matches nothing
+fn missing() -> SystemError {
+    SystemError::DatabaseError("no rows returned by a query that expected to return at least one row".to_string())
+}
+
+fn lock<T>(rows: &Mutex<T>) -> MutexGuard<'_, T> {
+    rows.lock().unwrap()
+}
@@ -0,0 +1,225 @@
+use super::{duplicate, lock, missing};
+use async_trait::async_trait;
+use auth_service::domain::entities::oauth_authorization_code::OAuthAuthorizationCode;
+use auth_service::domain::entities::oauth_client::OAuthClient;
+use auth_service::domain::entities::oauth_consent::OAuthConsent;
+use auth_service::domain::entities::oauth_token::OAuthToken;
+use auth_service::domain::repositories::oauth_repository::{
+    OAuthAuthorizationCodeRepository, OAuthClientRepository, OAuthConsentRepository, OAuthTokenRepository,
+};
+use shared::features::errors::SystemResult;
+use shared::utils::clock;
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use uuid::Uuid;
+
+#[derive(Clone, Default)]
+pub struct InMemoryOAuthClientRepository {
+    clients: Arc<Mutex<HashMap<Uuid, OAuthClient>>>,
+}
+
+impl InMemoryOAuthClientRepository {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl OAuthClientRepository for InMemoryOAuthClientRepository {
+    async fn create(&self, client: &OAuthClient) -> SystemResult<OAuthClient> {
+        lock(&self.clients).insert(client.id, client.clone());
+        Ok(client.clone())
+    }
+
+    async fn find_by_id(&self, id: Uuid) -> SystemResult<Option<OAuthClient>> {
+        Ok(lock(&self.clients).get(&id).cloned())
+    }
+
+    async fn find_by_owner(&self, owner_user_id: Uuid) -> SystemResult<Vec<OAuthClient>> {
+        let mut clients: Vec<_> = lock(&self.clients)
+            .values()
+            .filter(|c| c.owner_user_id == owner_user_id && c.is_active)
+            .cloned()
+            .collect();
+        clients.sort_by_key(|r| Reverse(r.created_at));
+        Ok(clients)
+    }
+
+    async fn update(&self, client: &OAuthClient) -> SystemResult<OAuthClient> {
+        let mut clients = lock(&self.clients);
+        let stored = clients.get_mut(&client.id).ok_or_else(missing)?;
+        stored.name = client.name.clone();
+        stored.redirect_uris = client.redirect_uris.clone();
+        stored.grant_types = client.grant_types.clone();
+        stored.scopes = client.scopes.clone();
+        stored.is_active = client.is_active;
+        Ok(stored.clone())
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct InMemoryOAuthAuthorizationCodeRepository {
+    codes: Arc<Mutex<HashMap<Uuid, OAuthAuthorizationCode>>>,
+}
+
+impl InMemoryOAuthAuthorizationCodeRepository {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn all(&self) -> Vec<OAuthAuthorizationCode> {
+        lock(&self.codes).values().cloned().collect()
+    }
+}
+
+#[async_trait]
+impl OAuthAuthorizationCodeRepository for InMemoryOAuthAuthorizationCodeRepository {
+    async fn create(&self, code: &OAuthAuthorizationCode) -> SystemResult<OAuthAuthorizationCode> {
+        let mut codes = lock(&self.codes);
+        if codes.values().any(|c| c.code_hash == code.code_hash) {
+            return Err(duplicate("oauth_authorization_codes", &code.code_hash));
+        }
+        codes.insert(code.id, code.clone());
+        Ok(code.clone())
+    }
+
+    async fn find_by_code_hash(&self, code_hash: &str) -> SystemResult<Option<OAuthAuthorizationCode>> {
+        Ok(lock(&self.codes).values().find(|c| c.code_hash == code_hash).cloned())
+    }
+
+    async fn consume(&self, id: Uuid) -> SystemResult<Option<OAuthAuthorizationCode>> {
+        Ok(lock(&self.codes).get_mut(&id).filter(|c| !c.is_used).map(|stored| {
+            stored.mark_as_used();
+            stored.clone()
+        }))
+    }
+
+    async fn cleanup_expired(&self) -> SystemResult<u64> {
+        let now = clock::now();
+        let mut codes = lock(&self.codes);
+        let before = codes.len();
+        codes.retain(|_, c| c.expires_at >= now && !c.is_used);
+        Ok((before - codes.len()) as u64)
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct InMemoryOAuthTokenRepository {
+    tokens: Arc<Mutex<HashMap<Uuid, OAuthToken>>>,
+}
+
+impl InMemoryOAuthTokenRepository {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn all(&self) -> Vec<OAuthToken> {
+        lock(&self.tokens).values().cloned().collect()
+    }
+}
+
+#[async_trait]
+impl OAuthTokenRepository for InMemoryOAuthTokenRepository {
+    async fn create(&self, token: &OAuthToken) -> SystemResult<OAuthToken> {
+        let mut tokens = lock(&self.tokens);
+        if tokens.values().any(|t| t.token_hash == token.token_hash) {
+            return Err(duplicate("oauth_tokens", &token.token_hash));
+        }
+        tokens.insert(token.id, token.clone());
+        Ok(token.clone())
+    }
+
+    async fn find_by_token_hash(&self, token_hash: &str) -> SystemResult<Option<OAuthToken>> {
+        Ok(lock(&self.tokens).values().find(|t| t.token_hash == token_hash).cloned())
+    }
+
+    async fn update(&self, token: &OAuthToken) -> SystemResult<OAuthToken> {
+        if let Some(stored) = lock(&self.tokens).get_mut(&token.id) {
+            stored.is_revoked = token.is_revoked;
+            stored.revoked_at = token.revoked_at;
+        }
+        Ok(token.clone())
+    }
+
+    async fn consume(&self, id: Uuid) -> SystemResult<Option<OAuthToken>> {
+        Ok(lock(&self.tokens).get_mut(&id).filter(|t| !t.is_revoked).map(|stored| {
+            stored.revoke();
+            stored.clone()
+        }))
+    }
+
+    async fn revoke_for_user_and_client(&self, user_id: Uuid, client_id: Uuid) -> SystemResult<u64> {
+        let mut revoked = 0;
+        for token in lock(&self.tokens).values_mut() {
+            if token.user_id == user_id && token.client_id == client_id && !token.is_revoked {
+                token.revoke();
+                revoked += 1;
+            }
+        }
+        Ok(revoked)
+    }
+
+    async fn cleanup_expired(&self) -> SystemResult<u64> {
+        let now = clock::now();
+        let mut tokens = lock(&self.tokens);
+        let before = tokens.len();
+        tokens.retain(|_, t| t.expires_at >= now);
+        Ok((before - tokens.len()) as u64)
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct InMemoryOAuthConsentRepository {
+    consents: Arc<Mutex<HashMap<Uuid, OAuthConsent>>>,
+}
+
+impl InMemoryOAuthConsentRepository {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl OAuthConsentRepository for InMemoryOAuthConsentRepository {
+    async fn upsert(&self, consent: &OAuthConsent) -> SystemResult<OAuthConsent> {
+        let mut consents = lock(&self.consents);
+        // ON CONFLICT (user_id, client_id) keeps the existing row's id
+        if let Some(stored) = consents
+            .values_mut()
+            .find(|c| c.user_id == consent.user_id && c.client_id == consent.client_id)
+        {
+            stored.scopes = consent.scopes.clone();
+            stored.granted_at = consent.granted_at;
+            stored.revoked_at = None;
+            return Ok(stored.clone());
+        }
+        consents.insert(consent.id, consent.clone());
+        Ok(consent.clone())
+    }
+
+    async fn find_by_user_and_client(&self, user_id: Uuid, client_id: Uuid) -> SystemResult<Option<OAuthConsent>> {
+        Ok(lock(&self.consents)
+            .values()
+            .find(|c| c.user_id == user_id && c.client_id == client_id)
+            .cloned())
+    }
+
+    async fn find_active_by_user(&self, user_id: Uuid) -> SystemResult<Vec<OAuthConsent>> {
+        let mut consents: Vec<_> = lock(&self.consents)
+            .values()
+            .filter(|c| c.user_id == user_id && c.revoked_at.is_none())
+            .cloned()
+            .collect();
+        consents.sort_by_key(|r| Reverse(r.granted_at));
+        Ok(consents)
+    }
+
+    async fn update(&self, consent: &OAuthConsent) -> SystemResult<OAuthConsent> {
+        if let Some(stored) = lock(&self.consents).get_mut(&consent.id) {
+            stored.scopes = consent.scopes.clone();
+            stored.revoked_at = consent.revoked_at;
+        }
+        Ok(consent.clone())
+    }
+}
@@ -0,0 +1,238 @@
+use super::{duplicate, lock, missing};
+use async_trait::async_trait;
+use auth_service::domain::entities::organisation::Organisation;
+use auth_service::domain::entities::organisation_invitation::OrganisationInvitation;
+use auth_service::domain::entities::organisation_membership::OrganisationMembership;
+use auth_service::domain::repositories::organisation_repository::{
+    OrganisationInvitationRepository, OrganisationMembershipRepository, OrganisationRepository,
+};
+use shared::entities::enums::OrganisationRole;
+use shared::features::errors::SystemResult;
+use shared::utils::clock;
+use shared::utils::messaging::outbox::OutboxEvent;
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use uuid::Uuid;
+
+// Organisations and memberships are joined in SQL, so both repositories read
+// the same tables; get the membership repository from `memberships()`
+#[derive(Default)]
+struct Tables {
+    organisations: HashMap<Uuid, Organisation>,
+    memberships: HashMap<Uuid, OrganisationMembership>,
+}
+
+impl Tables {
+    fn is_active(&self, organisation_id: Uuid) -> bool {
+        self.organisations.get(&organisation_id).is_some_and(|o| o.is_active)
+    }
+
+    fn insert_membership(&mut self, membership: &OrganisationMembership) -> SystemResult<()> {
+        if self
+            .memberships
+            .values()
+            .any(|m| m.organisation_id == membership.organisation_id && m.user_id == membership.user_id)
+        {
+            return Err(duplicate("organisation_memberships", membership.user_id));
+        }
+        self.memberships.insert(membership.id, membership.clone());
+        Ok(())
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct InMemoryOrganisationRepository {
+    tables: Arc<Mutex<Tables>>,
+}
+
+impl InMemoryOrganisationRepository {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn memberships(&self) -> InMemoryOrganisationMembershipRepository {
+        InMemoryOrganisationMembershipRepository {
+            tables: self.tables.clone(),
+        }
+    }
+}
+
+#[async_trait]
+impl OrganisationRepository for InMemoryOrganisationRepository {
+    async fn create_with_owner(
+        &self,
+        organisation: &Organisation,
+        owner: &OrganisationMembership,
+    ) -> SystemResult<Organisation> {
+        let mut tables = lock(&self.tables);
+        if tables.organisations.values().any(|o| o.slug == organisation.slug) {
+            return Err(duplicate("organisations", &organisation.slug));
+        }
+        tables.organisations.insert(organisation.id, organisation.clone());
+        if let Err(e) = tables.insert_membership(owner) {
+            tables.organisations.remove(&organisation.id);
+            return Err(e);
+        }
+        Ok(organisation.clone())
+    }
+
+    async fn find_by_id(&self, id: Uuid) -> SystemResult<Option<Organisation>> {
+        Ok(lock(&self.tables).organisations.get(&id).cloned())
+    }
+
+    async fn exists_by_slug(&self, slug: &str) -> SystemResult<bool> {
+        Ok(lock(&self.tables).organisations.values().any(|o| o.slug == slug))
+    }
+
+    async fn find_by_member(&self, user_id: Uuid) -> SystemResult<Vec<Organisation>> {
+        let tables = lock(&self.tables);
+        let mut organisations: Vec<_> = tables
+            .memberships
+            .values()
+            .filter(|m| m.user_id == user_id)
+            .filter_map(|m| tables.organisations.get(&m.organisation_id))
+            .filter(|o| o.is_active)
+            .cloned()
+            .collect();
+        organisations.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(organisations)
+    }
+}
+
+#[derive(Clone)]
+pub struct InMemoryOrganisationMembershipRepository {
+    tables: Arc<Mutex<Tables>>,
+}
+
+#[async_trait]
+impl OrganisationMembershipRepository for InMemoryOrganisationMembershipRepository {
+    async fn create(&self, membership: &OrganisationMembership) -> SystemResult<OrganisationMembership> {
+        lock(&self.tables).insert_membership(membership)?;
+        Ok(membership.clone())
+    }
+
+    async fn find(&self, organisation_id: Uuid, user_id: Uuid) -> SystemResult<Option<OrganisationMembership>> {
+        Ok(lock(&self.tables)
+            .memberships
+            .values()
+            .find(|m| m.organisation_id == organisation_id && m.user_id == user_id)
+            .cloned())
+    }
+
+    async fn find_by_user(&self, user_id: Uuid) -> SystemResult<Vec<OrganisationMembership>> {
+        let mut memberships: Vec<_> = lock(&self.tables)
+            .memberships
+            .values()
+            .filter(|m| m.user_id == user_id)
+            .cloned()
+            .collect();
+        memberships.sort_by_key(|m| m.created_at);
+        Ok(memberships)
+    }
+
+    async fn find_by_organisation(&self, organisation_id: Uuid) -> SystemResult<Vec<OrganisationMembership>> {
+        let mut memberships: Vec<_> = lock(&self.tables)
+            .memberships
+            .values()
+            .filter(|m| m.organisation_id == organisation_id)
+            .cloned()
+            .collect();
+        memberships.sort_by_key(|m| m.created_at);
+        Ok(memberships)
+    }
+
+    async fn find_default_for_user(&self, user_id: Uuid) -> SystemResult<Option<OrganisationMembership>> {
+        let tables = lock(&self.tables);
+        // ORDER BY last_active_at DESC NULLS LAST, created_at
+        Ok(tables
+            .memberships
+            .values()
+            .filter(|m| m.user_id == user_id && tables.is_active(m.organisation_id))
+            .min_by_key(|m| (m.last_active_at.is_none(), Reverse(m.last_active_at), m.created_at))
+            .cloned())
+    }
+
+    async fn update(&self, membership: &OrganisationMembership) -> SystemResult<OrganisationMembership> {
+        let mut tables = lock(&self.tables);
+        let stored = tables.memberships.get_mut(&membership.id).ok_or_else(missing)?;
+        stored.role = membership.role.clone();
+        stored.last_active_at = membership.last_active_at;
+        Ok(stored.clone())
+    }
+
+    async fn delete(&self, id: Uuid) -> SystemResult<()> {
+        lock(&self.tables).memberships.remove(&id);
+        Ok(())
+    }
+
+    async fn count_owners(&self, organisation_id: Uuid) -> SystemResult<i64> {
+        Ok(lock(&self.tables)
+            .memberships
+            .values()
+            .filter(|m| m.organisation_id == organisation_id && m.role == OrganisationRole::Owner)
+            .count() as i64)
+    }
+}
+
+// Invitation emails written "in the same transaction" are kept in `outbox()`
+#[derive(Clone, Default)]
+pub struct InMemoryOrganisationInvitationRepository {
+    invitations: Arc<Mutex<HashMap<Uuid, OrganisationInvitation>>>,
+    outbox: Arc<Mutex<Vec<OutboxEvent>>>,
+}
+
+impl InMemoryOrganisationInvitationRepository {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn outbox(&self) -> Vec<OutboxEvent> {
+        lock(&self.outbox).clone()
+    }
+}
+
+#[async_trait]
+impl OrganisationInvitationRepository for InMemoryOrganisationInvitationRepository {
+    async fn create_with_event(
+        &self,
+        invitation: &OrganisationInvitation,
+        event: &OutboxEvent,
+    ) -> SystemResult<OrganisationInvitation> {
+        let mut invitations = lock(&self.invitations);
+        if invitations.values().any(|i| i.token_hash == invitation.token_hash) {
+            return Err(duplicate("organisation_invitations", &invitation.token_hash));
+        }
+        invitations.insert(invitation.id, invitation.clone());
+        lock(&self.outbox).push(event.clone());
+        Ok(invitation.clone())
+    }
+
+    async fn find_by_id(&self, id: Uuid) -> SystemResult<Option<OrganisationInvitation>> {
+        Ok(lock(&self.invitations).get(&id).cloned())
+    }
+
+    async fn find_pending_by_organisation(&self, organisation_id: Uuid) -> SystemResult<Vec<OrganisationInvitation>> {
+        let now = clock::now();
+        let mut invitations: Vec<_> = lock(&self.invitations)
+            .values()
+            .filter(|i| {
+                i.organisation_id == organisation_id
+                    && i.accepted_at.is_none()
+                    && i.revoked_at.is_none()
+                    && i.expires_at > now
+            })
+            .cloned()
+            .collect();
+        invitations.sort_by_key(|r| Reverse(r.created_at));
+        Ok(invitations)
+    }
+
+    async fn update(&self, invitation: &OrganisationInvitation) -> SystemResult<OrganisationInvitation> {
+        let mut invitations = lock(&self.invitations);
+        let stored = invitations.get_mut(&invitation.id).ok_or_else(missing)?;
+        stored.accepted_at = invitation.accepted_at;
+        stored.revoked_at = invitation.revoked_at;
+        Ok(stored.clone())
+    }
+}
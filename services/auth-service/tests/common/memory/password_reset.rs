@@ -0,0 +1,86 @@
+use super::{duplicate, lock, missing, InMemoryUserRepository};
+use async_trait::async_trait;
+use auth_service::domain::entities::password_reset_token::PasswordResetToken;
+use auth_service::domain::entities::user::User;
+use auth_service::domain::repositories::password_reset_repository::PasswordResetRepository;
+use shared::features::errors::SystemResult;
+use shared::utils::clock;
+use shared::utils::messaging::outbox::OutboxEvent;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use uuid::Uuid;
+
+// Events written "in the same transaction" are kept in order in `outbox()`.
+// Completing a reset writes the user to the shared `users` repository.
+#[derive(Clone)]
+pub struct InMemoryPasswordResetRepository {
+    tokens: Arc<Mutex<HashMap<Uuid, PasswordResetToken>>>,
+    outbox: Arc<Mutex<Vec<OutboxEvent>>>,
+    users: InMemoryUserRepository,
+}
+
+impl InMemoryPasswordResetRepository {
+    pub fn new(users: &InMemoryUserRepository) -> Self {
+        Self {
+            tokens: Default::default(),
+            outbox: Default::default(),
+            users: users.clone(),
+        }
+    }
+
+    pub fn all(&self) -> Vec<PasswordResetToken> {
+        lock(&self.tokens).values().cloned().collect()
+    }
+
+    pub fn outbox(&self) -> Vec<OutboxEvent> {
+        lock(&self.outbox).clone()
+    }
+}
+
+#[async_trait]
+impl PasswordResetRepository for InMemoryPasswordResetRepository {
+    async fn create_with_event(&self, token: &PasswordResetToken, event: &OutboxEvent) -> SystemResult<PasswordResetToken> {
+        let mut tokens = lock(&self.tokens);
+        if tokens.values().any(|t| t.token_hash == token.token_hash) {
+            return Err(duplicate("password_reset_tokens", &token.token_hash));
+        }
+        tokens.insert(token.id, token.clone());
+        lock(&self.outbox).push(event.clone());
+        Ok(token.clone())
+    }
+
+    async fn find_by_token_hash(&self, token_hash: &str) -> SystemResult<Option<PasswordResetToken>> {
+        Ok(lock(&self.tokens).values().find(|t| t.token_hash == token_hash).cloned())
+    }
+
+    async fn complete_with_event(
+        &self,
+        token: &PasswordResetToken,
+        user: &User,
+        event: &OutboxEvent,
+    ) -> SystemResult<PasswordResetToken> {
+        let mut tokens = lock(&self.tokens);
+        let stored = tokens.get_mut(&token.id).ok_or_else(missing)?;
+        *stored = token.clone();
+        self.users.put(user);
+        lock(&self.outbox).push(event.clone());
+        Ok(token.clone())
+    }
+
+    async fn cleanup_expired(&self) -> SystemResult<u64> {
+        let now = clock::now();
+        let mut tokens = lock(&self.tokens);
+        let before = tokens.len();
+        tokens.retain(|_, t| t.expires_at >= now);
+        Ok((before - tokens.len()) as u64)
+    }
+
+    async fn revoke_all_for_user(&self, user_id: Uuid) -> SystemResult<()> {
+        for token in lock(&self.tokens).values_mut() {
+            if token.user_id == user_id && !token.is_used {
+                token.mark_as_used();
+            }
+        }
+        Ok(())
+    }
+}
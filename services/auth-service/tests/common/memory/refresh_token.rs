@@ -0,0 +1,75 @@
+use super::{duplicate, lock, missing};
+use async_trait::async_trait;
+use auth_service::domain::entities::refresh_token::RefreshToken;
+use auth_service::domain::repositories::refresh_token_repository::RefreshTokenRepository;
+use shared::features::errors::SystemResult;
+use shared::utils::clock;
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use uuid::Uuid;
+
+#[derive(Clone, Default)]
+pub struct InMemoryRefreshTokenRepository {
+    tokens: Arc<Mutex<HashMap<Uuid, RefreshToken>>>,
+}
+
+impl InMemoryRefreshTokenRepository {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn all(&self) -> Vec<RefreshToken> {
+        lock(&self.tokens).values().cloned().collect()
+    }
+}
+
+#[async_trait]
+impl RefreshTokenRepository for InMemoryRefreshTokenRepository {
+    async fn create(&self, token: &RefreshToken) -> SystemResult<RefreshToken> {
+        let mut tokens = lock(&self.tokens);
+        if tokens.values().any(|t| t.token_hash == token.token_hash) {
+            return Err(duplicate("refresh_tokens", &token.token_hash));
+        }
+        tokens.insert(token.id, token.clone());
+        Ok(token.clone())
+    }
+
+    async fn find_by_token_hash(&self, token_hash: &str) -> SystemResult<Option<RefreshToken>> {
+        Ok(lock(&self.tokens).values().find(|t| t.token_hash == token_hash).cloned())
+    }
+
+    async fn find_by_user_id(&self, user_id: Uuid) -> SystemResult<Vec<RefreshToken>> {
+        let mut tokens: Vec<_> = lock(&self.tokens)
+            .values()
+            .filter(|t| t.user_id == user_id)
+            .cloned()
+            .collect();
+        tokens.sort_by_key(|r| Reverse(r.created_at));
+        Ok(tokens)
+    }
+
+    async fn update(&self, token: &RefreshToken) -> SystemResult<RefreshToken> {
+        let mut tokens = lock(&self.tokens);
+        let stored = tokens.get_mut(&token.id).ok_or_else(missing)?;
+        *stored = token.clone();
+        Ok(token.clone())
+    }
+
+    async fn revoke_all_for_user(&self, user_id: Uuid) -> SystemResult<()> {
+        for token in lock(&self.tokens).values_mut() {
+            if token.user_id == user_id && !token.is_revoked {
+                token.revoke();
+            }
+        }
+        Ok(())
+    }
+
+    async fn cleanup_expired(&self) -> SystemResult<u64> {
+        let now = clock::now();
+        let mut tokens = lock(&self.tokens);
+        let before = tokens.len();
+        tokens.retain(|_, t| t.expires_at >= now);
+        Ok((before - tokens.len()) as u64)
+    }
+}
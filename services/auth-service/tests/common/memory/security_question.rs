@@ -0,0 +1,93 @@
+use super::{duplicate, lock, missing};
+use async_trait::async_trait;
+use auth_service::domain::entities::security_question::SecurityQuestion;
+use auth_service::domain::entities::user_security_question::UserSecurityQuestion;
+use auth_service::domain::repositories::security_repository::{
+    SecurityQuestionRepository, UserSecurityQuestionRepository,
+};
+use shared::features::errors::SystemResult;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use uuid::Uuid;
+
+// The question catalogue is managed outside the service, so it is seeded
+#[derive(Clone, Default)]
+pub struct InMemorySecurityQuestionRepository {
+    questions: Arc<Mutex<Vec<SecurityQuestion>>>,
+}
+
+impl InMemorySecurityQuestionRepository {
+    pub fn with_questions(questions: Vec<SecurityQuestion>) -> Self {
+        Self {
+            questions: Arc::new(Mutex::new(questions)),
+        }
+    }
+}
+
+#[async_trait]
+impl SecurityQuestionRepository for InMemorySecurityQuestionRepository {
+    async fn get_all_questions(&self) -> SystemResult<Vec<SecurityQuestion>> {
+        Ok(lock(&self.questions).clone())
+    }
+
+    async fn get_active_questions(&self) -> SystemResult<Vec<SecurityQuestion>> {
+        Ok(lock(&self.questions).iter().filter(|q| q.is_active).cloned().collect())
+    }
+
+    async fn find_by_id(&self, id: Uuid) -> SystemResult<Option<SecurityQuestion>> {
+        Ok(lock(&self.questions).iter().find(|q| q.id == id).cloned())
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct InMemoryUserSecurityQuestionRepository {
+    answers: Arc<Mutex<HashMap<Uuid, UserSecurityQuestion>>>,
+}
+
+impl InMemoryUserSecurityQuestionRepository {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl UserSecurityQuestionRepository for InMemoryUserSecurityQuestionRepository {
+    async fn create(&self, user_question: &UserSecurityQuestion) -> SystemResult<UserSecurityQuestion> {
+        let mut answers = lock(&self.answers);
+        if answers
+            .values()
+            .any(|a| a.user_id == user_question.user_id && a.question_id == user_question.question_id)
+        {
+            return Err(duplicate("user_security_questions", user_question.question_id));
+        }
+        answers.insert(user_question.id, user_question.clone());
+        Ok(user_question.clone())
+    }
+
+    async fn find_by_user_id(&self, user_id: Uuid) -> SystemResult<Vec<UserSecurityQuestion>> {
+        Ok(lock(&self.answers).values().filter(|a| a.user_id == user_id).cloned().collect())
+    }
+
+    async fn update(&self, user_question: &UserSecurityQuestion) -> SystemResult<UserSecurityQuestion> {
+        let mut answers = lock(&self.answers);
+        let stored = answers.get_mut(&user_question.id).ok_or_else(missing)?;
+        *stored = user_question.clone();
+        Ok(user_question.clone())
+    }
+
+    async fn delete_by_user_id(&self, user_id: Uuid) -> SystemResult<()> {
+        lock(&self.answers).retain(|_, a| a.user_id != user_id);
+        Ok(())
+    }
+
+    async fn find_by_user_and_question(
+        &self,
+        user_id: Uuid,
+        question_id: Uuid,
+    ) -> SystemResult<Option<UserSecurityQuestion>> {
+        Ok(lock(&self.answers)
+            .values()
+            .find(|a| a.user_id == user_id && a.question_id == question_id)
+            .cloned())
+    }
+}
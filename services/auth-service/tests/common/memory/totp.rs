@@ -0,0 +1,42 @@
+use super::{lock, missing};
+use async_trait::async_trait;
+use auth_service::domain::entities::user_totp::UserTotp;
+use auth_service::domain::repositories::totp_repository::TotpRepository;
+use shared::features::errors::SystemResult;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use uuid::Uuid;
+
+#[derive(Clone, Default)]
+pub struct InMemoryTotpRepository {
+    factors: Arc<Mutex<HashMap<Uuid, UserTotp>>>,
+}
+
+impl InMemoryTotpRepository {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get(&self, user_id: Uuid) -> Option<UserTotp> {
+        lock(&self.factors).get(&user_id).cloned()
+    }
+}
+
+#[async_trait]
+impl TotpRepository for InMemoryTotpRepository {
+    async fn upsert(&self, totp: &UserTotp) -> SystemResult<UserTotp> {
+        lock(&self.factors).insert(totp.user_id, totp.clone());
+        Ok(totp.clone())
+    }
+
+    async fn find_by_user(&self, user_id: Uuid) -> SystemResult<Option<UserTotp>> {
+        Ok(lock(&self.factors).get(&user_id).cloned())
+    }
+
+    async fn update(&self, totp: &UserTotp) -> SystemResult<UserTotp> {
+        let mut factors = lock(&self.factors);
+        let stored = factors.get_mut(&totp.user_id).ok_or_else(missing)?;
+        *stored = totp.clone();
+        Ok(totp.clone())
+    }
+}
@@ -0,0 +1,80 @@
+use super::{duplicate, lock, missing};
+use async_trait::async_trait;
+use auth_service::domain::entities::user::User;
+use auth_service::domain::repositories::user_repository::UserRepository;
+use shared::features::errors::SystemResult;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use uuid::Uuid;
+
+#[derive(Clone, Default)]
+pub struct InMemoryUserRepository {
+    users: Arc<Mutex<HashMap<Uuid, User>>>,
+}
+
+impl InMemoryUserRepository {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get(&self, id: Uuid) -> Option<User> {
+        lock(&self.users).get(&id).cloned()
+    }
+
+    // For fakes that write users alongside their own table
+    pub(super) fn put(&self, user: &User) {
+        lock(&self.users).insert(user.id, user.clone());
+    }
+}
+
+#[async_trait]
+impl UserRepository for InMemoryUserRepository {
+    async fn create(&self, user: &User) -> SystemResult<User> {
+        let mut users = lock(&self.users);
+        if users.values().any(|u| u.email == user.email) {
+            return Err(duplicate("users", &user.email));
+        }
+        if let Some(phone) = &user.phone_number {
+            if users.values().any(|u| u.phone_number.as_ref() == Some(phone)) {
+                return Err(duplicate("users", phone));
+            }
+        }
+        users.insert(user.id, user.clone());
+        Ok(user.clone())
+    }
+
+    async fn find_by_id(&self, id: &Uuid) -> SystemResult<Option<User>> {
+        Ok(lock(&self.users).get(id).cloned())
+    }
+
+    async fn find_by_email(&self, email: &str) -> SystemResult<Option<User>> {
+        Ok(lock(&self.users).values().find(|u| u.email == email).cloned())
+    }
+
+    async fn find_by_phone(&self, phone: &str) -> SystemResult<Option<User>> {
+        Ok(lock(&self.users)
+            .values()
+            .find(|u| u.phone_number.as_deref() == Some(phone))
+            .cloned())
+    }
+
+    async fn update(&self, user: &User) -> SystemResult<User> {
+        let mut users = lock(&self.users);
+        let stored = users.get_mut(&user.id).ok_or_else(missing)?;
+        *stored = user.clone();
+        Ok(user.clone())
+    }
+
+    async fn delete(&self, id: &Uuid) -> SystemResult<()> {
+        lock(&self.users).remove(id);
+        Ok(())
+    }
+
+    async fn exists_by_email(&self, email: &str) -> SystemResult<bool> {
+        Ok(lock(&self.users).values().any(|u| u.email == email))
+    }
+
+    async fn exists_by_phone(&self, phone: &str) -> SystemResult<bool> {
+        Ok(lock(&self.users).values().any(|u| u.phone_number.as_deref() == Some(phone)))
+    }
+}
@@ -0,0 +1,160 @@
+// The auth app on ephemeral infrastructure (see `test_support`), driven
+// in-process through `actix_web::test`
+#![allow(dead_code)] // each test binary uses a different part
+
+pub mod fixtures;
+pub mod memory;
+
+use actix_web::http::StatusCode;
+use actix_web::test;
+use auth_service::cache::CACHE_KEYS;
+use auth_service::config::pipeline::controller_setup::build_controllers;
+use auth_service::config::pipeline::database_setup::MIGRATIONS;
+use auth_service::config::pipeline::health_setup::build_health_registry;
+use auth_service::config::pipeline::redis_setup::{build_idempotency_store, build_revoked_tokens};
+use auth_service::config::pipeline::service_setup::build_use_cases;
+use auth_service::config::pipeline::settings_setup::build_runtime_settings;
+use auth_service::config::pipeline::{build_app, HttpState};
+use auth_service::infrastructure::config::AppConfig;
+use auth_service::infrastructure::messaging::notification_publisher::NotificationPublisher;
+use serde_json::Value;
+use shared::config::database_config::DatabaseConfig;
+use shared::config::jwt_config::JwtConfig;
+use shared::config::messaging_config::MessagingConfig;
+use shared::config::redis_config::RedisConfig;
+use shared::utils::caching::CacheService;
+use shared::utils::messaging::MessageBroker;
+use sqlx::{Pool, Postgres};
+use std::sync::Arc;
+use test_support::TestInfra;
+use tokio::sync::broadcast;
+
+// Requests come from here unless a test says otherwise
+pub const CLIENT_ADDR: &str = "127.0.0.1:40000";
+
+pub struct TestApp {
+    pub infra: TestInfra,
+    pub config: AppConfig,
+    pub state: HttpState,
+    // Background listeners (runtime settings) stop when the app is dropped
+    _shutdown_tx: broadcast::Sender<()>,
+}
+
+impl TestApp {
+    // None when the infrastructure is unavailable and the test should skip
+    pub async fn spawn() -> Option<Self> {
+        let infra = TestInfra::start(&MIGRATIONS).await?;
+        let config = test_config(&infra);
+        let db_pool = infra.database.pool.clone();
+        let redis_pool = infra.redis.pool.clone();
+        let (shutdown_tx, _) = broadcast::channel(1);
+
+        let broker = MessageBroker::new(&config.messaging).await.expect("Failed to connect to RabbitMQ");
+        let publisher = Arc::new(NotificationPublisher::new(Arc::new(broker.clone())));
+        let settings = build_runtime_settings(&config, &db_pool, &shutdown_tx)
+            .await
+            .expect("Failed to load runtime settings");
+        let use_cases = build_use_cases(&config, &db_pool, redis_pool.clone(), &publisher, &settings);
+
+        let state = HttpState {
+            config: config.clone(),
+            api_key_validator: use_cases.api_key.clone(),
+            revoked_tokens: build_revoked_tokens(&config, redis_pool.clone()),
+            impersonation_auditor: use_cases.impersonation.clone(),
+            controllers: build_controllers(use_cases),
+            health: build_health_registry(&db_pool, &redis_pool, &broker),
+            broker,
+            settings,
+            idempotency_store: build_idempotency_store(&config, redis_pool),
+        };
+        Some(Self {
+            infra,
+            config,
+            state,
+            _shutdown_tx: shutdown_tx,
+        })
+    }
+
+    pub fn db(&self) -> &Pool<Postgres> {
+        &self.infra.database.pool
+    }
+
+    pub fn cache(&self) -> CacheService {
+        CacheService::new(self.infra.redis.pool.clone(), self.config.redis_figure_config.clone())
+    }
+
+    // POSTs a JSON body and returns the status with the parsed JSON response
+    pub async fn post(&self, path: &str, body: Value) -> (StatusCode, Value) {
+        let app = test::init_service(build_app(self.state.clone())).await;
+        let request = test::TestRequest::post()
+            .uri(path)
+            .peer_addr(CLIENT_ADDR.parse().unwrap())
+            .set_json(body)
+            .to_request();
+        let response = test::call_service(&app, request).await;
+        let status = response.status();
+        let body = test::read_body(response).await;
+        (status, serde_json::from_slice(&body).unwrap_or(Value::Null))
+    }
+
+    // GETs `path` as the holder of `access_token`
+    pub async fn get_as(&self, path: &str, access_token: &str) -> (StatusCode, Value) {
+        let app = test::init_service(build_app(self.state.clone())).await;
+        let request = test::TestRequest::get()
+            .uri(path)
+            .peer_addr(CLIENT_ADDR.parse().unwrap())
+            .insert_header(("Authorization", format!("Bearer {}", access_token)))
+            .to_request();
+        let response = test::call_service(&app, request).await;
+        let status = response.status();
+        let body = test::read_body(response).await;
+        (status, serde_json::from_slice(&body).unwrap_or(Value::Null))
+    }
+
+    // The code `send_otp` stored for `identifier`, as a user would receive it
+    pub async fn sent_otp(&self, identifier: &str) -> Option<String> {
+        self.cache().get::<String>(&CACHE_KEYS.key("otp", identifier)).await.unwrap()
+    }
+
+    // The token in the latest password reset email queued for `user_id`
+    pub async fn sent_reset_token(&self, user_id: uuid::Uuid) -> Option<String> {
+        sqlx::query_scalar(
+            r#"
+            SELECT payload->'payload'->>'reset_token'
+            FROM outbox
+            WHERE aggregate_id = $1 AND payload->'payload' ? 'reset_token'
+            ORDER BY sequence DESC
+            LIMIT 1
+            "#,
+        )
+        .bind(user_id.to_string())
+        .fetch_optional(self.db())
+        .await
+        .unwrap()
+    }
+}
+
+fn test_config(infra: &TestInfra) -> AppConfig {
+    AppConfig {
+        database: DatabaseConfig {
+            url: infra.database.url.clone(),
+            ..Default::default()
+        },
+        redis: RedisConfig {
+            url: infra.redis.url.clone(),
+            ..Default::default()
+        },
+        jwt: JwtConfig {
+            secret: "integration-test-secret-that-is-long-enough".to_string(),
+            ..Default::default()
+        },
+        otp: Default::default(),
+        server: Default::default(),
+        messaging: MessagingConfig {
+            rabbitmq_url: infra.rabbitmq.url.clone(),
+            ..Default::default()
+        },
+        redis_figure_config: Default::default(),
+        step_up: Default::default(),
+    }
+}
@@ -0,0 +1,283 @@
+// Repositories against a migrated Postgres; needs no Redis or RabbitMQ
+mod common;
+
+use auth_service::config::pipeline::database_setup::MIGRATIONS;
+use auth_service::domain::entities::blacklisted_token::TokenType;
+use auth_service::domain::entities::oauth_authorization_code::OAuthAuthorizationCode;
+use auth_service::domain::entities::oauth_client::{grant_types, OAuthClient};
+use auth_service::domain::entities::oauth_token::OAuthToken;
+use auth_service::domain::repositories::login_attempt_repository::LoginAttemptRepository;
+use auth_service::domain::repositories::oauth_repository::{
+    OAuthAuthorizationCodeRepository, OAuthClientRepository, OAuthTokenRepository,
+};
+use auth_service::domain::repositories::password_reset_repository::PasswordResetRepository;
+use auth_service::domain::repositories::refresh_token_repository::RefreshTokenRepository;
+use auth_service::domain::repositories::user_repository::UserRepository;
+use auth_service::infrastructure::database::login_attempt_repository_impl::PostgresLoginAttemptRepository;
+use auth_service::infrastructure::database::oauth_repository_impl::{
+    PostgresOAuthAuthorizationCodeRepository, PostgresOAuthClientRepository, PostgresOAuthTokenRepository,
+};
+use auth_service::infrastructure::database::password_reset_repository_impl::PostgresPasswordResetRepository;
+use auth_service::infrastructure::database::refresh_token_repository_impl::PostgresRefreshTokenRepository;
+use auth_service::infrastructure::database::user_repository_impl::PostgresUserRepository;
+use auth_service::infrastructure::messaging::notification_publisher::NotificationPublisher;
+use chrono::{Duration, Utc};
+use common::fixtures::{LoginAttemptFixture, PasswordResetTokenFixture, RefreshTokenFixture, UserFixture};
+use shared::entities::enums::UserRole;
+use serde_json::json;
+use shared::features::helper::token_helper::TokenHelper;
+use shared::utils::messaging::outbox::{Outbox, OutboxEvent, OutboxRelay, OutboxRelayConfig};
+use shared::utils::migrations::MigrationState;
+use sqlx::PgPool;
+use std::sync::Arc;
+use test_support::{InMemoryBroker, TestDatabase};
+
+#[tokio::test]
+async fn migrations_revert_and_reapply_cleanly() {
+    let Some(db) = TestDatabase::migrated(&MIGRATIONS).await else { return };
+
+    MIGRATIONS.down(&db.pool, Some(0)).await.unwrap();
+    let status = MIGRATIONS.status(&db.pool).await.unwrap();
+    assert!(status.iter().all(|m| m.state == MigrationState::Pending));
+
+    MIGRATIONS.up(&db.pool).await.unwrap();
+    let status = MIGRATIONS.status(&db.pool).await.unwrap();
+    assert!(status.iter().all(|m| m.state == MigrationState::Applied));
+}
+
+#[tokio::test]
+async fn users_are_found_by_email_and_phone() {
+    let Some(db) = TestDatabase::migrated(&MIGRATIONS).await else { return };
+    let repo = PostgresUserRepository::new(db.pool.clone());
+
+    let user = UserFixture::new()
+        .with_phone("+2348012345678")
+        .with_role(UserRole::Landlord)
+        .insert(&db.pool)
+        .await;
+
+    let by_email = repo.find_by_email(&user.email).await.unwrap().unwrap();
+    assert_eq!(by_email.id, user.id);
+    assert_eq!(by_email.role, UserRole::Landlord);
+    let by_phone = repo.find_by_phone("+2348012345678").await.unwrap().unwrap();
+    assert_eq!(by_phone.id, user.id);
+    assert!(repo.exists_by_email(&user.email).await.unwrap());
+}
+
+#[tokio::test]
+async fn refresh_tokens_are_found_by_hash_and_revoked_per_user() {
+    let Some(db) = TestDatabase::migrated(&MIGRATIONS).await else { return };
+    let repo = PostgresRefreshTokenRepository::new(db.pool.clone());
+    let user = UserFixture::new().insert(&db.pool).await;
+    let other = UserFixture::new().insert(&db.pool).await;
+
+    let (token, value) = RefreshTokenFixture::for_user(user.id).insert(&db.pool).await;
+    RefreshTokenFixture::for_user(user.id).insert(&db.pool).await;
+    let (other_token, _) = RefreshTokenFixture::for_user(other.id).insert(&db.pool).await;
+
+    let found = repo.find_by_token_hash(&TokenHelper::hash_token(&value)).await.unwrap().unwrap();
+    assert_eq!(found.id, token.id);
+    assert_eq!(found.ip_address.as_deref(), Some("127.0.0.1"));
+    assert!(found.is_valid());
+
+    repo.revoke_all_for_user(user.id).await.unwrap();
+    let tokens = repo.find_by_user_id(user.id).await.unwrap();
+    assert_eq!(tokens.len(), 2);
+    assert!(tokens.iter().all(|t| t.is_revoked && t.revoked_at.is_some()));
+    let other_token = repo.find_by_token_hash(&other_token.token_hash).await.unwrap().unwrap();
+    assert!(!other_token.is_revoked);
+}
+
+#[tokio::test]
+async fn expired_refresh_tokens_are_cleaned_up() {
+    let Some(db) = TestDatabase::migrated(&MIGRATIONS).await else { return };
+    let repo = PostgresRefreshTokenRepository::new(db.pool.clone());
+    let user = UserFixture::new().insert(&db.pool).await;
+
+    let (expired, _) = RefreshTokenFixture::for_user(user.id).expired().insert(&db.pool).await;
+    let (live, _) = RefreshTokenFixture::for_user(user.id).insert(&db.pool).await;
+    assert!(!expired.is_valid());
+
+    assert_eq!(repo.cleanup_expired().await.unwrap(), 1);
+    let remaining = repo.find_by_user_id(user.id).await.unwrap();
+    assert_eq!(remaining.iter().map(|t| t.id).collect::<Vec<_>>(), vec![live.id]);
+}
+
+#[tokio::test]
+async fn failed_login_attempts_are_counted_within_the_window() {
+    let Some(db) = TestDatabase::migrated(&MIGRATIONS).await else { return };
+    let repo = PostgresLoginAttemptRepository::new(db.pool.clone());
+    let identifier = "counted@example.com";
+
+    LoginAttemptFixture::for_identifier(identifier).insert(&db.pool).await;
+    LoginAttemptFixture::for_identifier(identifier).insert(&db.pool).await;
+    LoginAttemptFixture::for_identifier(identifier).successful().insert(&db.pool).await;
+    LoginAttemptFixture::for_identifier(identifier).made_ago(Duration::hours(2)).insert(&db.pool).await;
+    LoginAttemptFixture::for_identifier("other@example.com").with_ip("10.0.0.7").insert(&db.pool).await;
+
+    let since = Utc::now() - Duration::minutes(15);
+    assert_eq!(repo.get_failed_attempts_count(identifier, since).await.unwrap(), 2);
+    assert_eq!(repo.count_failed_attempts_by_ip("127.0.0.1", since).await.unwrap(), 2);
+    assert_eq!(repo.count_failed_attempts_by_ip("10.0.0.7", since).await.unwrap(), 1);
+}
+
+#[tokio::test]
+async fn password_reset_tokens_queue_their_email() {
+    let Some(db) = TestDatabase::migrated(&MIGRATIONS).await else { return };
+    let repo = PostgresPasswordResetRepository::new(db.pool.clone());
+    let user = UserFixture::new().insert(&db.pool).await;
+
+    let (token, value) = PasswordResetTokenFixture::for_user(&user).insert(&db.pool).await;
+
+    let found = repo.find_by_token_hash(&TokenHelper::hash_token(&value)).await.unwrap().unwrap();
+    assert_eq!(found.id, token.id);
+    assert!(found.is_valid());
+    let queued: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM outbox WHERE aggregate_id = $1")
+        .bind(user.id.to_string())
+        .fetch_one(&db.pool)
+        .await
+        .unwrap();
+    assert_eq!(queued, 1);
+}
+
+#[tokio::test]
+async fn completing_a_password_reset_is_all_or_nothing() {
+    let Some(db) = TestDatabase::migrated(&MIGRATIONS).await else { return };
+    let repo = PostgresPasswordResetRepository::new(db.pool.clone());
+    let users = PostgresUserRepository::new(db.pool.clone());
+    let user = UserFixture::new().insert(&db.pool).await;
+    let (mut token, _) = PasswordResetTokenFixture::for_user(&user).insert(&db.pool).await;
+    let event = NotificationPublisher::password_changed_event(user.id, &user.email).unwrap();
+    let mut changed = user.clone();
+    changed.password_hash = "new-hash".to_string();
+
+    // A token that is not stored fails the transaction after the user update
+    let mut unknown = token.clone();
+    unknown.id = uuid::Uuid::new_v4();
+    unknown.mark_as_used();
+    assert!(repo.complete_with_event(&unknown, &changed, &event).await.is_err());
+    let stored = users.find_by_id(&user.id).await.unwrap().unwrap();
+    assert_eq!(stored.password_hash, user.password_hash);
+
+    token.mark_as_used();
+    repo.complete_with_event(&token, &changed, &event).await.unwrap();
+
+    let stored = users.find_by_id(&user.id).await.unwrap().unwrap();
+    assert_eq!(stored.password_hash, "new-hash");
+    let found = repo.find_by_token_hash(&token.token_hash).await.unwrap().unwrap();
+    assert!(!found.is_valid());
+    let queued: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM outbox WHERE aggregate_id = $1")
+        .bind(user.id.to_string())
+        .fetch_one(&db.pool)
+        .await
+        .unwrap();
+    assert_eq!(queued, 2);
+}
+
+#[tokio::test]
+async fn oauth_codes_and_refresh_tokens_are_spent_once_under_concurrency() {
+    let Some(db) = TestDatabase::migrated(&MIGRATIONS).await else { return };
+    let codes = PostgresOAuthAuthorizationCodeRepository::new(db.pool.clone());
+    let tokens = PostgresOAuthTokenRepository::new(db.pool.clone());
+    let user = UserFixture::new().insert(&db.pool).await;
+    let client = PostgresOAuthClientRepository::new(db.pool.clone())
+        .create(&OAuthClient::new(
+            user.id,
+            "Partner".to_string(),
+            None,
+            vec!["https://partner.example.com/callback".to_string()],
+            vec![grant_types::AUTHORIZATION_CODE.to_string()],
+            vec!["read:properties".to_string()],
+        ))
+        .await
+        .unwrap();
+    let expires_at = Utc::now() + Duration::minutes(10);
+
+    let code = codes
+        .create(&OAuthAuthorizationCode::new(
+            TokenHelper::hash_token("code"),
+            client.id,
+            user.id,
+            "https://partner.example.com/callback".to_string(),
+            vec!["read:properties".to_string()],
+            None,
+            None,
+            expires_at,
+        ))
+        .await
+        .unwrap();
+    let (first, second) = tokio::join!(codes.consume(code.id), codes.consume(code.id));
+    let spent: Vec<_> = [first.unwrap(), second.unwrap()].into_iter().flatten().collect();
+    assert_eq!(spent.len(), 1);
+    assert!(spent[0].is_used);
+
+    let refresh = tokens
+        .create(&OAuthToken::new(
+            uuid::Uuid::new_v4(),
+            TokenHelper::hash_token("refresh"),
+            TokenType::Refresh,
+            client.id,
+            user.id,
+            vec!["read:properties".to_string()],
+            expires_at,
+        ))
+        .await
+        .unwrap();
+    let (first, second) = tokio::join!(tokens.consume(refresh.id), tokens.consume(refresh.id));
+    let spent: Vec<_> = [first.unwrap(), second.unwrap()].into_iter().flatten().collect();
+    assert_eq!(spent.len(), 1);
+    assert!(spent[0].is_revoked);
+}
+
+async fn queue_event(pool: &PgPool, aggregate_id: &str, routing_key: &str) {
+    let event = OutboxEvent {
+        id: uuid::Uuid::new_v4(),
+        aggregate_type: "user".to_string(),
+        aggregate_id: aggregate_id.to_string(),
+        exchange: "borough.events".to_string(),
+        routing_key: routing_key.to_string(),
+        payload: json!({}),
+    };
+    Outbox::enqueue(&mut pool.acquire().await.unwrap(), &event).await.unwrap();
+}
+
+#[tokio::test]
+async fn the_outbox_relay_keeps_aggregates_in_order_and_dead_letters_what_never_sends() {
+    let Some(db) = TestDatabase::migrated(&MIGRATIONS).await else { return };
+    queue_event(&db.pool, "a", "a.1").await;
+    queue_event(&db.pool, "a", "a.2").await;
+    queue_event(&db.pool, "b", "b.1").await;
+    let config = OutboxRelayConfig {
+        batch_size: 1,
+        max_attempts: 2,
+        ..OutboxRelayConfig::default()
+    };
+    let down = InMemoryBroker::new();
+    down.go_down();
+    let failing_relay = OutboxRelay::new(db.pool.clone(), Arc::new(down), config.clone());
+    let broker = InMemoryBroker::new();
+    let relay = OutboxRelay::new(db.pool.clone(), Arc::new(broker.clone()), config);
+    let routing_keys = || broker.published().into_iter().map(|m| m.routing_key).collect::<Vec<_>>();
+
+    // a.1 backs off; a.2 waits behind it and does not take b.1's place in the batch
+    assert_eq!(failing_relay.relay_batch().await.unwrap(), 0);
+    assert_eq!(relay.relay_batch().await.unwrap(), 1);
+    assert_eq!(relay.relay_batch().await.unwrap(), 0);
+    assert_eq!(routing_keys(), ["b.1"]);
+
+    // Its last attempt dead-letters a.1, which stops holding up a.2
+    sqlx::query("UPDATE outbox SET next_attempt_at = NOW() WHERE routing_key = 'a.1'")
+        .execute(&db.pool)
+        .await
+        .unwrap();
+    assert_eq!(failing_relay.relay_batch().await.unwrap(), 0);
+    assert_eq!(relay.relay_batch().await.unwrap(), 1);
+    assert_eq!(routing_keys(), ["b.1", "a.2"]);
+
+    let (attempts, dead_lettered): (i32, bool) =
+        sqlx::query_as("SELECT attempts, failed_at IS NOT NULL FROM outbox WHERE routing_key = 'a.1'")
+            .fetch_one(&db.pool)
+            .await
+            .unwrap();
+    assert_eq!((attempts, dead_lettered), (2, true));
+}
@@ -0,0 +1,80 @@
+// Sending and verifying one-time codes through the HTTP API
+mod common;
+
+use actix_web::http::StatusCode;
+use auth_service::domain::repositories::user_repository::UserRepository;
+use auth_service::infrastructure::database::user_repository_impl::PostgresUserRepository;
+use common::fixtures::UserFixture;
+use common::TestApp;
+use serde_json::json;
+
+const SEND: &str = "/api/v1/auth/otp/send";
+const VERIFY: &str = "/api/v1/auth/otp/verify";
+
+#[actix_web::test]
+async fn verifying_the_sent_code_verifies_the_account() {
+    let Some(app) = TestApp::spawn().await else { return };
+    let user = UserFixture::new().unverified().insert(app.db()).await;
+
+    let (status, body) = app
+        .post(SEND, json!({ "identifier": user.email, "identifier_type": "Email" }))
+        .await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    let code = app.sent_otp(&user.email).await.expect("no code was stored");
+
+    let (status, body) = app.post(VERIFY, json!({ "identifier": user.email, "otp_code": code })).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+
+    let user = PostgresUserRepository::new(app.db().clone())
+        .find_by_id(&user.id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(user.is_verified);
+    // Codes are single use
+    let (status, _) = app.post(VERIFY, json!({ "identifier": user.email, "otp_code": code })).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[actix_web::test]
+async fn a_wrong_code_is_rejected() {
+    let Some(app) = TestApp::spawn().await else { return };
+    let user = UserFixture::new().unverified().insert(app.db()).await;
+    app.post(SEND, json!({ "identifier": user.email, "identifier_type": "Email" }))
+        .await;
+    let code = app.sent_otp(&user.email).await.unwrap();
+    let wrong = if code == "000000" { "111111" } else { "000000" };
+
+    let (status, _) = app.post(VERIFY, json!({ "identifier": user.email, "otp_code": wrong })).await;
+
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let user = PostgresUserRepository::new(app.db().clone())
+        .find_by_id(&user.id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(!user.is_verified);
+}
+
+#[actix_web::test]
+async fn verifying_without_a_sent_code_fails() {
+    let Some(app) = TestApp::spawn().await else { return };
+
+    let (status, _) = app
+        .post(VERIFY, json!({ "identifier": "nobody@example.com", "otp_code": "123456" }))
+        .await;
+
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[actix_web::test]
+async fn malformed_identifiers_are_rejected_before_sending() {
+    let Some(app) = TestApp::spawn().await else { return };
+
+    let (status, _) = app
+        .post(SEND, json!({ "identifier": "not-an-email", "identifier_type": "Email" }))
+        .await;
+
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(app.sent_otp("not-an-email").await, None);
+}
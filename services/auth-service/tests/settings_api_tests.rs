@@ -0,0 +1,156 @@
+// The admin settings routes over a real `runtime_settings` table
+use actix_web::http::StatusCode;
+use actix_web::{test, web, App};
+use auth_service::config::pipeline::database_setup::MIGRATIONS;
+use auth_service::infrastructure::settings::{LOGIN_LOCKOUT_MINUTES, LOGIN_MAX_FAILED_ATTEMPTS};
+use serde_json::{json, Value};
+use shared::config::jwt_config::JwtConfig;
+use shared::entities::enums::UserRole;
+use shared::features::helper::jwt_helper::JwtHelper;
+use shared::features::security::auth::AuthMiddleware;
+use shared::features::security::jwt::JwtClaims;
+use shared::features::settings::routes::settings_routes;
+use shared::features::settings::RuntimeSettings;
+use std::sync::Arc;
+use test_support::TestDatabase;
+use uuid::Uuid;
+
+fn jwt_config() -> JwtConfig {
+    JwtConfig {
+        secret: "settings-test-secret-that-is-long-enough".to_string(),
+        ..Default::default()
+    }
+}
+
+fn token_for(role: UserRole) -> String {
+    JwtHelper::sign_claims(&claims_for(role), &jwt_config().secret).unwrap()
+}
+
+fn claims_for(role: UserRole) -> JwtClaims {
+    let config = jwt_config();
+    JwtClaims::new(
+        Uuid::new_v4(),
+        "operator@example.com".to_string(),
+        role,
+        vec![],
+        config.issuer.clone(),
+        config.audience.clone(),
+        Uuid::new_v4(),
+        config.access_token_expiry as usize,
+    )
+}
+
+// None when Postgres is unavailable and the test should skip
+async fn settings() -> Option<(TestDatabase, Arc<RuntimeSettings>)> {
+    let database = TestDatabase::migrated(&MIGRATIONS).await?;
+    let settings = Arc::new(
+        RuntimeSettings::new(database.pool.clone())
+            .with_setting(&LOGIN_MAX_FAILED_ATTEMPTS)
+            .with_setting(&LOGIN_LOCKOUT_MINUTES),
+    );
+    Some((database, settings))
+}
+
+// PUTs `{"value": value}` to the setting as a holder of `role`
+async fn put(settings: &Arc<RuntimeSettings>, role: UserRole, key: &str, value: Value) -> (StatusCode, Value) {
+    put_with(settings, &token_for(role), key, value).await
+}
+
+async fn put_with(settings: &Arc<RuntimeSettings>, token: &str, key: &str, value: Value) -> (StatusCode, Value) {
+    let app = test::init_service(
+        App::new()
+            .wrap(AuthMiddleware::new(jwt_config()))
+            .app_data(web::Data::from(settings.clone()))
+            .service(settings_routes()),
+    )
+    .await;
+    let request = test::TestRequest::put()
+        .uri(&format!("/settings/{}", key))
+        .insert_header(("Authorization", format!("Bearer {}", token)))
+        .set_json(json!({ "value": value }))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    let status = response.status();
+    let body = test::read_body(response).await;
+    (status, serde_json::from_slice(&body).unwrap_or(Value::Null))
+}
+
+#[actix_web::test]
+async fn admins_can_change_a_setting_within_its_range() {
+    let Some((_database, settings)) = settings().await else { return };
+
+    let (status, body) = put(&settings, UserRole::Admin, LOGIN_MAX_FAILED_ATTEMPTS.key(), json!(3)).await;
+
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    assert_eq!(settings.get(&LOGIN_MAX_FAILED_ATTEMPTS), 3);
+    settings.refresh().await.unwrap();
+    assert_eq!(settings.get(&LOGIN_MAX_FAILED_ATTEMPTS), 3);
+}
+
+#[actix_web::test]
+async fn values_outside_a_settings_range_are_rejected() {
+    let Some((_database, settings)) = settings().await else { return };
+
+    for (setting, value) in [
+        (LOGIN_MAX_FAILED_ATTEMPTS.key(), json!(0)),
+        (LOGIN_MAX_FAILED_ATTEMPTS.key(), json!(-5)),
+        (LOGIN_MAX_FAILED_ATTEMPTS.key(), json!(101)),
+        (LOGIN_LOCKOUT_MINUTES.key(), json!(0)),
+        (LOGIN_LOCKOUT_MINUTES.key(), json!(60 * 24 * 365)),
+    ] {
+        let (status, body) = put(&settings, UserRole::SuperAdmin, setting, value.clone()).await;
+
+        assert_eq!(status, StatusCode::BAD_REQUEST, "{} = {}: {}", setting, value, body);
+    }
+    assert_eq!(settings.get(&LOGIN_MAX_FAILED_ATTEMPTS), 5);
+    assert_eq!(settings.get(&LOGIN_LOCKOUT_MINUTES), 30);
+}
+
+#[actix_web::test]
+async fn values_of_the_wrong_type_are_rejected() {
+    let Some((_database, settings)) = settings().await else { return };
+
+    let (status, _) = put(&settings, UserRole::Admin, LOGIN_LOCKOUT_MINUTES.key(), json!("forever")).await;
+
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[actix_web::test]
+async fn only_admins_can_change_settings() {
+    let Some((_database, settings)) = settings().await else { return };
+
+    let (status, _) = put(&settings, UserRole::Tenant, LOGIN_MAX_FAILED_ATTEMPTS.key(), json!(50)).await;
+
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    assert_eq!(settings.get(&LOGIN_MAX_FAILED_ATTEMPTS), 5);
+}
+
+#[actix_web::test]
+async fn delegated_admin_credentials_cannot_change_settings() {
+    let Some((_database, settings)) = settings().await else { return };
+
+    for claims in [
+        claims_for(UserRole::Admin).with_api_key_id(Uuid::new_v4()),
+        claims_for(UserRole::SuperAdmin).with_client_id(Uuid::new_v4().to_string()),
+    ] {
+        let token = JwtHelper::sign_claims(&claims, &jwt_config().secret).unwrap();
+        let (status, _) = put_with(&settings, &token, LOGIN_MAX_FAILED_ATTEMPTS.key(), json!(50)).await;
+
+        assert_eq!(status, StatusCode::FORBIDDEN);
+    }
+    assert_eq!(settings.get(&LOGIN_MAX_FAILED_ATTEMPTS), 5);
+}
+
+#[actix_web::test]
+async fn ranges_also_apply_to_values_already_stored() {
+    let Some((database, settings)) = settings().await else { return };
+
+    sqlx::query("INSERT INTO runtime_settings (key, value) VALUES ($1, '0'::jsonb)")
+        .bind(LOGIN_MAX_FAILED_ATTEMPTS.key())
+        .execute(&database.pool)
+        .await
+        .unwrap();
+    settings.refresh().await.unwrap();
+
+    assert_eq!(settings.get(&LOGIN_MAX_FAILED_ATTEMPTS), 5);
+}
@@ -0,0 +1,1042 @@
+// Use cases on the in-memory repositories, caches and broker in
+// `common::memory`; needs no infrastructure at all
+mod common;
+
+use auth_service::application::use_cases::api_key_use_case::ApiKeyUseCase;
+use auth_service::application::use_cases::impersonation_use_case::ImpersonationUseCase;
+use auth_service::application::use_cases::login_use_case::LoginUseCase;
+use auth_service::application::use_cases::oauth_use_case::{ClientCredentials, OAuthUseCase};
+use auth_service::application::use_cases::organisation_use_case::OrganisationUseCase;
+use auth_service::application::use_cases::otp_use_case::OtpUseCase;
+use auth_service::application::use_cases::password_reset_use_case::PasswordResetUseCase;
+use auth_service::application::use_cases::refresh_token_use_case::RefreshTokenUseCase;
+use auth_service::application::use_cases::security_question_use_case::SecurityQuestionUseCase;
+use auth_service::application::use_cases::step_up_use_case::StepUpUseCase;
+use auth_service::cache::auth_cache::AuthCache;
+use auth_service::cache::otp_cache::OtpCache;
+use auth_service::domain::entities::audit_log::audit_actions;
+use auth_service::domain::entities::oauth_client::grant_types;
+use auth_service::domain::entities::organisation_membership::OrganisationMembership;
+use auth_service::domain::entities::security_question::SecurityQuestion;
+use auth_service::domain::entities::user::User;
+use auth_service::domain::entities::user_permission::permissions;
+use auth_service::domain::entities::user_totp::UserTotp;
+use auth_service::domain::repositories::organisation_repository::OrganisationMembershipRepository;
+use auth_service::domain::repositories::refresh_token_repository::RefreshTokenRepository;
+use auth_service::domain::repositories::totp_repository::TotpRepository;
+use auth_service::domain::repositories::user_repository::UserRepository;
+use auth_service::infrastructure::messaging::notification_publisher::NotificationPublisher;
+use auth_service::infrastructure::settings::{LOGIN_LOCKOUT_MINUTES, LOGIN_MAX_FAILED_ATTEMPTS};
+use chrono::Duration;
+use common::fixtures::{RefreshTokenFixture, UserFixture, DEFAULT_PASSWORD};
+use common::memory::*;
+use shared::config::jwt_config::JwtConfig;
+use shared::config::step_up_config::StepUpConfig;
+use shared::entities::dtos::auth::api_key::CreateApiKeyRequest;
+use shared::entities::dtos::auth::auth::LoginRequest;
+use shared::entities::dtos::auth::impersonation::ImpersonateRequest;
+use shared::entities::dtos::auth::oauth::{
+    AuthorizeRequest, IntrospectRequest, RegisterOAuthClientRequest, RegisterOAuthClientResponse, RevokeTokenRequest,
+    TokenRequest,
+};
+use shared::entities::dtos::auth::organisation::{CreateOrganisationRequest, UpdateMemberRoleRequest};
+use shared::entities::dtos::auth::otp::{SendOtpRequest, VerifyOtpRequest};
+use shared::entities::dtos::auth::password::{PasswordResetConfirmRequest, PasswordResetRequest};
+use shared::entities::dtos::auth::step_up::{ReauthenticateRequest, ReauthenticationMethod};
+use shared::entities::dtos::auth::question::{
+    SecurityQuestionAnswer, SetSecurityQuestionsRequest, VerifySecurityQuestionsRequest,
+};
+use shared::entities::dtos::auth::token::RefreshTokenRequest;
+use shared::entities::enums::{IdentifierType, OrganisationRole, UserRole};
+use shared::events::notification_event::email_otp_requested_event::EmailOtpRequestedEvent;
+use shared::events::notification_event::password_reset_requested_event::PasswordResetRequestedEvent;
+use shared::events::{DomainEvent, EventEnvelope};
+use shared::features::errors::SystemError;
+use shared::features::helper::jwt_helper::JwtHelper;
+use shared::features::helper::totp_helper::TotpHelper;
+use shared::features::security::auth::{ImpersonatedRequest, ImpersonationAuditor};
+use shared::features::security::jwt::{amr, JwtClaims};
+use shared::features::settings::RuntimeSettings;
+use sqlx::postgres::PgPoolOptions;
+use std::sync::Arc;
+use test_support::{FakeClock, InMemoryBroker};
+use uuid::Uuid;
+
+fn jwt_config() -> JwtConfig {
+    JwtConfig {
+        secret: "use-case-test-secret-that-is-long-enough".to_string(),
+        ..Default::default()
+    }
+}
+
+// Defaults only; nothing is read from the (never opened) pool
+fn settings() -> Arc<RuntimeSettings> {
+    let pool = PgPoolOptions::new().connect_lazy("postgres://unused").unwrap();
+    Arc::new(
+        RuntimeSettings::new(pool)
+            .with_setting(&LOGIN_MAX_FAILED_ATTEMPTS)
+            .with_setting(&LOGIN_LOCKOUT_MINUTES),
+    )
+}
+
+fn session_for(user: &User) -> JwtClaims {
+    let config = jwt_config();
+    JwtClaims::new(
+        user.id,
+        user.email.clone(),
+        user.role.clone(),
+        vec![],
+        config.issuer,
+        config.audience,
+        Uuid::new_v4(),
+        config.access_token_expiry as usize,
+    )
+}
+
+async fn seed(users: &InMemoryUserRepository, user: User) -> User {
+    users.create(&user).await.unwrap()
+}
+
+fn login_request(user: &User, password: &str) -> LoginRequest {
+    LoginRequest {
+        identifier: user.email.clone(),
+        password: password.to_string(),
+        device_info: None,
+    }
+}
+
+struct Login {
+    use_case: LoginUseCase,
+    users: InMemoryUserRepository,
+    refresh_tokens: InMemoryRefreshTokenRepository,
+    cache: InMemoryAuthCache,
+}
+
+fn login() -> Login {
+    let users = InMemoryUserRepository::new();
+    let refresh_tokens = InMemoryRefreshTokenRepository::new();
+    let cache = InMemoryAuthCache::new();
+    let use_case = LoginUseCase::new(
+        Arc::new(users.clone()),
+        Arc::new(refresh_tokens.clone()),
+        Arc::new(InMemoryLoginAttemptRepository::new()),
+        Arc::new(InMemoryOrganisationRepository::new().memberships()),
+        Arc::new(cache.clone()),
+        jwt_config(),
+        settings(),
+    );
+    Login {
+        use_case,
+        users,
+        refresh_tokens,
+        cache,
+    }
+}
+
+#[tokio::test]
+async fn login_issues_tokens_and_caches_the_session() {
+    let login = login();
+    let user = seed(&login.users, UserFixture::new().with_failed_attempts(2).build()).await;
+
+    let (response, _) = login
+        .use_case
+        .execute(login_request(&user, DEFAULT_PASSWORD), "127.0.0.1".to_string(), None)
+        .await
+        .unwrap();
+
+    assert_eq!(login.cache.session(user.id), Some(response.access_token));
+    assert_eq!(login.refresh_tokens.all().len(), 1);
+    let stored = login.users.get(user.id).unwrap();
+    assert_eq!(stored.failed_login_attempts, 0);
+    assert!(stored.last_login_at.is_some());
+}
+
+#[tokio::test]
+async fn lockout_lifts_once_the_lockout_period_has_passed() {
+    let clock = FakeClock::new();
+    let _guard = clock.install();
+    let login = login();
+    let user = seed(&login.users, UserFixture::new().with_failed_attempts(4).build()).await;
+
+    let result = login
+        .use_case
+        .execute(login_request(&user, "Wrong-Password-1"), "127.0.0.1".to_string(), None)
+        .await;
+    assert!(matches!(result, Err(SystemError::InvalidCredentials)));
+
+    let result = login
+        .use_case
+        .execute(login_request(&user, DEFAULT_PASSWORD), "127.0.0.1".to_string(), None)
+        .await;
+    assert!(matches!(result, Err(SystemError::AccountLocked)));
+
+    clock.advance(Duration::minutes(LOGIN_LOCKOUT_MINUTES.default_value() + 1));
+    login
+        .use_case
+        .execute(login_request(&user, DEFAULT_PASSWORD), "127.0.0.1".to_string(), None)
+        .await
+        .unwrap();
+}
+
+fn refresh(users: &InMemoryUserRepository, refresh_tokens: &InMemoryRefreshTokenRepository) -> RefreshTokenUseCase {
+    RefreshTokenUseCase::new(
+        Arc::new(users.clone()),
+        Arc::new(refresh_tokens.clone()),
+        Arc::new(InMemoryOrganisationRepository::new().memberships()),
+        Arc::new(InMemoryAuthCache::new()),
+        jwt_config(),
+    )
+}
+
+#[tokio::test]
+async fn refreshing_rotates_the_refresh_token() {
+    let users = InMemoryUserRepository::new();
+    let refresh_tokens = InMemoryRefreshTokenRepository::new();
+    let use_case = refresh(&users, &refresh_tokens);
+    let user = seed(&users, UserFixture::new().build()).await;
+    let (token, value) = RefreshTokenFixture::for_user(user.id).build();
+    refresh_tokens.create(&token).await.unwrap();
+
+    let (response, _) = use_case
+        .execute(RefreshTokenRequest { refresh_token: value.clone() })
+        .await
+        .unwrap();
+    assert_ne!(response.refresh_token, value);
+
+    // The old token is spent
+    let result = use_case.execute(RefreshTokenRequest { refresh_token: value }).await;
+    assert!(matches!(result, Err(SystemError::InvalidRefreshToken)));
+    use_case
+        .execute(RefreshTokenRequest { refresh_token: response.refresh_token })
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn refresh_tokens_stop_working_when_they_expire() {
+    let clock = FakeClock::new();
+    let _guard = clock.install();
+    let users = InMemoryUserRepository::new();
+    let refresh_tokens = InMemoryRefreshTokenRepository::new();
+    let use_case = refresh(&users, &refresh_tokens);
+    let user = seed(&users, UserFixture::new().build()).await;
+    let (token, value) = RefreshTokenFixture::for_user(user.id).build();
+    refresh_tokens.create(&token).await.unwrap();
+
+    clock.set(token.expires_at + Duration::seconds(1));
+
+    let result = use_case.execute(RefreshTokenRequest { refresh_token: value }).await;
+    assert!(matches!(result, Err(SystemError::InvalidRefreshToken)));
+    assert_eq!(refresh_tokens.cleanup_expired().await.unwrap(), 1);
+}
+
+struct Otp {
+    use_case: OtpUseCase,
+    users: InMemoryUserRepository,
+    cache: InMemoryOtpCache,
+    broker: InMemoryBroker,
+}
+
+fn otp(cache: InMemoryOtpCache) -> Otp {
+    let users = InMemoryUserRepository::new();
+    let broker = InMemoryBroker::new();
+    let use_case = OtpUseCase::new(
+        Arc::new(users.clone()),
+        Arc::new(cache.clone()),
+        Arc::new(NotificationPublisher::new(Arc::new(broker.clone()))),
+        Default::default(),
+        settings(),
+    );
+    Otp {
+        use_case,
+        users,
+        cache,
+        broker,
+    }
+}
+
+fn send_otp_request(email: &str) -> SendOtpRequest {
+    SendOtpRequest {
+        identifier: email.to_string(),
+        identifier_type: IdentifierType::Email,
+    }
+}
+
+#[tokio::test]
+async fn a_sent_otp_verifies_the_account() {
+    let otp = otp(InMemoryOtpCache::default());
+    let user = seed(&otp.users, UserFixture::new().unverified().build()).await;
+
+    otp.use_case.send_otp(send_otp_request(&user.email)).await.unwrap();
+
+    let sent = otp.broker.events::<EmailOtpRequestedEvent>();
+    assert_eq!(sent.len(), 1);
+    assert_eq!(sent[0].payload.email, user.email);
+    assert_eq!(otp.cache.code(&user.email).as_deref(), Some(sent[0].payload.otp_code.as_str()));
+
+    otp.use_case
+        .verify_otp(VerifyOtpRequest {
+            identifier: user.email.clone(),
+            otp_code: sent[0].payload.otp_code.clone(),
+        })
+        .await
+        .unwrap();
+    assert!(otp.users.get(user.id).unwrap().is_verified);
+    assert_eq!(otp.cache.code(&user.email), None);
+}
+
+#[tokio::test]
+async fn otp_requests_are_limited_per_window() {
+    let clock = FakeClock::new();
+    let _guard = clock.install();
+    let otp = otp(InMemoryOtpCache::new(2, Duration::minutes(10)));
+
+    otp.use_case.send_otp(send_otp_request("limited@example.com")).await.unwrap();
+    otp.use_case.send_otp(send_otp_request("limited@example.com")).await.unwrap();
+    let result = otp.use_case.send_otp(send_otp_request("limited@example.com")).await;
+    assert!(matches!(result, Err(SystemError::OtpRateLimitExceeded)));
+
+    clock.advance(Duration::minutes(11));
+    otp.use_case.send_otp(send_otp_request("limited@example.com")).await.unwrap();
+    assert_eq!(otp.broker.events::<EmailOtpRequestedEvent>().len(), 3);
+}
+
+#[tokio::test]
+async fn sending_an_otp_fails_when_the_broker_is_down() {
+    let otp = otp(InMemoryOtpCache::default());
+    otp.broker.go_down();
+
+    let result = otp.use_case.send_otp(send_otp_request("offline@example.com")).await;
+
+    assert!(matches!(result, Err(SystemError::MessageBrokerError(_))));
+    assert!(otp.broker.published().is_empty());
+}
+
+#[tokio::test]
+async fn a_password_reset_changes_the_password_and_ends_every_session() {
+    let users = InMemoryUserRepository::new();
+    let resets = InMemoryPasswordResetRepository::new(&users);
+    let refresh_tokens = InMemoryRefreshTokenRepository::new();
+    let use_case = PasswordResetUseCase::new(
+        Arc::new(users.clone()),
+        Arc::new(resets.clone()),
+        Arc::new(refresh_tokens.clone()),
+        jwt_config().secret,
+    );
+    let user = seed(&users, UserFixture::new().with_failed_attempts(3).build()).await;
+    let (session, _) = RefreshTokenFixture::for_user(user.id).build();
+    refresh_tokens.create(&session).await.unwrap();
+
+    use_case
+        .request_password_reset(PasswordResetRequest {
+            identifier: user.email.clone(),
+            identifier_type: IdentifierType::Email,
+        })
+        .await
+        .unwrap();
+
+    // The reset email is queued with the token, not published directly
+    let queued = resets.outbox();
+    assert_eq!(queued.len(), 1);
+    assert_eq!(queued[0].routing_key, PasswordResetRequestedEvent::ROUTING_KEY);
+    let email: EventEnvelope<PasswordResetRequestedEvent> = serde_json::from_value(queued[0].payload.clone()).unwrap();
+
+    use_case
+        .confirm_password_reset(PasswordResetConfirmRequest {
+            token: email.payload.reset_token.clone(),
+            new_password: "Battery-Staple-42".to_string(),
+        })
+        .await
+        .unwrap();
+
+    let stored = users.get(user.id).unwrap();
+    assert_ne!(stored.password_hash, user.password_hash);
+    assert_eq!(stored.failed_login_attempts, 0);
+    assert!(refresh_tokens.all().iter().all(|t| !t.is_valid()));
+    assert_eq!(resets.outbox().len(), 2);
+
+    // A reset link works once
+    let result = use_case
+        .confirm_password_reset(PasswordResetConfirmRequest {
+            token: email.payload.reset_token,
+            new_password: "Battery-Staple-43".to_string(),
+        })
+        .await;
+    assert!(matches!(result, Err(SystemError::InvalidResetToken)));
+}
+
+#[tokio::test]
+async fn security_answers_are_verified_against_the_stored_hashes() {
+    let questions: Vec<_> = ["First pet?", "First school?"]
+        .into_iter()
+        .map(|q| SecurityQuestion::new(q.to_string(), Uuid::new_v4()))
+        .collect();
+    let use_case = SecurityQuestionUseCase::new(
+        Arc::new(InMemorySecurityQuestionRepository::with_questions(questions.clone())),
+        Arc::new(InMemoryUserSecurityQuestionRepository::new()),
+    );
+    let user_id = Uuid::new_v4();
+    let answers = |first: &str| {
+        vec![
+            SecurityQuestionAnswer {
+                question_id: questions[0].id,
+                answer: first.to_string(),
+            },
+            SecurityQuestionAnswer {
+                question_id: questions[1].id,
+                answer: "Hilltop".to_string(),
+            },
+        ]
+    };
+
+    use_case
+        .set_security_questions(user_id, SetSecurityQuestionsRequest { questions: answers("Rex") })
+        .await
+        .unwrap();
+
+    use_case
+        .verify_security_questions(user_id, VerifySecurityQuestionsRequest { answers: answers("Rex") })
+        .await
+        .unwrap();
+    let result = use_case
+        .verify_security_questions(user_id, VerifySecurityQuestionsRequest { answers: answers("Fido") })
+        .await;
+    assert!(matches!(result, Err(SystemError::SecurityQuestionFailed)));
+}
+
+#[tokio::test]
+async fn revoked_api_keys_no_longer_authenticate() {
+    let users = InMemoryUserRepository::new();
+    let keys = InMemoryApiKeyRepository::new();
+    let use_case = ApiKeyUseCase::new(
+        Arc::new(keys.clone()),
+        Arc::new(users.clone()),
+        Arc::new(InMemoryOrganisationRepository::new().memberships()),
+        jwt_config(),
+    );
+    let user = seed(&users, UserFixture::new().build()).await;
+    let caller = session_for(&user);
+
+    let (created, _) = use_case
+        .create_key(
+            &caller,
+            CreateApiKeyRequest {
+                name: "CI".to_string(),
+                organisation_id: None,
+                permissions: vec!["read:properties".to_string()],
+                allowed_ips: None,
+                expires_at: None,
+            },
+        )
+        .await
+        .unwrap();
+
+    let claims = use_case.authenticate(&created.key, Some("10.0.0.1")).await.unwrap();
+    assert_eq!(claims.sub, user.id);
+    assert_eq!(claims.api_key_id, Some(created.id));
+    assert_eq!(keys.get(created.id).unwrap().last_used_ip.as_deref(), Some("10.0.0.1"));
+
+    use_case.revoke_key(&caller, created.id).await.unwrap();
+
+    let result = use_case.authenticate(&created.key, Some("10.0.0.1")).await;
+    assert!(matches!(result, Err(SystemError::InvalidToken)));
+    assert!(use_case.list_keys(&caller).await.unwrap().0.is_empty());
+}
+
+#[tokio::test]
+async fn api_keys_cannot_carry_permissions_beyond_the_callers_role() {
+    let users = InMemoryUserRepository::new();
+    let keys = InMemoryApiKeyRepository::new();
+    let use_case = ApiKeyUseCase::new(
+        Arc::new(keys.clone()),
+        Arc::new(users.clone()),
+        Arc::new(InMemoryOrganisationRepository::new().memberships()),
+        jwt_config(),
+    );
+    let tenant = seed(&users, UserFixture::new().build()).await;
+    let request = |permission: &str| CreateApiKeyRequest {
+        name: "Escalation".to_string(),
+        organisation_id: None,
+        permissions: vec![permission.to_string()],
+        allowed_ips: None,
+        expires_at: None,
+    };
+
+    for permission in ["manage:properties", "refund:payments", "read:users"] {
+        let result = use_case.create_key(&session_for(&tenant), request(permission)).await;
+        assert!(matches!(result, Err(SystemError::PermissionDenied(_))), "{}", permission);
+    }
+    assert!(use_case.list_keys(&session_for(&tenant)).await.unwrap().0.is_empty());
+
+    // A forged secret with a valid prefix is still rejected
+    let (created, _) = use_case.create_key(&session_for(&tenant), request("read:bookings")).await.unwrap();
+    let forged = format!("{}x", created.key);
+    assert!(matches!(use_case.authenticate(&forged, None).await, Err(SystemError::InvalidToken)));
+}
+
+#[tokio::test]
+async fn api_keys_lose_permissions_their_owner_loses() {
+    let users = InMemoryUserRepository::new();
+    let use_case = ApiKeyUseCase::new(
+        Arc::new(InMemoryApiKeyRepository::new()),
+        Arc::new(users.clone()),
+        Arc::new(InMemoryOrganisationRepository::new().memberships()),
+        jwt_config(),
+    );
+    let mut landlord = seed(&users, UserFixture::new().with_role(UserRole::Landlord).build()).await;
+    let (created, _) = use_case
+        .create_key(
+            &session_for(&landlord),
+            CreateApiKeyRequest {
+                name: "Listings sync".to_string(),
+                organisation_id: None,
+                permissions: vec![
+                    permissions::READ_PROPERTIES.to_string(),
+                    permissions::WRITE_PROPERTIES.to_string(),
+                ],
+                allowed_ips: None,
+                expires_at: None,
+            },
+        )
+        .await
+        .unwrap();
+
+    landlord.role = UserRole::Guest;
+    users.update(&landlord).await.unwrap();
+    let claims = use_case.authenticate(&created.key, None).await.unwrap();
+
+    assert_eq!(claims.permissions, [permissions::READ_PROPERTIES]);
+}
+
+#[tokio::test]
+async fn the_last_owner_cannot_leave_an_organisation() {
+    let users = InMemoryUserRepository::new();
+    let organisations = InMemoryOrganisationRepository::new();
+    let use_case = OrganisationUseCase::new(
+        Arc::new(organisations.clone()),
+        Arc::new(organisations.memberships()),
+        Arc::new(InMemoryOrganisationInvitationRepository::new()),
+        Arc::new(users.clone()),
+        jwt_config(),
+    );
+    let owner = seed(&users, UserFixture::new().build()).await;
+    let caller = session_for(&owner);
+
+    let (organisation, _) = use_case
+        .create_organisation(&caller, CreateOrganisationRequest { name: "Harbour Lets".to_string() })
+        .await
+        .unwrap();
+    assert_eq!(organisation.slug, "harbour-lets");
+
+    let (listed, _) = use_case.list_organisations(&caller).await.unwrap();
+    assert_eq!(listed.len(), 1);
+
+    let result = use_case.remove_member(&caller, organisation.id, owner.id).await;
+    assert!(matches!(result, Err(SystemError::ValidationError(_))));
+
+    // Slugs are unique
+    let result = use_case
+        .create_organisation(&caller, CreateOrganisationRequest { name: "Harbour  Lets".to_string() })
+        .await;
+    assert!(matches!(result, Err(SystemError::ValidationError(_))));
+}
+
+#[tokio::test]
+async fn admins_cannot_change_the_roles_of_their_peers() {
+    let users = InMemoryUserRepository::new();
+    let organisations = InMemoryOrganisationRepository::new();
+    let memberships = organisations.memberships();
+    let use_case = OrganisationUseCase::new(
+        Arc::new(organisations.clone()),
+        Arc::new(memberships.clone()),
+        Arc::new(InMemoryOrganisationInvitationRepository::new()),
+        Arc::new(users.clone()),
+        jwt_config(),
+    );
+    let owner = seed(&users, UserFixture::new().build()).await;
+    let (organisation, _) = use_case
+        .create_organisation(&session_for(&owner), CreateOrganisationRequest { name: "Harbour Lets".to_string() })
+        .await
+        .unwrap();
+    let mut members = Vec::new();
+    for role in [OrganisationRole::Admin, OrganisationRole::Admin, OrganisationRole::Member] {
+        let user = seed(&users, UserFixture::new().build()).await;
+        memberships
+            .create(&OrganisationMembership::new(organisation.id, user.id, role))
+            .await
+            .unwrap();
+        members.push(user);
+    }
+    let [admin, other_admin, member] = <[User; 3]>::try_from(members).unwrap();
+    let role = |role: OrganisationRole| UpdateMemberRoleRequest { role };
+
+    let result = use_case
+        .update_member_role(&session_for(&admin), organisation.id, other_admin.id, role(OrganisationRole::Member))
+        .await;
+    assert!(matches!(result, Err(SystemError::PermissionDenied(_))));
+    let result = use_case
+        .update_member_role(&session_for(&admin), organisation.id, owner.id, role(OrganisationRole::Member))
+        .await;
+    assert!(matches!(result, Err(SystemError::PermissionDenied(_))));
+
+    // Below themselves admins are free to, and owners can demote admins
+    use_case
+        .update_member_role(&session_for(&admin), organisation.id, member.id, role(OrganisationRole::Manager))
+        .await
+        .unwrap();
+    use_case
+        .update_member_role(&session_for(&owner), organisation.id, other_admin.id, role(OrganisationRole::Member))
+        .await
+        .unwrap();
+}
+
+fn step_up(users: &InMemoryUserRepository, totps: &InMemoryTotpRepository, otp_cache: &InMemoryOtpCache) -> StepUpUseCase {
+    StepUpUseCase::new(
+        Arc::new(users.clone()),
+        Arc::new(totps.clone()),
+        Arc::new(otp_cache.clone()),
+        jwt_config(),
+        StepUpConfig::default(),
+        settings(),
+    )
+}
+
+fn reauthenticate_with(method: ReauthenticationMethod, code: &str) -> ReauthenticateRequest {
+    ReauthenticateRequest {
+        method,
+        password: None,
+        code: Some(code.to_string()),
+    }
+}
+
+#[tokio::test]
+async fn wrong_step_up_codes_lock_the_account() {
+    let users = InMemoryUserRepository::new();
+    let totps = InMemoryTotpRepository::new();
+    let otp_cache = InMemoryOtpCache::default();
+    let use_case = step_up(&users, &totps, &otp_cache);
+    let user = seed(&users, UserFixture::new().build()).await;
+    let caller = session_for(&user);
+
+    let mut totp = UserTotp::new(user.id, TotpHelper::generate_secret());
+    totp.confirm();
+    totps.upsert(&totp).await.unwrap();
+    let now = shared::utils::clock::now().timestamp();
+    let wrong_totp = (0..1_000_000)
+        .map(|n| format!("{:06}", n))
+        .find(|code| TotpHelper::verify(&totp.secret, code, now).is_none())
+        .unwrap();
+
+    // Authenticator and emailed codes count against the same limit
+    let result = use_case
+        .reauthenticate(&caller, reauthenticate_with(ReauthenticationMethod::Totp, &wrong_totp))
+        .await;
+    assert!(matches!(result, Err(SystemError::InvalidOtp(_))));
+    otp_cache.store_otp(&user.email, "246810", 10).await.unwrap();
+    for _ in 1..*LOGIN_MAX_FAILED_ATTEMPTS.default_value() {
+        let result = use_case
+            .reauthenticate(&caller, reauthenticate_with(ReauthenticationMethod::Otp, "135791"))
+            .await;
+        assert!(matches!(result, Err(SystemError::InvalidOtp(_))));
+    }
+    assert!(users.get(user.id).unwrap().is_locked());
+
+    // Once locked, even the right code is refused
+    let result = use_case
+        .reauthenticate(&caller, reauthenticate_with(ReauthenticationMethod::Otp, "246810"))
+        .await;
+    assert!(matches!(result, Err(SystemError::AccountLocked)));
+}
+
+
+#[tokio::test]
+async fn a_step_up_code_issues_a_token_naming_the_factor() {
+    let users = InMemoryUserRepository::new();
+    let totps = InMemoryTotpRepository::new();
+    let otp_cache = InMemoryOtpCache::default();
+    let use_case = step_up(&users, &totps, &otp_cache);
+    let user = seed(&users, UserFixture::new().with_failed_attempts(2).build()).await;
+    let caller = session_for(&user);
+    let config = jwt_config();
+
+    otp_cache.store_otp(&user.email, "246810", 10).await.unwrap();
+    let (response, _) = use_case
+        .reauthenticate(&caller, reauthenticate_with(ReauthenticationMethod::Otp, "246810"))
+        .await
+        .unwrap();
+    let claims = JwtHelper::validate_access_token(&response.access_token, &config).unwrap();
+    assert_eq!(claims.amr, vec![amr::OTP.to_string()]);
+    assert!(claims.authenticated_within(60));
+    assert_eq!(otp_cache.code(&user.email), None);
+    assert_eq!(users.get(user.id).unwrap().failed_login_attempts, 0);
+
+    let mut totp = UserTotp::new(user.id, TotpHelper::generate_secret());
+    totp.confirm();
+    totps.upsert(&totp).await.unwrap();
+    let step = TotpHelper::time_step(shared::utils::clock::now().timestamp());
+    let code = TotpHelper::code_at(&totp.secret, step).unwrap();
+    let (response, _) = use_case
+        .reauthenticate(&caller, reauthenticate_with(ReauthenticationMethod::Totp, &code))
+        .await
+        .unwrap();
+    let claims = JwtHelper::validate_access_token(&response.access_token, &config).unwrap();
+    assert_eq!(claims.amr, vec![amr::TOTP.to_string()]);
+
+    // An authenticator code works once
+    let result = use_case
+        .reauthenticate(&caller, reauthenticate_with(ReauthenticationMethod::Totp, &code))
+        .await;
+    assert!(matches!(result, Err(SystemError::InvalidOtp(_))));
+}
+
+#[tokio::test]
+async fn impersonation_is_audited_from_start_to_every_request() {
+    let users = InMemoryUserRepository::new();
+    let audit_logs = InMemoryAuditLogRepository::new();
+    let use_case = ImpersonationUseCase::new(
+        Arc::new(users.clone()),
+        Arc::new(InMemoryOrganisationRepository::new().memberships()),
+        Arc::new(audit_logs.clone()),
+        jwt_config(),
+    );
+    let admin = seed(&users, UserFixture::new().with_role(UserRole::Admin).build()).await;
+    let tenant = seed(&users, UserFixture::new().build()).await;
+
+    let (response, _) = use_case
+        .start(
+            &session_for(&admin),
+            ImpersonateRequest {
+                user_id: tenant.id,
+                reason: "  Investigating support ticket 4512  ".to_string(),
+            },
+            Some("203.0.113.7:51234".to_string()),
+            Some("support-console".to_string()),
+        )
+        .await
+        .unwrap();
+    let claims = JwtHelper::validate_access_token(&response.access_token, &jwt_config()).unwrap();
+    assert_eq!(claims.sub, tenant.id);
+    assert_eq!(claims.act.as_ref().map(|actor| actor.sub), Some(admin.id));
+
+    let started = audit_logs.all();
+    assert_eq!(started.len(), 1);
+    assert_eq!(started[0].action, audit_actions::IMPERSONATION_STARTED);
+    assert_eq!(started[0].user_id, Some(admin.id));
+    assert_eq!(started[0].resource_id, Some(tenant.id));
+    assert_eq!(started[0].ip_address.as_deref(), Some("203.0.113.7"));
+    let metadata = started[0].metadata.clone().unwrap();
+    assert_eq!(metadata["reason"], "Investigating support ticket 4512");
+    assert_eq!(metadata["token_id"], claims.jti.to_string());
+
+    let request = ImpersonatedRequest {
+        method: "DELETE".to_string(),
+        path: "/api/v1/auth/sessions".to_string(),
+        status: 200,
+        ip_address: None,
+        user_agent: None,
+    };
+    use_case.record(&claims, &request).await;
+    // The administrator's own requests are not impersonated
+    use_case.record(&session_for(&admin), &request).await;
+
+    let logs = audit_logs.all();
+    assert_eq!(logs.len(), 2);
+    let recorded = logs.iter().find(|log| log.action == audit_actions::IMPERSONATED_REQUEST).unwrap();
+    assert_eq!(recorded.user_id, Some(tenant.id));
+    let metadata = recorded.metadata.clone().unwrap();
+    assert_eq!(metadata["impersonated_by"], admin.id.to_string());
+    assert_eq!(metadata["path"], "/api/v1/auth/sessions");
+    assert_eq!(metadata["status"], 200);
+}
+
+#[tokio::test]
+async fn administrators_cannot_be_impersonated() {
+    let users = InMemoryUserRepository::new();
+    let audit_logs = InMemoryAuditLogRepository::new();
+    let use_case = ImpersonationUseCase::new(
+        Arc::new(users.clone()),
+        Arc::new(InMemoryOrganisationRepository::new().memberships()),
+        Arc::new(audit_logs.clone()),
+        jwt_config(),
+    );
+    let admin = seed(&users, UserFixture::new().with_role(UserRole::Admin).build()).await;
+    let other_admin = seed(&users, UserFixture::new().with_role(UserRole::Admin).build()).await;
+
+    let result = use_case
+        .start(
+            &session_for(&admin),
+            ImpersonateRequest {
+                user_id: other_admin.id,
+                reason: "Investigating support ticket 4512".to_string(),
+            },
+            None,
+            None,
+        )
+        .await;
+
+    assert!(matches!(result, Err(SystemError::PermissionDenied(_))));
+    assert!(audit_logs.all().is_empty());
+}
+
+const REDIRECT_URI: &str = "https://partner.example.com/callback";
+
+struct OAuth {
+    use_case: OAuthUseCase,
+    users: InMemoryUserRepository,
+    tokens: InMemoryOAuthTokenRepository,
+    cache: InMemoryAuthCache,
+}
+
+fn oauth() -> OAuth {
+    let users = InMemoryUserRepository::new();
+    let tokens = InMemoryOAuthTokenRepository::new();
+    let cache = InMemoryAuthCache::new();
+    let use_case = OAuthUseCase::new(
+        Arc::new(InMemoryOAuthClientRepository::new()),
+        Arc::new(InMemoryOAuthAuthorizationCodeRepository::new()),
+        Arc::new(tokens.clone()),
+        Arc::new(InMemoryOAuthConsentRepository::new()),
+        Arc::new(users.clone()),
+        Arc::new(cache.clone()),
+        jwt_config(),
+    );
+    OAuth {
+        use_case,
+        users,
+        tokens,
+        cache,
+    }
+}
+
+// Registers a confidential code-flow client for `scopes` on behalf of `owner`
+async fn register_partner(
+    oauth: &OAuth,
+    owner: &User,
+    scopes: &[&str],
+) -> Result<RegisterOAuthClientResponse, SystemError> {
+    oauth
+        .use_case
+        .register_client(
+            &session_for(owner),
+            RegisterOAuthClientRequest {
+                name: "Partner".to_string(),
+                redirect_uris: vec![REDIRECT_URI.to_string()],
+                grant_types: vec![
+                    grant_types::AUTHORIZATION_CODE.to_string(),
+                    grant_types::REFRESH_TOKEN.to_string(),
+                ],
+                scopes: scopes.iter().map(|s| s.to_string()).collect(),
+                is_confidential: Some(true),
+            },
+        )
+        .await
+        .map(|(client, _)| client)
+}
+
+async fn authorize_as(oauth: &OAuth, user: &User, client_id: Uuid, scope: Option<&str>) -> Result<String, SystemError> {
+    let (authorized, _) = oauth
+        .use_case
+        .authorize(
+            &session_for(user),
+            AuthorizeRequest {
+                response_type: "code".to_string(),
+                client_id,
+                redirect_uri: REDIRECT_URI.to_string(),
+                scope: scope.map(String::from),
+                state: Some("xyz".to_string()),
+                code_challenge: None,
+                code_challenge_method: None,
+            },
+        )
+        .await?;
+    assert!(authorized.redirect_uri.starts_with(REDIRECT_URI));
+    assert!(authorized.redirect_uri.ends_with("&state=xyz"));
+    Ok(authorized.code)
+}
+
+fn credentials_of(client: RegisterOAuthClientResponse) -> ClientCredentials {
+    ClientCredentials {
+        client_id: client.client_id.to_string(),
+        client_secret: client.client_secret,
+    }
+}
+
+// Registers a confidential client owned by a landlord and has a tenant
+// authorize it; returns the client's credentials and the code
+async fn authorized_client(oauth: &OAuth) -> (ClientCredentials, String) {
+    let owner = seed(&oauth.users, UserFixture::new().with_role(UserRole::Landlord).build()).await;
+    let tenant = seed(&oauth.users, UserFixture::new().build()).await;
+
+    let client = register_partner(oauth, &owner, &[permissions::READ_PROPERTIES]).await.unwrap();
+    let code = authorize_as(oauth, &tenant, client.client_id, None).await.unwrap();
+    (credentials_of(client), code)
+}
+
+fn code_exchange(code: &str) -> TokenRequest {
+    TokenRequest {
+        grant_type: grant_types::AUTHORIZATION_CODE.to_string(),
+        code: Some(code.to_string()),
+        redirect_uri: Some(REDIRECT_URI.to_string()),
+        code_verifier: None,
+        refresh_token: None,
+        scope: None,
+        client_id: None,
+        client_secret: None,
+    }
+}
+
+#[tokio::test]
+async fn an_authorization_code_is_exchanged_once() {
+    let oauth = oauth();
+    let (credentials, code) = authorized_client(&oauth).await;
+
+    let tokens = oauth
+        .use_case
+        .exchange_token(credentials.clone(), code_exchange(&code))
+        .await
+        .unwrap();
+    assert_eq!(tokens.scope, permissions::READ_PROPERTIES);
+    assert!(tokens.refresh_token.is_some());
+    assert_eq!(oauth.tokens.all().len(), 2);
+
+    // A replayed code is treated as leaked and everything it produced dies
+    let result = oauth.use_case.exchange_token(credentials, code_exchange(&code)).await;
+    assert!(matches!(result, Err(SystemError::InvalidGrant(_))));
+    assert!(oauth.tokens.all().iter().all(|token| token.is_revoked));
+}
+
+#[tokio::test]
+async fn codes_need_the_right_secret_and_redirect_uri() {
+    let oauth = oauth();
+    let (credentials, code) = authorized_client(&oauth).await;
+
+    let wrong_secret = ClientCredentials {
+        client_secret: Some("not-the-secret".to_string()),
+        ..credentials.clone()
+    };
+    let result = oauth.use_case.exchange_token(wrong_secret, code_exchange(&code)).await;
+    assert!(matches!(result, Err(SystemError::InvalidClient)));
+
+    let mut elsewhere = code_exchange(&code);
+    elsewhere.redirect_uri = Some("https://attacker.example.com/callback".to_string());
+    let result = oauth.use_case.exchange_token(credentials, elsewhere).await;
+    assert!(matches!(result, Err(SystemError::InvalidGrant(_))));
+    assert!(oauth.tokens.all().is_empty());
+}
+
+#[tokio::test]
+async fn revoked_access_tokens_are_blacklisted_and_inactive() {
+    let oauth = oauth();
+    let (credentials, code) = authorized_client(&oauth).await;
+    let tokens = oauth
+        .use_case
+        .exchange_token(credentials.clone(), code_exchange(&code))
+        .await
+        .unwrap();
+
+    oauth
+        .use_case
+        .revoke(
+            credentials.clone(),
+            RevokeTokenRequest {
+                token: tokens.access_token.clone(),
+                token_type_hint: None,
+                client_id: None,
+                client_secret: None,
+            },
+        )
+        .await
+        .unwrap();
+
+    assert!(oauth.cache.is_token_blacklisted(&tokens.access_token).await.unwrap());
+    let introspected = oauth
+        .use_case
+        .introspect(
+            credentials,
+            IntrospectRequest {
+                token: tokens.access_token,
+                token_type_hint: None,
+                client_id: None,
+                client_secret: None,
+            },
+        )
+        .await
+        .unwrap();
+    assert!(!introspected.active);
+    // The refresh token is untouched
+    let stored = oauth.tokens.all();
+    assert_eq!(stored.iter().filter(|token| token.is_revoked).count(), 1);
+}
+
+#[tokio::test]
+async fn granted_scopes_never_exceed_the_users_role() {
+    let oauth = oauth();
+    let manager = seed(&oauth.users, UserFixture::new().with_role(UserRole::PropertyManager).build()).await;
+    let guest = seed(&oauth.users, UserFixture::new().with_role(UserRole::Guest).build()).await;
+    let client = register_partner(
+        &oauth,
+        &manager,
+        &[permissions::READ_PROPERTIES, permissions::WRITE_PROPERTIES, permissions::CANCEL_BOOKINGS],
+    )
+    .await
+    .unwrap();
+
+    let result = authorize_as(&oauth, &guest, client.client_id, Some(permissions::WRITE_PROPERTIES)).await;
+    assert!(matches!(result, Err(SystemError::InvalidScope(_))));
+
+    // Asking for nothing in particular gets only what a guest may do
+    let code = authorize_as(&oauth, &guest, client.client_id, None).await.unwrap();
+    let tokens = oauth
+        .use_case
+        .exchange_token(credentials_of(client), code_exchange(&code))
+        .await
+        .unwrap();
+    assert_eq!(tokens.scope, permissions::READ_PROPERTIES);
+    let claims = JwtHelper::validate_access_token(&tokens.access_token, &jwt_config()).unwrap();
+    assert_eq!(claims.permissions, [permissions::READ_PROPERTIES]);
+}
+
+#[tokio::test]
+async fn clients_cannot_be_registered_for_scopes_beyond_the_owners_role() {
+    let oauth = oauth();
+    let landlord = seed(&oauth.users, UserFixture::new().with_role(UserRole::Landlord).build()).await;
+
+    let result = register_partner(&oauth, &landlord, &[permissions::READ_PROPERTIES, permissions::CANCEL_BOOKINGS]).await;
+
+    assert!(matches!(result, Err(SystemError::PermissionDenied(_))));
+}
+
+#[tokio::test]
+async fn refreshed_tokens_lose_scopes_the_user_no_longer_holds() {
+    let oauth = oauth();
+    let manager = seed(&oauth.users, UserFixture::new().with_role(UserRole::PropertyManager).build()).await;
+    let mut tenant = seed(&oauth.users, UserFixture::new().build()).await;
+    let client = register_partner(&oauth, &manager, &[permissions::READ_PROPERTIES, permissions::WRITE_BOOKINGS])
+        .await
+        .unwrap();
+    let credentials = credentials_of(client.clone());
+    let code = authorize_as(&oauth, &tenant, client.client_id, None).await.unwrap();
+    let tokens = oauth
+        .use_case
+        .exchange_token(credentials.clone(), code_exchange(&code))
+        .await
+        .unwrap();
+    assert_eq!(tokens.scope, "read:properties write:bookings");
+
+    tenant.role = UserRole::Guest;
+    oauth.users.update(&tenant).await.unwrap();
+    let refreshed = oauth
+        .use_case
+        .exchange_token(
+            credentials,
+            TokenRequest {
+                grant_type: grant_types::REFRESH_TOKEN.to_string(),
+                refresh_token: tokens.refresh_token,
+                ..code_exchange("")
+            },
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(refreshed.scope, permissions::READ_PROPERTIES);
+}
@@ -1 +1,26 @@
-// Configuration for booking service
+// Configuration
+use serde::{Deserialize, Serialize};
+use shared::config::jwt_config::JwtConfig;
+use shared::config::loader::ConfigLoader;
+use shared::config::redis_config::{RedisConfig, RedisFigureConfig};
+use shared::config::server_config::ServerConfig;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppConfig {
+    pub server: ServerConfig,
+    pub jwt: JwtConfig,
+    pub redis: RedisConfig,
+    pub redis_figure_config: RedisFigureConfig,
+}
+
+impl AppConfig {
+    pub fn loader() -> ConfigLoader {
+        ConfigLoader::new("booking-service")
+            .section::<ServerConfig>("server")
+            .section::<JwtConfig>("jwt")
+            .section::<RedisConfig>("redis")
+            .section::<RedisFigureConfig>("redis_figure_config")
+            .with_default("server.port", 8004)
+            .with_default("server.grpc_port", 9004)
+    }
+}
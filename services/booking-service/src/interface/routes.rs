@@ -1,15 +1,5 @@
 use actix_web::{web, HttpResponse, Result};
-use chrono::Utc;
-use shared::entities::models::HealthResponse;
-
-pub async fn health_check() -> Result<HttpResponse> {
-    let response = HealthResponse {
-        status: "OK".to_string(),
-        timestamp: Utc::now(),
-        service: "booking-service".to_string(),
-    };
-    Ok(HttpResponse::Ok().json(response))
-}
+use shared::features::health::health_routes;
 
 pub async fn create_booking() -> Result<HttpResponse> {
     Ok(HttpResponse::Ok().json(serde_json::json!({
@@ -24,7 +14,7 @@ pub async fn get_bookings() -> Result<HttpResponse> {
 }
 
 pub fn configure_routes(cfg: &mut web::ServiceConfig) {
-    cfg.route("/health", web::get().to(health_check))
+    cfg.service(health_routes())
         .route("/bookings", web::post().to(create_booking))
         .route("/bookings", web::get().to(get_bookings));
 }
@@ -1 +1,18 @@
 // Configuration
+use serde::{Deserialize, Serialize};
+use shared::config::loader::ConfigLoader;
+use shared::config::server_config::ServerConfig;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppConfig {
+    pub server: ServerConfig,
+}
+
+impl AppConfig {
+    pub fn loader() -> ConfigLoader {
+        ConfigLoader::new("feedback-service")
+            .section::<ServerConfig>("server")
+            .with_default("server.port", 8007)
+            .with_default("server.grpc_port", 9007)
+    }
+}
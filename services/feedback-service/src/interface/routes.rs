@@ -1,16 +1,6 @@
-use actix_web::{web, HttpResponse, Result};
-use chrono::Utc;
-use shared::entities::models::HealthResponse;
-
-pub async fn health_check() -> Result<HttpResponse> {
-    let response = HealthResponse {
-        status: "OK".to_string(),
-        timestamp: Utc::now(),
-        service: "feedback-service".to_string(),
-    };
-    Ok(HttpResponse::Ok().json(response))
-}
+use actix_web::web;
+use shared::features::health::health_routes;
 
 pub fn configure_routes(cfg: &mut web::ServiceConfig) {
-    cfg.route("/health", web::get().to(health_check));
+    cfg.service(health_routes());
 }
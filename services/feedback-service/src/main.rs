@@ -1,7 +1,18 @@
-use actix_web::{middleware::Logger, App, HttpServer};
+use actix_web::{middleware::Logger, web, App, HttpServer};
 use dotenv::dotenv;
 use std::env;
+use std::sync::Arc;
+use std::time::Duration;
 use tokio::select;
+use tokio::sync::broadcast;
+use tonic::transport::Server;
+use shared::config::loader::ConfigArgs;
+use shared::config::tracing_config::TracingConfig;
+use shared::features::errors::SystemError;
+use shared::features::health::HealthRegistry;
+use shared::features::metrics::{metrics_routes, MetricsMiddleware};
+use shared::features::observability::{init_tracing, TracingMiddleware};
+use shared::features::problem::problem_details;
 
 mod application;
 mod cache;
@@ -11,24 +22,27 @@ mod grpc;
 mod infrastructure;
 mod interface;
 
+use crate::config::AppConfig;
 use crate::interface::routes::configure_routes;
 
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
     // Initialize environment
     dotenv().ok();
-    env_logger::init();
+    let _tracing = init_tracing(&TracingConfig::from_env("feedback-service")).expect("Failed to initialise tracing");
 
-    let host = env::var("HOST").unwrap_or_else(|_| "127.0.0.1".to_string());
-    let port: u16 = env::var("PORT")
-        .unwrap_or_else(|_| "8007".to_string())
-        .parse()
-        .expect("PORT must be a valid number");
+    // Fails fast, listing every invalid setting
+    let (config_args, _) = ConfigArgs::parse(env::args().skip(1)).unwrap_or_else(|e| exit_with(e));
+    let loader = AppConfig::loader();
+    let config: AppConfig = loader.load(&config_args).unwrap_or_else(|e| exit_with(e));
+    if config_args.print_config {
+        println!("{}", loader.render(&config));
+        return Ok(());
+    }
 
-    let grpc_port: u16 = env::var("GRPC_PORT")
-        .unwrap_or_else(|_| "9007".to_string())
-        .parse()
-        .expect("GRPC_PORT must be a valid number");
+    let host = config.server.host.clone();
+    let port = config.server.port;
+    let grpc_port = config.server.grpc_port;
 
     log::info!(
         "Starting Feedback Service on {}:{} (HTTP) and {}:{} (gRPC)",
@@ -38,22 +52,30 @@ async fn main() -> std::io::Result<()> {
         grpc_port
     );
 
+    // No dependencies yet, so readiness has no checks
+    let health = Arc::new(HealthRegistry::new("feedback-service"));
+    let (_shutdown_tx, shutdown_rx) = broadcast::channel(1);
+
     // Start HTTP server
-    let http_server = HttpServer::new(|| {
+    let http_health = health.clone();
+    let http_server = HttpServer::new(move || {
         App::new()
             .wrap(Logger::default())
+            .wrap(MetricsMiddleware)
+            .wrap(TracingMiddleware)
+            .app_data(web::Data::from(http_health.clone()))
+            .service(metrics_routes())
+            .configure(problem_details)
             .configure(configure_routes)
     })
     .bind(format!("{}:{}", host, port))?
     .run();
 
     // Start gRPC server
-    // let grpc_addr = format!("{}:{}", host, grpc_port).parse().unwrap();
-    // let health_service = HealthServiceImpl::default();
-
-    // let grpc_server = Server::builder()
-    //     .add_service(shared::grpc_clients::health::health_server::HealthServer::new(health_service))
-    //     .serve(grpc_addr);
+    let grpc_addr = format!("{}:{}", host, grpc_port).parse().expect("Invalid gRPC address");
+    let grpc_server = Server::builder()
+        .add_service(health.grpc_service(Duration::from_secs(10), shutdown_rx))
+        .serve(grpc_addr);
 
     // Run both servers concurrently
     select! {
@@ -62,12 +84,17 @@ async fn main() -> std::io::Result<()> {
                 log::error!("HTTP server error: {}", err);
             }
         }
-        // result = grpc_server => {
-        //     if let Err(err) = result {
-        //         log::error!("gRPC server error: {}", err);
-        //     }
-        // }
+        result = grpc_server => {
+            if let Err(err) = result {
+                log::error!("gRPC server error: {}", err);
+            }
+        }
     }
 
     Ok(())
 }
+
+fn exit_with(err: SystemError) -> ! {
+    eprintln!("{}", err);
+    std::process::exit(1);
+}
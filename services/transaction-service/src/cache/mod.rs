@@ -1 +1,19 @@
-// Redis caching
+// Redis caching for transaction service
+use crate::config::AppConfig;
+use deadpool_redis::{Config, Pool, Runtime};
+use shared::features::idempotency::{IdempotencyStore, RedisIdempotencyStore};
+use shared::utils::caching::{CacheKeys, CacheService};
+use std::sync::Arc;
+
+// Bump the version when a cached value changes shape
+pub const CACHE_KEYS: CacheKeys = CacheKeys::new("transaction", 1);
+
+pub fn create_redis_client(config: &AppConfig) -> Result<Arc<Pool>, deadpool_redis::CreatePoolError> {
+    Config::from_url(&config.redis.url).create_pool(Some(Runtime::Tokio1)).map(Arc::new)
+}
+
+// Responses kept for `Idempotency-Key` retries
+pub fn build_idempotency_store(config: &AppConfig, redis_client: Arc<Pool>) -> Arc<dyn IdempotencyStore> {
+    let cache = CacheService::new(redis_client, config.redis_figure_config.clone());
+    Arc::new(RedisIdempotencyStore::new(cache, CACHE_KEYS))
+}
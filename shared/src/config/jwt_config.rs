@@ -1,5 +1,8 @@
+use crate::config::loader::{ConfigSection, Violations};
 use serde::{Deserialize, Serialize};
-use std::env;
+
+// HS256 keys shorter than the hash output weaken the signature
+const MIN_SECRET_LENGTH: usize = 32;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct JwtConfig {
@@ -10,20 +13,43 @@ pub struct JwtConfig {
     pub audience: String,
 }
 
-impl JwtConfig {
-    pub fn from_env() -> Self {
+impl Default for JwtConfig {
+    fn default() -> Self {
         Self {
-            secret: env::var("JWT_SECRET").expect("JWT_SECRET must be set"),
-            access_token_expiry: env::var("JWT_ACCESS_TOKEN_EXPIRY")
-                .unwrap_or_else(|_| "900".to_string()) // 15 minutes
-                .parse()
-                .expect("JWT_ACCESS_TOKEN_EXPIRY must be a valid number"),
-            refresh_token_expiry: env::var("JWT_REFRESH_TOKEN_EXPIRY")
-                .unwrap_or_else(|_| "604800".to_string()) // 7 days
-                .parse()
-                .expect("JWT_REFRESH_TOKEN_EXPIRY must be a valid number"),
-            issuer: env::var("JWT_ISSUER").unwrap_or_else(|_| "auth-service".to_string()),
-            audience: env::var("JWT_AUDIENCE").unwrap_or_else(|_| "borough-platform".to_string()),
+            secret: String::new(),
+            access_token_expiry: 900,     // 15 minutes
+            refresh_token_expiry: 604800, // 7 days
+            issuer: "auth-service".to_string(),
+            audience: "borough-platform".to_string(),
         }
     }
 }
+
+impl ConfigSection for JwtConfig {
+    const ENV: &'static [(&'static str, &'static str)] = &[
+        ("JWT_SECRET", "secret"),
+        ("JWT_ACCESS_TOKEN_EXPIRY", "access_token_expiry"),
+        ("JWT_REFRESH_TOKEN_EXPIRY", "refresh_token_expiry"),
+        ("JWT_ISSUER", "issuer"),
+        ("JWT_AUDIENCE", "audience"),
+    ];
+
+    const SECRETS: &'static [&'static str] = &["secret"];
+
+    fn validate(&self, violations: &mut Violations) {
+        violations.check(!self.secret.is_empty(), "secret", "must be set");
+        violations.check(
+            self.secret.is_empty() || self.secret.len() >= MIN_SECRET_LENGTH,
+            "secret",
+            format!("must be at least {} bytes", MIN_SECRET_LENGTH),
+        );
+        violations.check(self.access_token_expiry > 0, "access_token_expiry", "must be at least 1 second");
+        violations.check(
+            self.refresh_token_expiry > self.access_token_expiry,
+            "refresh_token_expiry",
+            "must be longer than access_token_expiry",
+        );
+        violations.check(!self.issuer.is_empty(), "issuer", "must be set");
+        violations.check(!self.audience.is_empty(), "audience", "must be set");
+    }
+}
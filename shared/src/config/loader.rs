@@ -0,0 +1,330 @@
+use crate::features::errors::{SystemError, SystemResult};
+use config::{Config, File, FileFormat};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+const REDACTED: &str = "<redacted>";
+
+// A block of settings, e.g. `DatabaseConfig` under `[database]`. `ENV` maps
+// environment variables onto its fields; `validate` reports every bad value,
+// not just the first.
+pub trait ConfigSection: Serialize + DeserializeOwned + Default {
+    const ENV: &'static [(&'static str, &'static str)]; // (variable, field)
+
+    // Never printed by `--print-config`. Each can also be read from the file
+    // named by `<VARIABLE>_FILE`, as Docker and Kubernetes mount secrets.
+    const SECRETS: &'static [&'static str] = &[];
+
+    fn validate(&self, _violations: &mut Violations) {}
+}
+
+#[derive(Default)]
+pub struct Violations(Vec<(&'static str, String)>);
+
+impl Violations {
+    pub fn check(&mut self, ok: bool, field: &'static str, message: impl Into<String>) {
+        if !ok {
+            self.0.push((field, message.into()));
+        }
+    }
+}
+
+type Validator = Box<dyn Fn(&Value) -> Vec<String> + Send + Sync>;
+
+// Command line flags shared by every service:
+//
+//     --config <file>      TOML file to read instead of `config/<service>.toml`
+//     --set <key>=<value>  override one setting, e.g. `--set server.port=9000`
+//     --print-config       print the effective configuration and exit
+#[derive(Debug, Clone, Default)]
+pub struct ConfigArgs {
+    pub file: Option<PathBuf>,
+    pub overrides: Vec<(String, String)>,
+    pub print_config: bool,
+}
+
+impl ConfigArgs {
+    // Takes the config flags out of `args` and returns the rest, e.g. a
+    // subcommand and its arguments
+    pub fn parse(args: impl IntoIterator<Item = String>) -> SystemResult<(Self, Vec<String>)> {
+        let mut parsed = Self::default();
+        let mut rest = Vec::new();
+        let mut args = args.into_iter();
+
+        while let Some(arg) = args.next() {
+            let (flag, inline) = match arg.split_once('=') {
+                Some((flag, value)) if flag.starts_with("--") => (flag.to_string(), Some(value.to_string())),
+                _ => (arg.clone(), None),
+            };
+            match flag.as_str() {
+                "--print-config" => parsed.print_config = true,
+                "--config" => {
+                    let value = inline.or_else(|| args.next()).ok_or_else(|| missing_value("--config"))?;
+                    parsed.file = Some(PathBuf::from(value));
+                }
+                "--set" => {
+                    let value = inline.or_else(|| args.next()).ok_or_else(|| missing_value("--set"))?;
+                    let (key, value) = value.split_once('=').ok_or_else(|| {
+                        SystemError::ConfigurationError(format!("--set expects <key>=<value>, got {}", value))
+                    })?;
+                    parsed.overrides.push((key.to_string(), value.to_string()));
+                }
+                _ => rest.push(arg),
+            }
+        }
+        Ok((parsed, rest))
+    }
+}
+
+fn missing_value(flag: &str) -> SystemError {
+    SystemError::ConfigurationError(format!("{} expects a value", flag))
+}
+
+// Builds a service's configuration from, lowest precedence first:
+//
+// 1. the defaults of each section, with per-service overrides (`with_default`)
+// 2. a TOML file: `--config`, `CONFIG_FILE`, or `config/<service>.toml` if present
+// 3. environment variables (and `<VARIABLE>_FILE` for secrets)
+// 4. `--set` flags
+//
+// Every type and validation error is collected before failing, so a bad
+// deployment reports all of its mistakes at once.
+pub struct ConfigLoader {
+    service: String,
+    defaults: Value,
+    env: Vec<(&'static str, String)>, // (variable, full key)
+    secrets: Vec<String>,
+    validators: Vec<Validator>,
+}
+
+impl ConfigLoader {
+    pub fn new(service: impl Into<String>) -> Self {
+        Self {
+            service: service.into(),
+            defaults: Value::Object(Map::new()),
+            env: Vec::new(),
+            secrets: Vec::new(),
+            validators: Vec::new(),
+        }
+    }
+
+    pub fn section<S: ConfigSection + 'static>(mut self, name: &str) -> Self {
+        let defaults = serde_json::to_value(S::default()).expect("config defaults must serialize");
+        if let Value::Object(root) = &mut self.defaults {
+            root.insert(name.to_string(), defaults);
+        }
+        self.env
+            .extend(S::ENV.iter().map(|(var, field)| (*var, format!("{}.{}", name, field))));
+        self.secrets
+            .extend(S::SECRETS.iter().map(|field| format!("{}.{}", name, field)));
+
+        let section = name.to_string();
+        let env = S::ENV;
+        self.validators.push(Box::new(move |root: &Value| {
+            let value = root.get(&section).cloned().unwrap_or(Value::Null);
+            let parsed: S = match serde_json::from_value(value) {
+                Ok(parsed) => parsed,
+                Err(e) => return vec![format!("{}: {}", section, e)],
+            };
+            let mut violations = Violations::default();
+            parsed.validate(&mut violations);
+            violations
+                .0
+                .into_iter()
+                .map(|(field, message)| match env.iter().find(|(_, f)| *f == field) {
+                    Some((var, _)) => format!("{}.{}: {} (set {})", section, field, message, var),
+                    None => format!("{}.{}: {}", section, field, message),
+                })
+                .collect()
+        }));
+        self
+    }
+
+    // A default that differs per service, e.g. `server.port`
+    pub fn with_default(mut self, key: &str, value: impl Into<Value>) -> Self {
+        if let Some(slot) = pointer_mut(&mut self.defaults, key) {
+            *slot = value.into();
+        }
+        self
+    }
+
+    pub fn load<T: DeserializeOwned>(&self, args: &ConfigArgs) -> SystemResult<T> {
+        let mut errors = Vec::new();
+        let mut sources: HashMap<String, String> = HashMap::new();
+
+        let defaults = Config::try_from(&self.defaults).map_err(|e| SystemError::ConfigurationError(e.to_string()))?;
+        let mut builder = Config::builder().add_source(defaults);
+
+        let explicit = args.file.clone().or_else(|| env::var("CONFIG_FILE").ok().map(PathBuf::from));
+        let file = explicit
+            .clone()
+            .unwrap_or_else(|| PathBuf::from(format!("config/{}.toml", self.service)));
+        if explicit.is_some() || file.exists() {
+            builder = builder.add_source(File::from(file.clone()).format(FileFormat::Toml).required(true));
+        }
+
+        for (var, key) in &self.env {
+            let value = match env::var(var) {
+                Ok(value) => Some((value, var.to_string())),
+                Err(_) if self.secrets.contains(key) => {
+                    let file_var = format!("{}_FILE", var);
+                    match env::var(&file_var) {
+                        Ok(path) => match fs::read_to_string(&path) {
+                            Ok(secret) => Some((secret.trim().to_string(), file_var)),
+                            Err(e) => {
+                                errors.push(format!("{}: cannot read {} ({}): {}", key, file_var, path, e));
+                                None
+                            }
+                        },
+                        Err(_) => None,
+                    }
+                }
+                Err(_) => None,
+            };
+            if let Some((value, source)) = value {
+                builder = builder
+                    .set_override(key.as_str(), value)
+                    .map_err(|e| SystemError::ConfigurationError(e.to_string()))?;
+                sources.insert(key.clone(), source);
+            }
+        }
+
+        for (key, value) in &args.overrides {
+            if pointer(&self.defaults, key).is_none_or(Value::is_object) {
+                errors.push(format!("{}: unknown setting (from --set)", key));
+                continue;
+            }
+            builder = builder
+                .set_override(key.as_str(), value.as_str())
+                .map_err(|e| SystemError::ConfigurationError(e.to_string()))?;
+            sources.insert(key.clone(), "--set".to_string());
+        }
+
+        let merged = builder
+            .build()
+            .and_then(|config| config.try_deserialize::<Value>())
+            .map_err(|e| SystemError::ConfigurationError(format!("{}: {}", file.display(), e)))?;
+
+        // Values that failed to convert were replaced by their defaults, so
+        // validation still runs and reports the remaining problems
+        let merged = coerce(&self.defaults, merged, "", &sources, &mut errors);
+        for validator in &self.validators {
+            errors.extend(validator(&merged));
+        }
+        if !errors.is_empty() {
+            return Err(SystemError::ConfigurationError(format!(
+                "Invalid configuration for {}:\n  - {}",
+                self.service,
+                errors.join("\n  - ")
+            )));
+        }
+
+        serde_json::from_value(merged).map_err(|e| SystemError::ConfigurationError(e.to_string()))
+    }
+
+    // The effective configuration as TOML, with secrets masked
+    pub fn render<T: Serialize>(&self, config: &T) -> String {
+        let mut value = serde_json::to_value(config).unwrap_or(Value::Null);
+        for key in &self.secrets {
+            if let Some(slot) = pointer_mut(&mut value, key) {
+                if slot.as_str().is_some_and(|s| !s.is_empty()) {
+                    *slot = Value::String(REDACTED.to_string());
+                }
+            }
+        }
+        strip_nulls(&mut value);
+        toml::to_string_pretty(&value).unwrap_or_else(|e| format!("# Failed to render configuration: {}", e))
+    }
+}
+
+fn pointer<'a>(value: &'a Value, key: &str) -> Option<&'a Value> {
+    value.pointer(&format!("/{}", key.replace('.', "/")))
+}
+
+fn pointer_mut<'a>(value: &'a mut Value, key: &str) -> Option<&'a mut Value> {
+    value.pointer_mut(&format!("/{}", key.replace('.', "/")))
+}
+
+// TOML has no null; unset optional settings are left out
+fn strip_nulls(value: &mut Value) {
+    if let Value::Object(map) = value {
+        map.retain(|_, v| !v.is_null());
+        map.values_mut().for_each(strip_nulls);
+    }
+}
+
+// Environment variables and `--set` values arrive as strings; they are
+// converted to the type of the default. Mismatches are recorded, not
+// returned, so that one bad value does not hide the next.
+fn coerce(default: &Value, value: Value, key: &str, sources: &HashMap<String, String>, errors: &mut Vec<String>) -> Value {
+    let source = || match sources.get(key) {
+        Some(source) => format!(" from {}", source),
+        None => String::new(),
+    };
+
+    match (default, value) {
+        (Value::Object(defaults), Value::Object(mut values)) => {
+            let mut out = Map::new();
+            for (name, default) in defaults {
+                let child = if key.is_empty() { name.clone() } else { format!("{}.{}", key, name) };
+                let value = values.remove(name).unwrap_or_else(|| default.clone());
+                out.insert(name.clone(), coerce(default, value, &child, sources, errors));
+            }
+            for name in values.keys() {
+                let child = if key.is_empty() { name.clone() } else { format!("{}.{}", key, name) };
+                errors.push(format!("{}: unknown setting", child));
+            }
+            Value::Object(out)
+        }
+        (Value::Number(n), Value::String(s)) => {
+            let parsed = if n.is_u64() {
+                s.trim().parse::<u64>().ok().map(Value::from)
+            } else if n.is_i64() {
+                s.trim().parse::<i64>().ok().map(Value::from)
+            } else {
+                s.trim().parse::<f64>().ok().map(Value::from)
+            };
+            parsed.unwrap_or_else(|| {
+                let expected = if n.is_f64() { "a number" } else { "an integer" };
+                errors.push(format!("{}: expected {}, got {:?}{}", key, expected, s, source()));
+                Value::Number(n.clone())
+            })
+        }
+        (Value::Bool(b), Value::String(s)) => match s.trim().to_ascii_lowercase().as_str() {
+            "true" | "1" | "yes" => Value::Bool(true),
+            "false" | "0" | "no" => Value::Bool(false),
+            _ => {
+                errors.push(format!("{}: expected true or false, got {:?}{}", key, s, source()));
+                Value::Bool(*b)
+            }
+        },
+        (Value::String(_), Value::Number(n)) => Value::String(n.to_string()),
+        (Value::String(_), Value::Bool(b)) => Value::String(b.to_string()),
+        (Value::Null, value) => value,
+        (default, value) if same_kind(default, &value) => value,
+        (default, value) => {
+            errors.push(format!("{}: expected {}, got {}{}", key, kind(default), value, source()));
+            default.clone()
+        }
+    }
+}
+
+fn same_kind(a: &Value, b: &Value) -> bool {
+    std::mem::discriminant(a) == std::mem::discriminant(b)
+}
+
+fn kind(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "nothing",
+        Value::Bool(_) => "true or false",
+        Value::Number(_) => "a number",
+        Value::String(_) => "a string",
+        Value::Array(_) => "a list",
+        Value::Object(_) => "a table",
+    }
+}
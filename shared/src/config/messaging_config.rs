@@ -1,5 +1,5 @@
+use crate::config::loader::{ConfigSection, Violations};
 use serde::{Deserialize, Serialize};
-use std::env;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MessagingConfig {
@@ -7,29 +7,64 @@ pub struct MessagingConfig {
     pub exchange_name: String,
     pub queue_name: String,
     pub routing_key: String,
-    pub connection_timeout: u64,
-    pub heartbeat: u16,
+    pub connection_timeout: u64, // seconds
+    pub heartbeat: u16,          // seconds
+    pub channel_pool_size: usize,
+    pub prefetch_count: u16,
+    pub max_retries: u32,
+    pub retry_delay: u64,     // seconds before the first retry
+    pub retry_max_delay: u64, // seconds, cap for the exponential backoff
 }
 
-impl MessagingConfig {
-    pub fn from_env() -> Self {
+impl Default for MessagingConfig {
+    fn default() -> Self {
         Self {
-            rabbitmq_url: env::var("RABBITMQ_URL")
-                .unwrap_or_else(|_| "amqp://localhost:5672".to_string()),
-            exchange_name: env::var("MESSAGING_EXCHANGE_NAME")
-                .unwrap_or_else(|_| "auth_events".to_string()),
-            queue_name: env::var("MESSAGING_QUEUE_NAME")
-                .unwrap_or_else(|_| "auth_notifications".to_string()),
-            routing_key: env::var("MESSAGING_ROUTING_KEY")
-                .unwrap_or_else(|_| "auth.notifications".to_string()),
-            connection_timeout: env::var("MESSAGING_CONNECTION_TIMEOUT")
-                .unwrap_or_else(|_| "30".to_string())
-                .parse()
-                .expect("MESSAGING_CONNECTION_TIMEOUT must be a valid number"),
-            heartbeat: env::var("MESSAGING_HEARTBEAT")
-                .unwrap_or_else(|_| "60".to_string())
-                .parse()
-                .expect("MESSAGING_HEARTBEAT must be a valid number"),
+            rabbitmq_url: "amqp://localhost:5672".to_string(),
+            exchange_name: "auth_events".to_string(),
+            queue_name: "auth_notifications".to_string(),
+            routing_key: "auth.notifications".to_string(),
+            connection_timeout: 30,
+            heartbeat: 60,
+            channel_pool_size: 4,
+            prefetch_count: 10,
+            max_retries: 5,
+            retry_delay: 5,
+            retry_max_delay: 600,
         }
     }
 }
+
+impl ConfigSection for MessagingConfig {
+    const ENV: &'static [(&'static str, &'static str)] = &[
+        ("RABBITMQ_URL", "rabbitmq_url"),
+        ("MESSAGING_EXCHANGE_NAME", "exchange_name"),
+        ("MESSAGING_QUEUE_NAME", "queue_name"),
+        ("MESSAGING_ROUTING_KEY", "routing_key"),
+        ("MESSAGING_CONNECTION_TIMEOUT", "connection_timeout"),
+        ("MESSAGING_HEARTBEAT", "heartbeat"),
+        ("MESSAGING_CHANNEL_POOL_SIZE", "channel_pool_size"),
+        ("MESSAGING_PREFETCH_COUNT", "prefetch_count"),
+        ("MESSAGING_MAX_RETRIES", "max_retries"),
+        ("MESSAGING_RETRY_DELAY", "retry_delay"),
+        ("MESSAGING_RETRY_MAX_DELAY", "retry_max_delay"),
+    ];
+
+    // The URL carries the password
+    const SECRETS: &'static [&'static str] = &["rabbitmq_url"];
+
+    fn validate(&self, violations: &mut Violations) {
+        violations.check(
+            self.rabbitmq_url.starts_with("amqp://") || self.rabbitmq_url.starts_with("amqps://"),
+            "rabbitmq_url",
+            "must be an amqp:// or amqps:// URL",
+        );
+        violations.check(self.channel_pool_size > 0, "channel_pool_size", "must be at least 1");
+        violations.check(self.prefetch_count > 0, "prefetch_count", "must be at least 1");
+        violations.check(self.retry_delay > 0, "retry_delay", "must be at least 1 second");
+        violations.check(
+            self.retry_delay <= self.retry_max_delay,
+            "retry_max_delay",
+            "must not be shorter than retry_delay",
+        );
+    }
+}
@@ -1,6 +1,9 @@
+pub mod loader;
 pub mod server_config;
 pub mod redis_config;
 pub mod jwt_config;
 pub mod database_config;
 pub mod messaging_config;
-pub mod otp_config;
\ No newline at end of file
+pub mod otp_config;
+pub mod step_up_config;
+pub mod tracing_config;
@@ -1,5 +1,5 @@
+use crate::config::loader::{ConfigSection, Violations};
 use serde::{Deserialize, Serialize};
-use std::env;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OtpConfig {
@@ -10,29 +10,32 @@ pub struct OtpConfig {
     pub max_requests_per_window: u32,
 }
 
-impl OtpConfig {
-    pub fn from_env() -> Self {
+impl Default for OtpConfig {
+    fn default() -> Self {
         Self {
-            length: env::var("OTP_LENGTH")
-                .unwrap_or_else(|_| "6".to_string())
-                .parse()
-                .expect("OTP_LENGTH must be a valid number"),
-            expiry_seconds: env::var("OTP_EXPIRY_SECONDS")
-                .unwrap_or_else(|_| "300".to_string()) // 5 minutes
-                .parse()
-                .expect("OTP_EXPIRY_SECONDS must be a valid number"),
-            max_attempts: env::var("OTP_MAX_ATTEMPTS")
-                .unwrap_or_else(|_| "3".to_string())
-                .parse()
-                .expect("OTP_MAX_ATTEMPTS must be a valid number"),
-            rate_limit_window: env::var("OTP_RATE_LIMIT_WINDOW")
-                .unwrap_or_else(|_| "3600".to_string()) // 1 hour
-                .parse()
-                .expect("OTP_RATE_LIMIT_WINDOW must be a valid number"),
-            max_requests_per_window: env::var("OTP_MAX_REQUESTS_PER_WINDOW")
-                .unwrap_or_else(|_| "5".to_string())
-                .parse()
-                .expect("OTP_MAX_REQUESTS_PER_WINDOW must be a valid number"),
+            length: 6,
+            expiry_seconds: 300, // 5 minutes
+            max_attempts: 3,
+            rate_limit_window: 3600, // 1 hour
+            max_requests_per_window: 5,
         }
     }
 }
+
+impl ConfigSection for OtpConfig {
+    const ENV: &'static [(&'static str, &'static str)] = &[
+        ("OTP_LENGTH", "length"),
+        ("OTP_EXPIRY_SECONDS", "expiry_seconds"),
+        ("OTP_MAX_ATTEMPTS", "max_attempts"),
+        ("OTP_RATE_LIMIT_WINDOW", "rate_limit_window"),
+        ("OTP_MAX_REQUESTS_PER_WINDOW", "max_requests_per_window"),
+    ];
+
+    fn validate(&self, violations: &mut Violations) {
+        violations.check((4..=10).contains(&self.length), "length", "must be between 4 and 10 digits");
+        violations.check(self.expiry_seconds > 0, "expiry_seconds", "must be at least 1 second");
+        violations.check(self.max_attempts > 0, "max_attempts", "must be at least 1");
+        violations.check(self.rate_limit_window > 0, "rate_limit_window", "must be at least 1 second");
+        violations.check(self.max_requests_per_window > 0, "max_requests_per_window", "must be at least 1");
+    }
+}
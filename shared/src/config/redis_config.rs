@@ -1,5 +1,5 @@
+use crate::config::loader::{ConfigSection, Violations};
 use serde::{Deserialize, Serialize};
-use std::env;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RedisConfig {
@@ -10,30 +10,40 @@ pub struct RedisConfig {
     pub pool_timeout: u64,
 }
 
-impl RedisConfig {
-    pub fn from_env() -> Self {
+impl Default for RedisConfig {
+    fn default() -> Self {
         Self {
-            url: env::var("REDIS_URL").unwrap_or_else(|_| "redis://localhost:6379".to_string()),
-            max_connections: env::var("REDIS_MAX_CONNECTIONS")
-                .unwrap_or_else(|_| "10".to_string())
-                .parse()
-                .expect("REDIS_MAX_CONNECTIONS must be a valid number"),
-            connect_timeout: env::var("REDIS_CONNECT_TIMEOUT")
-                .unwrap_or_else(|_| "5".to_string())
-                .parse()
-                .expect("REDIS_CONNECT_TIMEOUT must be a valid number"),
-            idle_timeout: env::var("REDIS_IDLE_TIMEOUT")
-                .unwrap_or_else(|_| "300".to_string())
-                .parse()
-                .expect("REDIS_IDLE_TIMEOUT must be a valid number"),
-            pool_timeout: env::var("REDIS_POOL_TIMEOUT")
-                .unwrap_or_else(|_| "10".to_string())
-                .parse()
-                .expect("REDIS_POOL_TIMEOUT must be a valid number")
+            url: "redis://localhost:6379".to_string(),
+            max_connections: 10,
+            connect_timeout: 5,
+            idle_timeout: 300,
+            pool_timeout: 10,
         }
     }
 }
 
+impl ConfigSection for RedisConfig {
+    const ENV: &'static [(&'static str, &'static str)] = &[
+        ("REDIS_URL", "url"),
+        ("REDIS_MAX_CONNECTIONS", "max_connections"),
+        ("REDIS_CONNECT_TIMEOUT", "connect_timeout"),
+        ("REDIS_IDLE_TIMEOUT", "idle_timeout"),
+        ("REDIS_POOL_TIMEOUT", "pool_timeout"),
+    ];
+
+    // The URL may carry the password
+    const SECRETS: &'static [&'static str] = &["url"];
+
+    fn validate(&self, violations: &mut Violations) {
+        violations.check(
+            self.url.starts_with("redis://") || self.url.starts_with("rediss://"),
+            "url",
+            "must be a redis:// or rediss:// URL",
+        );
+        violations.check(self.max_connections > 0, "max_connections", "must be at least 1");
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RedisFigureConfig {
     pub default_ttl_seconds: u64,
@@ -41,21 +51,26 @@ pub struct RedisFigureConfig {
     pub max_requests_per_window: i32,
 }
 
-impl RedisFigureConfig {
-    pub fn from_env() -> Self {
+impl Default for RedisFigureConfig {
+    fn default() -> Self {
         Self {
-            default_ttl_seconds: env::var("DEFAULT_TTL_SECONDS")
-                .unwrap_or_else(|_| "3600".to_string())
-                .parse()
-                .expect("DEFAULT_TTL_SECONDS must be a valid number"),
-            rate_limit_window_minutes: env::var("RATE_LIMIT_WINDOW_MINUTES")
-                .unwrap_or_else(|_| "15".to_string())
-                .parse()
-                .expect("RATE_LIMIT_WINDOW_MINUTES must be a valid number"),
-            max_requests_per_window: env::var("MAX_REQUESTS_PER_WINDOW")
-                .unwrap_or_else(|_| "5".to_string())
-                .parse()
-                .expect("MAX_REQUESTS_PER_WINDOW must be a valid number"),
+            default_ttl_seconds: 3600,
+            rate_limit_window_minutes: 15,
+            max_requests_per_window: 5,
         }
     }
-}
\ No newline at end of file
+}
+
+impl ConfigSection for RedisFigureConfig {
+    const ENV: &'static [(&'static str, &'static str)] = &[
+        ("DEFAULT_TTL_SECONDS", "default_ttl_seconds"),
+        ("RATE_LIMIT_WINDOW_MINUTES", "rate_limit_window_minutes"),
+        ("MAX_REQUESTS_PER_WINDOW", "max_requests_per_window"),
+    ];
+
+    fn validate(&self, violations: &mut Violations) {
+        violations.check(self.default_ttl_seconds > 0, "default_ttl_seconds", "must be at least 1 second");
+        violations.check(self.rate_limit_window_minutes > 0, "rate_limit_window_minutes", "must be at least 1 minute");
+        violations.check(self.max_requests_per_window > 0, "max_requests_per_window", "must be at least 1");
+    }
+}
@@ -1,41 +1,66 @@
+use crate::config::loader::{ConfigSection, Violations};
+use crate::features::security::auth::TrustedProxies;
 use serde::{Deserialize, Serialize};
-use std::env;
 use std::thread;
 
+// Ports differ per service; each sets its own with
+// `ConfigLoader::with_default("server.port", ..)`
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ServerConfig {
     pub host: String,
     pub port: u16,
+    pub grpc_port: u16,
     pub workers: usize,
     pub keep_alive: u64,
     pub client_timeout: u64,
     pub client_shutdown: u64,
+    // Comma-separated proxy addresses or CIDR ranges whose X-Forwarded-For
+    // is believed; empty means clients connect directly
+    pub trusted_proxies: String,
 }
 
-impl ServerConfig {
-    pub fn from_env() -> Self {
+impl Default for ServerConfig {
+    fn default() -> Self {
         Self {
-            host: env::var("SERVER_HOST").unwrap_or_else(|_| "0.0.0.0".to_string()),
-            port: env::var("SERVER_PORT")
-                .unwrap_or_else(|_| "8001".to_string())
-                .parse()
-                .expect("SERVER_PORT must be a valid number"),
-            workers: env::var("SERVER_WORKERS")
-                .unwrap_or_else(|_| thread::available_parallelism().unwrap().get().to_string())
-                .parse()
-                .expect("SERVER_WORKERS must be a valid number"),
-            keep_alive: env::var("SERVER_KEEP_ALIVE")
-                .unwrap_or_else(|_| "75".to_string())
-                .parse()
-                .expect("SERVER_KEEP_ALIVE must be a valid number"),
-            client_timeout: env::var("SERVER_CLIENT_TIMEOUT")
-                .unwrap_or_else(|_| "5000".to_string())
-                .parse()
-                .expect("SERVER_CLIENT_TIMEOUT must be a valid number"),
-            client_shutdown: env::var("SERVER_CLIENT_SHUTDOWN")
-                .unwrap_or_else(|_| "5000".to_string())
-                .parse()
-                .expect("SERVER_CLIENT_SHUTDOWN must be a valid number"),
+            host: "0.0.0.0".to_string(),
+            port: 8000,
+            grpc_port: 9000,
+            workers: thread::available_parallelism().map(|n| n.get()).unwrap_or(1),
+            keep_alive: 75,
+            client_timeout: 5000,
+            client_shutdown: 5000,
+            trusted_proxies: String::new(),
+        }
+    }
+}
+
+impl ConfigSection for ServerConfig {
+    const ENV: &'static [(&'static str, &'static str)] = &[
+        ("SERVER_HOST", "host"),
+        ("SERVER_PORT", "port"),
+        ("SERVER_GRPC_PORT", "grpc_port"),
+        ("SERVER_WORKERS", "workers"),
+        ("SERVER_KEEP_ALIVE", "keep_alive"),
+        ("SERVER_CLIENT_TIMEOUT", "client_timeout"),
+        ("SERVER_CLIENT_SHUTDOWN", "client_shutdown"),
+        ("SERVER_TRUSTED_PROXIES", "trusted_proxies"),
+    ];
+
+    fn validate(&self, violations: &mut Violations) {
+        violations.check(!self.host.is_empty(), "host", "must be set");
+        violations.check(self.port > 0, "port", "must be between 1 and 65535");
+        violations.check(self.grpc_port > 0, "grpc_port", "must be between 1 and 65535");
+        violations.check(self.port != self.grpc_port, "grpc_port", "must differ from port");
+        violations.check(self.workers > 0, "workers", "must be at least 1");
+        if let Err(e) = TrustedProxies::parse(&self.trusted_proxies) {
+            violations.check(false, "trusted_proxies", e);
         }
     }
 }
+
+impl ServerConfig {
+    // Validated at load time, so a bad list cannot reach here
+    pub fn trusted_proxies(&self) -> TrustedProxies {
+        TrustedProxies::parse(&self.trusted_proxies).unwrap_or_default()
+    }
+}
@@ -0,0 +1,23 @@
+use crate::config::loader::{ConfigSection, Violations};
+use serde::{Deserialize, Serialize};
+
+// How recently a caller must have proven their identity for sensitive
+// operations guarded by `RecentlyAuthenticated`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StepUpConfig {
+    pub max_age_seconds: u64,
+}
+
+impl Default for StepUpConfig {
+    fn default() -> Self {
+        Self { max_age_seconds: 300 } // 5 minutes
+    }
+}
+
+impl ConfigSection for StepUpConfig {
+    const ENV: &'static [(&'static str, &'static str)] = &[("STEP_UP_MAX_AGE_SECONDS", "max_age_seconds")];
+
+    fn validate(&self, violations: &mut Violations) {
+        violations.check(self.max_age_seconds > 0, "max_age_seconds", "must be at least 1 second");
+    }
+}
@@ -0,0 +1,38 @@
+use serde::{Deserialize, Serialize};
+use std::env;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TracingConfig {
+    pub service_name: String,
+    pub otlp_endpoint: Option<String>, // spans are only exported when set
+    pub sample_ratio: f64,             // share of new traces that are recorded, 0.0 - 1.0
+    pub log_filter: String,
+    pub log_format: LogFormat,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogFormat {
+    Json,
+    Text, // for reading logs in a terminal during development
+}
+
+impl TracingConfig {
+    pub fn from_env(service_name: &str) -> Self {
+        Self {
+            service_name: env::var("OTEL_SERVICE_NAME").unwrap_or_else(|_| service_name.to_string()),
+            otlp_endpoint: env::var("OTEL_EXPORTER_OTLP_ENDPOINT")
+                .ok()
+                .filter(|endpoint| !endpoint.is_empty()),
+            sample_ratio: env::var("OTEL_TRACES_SAMPLER_ARG")
+                .unwrap_or_else(|_| "1.0".to_string())
+                .parse()
+                .expect("OTEL_TRACES_SAMPLER_ARG must be a valid number"),
+            log_filter: env::var("RUST_LOG").unwrap_or_else(|_| "info".to_string()),
+            log_format: match env::var("LOG_FORMAT").as_deref() {
+                Ok("text") => LogFormat::Text,
+                _ => LogFormat::Json,
+            },
+        }
+    }
+}
@@ -1,9 +1,13 @@
 use serde::{Deserialize, Serialize};
+use validator::Validate;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
 pub struct LoginRequest {
+    #[validate(length(min = 1, max = 254))]
     pub identifier: String, // email or phone
+    #[validate(length(min = 1, max = 128))]
     pub password: String,
+    #[validate(length(max = 255))]
     pub device_info: Option<String>,
 }
 
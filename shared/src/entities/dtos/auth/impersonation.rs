@@ -0,0 +1,22 @@
+use crate::entities::enums::UserRole;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+pub struct ImpersonateRequest {
+    pub user_id: Uuid,
+    #[validate(length(min = 10, max = 500))]
+    pub reason: String, // recorded in the audit trail
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImpersonationResponse {
+    pub access_token: String,
+    pub expires_in: i64,
+    pub expires_at: DateTime<Utc>,
+    pub user_id: Uuid,
+    pub email: String,
+    pub role: UserRole,
+}
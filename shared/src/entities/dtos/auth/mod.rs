@@ -1,5 +1,10 @@
+pub mod api_key;
 pub mod auth;
+pub mod impersonation;
+pub mod oauth;
+pub mod organisation;
 pub mod otp;
 pub mod password;
 pub mod question;
+pub mod step_up;
 pub mod token;
\ No newline at end of file
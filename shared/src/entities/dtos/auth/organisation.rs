@@ -0,0 +1,69 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+use uuid::Uuid;
+use crate::entities::enums::OrganisationRole;
+use crate::features::validation::email;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+pub struct CreateOrganisationRequest {
+    #[validate(length(min = 1, max = 100))]
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrganisationResponse {
+    pub id: Uuid,
+    pub name: String,
+    pub slug: String,
+    pub role: OrganisationRole, // the caller's role in this organisation
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrganisationMemberResponse {
+    pub user_id: Uuid,
+    pub email: String,
+    pub role: OrganisationRole,
+    pub joined_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+pub struct UpdateMemberRoleRequest {
+    pub role: OrganisationRole,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+pub struct InviteMemberRequest {
+    #[validate(custom(function = "email"))]
+    pub email: String,
+    pub role: OrganisationRole,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InvitationResponse {
+    pub id: Uuid,
+    pub organisation_id: Uuid,
+    pub email: String,
+    pub role: OrganisationRole,
+    pub expires_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+pub struct AcceptInvitationRequest {
+    #[validate(length(min = 1))]
+    pub token: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+pub struct SwitchOrganisationRequest {
+    pub organisation_id: Uuid,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SwitchOrganisationResponse {
+    pub access_token: String,
+    pub expires_in: i64,
+    pub organisation_id: Uuid,
+    pub role: OrganisationRole,
+}
@@ -1,15 +1,24 @@
 use serde::{Deserialize, Serialize};
+use validator::{Validate, ValidationError};
 use crate::entities::enums::IdentifierType;
+use crate::features::validation::{identifier, on_field};
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Validate)]
+#[validate(schema(function = "validate_send_otp"))]
 pub struct SendOtpRequest {
     pub identifier: String,
     pub identifier_type: IdentifierType,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+fn validate_send_otp(req: &SendOtpRequest) -> Result<(), ValidationError> {
+    identifier(&req.identifier, &req.identifier_type).map_err(|e| on_field("identifier", e))
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
 pub struct VerifyOtpRequest {
+    #[validate(length(min = 1, max = 254))]
     pub identifier: String,
+    #[validate(length(min = 4, max = 10))]
     pub otp_code: String,
 }
 
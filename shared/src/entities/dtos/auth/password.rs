@@ -1,16 +1,25 @@
 use serde::{Deserialize, Serialize};
+use validator::{Validate, ValidationError};
 use uuid::Uuid;
 use crate::entities::enums::IdentifierType;
+use crate::features::validation::{identifier, on_field};
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Validate)]
+#[validate(schema(function = "validate_password_reset"))]
 pub struct PasswordResetRequest {
     pub identifier: String, // email or phone
     pub identifier_type: IdentifierType,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+fn validate_password_reset(req: &PasswordResetRequest) -> Result<(), ValidationError> {
+    identifier(&req.identifier, &req.identifier_type).map_err(|e| on_field("identifier", e))
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
 pub struct PasswordResetConfirmRequest {
+    #[validate(length(min = 1))]
     pub token: String,
+    #[validate(length(min = 8, max = 128))]
     pub new_password: String,
 }
 
@@ -1,5 +1,7 @@
 use chrono::{DateTime, Utc};
+use crate::features::validation::on_field;
 use serde::{Deserialize, Serialize};
+use validator::{Validate, ValidationError};
 use uuid::Uuid;
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -8,22 +10,45 @@ pub struct SecurityQuestion {
     pub question: String,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Validate)]
+#[validate(schema(function = "validate_question_count", skip_on_field_errors = false))]
 pub struct SetSecurityQuestionsRequest {
+    #[validate(nested)]
     pub questions: Vec<SecurityQuestionAnswer>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Validate)]
 pub struct SecurityQuestionAnswer {
     pub question_id: Uuid,
+    #[validate(length(min = 2, max = 200))]
     pub answer: String,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Validate)]
+#[validate(schema(function = "validate_answer_count", skip_on_field_errors = false))]
 pub struct VerifySecurityQuestionsRequest {
+    #[validate(nested)]
     pub answers: Vec<SecurityQuestionAnswer>,
 }
 
+// validator panics when `length` and `nested` both fail on one field, so
+// the counts are checked at struct level
+fn validate_question_count(req: &SetSecurityQuestionsRequest) -> Result<(), ValidationError> {
+    count("questions", req.questions.len(), 2, 5)
+}
+
+fn validate_answer_count(req: &VerifySecurityQuestionsRequest) -> Result<(), ValidationError> {
+    count("answers", req.answers.len(), 1, 5)
+}
+
+fn count(field: &'static str, len: usize, min: usize, max: usize) -> Result<(), ValidationError> {
+    if (min..=max).contains(&len) {
+        return Ok(());
+    }
+    let message = format!("must have between {} and {} items", min, max);
+    Err(on_field(field, ValidationError::new("length").with_message(message.into())))
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SecurityQuestionRequest {
     pub user_id: Uuid,
@@ -0,0 +1,52 @@
+use serde::{Deserialize, Serialize};
+use crate::features::validation::on_field;
+use validator::{Validate, ValidationError};
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ReauthenticationMethod {
+    Password,
+    Otp,  // code sent with /otp/send to the account email
+    Totp, // authenticator app code
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+#[validate(schema(function = "validate_reauthentication"))]
+pub struct ReauthenticateRequest {
+    pub method: ReauthenticationMethod,
+    #[validate(length(max = 128))]
+    pub password: Option<String>,
+    #[validate(length(min = 4, max = 10))]
+    pub code: Option<String>,
+}
+
+// Password re-authentication needs the password, the others a code
+fn validate_reauthentication(req: &ReauthenticateRequest) -> Result<(), ValidationError> {
+    let (field, value) = match req.method {
+        ReauthenticationMethod::Password => ("password", &req.password),
+        ReauthenticationMethod::Otp | ReauthenticationMethod::Totp => ("code", &req.code),
+    };
+    match value {
+        Some(value) if !value.trim().is_empty() => Ok(()),
+        _ => Err(on_field(field, ValidationError::new("required"))),
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReauthenticateResponse {
+    pub access_token: String,
+    pub expires_in: i64,
+    pub auth_time: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TotpEnrollmentResponse {
+    pub secret: String,
+    pub otpauth_url: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+pub struct ConfirmTotpRequest {
+    #[validate(length(min = 6, max = 10))]
+    pub code: String,
+}
@@ -44,6 +44,40 @@ impl std::str::FromStr for UserRole {
     }
 }
 
+// Role a user holds inside a single organisation, independent of their platform `UserRole`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum OrganisationRole {
+    Owner,
+    Admin,
+    Manager,
+    Member,
+}
+
+impl std::fmt::Display for OrganisationRole {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OrganisationRole::Owner => write!(f, "owner"),
+            OrganisationRole::Admin => write!(f, "admin"),
+            OrganisationRole::Manager => write!(f, "manager"),
+            OrganisationRole::Member => write!(f, "member"),
+        }
+    }
+}
+
+impl std::str::FromStr for OrganisationRole {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "owner" => Ok(OrganisationRole::Owner),
+            "admin" => Ok(OrganisationRole::Admin),
+            "manager" => Ok(OrganisationRole::Manager),
+            "member" => Ok(OrganisationRole::Member),
+            _ => Err(format!("Invalid organisation role: {}", s)),
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub enum IdentifierType {
     Email,
@@ -0,0 +1,139 @@
+use crate::events::ExchangeType;
+use chrono::{DateTime, Utc};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use uuid::Uuid;
+
+// Namespace of the UUIDv5 ids given to bodies published without an envelope
+const LEGACY_EVENT_NAMESPACE: Uuid = Uuid::from_u128(0x6c9e_2b4a_1f3d_5e87_a0c4_92d1_7b35_e608);
+
+// A strongly-typed event payload. The routing key, event type and schema
+// version are fixed at compile time, so a publisher and a subscriber of the
+// same type can never disagree on where the event goes.
+pub trait DomainEvent: Serialize + DeserializeOwned + Send + Sync + 'static {
+    const EVENT_TYPE: &'static str;
+    const ROUTING_KEY: &'static str;
+    const SCHEMA_VERSION: u32 = 1;
+    const EXCHANGE: ExchangeType = ExchangeType::Topic;
+
+    // Rewrites a payload written with an older schema version into the
+    // current shape. Bump `SCHEMA_VERSION` and extend this when a payload
+    // changes incompatibly; versions it does not know are rejected.
+    fn upcast(version: u32, payload: Value) -> Result<Value, String> {
+        let _ = payload;
+        Err(format!(
+            "{} has no upcast from schema version {} to {}",
+            Self::EVENT_TYPE,
+            version,
+            Self::SCHEMA_VERSION
+        ))
+    }
+}
+
+// Every inter-service message is wrapped in this envelope. `correlation_id`
+// ties together everything triggered by one request; `causation_id` is the id
+// of the event that directly caused this one.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EventEnvelope<T = Value> {
+    pub event_id: Uuid,
+    pub event_type: String,
+    pub schema_version: u32,
+    pub source_service: String,
+    pub correlation_id: Option<Uuid>,
+    pub causation_id: Option<Uuid>,
+    pub occurred_at: DateTime<Utc>,
+    pub payload: T,
+}
+
+impl<E: DomainEvent> EventEnvelope<E> {
+    pub fn new(source_service: &str, payload: E) -> Self {
+        Self {
+            event_id: Uuid::new_v4(),
+            event_type: E::EVENT_TYPE.to_string(),
+            schema_version: E::SCHEMA_VERSION,
+            source_service: source_service.to_string(),
+            correlation_id: None,
+            causation_id: None,
+            occurred_at: Utc::now(),
+            payload,
+        }
+    }
+
+    pub fn with_correlation_id(mut self, correlation_id: Uuid) -> Self {
+        self.correlation_id = Some(correlation_id);
+        self
+    }
+
+    // Marks this event as a consequence of `cause`, inheriting its correlation
+    pub fn caused_by<C>(mut self, cause: &EventEnvelope<C>) -> Self {
+        self.causation_id = Some(cause.event_id);
+        self.correlation_id = Some(cause.correlation_id.unwrap_or(cause.event_id));
+        self
+    }
+
+    pub fn routing_key(&self) -> &'static str {
+        E::ROUTING_KEY
+    }
+
+    pub fn exchange(&self) -> ExchangeType {
+        E::EXCHANGE
+    }
+
+    pub fn to_bytes(&self) -> Result<Vec<u8>, String> {
+        serde_json::to_vec(self).map_err(|e| format!("Failed to serialize {}: {}", E::EVENT_TYPE, e))
+    }
+
+    // Decodes an envelope of type `E`, upcasting older payloads. Bodies
+    // published before envelopes existed (a bare payload object) are read as
+    // schema version 0, with an id derived from the body so redeliveries of
+    // one message deduplicate in the inbox.
+    pub fn decode(data: &[u8]) -> Result<Self, String> {
+        let body: Value = serde_json::from_slice(data).map_err(|e| format!("Invalid JSON: {}", e))?;
+
+        let raw = if body.get("event_type").is_some() {
+            serde_json::from_value::<EventEnvelope<Value>>(body).map_err(|e| format!("Invalid envelope: {}", e))?
+        } else {
+            EventEnvelope {
+                event_id: Uuid::new_v5(&LEGACY_EVENT_NAMESPACE, &[E::ROUTING_KEY.as_bytes(), b"\n", data].concat()),
+                event_type: E::EVENT_TYPE.to_string(),
+                schema_version: 0,
+                source_service: "unknown".to_string(),
+                correlation_id: None,
+                causation_id: None,
+                occurred_at: Utc::now(),
+                payload: body,
+            }
+        };
+
+        if raw.event_type != E::EVENT_TYPE {
+            return Err(format!("Expected {} but received {}", E::EVENT_TYPE, raw.event_type));
+        }
+
+        let payload = match raw.schema_version {
+            v if v == E::SCHEMA_VERSION => raw.payload,
+            v if v < E::SCHEMA_VERSION => E::upcast(v, raw.payload)?,
+            v => {
+                return Err(format!(
+                    "{} schema version {} is newer than supported version {}",
+                    E::EVENT_TYPE,
+                    v,
+                    E::SCHEMA_VERSION
+                ))
+            }
+        };
+        let payload = serde_json::from_value::<E>(payload)
+            .map_err(|e| format!("Invalid {} payload: {}", E::EVENT_TYPE, e))?;
+
+        Ok(EventEnvelope {
+            event_id: raw.event_id,
+            event_type: raw.event_type,
+            schema_version: E::SCHEMA_VERSION,
+            source_service: raw.source_service,
+            correlation_id: raw.correlation_id,
+            causation_id: raw.causation_id,
+            occurred_at: raw.occurred_at,
+            payload,
+        })
+    }
+}
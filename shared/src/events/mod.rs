@@ -1,21 +1,13 @@
 use std::fmt::Display;
-use chrono::{DateTime, Utc};
 use lapin::ExchangeKind;
-use serde::{Deserialize, Serialize};
-use uuid::Uuid;
 
 pub mod auth_event;
+pub mod envelope;
+pub mod notification_event;
 pub mod user_event;
 
-// Event types for inter-service communication
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct Event {
-    pub id: Uuid,
-    pub event_type: String,
-    pub payload: serde_json::Value,
-    pub source_service: String,
-    pub timestamp: DateTime<Utc>,
-}
+// Inter-service messages are `EventEnvelope`s around a `DomainEvent` payload
+pub use envelope::{DomainEvent, EventEnvelope};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ExchangeType {
@@ -47,25 +39,3 @@ impl From<ExchangeType> for ExchangeKind {
         }
     }
 }
-
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum RoutingKey {
-    EmailOtp,
-    SmsOtp,
-    EmailPasswordReset,
-    EmailPasswordChanged,
-    Broadcast,
-}
-
-impl Display for RoutingKey {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let str = match self {
-            RoutingKey::EmailOtp => "notification.email.otp".to_string(),
-            RoutingKey::SmsOtp => "notification.sms.otp".to_string(),
-            RoutingKey::EmailPasswordReset => "notification.email.password_reset".to_string(),
-            RoutingKey::EmailPasswordChanged => "notification.email.password_changed".to_string(),
-            RoutingKey::Broadcast => "notification.broadcast".to_string(),
-        };
-        write!(f, "{}", str)
-    }
-}
\ No newline at end of file
@@ -0,0 +1,19 @@
+use crate::events::envelope::DomainEvent;
+use crate::events::ExchangeType;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BroadcastEvent {
+    pub message: String,
+}
+
+impl DomainEvent for BroadcastEvent {
+    const EVENT_TYPE: &'static str = "broadcast";
+    const ROUTING_KEY: &'static str = "notification.broadcast";
+    const EXCHANGE: ExchangeType = ExchangeType::Fanout;
+
+    fn upcast(version: u32, payload: Value) -> Result<Value, String> {
+        super::upcast_legacy(Self::EVENT_TYPE, version, payload)
+    }
+}
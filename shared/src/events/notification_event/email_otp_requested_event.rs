@@ -0,0 +1,18 @@
+use crate::events::envelope::DomainEvent;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmailOtpRequestedEvent {
+    pub email: String,
+    pub otp_code: String,
+}
+
+impl DomainEvent for EmailOtpRequestedEvent {
+    const EVENT_TYPE: &'static str = "email_otp_requested";
+    const ROUTING_KEY: &'static str = "notification.email.otp";
+
+    fn upcast(version: u32, payload: Value) -> Result<Value, String> {
+        super::upcast_legacy(Self::EVENT_TYPE, version, payload)
+    }
+}
@@ -0,0 +1,22 @@
+use serde_json::Value;
+
+pub mod broadcast_event;
+pub mod email_otp_requested_event;
+pub mod organisation_invitation_event;
+pub mod password_changed_event;
+pub mod password_reset_requested_event;
+pub mod sms_otp_requested_event;
+
+// Version 0 payloads are the bare JSON objects published before envelopes,
+// which carried the email template name alongside the fields
+fn upcast_legacy(event_type: &str, version: u32, mut payload: Value) -> Result<Value, String> {
+    match version {
+        0 => {
+            if let Some(fields) = payload.as_object_mut() {
+                fields.remove("template");
+            }
+            Ok(payload)
+        }
+        v => Err(format!("{} has no upcast from schema version {}", event_type, v)),
+    }
+}
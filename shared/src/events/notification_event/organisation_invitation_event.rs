@@ -0,0 +1,19 @@
+use crate::events::envelope::DomainEvent;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrganisationInvitationEvent {
+    pub email: String,
+    pub organisation_name: String,
+    pub invitation_token: String,
+}
+
+impl DomainEvent for OrganisationInvitationEvent {
+    const EVENT_TYPE: &'static str = "organisation_invitation_created";
+    const ROUTING_KEY: &'static str = "notification.email.organisation_invitation";
+
+    fn upcast(version: u32, payload: Value) -> Result<Value, String> {
+        super::upcast_legacy(Self::EVENT_TYPE, version, payload)
+    }
+}
@@ -0,0 +1,17 @@
+use crate::events::envelope::DomainEvent;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PasswordChangedEvent {
+    pub email: String,
+}
+
+impl DomainEvent for PasswordChangedEvent {
+    const EVENT_TYPE: &'static str = "password_changed";
+    const ROUTING_KEY: &'static str = "notification.email.password_changed";
+
+    fn upcast(version: u32, payload: Value) -> Result<Value, String> {
+        super::upcast_legacy(Self::EVENT_TYPE, version, payload)
+    }
+}
@@ -0,0 +1,18 @@
+use crate::events::envelope::DomainEvent;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PasswordResetRequestedEvent {
+    pub email: String,
+    pub reset_token: String,
+}
+
+impl DomainEvent for PasswordResetRequestedEvent {
+    const EVENT_TYPE: &'static str = "password_reset_requested";
+    const ROUTING_KEY: &'static str = "notification.email.password_reset";
+
+    fn upcast(version: u32, payload: Value) -> Result<Value, String> {
+        super::upcast_legacy(Self::EVENT_TYPE, version, payload)
+    }
+}
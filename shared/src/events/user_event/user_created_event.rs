@@ -1,3 +1,4 @@
+use crate::events::envelope::DomainEvent;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
@@ -6,4 +7,9 @@ pub struct UserCreatedEvent {
     pub user_id: Uuid,
     pub email: String,
     pub user_type: String,
-}
\ No newline at end of file
+}
+
+impl DomainEvent for UserCreatedEvent {
+    const EVENT_TYPE: &'static str = "user_created";
+    const ROUTING_KEY: &'static str = "user.created";
+}
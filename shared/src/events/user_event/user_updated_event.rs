@@ -1,3 +1,4 @@
+use crate::events::envelope::DomainEvent;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
@@ -5,4 +6,9 @@ use uuid::Uuid;
 pub struct UserUpdatedEvent {
     pub user_id: Uuid,
     pub updated_fields: Vec<String>,
-}
\ No newline at end of file
+}
+
+impl DomainEvent for UserUpdatedEvent {
+    const EVENT_TYPE: &'static str = "user_updated";
+    const ROUTING_KEY: &'static str = "user.updated";
+}
@@ -1,6 +1,7 @@
 use actix_web::http::StatusCode;
-use actix_web::HttpResponse;
+use actix_web::{HttpResponse, ResponseError};
 use anyhow::Result;
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 use crate::entities::models::ApiResponse;
 
@@ -66,15 +67,39 @@ pub enum SystemError {
     #[error("Security question answer is incorrect")]
     SecurityQuestionFailed,
 
+    #[error("Recent authentication is required for this operation")]
+    ReauthenticationRequired,
+
+    #[error("Client authentication failed")]
+    InvalidClient,
+
+    #[error("Invalid authorization grant: {0}")]
+    InvalidGrant(String),
+
+    #[error("Invalid scope: {0}")]
+    InvalidScope(String),
+
+    #[error("Unsupported grant type: {0}")]
+    UnsupportedGrantType(String),
+
     #[error("User not found: {0}")]
     UserNotFound(String),
 
+    #[error("Resource not found: {0}")]
+    NotFound(String),
+
     #[error("Email already exists: {0}")]
     EmailExists(String),
 
     #[error("Phone number already exists: {0}")]
     PhoneExists(String),
 
+    #[error("Idempotency key was already used for a different request")]
+    IdempotencyKeyReused,
+
+    #[error("A request with this idempotency key is still being processed")]
+    RequestInProgress,
+
     #[error("Database error: {0}")]
     DatabaseError(String),
 
@@ -96,6 +121,9 @@ pub enum SystemError {
     #[error("Validation error: {0}")]
     ValidationError(String),
 
+    #[error("Validation failed: {}", .0.iter().map(|e| format!("{} {}", e.field, e.message)).collect::<Vec<_>>().join("; "))]
+    InvalidFields(Vec<FieldError>),
+
     #[error("File not found: {0}")]
     FileNotFound(String),
 
@@ -196,59 +224,181 @@ pub fn map_success_to_response<T: serde::Serialize>(
     HttpResponse::build(success.status_code()).json(response)
 }
 
-pub fn map_auth_error_to_response(err: &SystemError) -> HttpResponse {
+impl SystemError {
+    pub fn http_status(&self) -> StatusCode {
+        match self {
+            SystemError::RateLimitExceeded(_) => StatusCode::TOO_MANY_REQUESTS,
+            SystemError::InvalidCredentials => StatusCode::UNAUTHORIZED,
+            SystemError::AccountLocked => StatusCode::FORBIDDEN,
+            SystemError::AccountNotVerified => StatusCode::FORBIDDEN,
+            SystemError::AccountInactive => StatusCode::FORBIDDEN,
+            SystemError::EmailNotVerified => StatusCode::FORBIDDEN,
+            SystemError::OtpExpired => StatusCode::BAD_REQUEST,
+            SystemError::TokenExpired => StatusCode::UNAUTHORIZED,
+            SystemError::InvalidOtp(_) => StatusCode::BAD_REQUEST,
+            SystemError::OtpNotFound => StatusCode::NOT_FOUND,
+            SystemError::OtpRateLimitExceeded => StatusCode::TOO_MANY_REQUESTS,
+            SystemError::InvalidToken => StatusCode::UNAUTHORIZED,
+            SystemError::TokenBlacklisted => StatusCode::UNAUTHORIZED,
+            SystemError::InvalidRefreshToken => StatusCode::UNAUTHORIZED,
+            SystemError::WeakPassword(_) => StatusCode::BAD_REQUEST,
+            SystemError::InvalidResetToken => StatusCode::BAD_REQUEST,
+            SystemError::SecurityQuestionFailed => StatusCode::BAD_REQUEST,
+            SystemError::ReauthenticationRequired => StatusCode::UNAUTHORIZED,
+            SystemError::InvalidClient => StatusCode::UNAUTHORIZED,
+            SystemError::InvalidGrant(_) => StatusCode::BAD_REQUEST,
+            SystemError::InvalidScope(_) => StatusCode::BAD_REQUEST,
+            SystemError::UnsupportedGrantType(_) => StatusCode::BAD_REQUEST,
+            SystemError::UserNotFound(_) => StatusCode::NOT_FOUND,
+            SystemError::NotFound(_) => StatusCode::NOT_FOUND,
+            SystemError::EmailExists(_) => StatusCode::CONFLICT,
+            SystemError::PhoneExists(_) => StatusCode::CONFLICT,
+            SystemError::IdempotencyKeyReused => StatusCode::CONFLICT,
+            SystemError::RequestInProgress => StatusCode::CONFLICT,
+            SystemError::DatabaseError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            SystemError::RedisError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            SystemError::ExternalServiceError(_) => StatusCode::BAD_GATEWAY,
+            SystemError::InternalError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            SystemError::HashingError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            SystemError::TokenError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            SystemError::ValidationError(_) => StatusCode::BAD_REQUEST,
+            SystemError::InvalidFields(_) => StatusCode::BAD_REQUEST,
+            SystemError::MessageBrokerError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            SystemError::InvalidEmail(_) => StatusCode::BAD_REQUEST,
+            SystemError::InvalidPhone(_) => StatusCode::BAD_REQUEST,
+            SystemError::FileNotFound(_) => StatusCode::NOT_FOUND,
+            SystemError::FileUploadError(_) => StatusCode::BAD_REQUEST,
+            SystemError::PermissionDenied(_) => StatusCode::FORBIDDEN,
+            SystemError::ConfigurationError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            SystemError::NetworkError(_) => StatusCode::BAD_GATEWAY,
+            SystemError::TimeoutError(_) => StatusCode::REQUEST_TIMEOUT,
+            SystemError::ParseError(_) => StatusCode::BAD_REQUEST,
+            SystemError::SerializationError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            SystemError::DeserializationError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            SystemError::UnknownError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
 
-    let (status, error_message) = match err {
-        SystemError::RateLimitExceeded(msg) => (StatusCode::TOO_MANY_REQUESTS, msg.clone()),
-        SystemError::InvalidCredentials => (StatusCode::UNAUTHORIZED, err.to_string()),
-        SystemError::AccountLocked => (StatusCode::FORBIDDEN, err.to_string()),
-        SystemError::AccountNotVerified => (StatusCode::FORBIDDEN, err.to_string()),
-        SystemError::AccountInactive => (StatusCode::FORBIDDEN, err.to_string()),
-        SystemError::EmailNotVerified => (StatusCode::FORBIDDEN, err.to_string()),
-        SystemError::OtpExpired => (StatusCode::BAD_REQUEST, err.to_string()),
-        SystemError::TokenExpired => (StatusCode::UNAUTHORIZED, err.to_string()),
-        SystemError::InvalidOtp(msg) => (StatusCode::BAD_REQUEST, msg.clone()),
-        SystemError::OtpNotFound => (StatusCode::NOT_FOUND, err.to_string()),
-        SystemError::OtpRateLimitExceeded => (StatusCode::TOO_MANY_REQUESTS, err.to_string()),
-        SystemError::InvalidToken => (StatusCode::UNAUTHORIZED, err.to_string()),
-        SystemError::TokenBlacklisted => (StatusCode::UNAUTHORIZED, err.to_string()),
-        SystemError::InvalidRefreshToken => (StatusCode::UNAUTHORIZED, err.to_string()),
-        SystemError::WeakPassword(msg) => (StatusCode::BAD_REQUEST, msg.clone()),
-        SystemError::InvalidResetToken => (StatusCode::BAD_REQUEST, err.to_string()),
-        SystemError::SecurityQuestionFailed => (StatusCode::BAD_REQUEST, err.to_string()),
-        SystemError::UserNotFound(msg) => (StatusCode::NOT_FOUND, msg.clone()),
-        SystemError::EmailExists(msg) => (StatusCode::CONFLICT, msg.clone()),
-        SystemError::PhoneExists(msg) => (StatusCode::CONFLICT, msg.clone()),
-        SystemError::DatabaseError(msg) => (StatusCode::INTERNAL_SERVER_ERROR, msg.clone()),
-        SystemError::RedisError(msg) => (StatusCode::INTERNAL_SERVER_ERROR, msg.clone()),
-        SystemError::ExternalServiceError(msg) => (StatusCode::BAD_GATEWAY, msg.clone()),
-        SystemError::InternalError(msg) => (StatusCode::INTERNAL_SERVER_ERROR, msg.clone()),
-        SystemError::HashingError(msg) => (StatusCode::INTERNAL_SERVER_ERROR, msg.clone()),
-        SystemError::TokenError(msg) => (StatusCode::INTERNAL_SERVER_ERROR, msg.clone()),
-        SystemError::ValidationError(msg) => (StatusCode::BAD_REQUEST, msg.clone()),
-        SystemError::MessageBrokerError(msg) => (StatusCode::INTERNAL_SERVER_ERROR, msg.clone()),
-        SystemError::InvalidEmail(msg) => (StatusCode::BAD_REQUEST, msg.clone()),
-        SystemError::InvalidPhone(msg) => (StatusCode::BAD_REQUEST, msg.clone()),
-        SystemError::FileNotFound(msg) => (StatusCode::NOT_FOUND, msg.clone()),
-        SystemError::FileUploadError(msg) => (StatusCode::BAD_REQUEST, msg.clone()),
-        SystemError::PermissionDenied(msg) => (StatusCode::FORBIDDEN, msg.clone()),
-        SystemError::ConfigurationError(msg) => (StatusCode::INTERNAL_SERVER_ERROR, msg.clone()),
-        SystemError::NetworkError(msg) => (StatusCode::BAD_GATEWAY, msg.clone()),
-        SystemError::TimeoutError(msg) => (StatusCode::REQUEST_TIMEOUT, msg.clone()),
-        SystemError::ParseError(msg) => (StatusCode::BAD_REQUEST, msg.clone()),
-        SystemError::SerializationError(msg) => (StatusCode::INTERNAL_SERVER_ERROR, msg.clone()),
-        SystemError::DeserializationError(msg) => (StatusCode::INTERNAL_SERVER_ERROR, msg.clone()),
-        SystemError::UnknownError(msg) => (StatusCode::INTERNAL_SERVER_ERROR, msg.clone()),
-    };
+    // Stable, machine-readable identifier sent to clients as `code`. Never
+    // rename one; clients branch on them.
+    pub fn code(&self) -> &'static str {
+        match self {
+            SystemError::RateLimitExceeded(_) => "rate_limited",
+            SystemError::InvalidCredentials => "invalid_credentials",
+            SystemError::AccountLocked => "account_locked",
+            SystemError::AccountNotVerified => "account_not_verified",
+            SystemError::AccountInactive => "account_inactive",
+            SystemError::EmailNotVerified => "email_not_verified",
+            SystemError::OtpExpired => "otp_expired",
+            SystemError::TokenExpired => "token_expired",
+            SystemError::InvalidOtp(_) => "invalid_otp",
+            SystemError::OtpNotFound => "otp_not_found",
+            SystemError::OtpRateLimitExceeded => "otp_rate_limited",
+            SystemError::InvalidToken => "invalid_token",
+            SystemError::TokenBlacklisted => "token_revoked",
+            SystemError::InvalidRefreshToken => "invalid_refresh_token",
+            SystemError::WeakPassword(_) => "weak_password",
+            SystemError::InvalidResetToken => "invalid_reset_token",
+            SystemError::SecurityQuestionFailed => "security_question_failed",
+            SystemError::ReauthenticationRequired => "reauthentication_required",
+            SystemError::InvalidClient => "invalid_client",
+            SystemError::InvalidGrant(_) => "invalid_grant",
+            SystemError::InvalidScope(_) => "invalid_scope",
+            SystemError::UnsupportedGrantType(_) => "unsupported_grant_type",
+            SystemError::UserNotFound(_) => "user_not_found",
+            SystemError::NotFound(_) => "not_found",
+            SystemError::EmailExists(_) => "email_exists",
+            SystemError::PhoneExists(_) => "phone_exists",
+            SystemError::IdempotencyKeyReused => "idempotency_key_reused",
+            SystemError::RequestInProgress => "request_in_progress",
+            SystemError::DatabaseError(_) => "internal_error",
+            SystemError::RedisError(_) => "internal_error",
+            SystemError::ExternalServiceError(_) => "upstream_error",
+            SystemError::InternalError(_) => "internal_error",
+            SystemError::HashingError(_) => "internal_error",
+            SystemError::TokenError(_) => "internal_error",
+            SystemError::ValidationError(_) => "validation_failed",
+            SystemError::InvalidFields(_) => "validation_failed",
+            SystemError::MessageBrokerError(_) => "internal_error",
+            SystemError::InvalidEmail(_) => "invalid_email",
+            SystemError::InvalidPhone(_) => "invalid_phone",
+            SystemError::FileNotFound(_) => "file_not_found",
+            SystemError::FileUploadError(_) => "file_upload_failed",
+            SystemError::PermissionDenied(_) => "permission_denied",
+            SystemError::ConfigurationError(_) => "internal_error",
+            SystemError::NetworkError(_) => "upstream_error",
+            SystemError::TimeoutError(_) => "timeout",
+            SystemError::ParseError(_) => "malformed_request",
+            SystemError::SerializationError(_) => "internal_error",
+            SystemError::DeserializationError(_) => "internal_error",
+            SystemError::UnknownError(_) => "internal_error",
+        }
+    }
 
-    let response = ApiResponse::<()> {
-        success: false,
-        data: None,
-        message: None,
-        error: Some(error_message),
-    };
+    // What the client is told. Internal failures are logged, not returned:
+    // their messages carry SQL, hostnames and the like.
+    pub fn public_message(&self) -> String {
+        if self.http_status() == StatusCode::INTERNAL_SERVER_ERROR {
+            return "An unexpected error occurred".to_string();
+        }
+        match self {
+            SystemError::RateLimitExceeded(msg)
+            | SystemError::InvalidOtp(msg)
+            | SystemError::WeakPassword(msg)
+            | SystemError::InvalidGrant(msg)
+            | SystemError::InvalidScope(msg)
+            | SystemError::UnsupportedGrantType(msg)
+            | SystemError::UserNotFound(msg)
+            | SystemError::NotFound(msg)
+            | SystemError::EmailExists(msg)
+            | SystemError::PhoneExists(msg)
+            | SystemError::ExternalServiceError(msg)
+            | SystemError::ValidationError(msg)
+            | SystemError::InvalidEmail(msg)
+            | SystemError::InvalidPhone(msg)
+            | SystemError::FileNotFound(msg)
+            | SystemError::FileUploadError(msg)
+            | SystemError::PermissionDenied(msg)
+            | SystemError::NetworkError(msg)
+            | SystemError::TimeoutError(msg)
+            | SystemError::ParseError(msg) => msg.clone(),
+            SystemError::InvalidFields(_) => "The request has invalid fields".to_string(),
+            _ => self.to_string(),
+        }
+    }
 
-    HttpResponse::build(status).json(response)
+    pub fn field_errors(&self) -> &[FieldError] {
+        match self {
+            SystemError::InvalidFields(errors) => errors,
+            _ => &[],
+        }
+    }
+}
+
+// One invalid field of a request, e.g.
+// `{"field": "email", "code": "email", "message": "must be a valid email address"}`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FieldError {
+    pub field: String,
+    pub code: String,
+    pub message: String,
+}
+
+impl FieldError {
+    pub fn new(field: impl Into<String>, code: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            field: field.into(),
+            code: code.into(),
+            message: message.into(),
+        }
+    }
+}
+
+// Renders the error as `application/problem+json` (see `ProblemDetails`).
+// Kept for handlers written before `SystemError` implemented `ResponseError`;
+// new code can return `Result<_, SystemError>` directly.
+pub fn map_auth_error_to_response(err: &SystemError) -> HttpResponse {
+    err.error_response()
 }
 
 pub type SystemResult<T> = Result<T, SystemError>;
@@ -0,0 +1,138 @@
+use crate::features::errors::{SystemError, SystemResult};
+use crate::features::health::HealthCheck;
+use crate::features::observability::grpc::inject_trace_context;
+use crate::utils::messaging::MessageBroker;
+use async_trait::async_trait;
+use deadpool_redis::redis;
+use sqlx::{Pool, Postgres};
+use std::sync::Arc;
+use tonic::transport::Endpoint;
+use tonic_health::pb::health_check_response::ServingStatus;
+use tonic_health::pb::health_client::HealthClient;
+use tonic_health::pb::HealthCheckRequest;
+
+pub struct PostgresCheck {
+    pool: Pool<Postgres>,
+}
+
+impl PostgresCheck {
+    pub fn new(pool: Pool<Postgres>) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl HealthCheck for PostgresCheck {
+    fn name(&self) -> &str {
+        "postgres"
+    }
+
+    async fn check(&self) -> SystemResult<()> {
+        sqlx::query("SELECT 1").execute(&self.pool).await?;
+        Ok(())
+    }
+}
+
+pub struct RedisCheck {
+    pool: Arc<deadpool_redis::Pool>,
+}
+
+impl RedisCheck {
+    pub fn new(pool: Arc<deadpool_redis::Pool>) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl HealthCheck for RedisCheck {
+    fn name(&self) -> &str {
+        "redis"
+    }
+
+    async fn check(&self) -> SystemResult<()> {
+        let mut conn = self.pool.get().await.map_err(|e| SystemError::RedisError(e.to_string()))?;
+        redis::cmd("PING")
+            .query_async::<String>(&mut conn)
+            .await
+            .map_err(|e| SystemError::RedisError(e.to_string()))?;
+        Ok(())
+    }
+}
+
+// Reports the broker connection as seen by the supervisor; it does not open
+// a channel of its own
+pub struct RabbitMqCheck {
+    broker: MessageBroker,
+}
+
+impl RabbitMqCheck {
+    pub fn new(broker: MessageBroker) -> Self {
+        Self { broker }
+    }
+}
+
+#[async_trait]
+impl HealthCheck for RabbitMqCheck {
+    fn name(&self) -> &str {
+        "rabbitmq"
+    }
+
+    async fn check(&self) -> SystemResult<()> {
+        let health = self.broker.health().await;
+        if health.connected {
+            return Ok(());
+        }
+        Err(SystemError::MessageBrokerError(
+            health.last_error.unwrap_or_else(|| "Not connected".to_string()),
+        ))
+    }
+}
+
+// Asks a downstream service for its status over `grpc.health.v1.Health`.
+// An empty `service` checks the server as a whole.
+pub struct GrpcCheck {
+    name: String,
+    endpoint: Endpoint,
+    service: String,
+}
+
+impl GrpcCheck {
+    pub fn new(name: impl Into<String>, uri: &str) -> SystemResult<Self> {
+        let endpoint = Endpoint::from_shared(uri.to_string())
+            .map_err(|e| SystemError::ConfigurationError(format!("Invalid gRPC endpoint {}: {}", uri, e)))?;
+        Ok(Self {
+            name: name.into(),
+            endpoint,
+            service: String::new(),
+        })
+    }
+
+    pub fn for_service(mut self, service: impl Into<String>) -> Self {
+        self.service = service.into();
+        self
+    }
+}
+
+#[async_trait]
+impl HealthCheck for GrpcCheck {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn check(&self) -> SystemResult<()> {
+        let channel = self
+            .endpoint
+            .connect()
+            .await
+            .map_err(|e| SystemError::ExternalServiceError(e.to_string()))?;
+        let response = HealthClient::with_interceptor(channel, inject_trace_context)
+            .check(HealthCheckRequest { service: self.service.clone() })
+            .await
+            .map_err(|e| SystemError::ExternalServiceError(e.message().to_string()))?;
+
+        match response.into_inner().status() {
+            ServingStatus::Serving => Ok(()),
+            status => Err(SystemError::ExternalServiceError(format!("{} is {}", self.name, status.as_str_name()))),
+        }
+    }
+}
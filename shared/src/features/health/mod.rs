@@ -0,0 +1,191 @@
+pub mod checks;
+
+use crate::features::errors::SystemResult;
+use actix_web::{web, HttpResponse, Scope};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use futures::future::join_all;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::broadcast;
+use tonic_health::pb::health_server::HealthServer;
+use tonic_health::server::{HealthReporter, HealthService};
+use tonic_health::ServingStatus;
+
+pub use checks::{GrpcCheck, PostgresCheck, RabbitMqCheck, RedisCheck};
+
+const DEFAULT_CHECK_TIMEOUT: Duration = Duration::from_secs(2);
+
+// A dependency the service needs in order to serve traffic. Checks run on
+// every readiness probe, so they should be cheap (a `SELECT 1`, a `PING`).
+#[async_trait]
+pub trait HealthCheck: Send + Sync {
+    fn name(&self) -> &str;
+    async fn check(&self) -> SystemResult<()>;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ComponentStatus {
+    Up,
+    Down,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ComponentHealth {
+    pub status: ComponentStatus,
+    pub latency_ms: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HealthReport {
+    pub status: &'static str,
+    pub service: String,
+    pub timestamp: DateTime<Utc>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub checks: BTreeMap<String, ComponentHealth>,
+}
+
+impl HealthReport {
+    pub fn is_ready(&self) -> bool {
+        self.checks.values().all(|c| c.status == ComponentStatus::Up)
+    }
+}
+
+// The checks behind a service's readiness. Liveness never looks at
+// dependencies: a service that is up but cut off from Postgres should be
+// taken out of rotation, not restarted.
+//
+//     let health = HealthRegistry::new("auth-service")
+//         .with_check(PostgresCheck::new(db_pool.clone()))
+//         .with_check(RedisCheck::new(redis_client.clone()));
+pub struct HealthRegistry {
+    service: String,
+    checks: Vec<Arc<dyn HealthCheck>>,
+    timeout: Duration,
+}
+
+impl HealthRegistry {
+    pub fn new(service: impl Into<String>) -> Self {
+        Self {
+            service: service.into(),
+            checks: Vec::new(),
+            timeout: DEFAULT_CHECK_TIMEOUT,
+        }
+    }
+
+    pub fn with_check(mut self, check: impl HealthCheck + 'static) -> Self {
+        self.checks.push(Arc::new(check));
+        self
+    }
+
+    // Applies to each check separately; a check that runs over counts as down
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    pub fn liveness(&self) -> HealthReport {
+        HealthReport {
+            status: "alive",
+            service: self.service.clone(),
+            timestamp: Utc::now(),
+            checks: BTreeMap::new(),
+        }
+    }
+
+    // Runs every check concurrently
+    pub async fn readiness(&self) -> HealthReport {
+        let results = join_all(self.checks.iter().map(|check| self.run(check.as_ref()))).await;
+        let checks: BTreeMap<String, ComponentHealth> = results.into_iter().collect();
+        let ready = checks.values().all(|c| c.status == ComponentStatus::Up);
+
+        HealthReport {
+            status: if ready { "ready" } else { "not_ready" },
+            service: self.service.clone(),
+            timestamp: Utc::now(),
+            checks,
+        }
+    }
+
+    async fn run(&self, check: &dyn HealthCheck) -> (String, ComponentHealth) {
+        let start = Instant::now();
+        let error = match tokio::time::timeout(self.timeout, check.check()).await {
+            Ok(Ok(())) => None,
+            Ok(Err(e)) => Some(e.to_string()),
+            Err(_) => Some(format!("Timed out after {}ms", self.timeout.as_millis())),
+        };
+        if let Some(error) = &error {
+            log::warn!("Health check {} failed: {}", check.name(), error);
+        }
+
+        let health = ComponentHealth {
+            status: if error.is_none() { ComponentStatus::Up } else { ComponentStatus::Down },
+            latency_ms: start.elapsed().as_millis() as u64,
+            error,
+        };
+        (check.name().to_string(), health)
+    }
+
+    // The standard `grpc.health.v1.Health` service, reporting the overall
+    // ("") status from the readiness checks, refreshed every `interval`
+    pub fn grpc_service(
+        self: &Arc<Self>,
+        interval: Duration,
+        mut shutdown_rx: broadcast::Receiver<()>,
+    ) -> HealthServer<HealthService> {
+        let reporter = HealthReporter::new();
+        let service = HealthService::from_health_reporter(reporter.clone());
+        let registry = self.clone();
+
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                tokio::select! {
+                    _ = ticker.tick() => {
+                        let status = if registry.readiness().await.is_ready() {
+                            ServingStatus::Serving
+                        } else {
+                            ServingStatus::NotServing
+                        };
+                        reporter.set_service_status("", status).await;
+                    }
+                    _ = shutdown_rx.recv() => {
+                        reporter.set_service_status("", ServingStatus::NotServing).await;
+                        break;
+                    }
+                }
+            }
+        });
+
+        HealthServer::new(service)
+    }
+}
+
+// GET /health and /health/live: the process is up
+// GET /health/ready: every dependency check passed (503 otherwise)
+//
+// Expects the `HealthRegistry` as app data.
+pub fn health_routes() -> Scope {
+    web::scope("/health")
+        .route("", web::get().to(live))
+        .route("/live", web::get().to(live))
+        .route("/ready", web::get().to(ready))
+}
+
+async fn live(registry: web::Data<HealthRegistry>) -> HttpResponse {
+    HttpResponse::Ok().json(registry.liveness())
+}
+
+async fn ready(registry: web::Data<HealthRegistry>) -> HttpResponse {
+    let report = registry.readiness().await;
+    if report.is_ready() {
+        HttpResponse::Ok().json(report)
+    } else {
+        HttpResponse::ServiceUnavailable().json(report)
+    }
+}
@@ -1,6 +1,7 @@
 use chrono::Utc;
 use rand::{rng, RngCore};
 use uuid::Uuid;
+use crate::config::jwt_config::JwtConfig;
 use crate::entities::enums::UserRole;
 use crate::features::errors::SystemError;
 use crate::features::security::jwt::JwtClaims;
@@ -19,8 +20,8 @@ impl JwtHelper {
         audience: &str,
         jti: Uuid
     ) -> Result<String, SystemError> {
-        let now = Utc::now();
-        let exp = (now + chrono::Duration::seconds(expiry_seconds)).timestamp() as usize;
+        // JwtClaims::new adds the lifetime to the issue time itself
+        let exp = expiry_seconds.max(0) as usize;
 
         let claims = JwtClaims::new(
             user_id,
@@ -61,6 +62,26 @@ impl JwtHelper {
             .map_err(|e| SystemError::TokenError(e.to_string()))
     }
 
+    pub fn sign_claims(claims: &JwtClaims, secret: &str) -> Result<String, SystemError> {
+        JwtHelper::generate_jwt(claims, secret)
+    }
+
+    pub fn validate_access_token(token: &str, config: &JwtConfig) -> Result<JwtClaims, SystemError> {
+        use jsonwebtoken::{decode, DecodingKey, Validation};
+
+        let mut validation = Validation::default();
+        validation.set_issuer(&[config.issuer.as_str()]);
+        validation.set_audience(&[config.audience.as_str()]);
+
+        let token_data = decode::<JwtClaims>(token, &DecodingKey::from_secret(config.secret.as_ref()), &validation)
+            .map_err(|e| match e.kind() {
+                jsonwebtoken::errors::ErrorKind::ExpiredSignature => SystemError::TokenExpired,
+                _ => SystemError::InvalidToken,
+            })?;
+
+        Ok(token_data.claims)
+    }
+
     pub fn validate_jwt(
         token: &str,
         secret: &str,
@@ -1,4 +1,6 @@
 pub mod password_helper;
 pub mod security_question_helper;
 pub mod jwt_helper;
-pub mod otp_helper;
\ No newline at end of file
+pub mod otp_helper;
+pub mod token_helper;
+pub mod totp_helper;
@@ -0,0 +1,25 @@
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use sha2::{Digest, Sha256};
+use subtle::ConstantTimeEq;
+
+pub struct TokenHelper;
+
+impl TokenHelper {
+    /// Deterministic digest for opaque tokens that must be looked up by value
+    /// (authorization codes, OAuth refresh tokens). Passwords keep using argon2.
+    pub fn hash_token(token: &str) -> String {
+        hex::encode(Sha256::digest(token.as_bytes()))
+    }
+
+    /// Checks `token` against a digest from `hash_token` in constant time, so
+    /// response timing does not reveal how much of a guess matched.
+    pub fn verify_token(token: &str, expected_hash: &str) -> bool {
+        Self::hash_token(token).as_bytes().ct_eq(expected_hash.as_bytes()).into()
+    }
+
+    /// PKCE `S256` transform from RFC 7636: BASE64URL(SHA256(code_verifier)).
+    pub fn pkce_s256_challenge(code_verifier: &str) -> String {
+        URL_SAFE_NO_PAD.encode(Sha256::digest(code_verifier.as_bytes()))
+    }
+}
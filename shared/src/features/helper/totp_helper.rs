@@ -0,0 +1,49 @@
+use data_encoding::BASE32_NOPAD;
+use hmac::{Hmac, Mac};
+use rand::{rng, RngCore};
+use sha1::Sha1;
+
+// RFC 6238 time-based one-time passwords with the parameters every
+// authenticator app understands: SHA-1, 6 digits, 30 second steps.
+pub struct TotpHelper;
+
+const STEP_SECONDS: i64 = 30;
+const DIGITS: u32 = 6;
+
+impl TotpHelper {
+    pub fn generate_secret() -> String {
+        let mut bytes = [0u8; 20];
+        rng().fill_bytes(&mut bytes);
+        BASE32_NOPAD.encode(&bytes)
+    }
+
+    pub fn provisioning_uri(issuer: &str, account: &str, secret: &str) -> String {
+        format!(
+            "otpauth://totp/{}:{}?secret={}&issuer={}&algorithm=SHA1&digits={}&period={}",
+            issuer, account, secret, issuer, DIGITS, STEP_SECONDS
+        )
+    }
+
+    pub fn time_step(unix_seconds: i64) -> i64 {
+        unix_seconds / STEP_SECONDS
+    }
+
+    pub fn code_at(secret: &str, step: i64) -> Option<String> {
+        let key = BASE32_NOPAD.decode(secret.trim_end_matches('=').as_bytes()).ok()?;
+        let mut mac = Hmac::<Sha1>::new_from_slice(&key).ok()?;
+        mac.update(&step.to_be_bytes());
+        let hash = mac.finalize().into_bytes();
+
+        let offset = (hash[hash.len() - 1] & 0x0f) as usize;
+        let binary = u32::from_be_bytes([hash[offset], hash[offset + 1], hash[offset + 2], hash[offset + 3]]) & 0x7fff_ffff;
+
+        Some(format!("{:0width$}", binary % 10u32.pow(DIGITS), width = DIGITS as usize))
+    }
+
+    // Accepts the current step and one either side for clock drift. Returns the
+    // matching step so callers can reject replays of the same code.
+    pub fn verify(secret: &str, code: &str, unix_seconds: i64) -> Option<i64> {
+        let current = Self::time_step(unix_seconds);
+        (current - 1..=current + 1).find(|step| Self::code_at(secret, *step).is_some_and(|c| c == code.trim()))
+    }
+}
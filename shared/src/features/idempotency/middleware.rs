@@ -0,0 +1,263 @@
+use super::{IdempotencyState, IdempotencyStore, StoredResponse};
+use crate::features::errors::SystemError;
+use crate::features::security::jwt::JwtClaims;
+use actix_web::body::{to_bytes, BoxBody, MessageBody};
+use actix_web::dev::{forward_ready, Payload, Service, ServiceRequest, ServiceResponse, Transform};
+use actix_web::http::header::{self, HeaderName, HeaderValue};
+use actix_web::http::{Method, StatusCode};
+use actix_web::{web, Error, HttpMessage, HttpResponse, ResponseError};
+use futures::future::LocalBoxFuture;
+use sha2::{Digest, Sha256};
+use std::future::{ready, Ready};
+use std::rc::Rc;
+use std::sync::Arc;
+use std::time::Duration;
+
+pub const IDEMPOTENCY_KEY_HEADER: &str = "Idempotency-Key";
+// Set on responses replayed from the store
+pub const IDEMPOTENCY_REPLAYED_HEADER: &str = "Idempotency-Replayed";
+
+const MAX_KEY_LENGTH: usize = 128;
+// How long a retry waits out a request that may have crashed
+const DEFAULT_LOCK_TTL: Duration = Duration::from_secs(60);
+const DEFAULT_RETENTION: Duration = Duration::from_secs(24 * 60 * 60);
+
+// Makes POST and PATCH requests that carry an `Idempotency-Key` header safe
+// to retry. The first request runs and its response is stored; a retry with
+// the same key and the same method, path and body gets that response back
+// (with `Idempotency-Replayed: true`) without running the handler again.
+//
+// - same key, different request: 409 `idempotency_key_reused`
+// - same key while the first request is still running: 409
+//   `request_in_progress`, with Retry-After
+// - 5xx responses are not stored, so the request can be retried for real
+//
+// Keys are scoped to the caller, so register it inside `AuthMiddleware`
+// (i.e. `.wrap()` it first). If the store is unreachable requests go
+// through unprotected rather than failing. Stored bodies are kept in clear,
+// so routes that hand out credentials belong in `except_for`.
+pub struct Idempotency {
+    store: Arc<dyn IdempotencyStore>,
+    retention: Duration,
+    lock_ttl: Duration,
+    methods: Rc<Vec<Method>>,
+    excluded_paths: Rc<Vec<String>>,
+}
+
+impl Idempotency {
+    pub fn new(store: Arc<dyn IdempotencyStore>) -> Self {
+        Self {
+            store,
+            retention: DEFAULT_RETENTION,
+            lock_ttl: DEFAULT_LOCK_TTL,
+            methods: Rc::new(vec![Method::POST, Method::PATCH]),
+            excluded_paths: Rc::new(Vec::new()),
+        }
+    }
+
+    // Path prefixes handled as if no key was sent, so their responses (tokens,
+    // secrets) are never stored
+    pub fn except_for(mut self, path_prefixes: &[&str]) -> Self {
+        Rc::make_mut(&mut self.excluded_paths).extend(path_prefixes.iter().map(|p| p.to_string()));
+        self
+    }
+
+    // How long a stored response is replayed for
+    pub fn with_retention(mut self, retention: Duration) -> Self {
+        self.retention = retention;
+        self
+    }
+
+    // Should exceed the slowest handler it guards
+    pub fn with_lock_ttl(mut self, lock_ttl: Duration) -> Self {
+        self.lock_ttl = lock_ttl;
+        self
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for Idempotency
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<BoxBody>;
+    type Error = Error;
+    type Transform = IdempotencyMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(IdempotencyMiddleware {
+            service: Rc::new(service),
+            store: self.store.clone(),
+            retention: self.retention,
+            lock_ttl: self.lock_ttl,
+            methods: self.methods.clone(),
+            excluded_paths: self.excluded_paths.clone(),
+        }))
+    }
+}
+
+pub struct IdempotencyMiddleware<S> {
+    service: Rc<S>,
+    store: Arc<dyn IdempotencyStore>,
+    retention: Duration,
+    lock_ttl: Duration,
+    methods: Rc<Vec<Method>>,
+    excluded_paths: Rc<Vec<String>>,
+}
+
+impl<S, B> Service<ServiceRequest> for IdempotencyMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<BoxBody>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, mut req: ServiceRequest) -> Self::Future {
+        let service = self.service.clone();
+        let excluded = self.excluded_paths.iter().any(|prefix| req.path().starts_with(prefix.as_str()));
+        let key = match idempotency_key(&req) {
+            Some(key) if self.methods.contains(req.method()) && !excluded => key,
+            _ => return Box::pin(async move { service.call(req).await.map(|res| res.map_into_boxed_body()) }),
+        };
+        let (store, retention, lock_ttl) = (self.store.clone(), self.retention, self.lock_ttl);
+
+        Box::pin(async move {
+            let key = match key {
+                Ok(key) => key,
+                Err(err) => return Ok(req.into_response(err.error_response())),
+            };
+            let scope = match req.extensions().get::<JwtClaims>() {
+                Some(claims) => claims.sub.to_string(),
+                None => "anonymous".to_string(),
+            };
+            let key = format!("{}:{}", scope, key);
+
+            // Read the body to fingerprint it, then put it back for the handler
+            let body = req.extract::<web::Bytes>().await?;
+            let fingerprint = fingerprint(&req, &body);
+            req.set_payload(Payload::from(body));
+
+            let state = match store.begin(&key, &fingerprint, lock_ttl).await {
+                Ok(state) => state,
+                Err(e) => {
+                    log::error!("Idempotency store unavailable, handling request without it: {}", e);
+                    return service.call(req).await.map(|res| res.map_into_boxed_body());
+                }
+            };
+
+            match state {
+                IdempotencyState::Started => {}
+                IdempotencyState::InProgress { fingerprint: stored } => {
+                    let err = if stored == fingerprint {
+                        SystemError::RequestInProgress
+                    } else {
+                        SystemError::IdempotencyKeyReused
+                    };
+                    let mut response = err.error_response();
+                    if stored == fingerprint {
+                        response.headers_mut().insert(header::RETRY_AFTER, HeaderValue::from_static("1"));
+                    }
+                    return Ok(req.into_response(response));
+                }
+                IdempotencyState::Completed(stored) if stored.fingerprint != fingerprint => {
+                    return Ok(req.into_response(SystemError::IdempotencyKeyReused.error_response()));
+                }
+                IdempotencyState::Completed(stored) => {
+                    log::debug!("Replaying stored response for idempotency key {}", key);
+                    return Ok(req.into_response(replay(&stored)));
+                }
+            }
+
+            let res = match service.call(req).await {
+                Ok(res) => res,
+                Err(e) => {
+                    abandon(store.as_ref(), &key).await;
+                    return Err(e);
+                }
+            };
+            if res.status().is_server_error() {
+                abandon(store.as_ref(), &key).await;
+                return Ok(res.map_into_boxed_body());
+            }
+
+            let (req, res) = res.into_parts();
+            let (res, body) = res.into_parts();
+            let body = match to_bytes(body).await {
+                Ok(body) => body,
+                Err(e) => {
+                    abandon(store.as_ref(), &key).await;
+                    let e: Box<dyn std::error::Error> = e.into();
+                    let err = SystemError::InternalError(format!("Failed to read response body: {}", e));
+                    return Ok(ServiceResponse::new(req, err.error_response()));
+                }
+            };
+
+            let stored = StoredResponse::new(&fingerprint, res.status().as_u16(), stored_headers(&res), &body);
+            if let Err(e) = store.complete(&key, &stored, retention).await {
+                log::warn!("Failed to store response for idempotency key {}: {}", key, e);
+            }
+            Ok(ServiceResponse::new(req, res.set_body(body).map_into_boxed_body()))
+        })
+    }
+}
+
+// None without the header; an error for a malformed one
+fn idempotency_key(req: &ServiceRequest) -> Option<Result<String, SystemError>> {
+    let value = req.headers().get(IDEMPOTENCY_KEY_HEADER)?;
+    let key = value.to_str().ok().map(str::trim).unwrap_or_default();
+    if key.is_empty() || key.len() > MAX_KEY_LENGTH || !key.bytes().all(|b| b.is_ascii_graphic()) {
+        return Some(Err(SystemError::ValidationError(format!(
+            "{} must be 1 to {} printable ASCII characters",
+            IDEMPOTENCY_KEY_HEADER, MAX_KEY_LENGTH
+        ))));
+    }
+    Some(Ok(key.to_string()))
+}
+
+fn fingerprint(req: &ServiceRequest, body: &[u8]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(req.method().as_str().as_bytes());
+    hasher.update(b"\n");
+    hasher.update(req.uri().path_and_query().map(|p| p.as_str()).unwrap_or("/").as_bytes());
+    hasher.update(b"\n");
+    hasher.update(body);
+    hex::encode(hasher.finalize())
+}
+
+// Framing headers are recomputed for the replayed body
+fn stored_headers(res: &HttpResponse<impl MessageBody>) -> Vec<(String, String)> {
+    res.headers()
+        .iter()
+        .filter(|(name, _)| {
+            !matches!(*name, &header::CONTENT_LENGTH | &header::TRANSFER_ENCODING | &header::CONNECTION)
+        })
+        .filter_map(|(name, value)| Some((name.to_string(), value.to_str().ok()?.to_string())))
+        .collect()
+}
+
+fn replay(stored: &StoredResponse) -> HttpResponse {
+    let status = StatusCode::from_u16(stored.status).unwrap_or(StatusCode::OK);
+    let mut response = HttpResponse::build(status);
+    for (name, value) in &stored.headers {
+        if let (Ok(name), Ok(value)) = (HeaderName::try_from(name.as_str()), HeaderValue::from_str(value)) {
+            response.append_header((name, value));
+        }
+    }
+    response
+        .insert_header((IDEMPOTENCY_REPLAYED_HEADER, "true"))
+        .body(stored.body_bytes())
+}
+
+async fn abandon(store: &dyn IdempotencyStore, key: &str) {
+    if let Err(e) = store.abandon(key).await {
+        log::warn!("Failed to release idempotency key {}: {}", key, e);
+    }
+}
@@ -0,0 +1,60 @@
+mod middleware;
+mod store;
+
+pub use middleware::{Idempotency, IdempotencyMiddleware, IDEMPOTENCY_KEY_HEADER, IDEMPOTENCY_REPLAYED_HEADER};
+pub use store::{PgIdempotencyStore, RedisIdempotencyStore};
+
+use crate::features::errors::SystemResult;
+use async_trait::async_trait;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+pub const IDEMPOTENCY_SCHEMA_SQL: &str = include_str!("schema.sql");
+
+// Where `Idempotency` keeps the first response for each key. `begin` must
+// be atomic: of two requests racing on one key exactly one gets `Started`.
+#[async_trait]
+pub trait IdempotencyStore: Send + Sync {
+    // Claims `key` for a request with `fingerprint`. The claim lapses after
+    // `lock_ttl` so a crashed request does not block its retries forever.
+    async fn begin(&self, key: &str, fingerprint: &str, lock_ttl: Duration) -> SystemResult<IdempotencyState>;
+
+    // Stores the response retries get for the next `ttl`
+    async fn complete(&self, key: &str, response: &StoredResponse, ttl: Duration) -> SystemResult<()>;
+
+    // Drops the claim so the request can be retried (on 5xx and the like)
+    async fn abandon(&self, key: &str) -> SystemResult<()>;
+}
+
+#[derive(Debug, Clone)]
+pub enum IdempotencyState {
+    Started,
+    InProgress { fingerprint: String },
+    Completed(StoredResponse),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StoredResponse {
+    pub fingerprint: String,
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    // Base64, so any body survives the JSON round trip
+    pub body: String,
+}
+
+impl StoredResponse {
+    pub fn new(fingerprint: &str, status: u16, headers: Vec<(String, String)>, body: &[u8]) -> Self {
+        Self {
+            fingerprint: fingerprint.to_string(),
+            status,
+            headers,
+            body: STANDARD.encode(body),
+        }
+    }
+
+    pub fn body_bytes(&self) -> Vec<u8> {
+        STANDARD.decode(&self.body).unwrap_or_default()
+    }
+}
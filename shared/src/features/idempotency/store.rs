@@ -0,0 +1,172 @@
+use super::{IdempotencyState, IdempotencyStore, StoredResponse};
+use crate::features::errors::{SystemError, SystemResult};
+use crate::utils::caching::{CacheKeys, CacheService};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use sqlx::{Pool, Postgres, Row};
+use std::time::Duration;
+
+// One Redis key per idempotency key. The claim is a SET NX with the lock
+// TTL; completing overwrites it with the response and the retention TTL.
+#[derive(Clone)]
+pub struct RedisIdempotencyStore {
+    cache: CacheService,
+    keys: CacheKeys,
+}
+
+#[derive(Serialize, Deserialize)]
+struct RedisRecord {
+    fingerprint: String,
+    response: Option<StoredResponse>,
+}
+
+impl RedisIdempotencyStore {
+    pub fn new(cache: CacheService, keys: CacheKeys) -> Self {
+        Self { cache, keys }
+    }
+
+    fn key(&self, key: &str) -> String {
+        self.keys.key("idempotency", key)
+    }
+}
+
+#[async_trait]
+impl IdempotencyStore for RedisIdempotencyStore {
+    async fn begin(&self, key: &str, fingerprint: &str, lock_ttl: Duration) -> SystemResult<IdempotencyState> {
+        let redis_key = self.key(key);
+        let claim = encode(&RedisRecord {
+            fingerprint: fingerprint.to_string(),
+            response: None,
+        })?;
+
+        // A second pass covers the record expiring between SET NX and GET
+        for _ in 0..2 {
+            if self.cache.set_if_absent(&redis_key, claim.as_str(), lock_ttl.as_secs().max(1)).await? {
+                return Ok(IdempotencyState::Started);
+            }
+            if let Some(raw) = self.cache.get::<String>(&redis_key).await? {
+                let record: RedisRecord = serde_json::from_str(&raw)
+                    .map_err(|e| SystemError::DeserializationError(format!("Invalid idempotency record: {}", e)))?;
+                return Ok(match record.response {
+                    Some(response) => IdempotencyState::Completed(response),
+                    None => IdempotencyState::InProgress { fingerprint: record.fingerprint },
+                });
+            }
+        }
+        Ok(IdempotencyState::InProgress { fingerprint: fingerprint.to_string() })
+    }
+
+    async fn complete(&self, key: &str, response: &StoredResponse, ttl: Duration) -> SystemResult<()> {
+        let record = encode(&RedisRecord {
+            fingerprint: response.fingerprint.clone(),
+            response: Some(response.clone()),
+        })?;
+        self.cache.set(&self.key(key), record, Some(ttl.as_secs().max(1))).await
+    }
+
+    async fn abandon(&self, key: &str) -> SystemResult<()> {
+        self.cache.delete(&self.key(key)).await
+    }
+}
+
+fn encode(record: &RedisRecord) -> SystemResult<String> {
+    serde_json::to_string(record).map_err(|e| SystemError::SerializationError(e.to_string()))
+}
+
+// The `idempotency_keys` table (`IDEMPOTENCY_SCHEMA_SQL`). A claim whose
+// lock has lapsed, or a record past its retention, is taken over in place.
+#[derive(Clone)]
+pub struct PgIdempotencyStore {
+    pool: Pool<Postgres>,
+}
+
+impl PgIdempotencyStore {
+    pub fn new(pool: Pool<Postgres>) -> Self {
+        Self { pool }
+    }
+
+    // Deletes records past their retention; run it now and then
+    #[tracing::instrument(name = "PgIdempotencyStore::purge_expired", skip_all, fields(db.system = "postgresql"))]
+    pub async fn purge_expired(&self) -> SystemResult<u64> {
+        let result = sqlx::query("DELETE FROM idempotency_keys WHERE expires_at < NOW()")
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
+
+#[async_trait]
+impl IdempotencyStore for PgIdempotencyStore {
+    #[tracing::instrument(name = "PgIdempotencyStore::begin", skip_all, fields(db.system = "postgresql"))]
+    async fn begin(&self, key: &str, fingerprint: &str, lock_ttl: Duration) -> SystemResult<IdempotencyState> {
+        let claimed = sqlx::query(
+            r#"
+            INSERT INTO idempotency_keys (key, fingerprint, locked_until, expires_at)
+            VALUES ($1, $2, NOW() + make_interval(secs => $3), NOW() + make_interval(secs => $3))
+            ON CONFLICT (key) DO UPDATE
+            SET fingerprint = EXCLUDED.fingerprint,
+                response = NULL,
+                locked_until = EXCLUDED.locked_until,
+                expires_at = EXCLUDED.expires_at,
+                created_at = NOW()
+            WHERE idempotency_keys.expires_at < NOW()
+               OR (idempotency_keys.response IS NULL AND idempotency_keys.locked_until < NOW())
+            RETURNING key
+            "#,
+        )
+        .bind(key)
+        .bind(fingerprint)
+        .bind(lock_ttl.as_secs_f64())
+        .fetch_optional(&self.pool)
+        .await?;
+        if claimed.is_some() {
+            return Ok(IdempotencyState::Started);
+        }
+
+        let row = sqlx::query("SELECT fingerprint, response::text AS response FROM idempotency_keys WHERE key = $1")
+            .bind(key)
+            .fetch_optional(&self.pool)
+            .await?;
+        let Some(row) = row else {
+            // Abandoned between the two statements
+            return Ok(IdempotencyState::InProgress { fingerprint: fingerprint.to_string() });
+        };
+
+        let response: Option<String> = row.try_get("response")?;
+        match response {
+            Some(response) => {
+                let response = serde_json::from_str(&response)
+                    .map_err(|e| SystemError::DeserializationError(format!("Invalid idempotency record: {}", e)))?;
+                Ok(IdempotencyState::Completed(response))
+            }
+            None => Ok(IdempotencyState::InProgress { fingerprint: row.try_get("fingerprint")? }),
+        }
+    }
+
+    #[tracing::instrument(name = "PgIdempotencyStore::complete", skip_all, fields(db.system = "postgresql"))]
+    async fn complete(&self, key: &str, response: &StoredResponse, ttl: Duration) -> SystemResult<()> {
+        let response = serde_json::to_string(response).map_err(|e| SystemError::SerializationError(e.to_string()))?;
+        sqlx::query(
+            r#"
+            UPDATE idempotency_keys
+            SET response = $2::jsonb, locked_until = NOW(), expires_at = NOW() + make_interval(secs => $3)
+            WHERE key = $1
+            "#,
+        )
+        .bind(key)
+        .bind(response)
+        .bind(ttl.as_secs_f64())
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    #[tracing::instrument(name = "PgIdempotencyStore::abandon", skip_all, fields(db.system = "postgresql"))]
+    async fn abandon(&self, key: &str) -> SystemResult<()> {
+        sqlx::query("DELETE FROM idempotency_keys WHERE key = $1 AND response IS NULL")
+            .bind(key)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+}
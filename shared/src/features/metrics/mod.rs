@@ -0,0 +1,188 @@
+pub mod middleware;
+
+use crate::utils::messaging::outbox::OutboxMetrics;
+use actix_web::{web, HttpResponse, Scope};
+use prometheus::{
+    register_histogram_vec, register_int_counter_vec, register_int_gauge, register_int_gauge_vec, Encoder,
+    HistogramVec, IntCounterVec, IntGauge, IntGaugeVec, TextEncoder,
+};
+use sqlx::{Pool, Postgres};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, LazyLock, Mutex};
+
+pub use middleware::MetricsMiddleware;
+
+// Shared metrics, registered in the default Prometheus registry on first use.
+// Services register their own domain metrics the same way and they are
+// served by `metrics_routes` along with these.
+
+pub static HTTP_REQUESTS_TOTAL: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    register_int_counter_vec!(
+        "http_requests_total",
+        "HTTP requests handled, by route and status",
+        &["method", "route", "status"]
+    )
+    .expect("Failed to register http_requests_total")
+});
+
+pub static HTTP_REQUEST_DURATION_SECONDS: LazyLock<HistogramVec> = LazyLock::new(|| {
+    register_histogram_vec!(
+        "http_request_duration_seconds",
+        "HTTP request latency, by route",
+        &["method", "route"],
+        vec![0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0]
+    )
+    .expect("Failed to register http_request_duration_seconds")
+});
+
+pub static DB_POOL_CONNECTIONS: LazyLock<IntGaugeVec> = LazyLock::new(|| {
+    register_int_gauge_vec!(
+        "db_pool_connections",
+        "Postgres pool connections, by state (idle, in_use, max)",
+        &["state"]
+    )
+    .expect("Failed to register db_pool_connections")
+});
+
+pub static REDIS_POOL_CONNECTIONS: LazyLock<IntGaugeVec> = LazyLock::new(|| {
+    register_int_gauge_vec!(
+        "redis_pool_connections",
+        "Redis pool connections, by state (idle, in_use, max, waiting)",
+        &["state"]
+    )
+    .expect("Failed to register redis_pool_connections")
+});
+
+pub static MESSAGES_PUBLISHED_TOTAL: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    register_int_counter_vec!(
+        "rabbitmq_messages_published_total",
+        "Messages published to RabbitMQ, by exchange and outcome (confirmed, rejected, failed)",
+        &["exchange", "outcome"]
+    )
+    .expect("Failed to register rabbitmq_messages_published_total")
+});
+
+pub static MESSAGES_CONSUMED_TOTAL: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    register_int_counter_vec!(
+        "rabbitmq_messages_consumed_total",
+        "Messages consumed from RabbitMQ, by queue and outcome (acked, retried, dead_lettered, rejected)",
+        &["queue", "outcome"]
+    )
+    .expect("Failed to register rabbitmq_messages_consumed_total")
+});
+
+pub static QUEUE_MESSAGES: LazyLock<IntGaugeVec> = LazyLock::new(|| {
+    register_int_gauge_vec!(
+        "rabbitmq_queue_messages",
+        "Messages waiting in a queue (consumer lag)",
+        &["queue"]
+    )
+    .expect("Failed to register rabbitmq_queue_messages")
+});
+
+pub static QUEUE_CONSUMERS: LazyLock<IntGaugeVec> = LazyLock::new(|| {
+    register_int_gauge_vec!("rabbitmq_queue_consumers", "Consumers attached to a queue", &["queue"])
+        .expect("Failed to register rabbitmq_queue_consumers")
+});
+
+static OUTBOX_PENDING: LazyLock<IntGauge> = LazyLock::new(|| {
+    register_int_gauge!("outbox_pending_events", "Outbox events not yet published")
+        .expect("Failed to register outbox_pending_events")
+});
+
+static OUTBOX_DEAD_LETTERED: LazyLock<IntGauge> = LazyLock::new(|| {
+    register_int_gauge!("outbox_dead_lettered_events", "Outbox events the relay gave up on")
+        .expect("Failed to register outbox_dead_lettered_events")
+});
+
+static OUTBOX_OLDEST_PENDING_AGE: LazyLock<IntGauge> = LazyLock::new(|| {
+    register_int_gauge!(
+        "outbox_oldest_pending_age_seconds",
+        "Age of the oldest unpublished outbox event"
+    )
+    .expect("Failed to register outbox_oldest_pending_age_seconds")
+});
+
+static OUTBOX_RELAYED_TOTAL: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    register_int_counter_vec!(
+        "outbox_relayed_events_total",
+        "Outbox publish attempts, by outcome (published, failed)",
+        &["outcome"]
+    )
+    .expect("Failed to register outbox_relayed_events_total")
+});
+
+type Sampler = Box<dyn Fn() + Send + Sync>;
+
+// Gauges read from somewhere else (pools, the outbox relay) are refreshed by
+// these right before each scrape
+static SAMPLERS: LazyLock<Mutex<Vec<Sampler>>> = LazyLock::new(|| Mutex::new(Vec::new()));
+
+pub fn register_sampler(sampler: impl Fn() + Send + Sync + 'static) {
+    if let Ok(mut samplers) = SAMPLERS.lock() {
+        samplers.push(Box::new(sampler));
+    }
+}
+
+pub fn watch_db_pool(pool: Pool<Postgres>) {
+    register_sampler(move || {
+        let size = pool.size() as i64;
+        let idle = pool.num_idle() as i64;
+        DB_POOL_CONNECTIONS.with_label_values(&["idle"]).set(idle);
+        DB_POOL_CONNECTIONS.with_label_values(&["in_use"]).set(size - idle);
+        DB_POOL_CONNECTIONS
+            .with_label_values(&["max"])
+            .set(pool.options().get_max_connections() as i64);
+    });
+}
+
+pub fn watch_redis_pool(pool: Arc<deadpool_redis::Pool>) {
+    register_sampler(move || {
+        let status = pool.status();
+        REDIS_POOL_CONNECTIONS.with_label_values(&["idle"]).set(status.available as i64);
+        REDIS_POOL_CONNECTIONS
+            .with_label_values(&["in_use"])
+            .set(status.size.saturating_sub(status.available) as i64);
+        REDIS_POOL_CONNECTIONS.with_label_values(&["max"]).set(status.max_size as i64);
+        REDIS_POOL_CONNECTIONS.with_label_values(&["waiting"]).set(status.waiting as i64);
+    });
+}
+
+// Exports the relay's own counters; they only ever grow, so the difference
+// since the last scrape is added to the Prometheus counters
+pub fn watch_outbox(metrics: Arc<OutboxMetrics>) {
+    let published_seen = AtomicU64::new(0);
+    let failed_seen = AtomicU64::new(0);
+    register_sampler(move || {
+        let snapshot = metrics.snapshot();
+        OUTBOX_PENDING.set(snapshot.pending);
+        OUTBOX_DEAD_LETTERED.set(snapshot.dead_lettered);
+        OUTBOX_OLDEST_PENDING_AGE.set(snapshot.oldest_pending_age_seconds);
+
+        let published = snapshot.published_total - published_seen.swap(snapshot.published_total, Ordering::Relaxed);
+        let failed = snapshot.failed_total - failed_seen.swap(snapshot.failed_total, Ordering::Relaxed);
+        OUTBOX_RELAYED_TOTAL.with_label_values(&["published"]).inc_by(published);
+        OUTBOX_RELAYED_TOTAL.with_label_values(&["failed"]).inc_by(failed);
+    });
+}
+
+// GET /metrics in the Prometheus text format
+pub fn metrics_routes() -> Scope {
+    web::scope("/metrics").route("", web::get().to(metrics_handler))
+}
+
+async fn metrics_handler() -> HttpResponse {
+    if let Ok(samplers) = SAMPLERS.lock() {
+        samplers.iter().for_each(|sample| sample());
+    }
+
+    let encoder = TextEncoder::new();
+    let mut buffer = Vec::new();
+    if let Err(e) = encoder.encode(&prometheus::gather(), &mut buffer) {
+        log::error!("Failed to encode metrics: {}", e);
+        return HttpResponse::InternalServerError().finish();
+    }
+    HttpResponse::Ok()
+        .content_type(encoder.format_type())
+        .body(buffer)
+}
@@ -1,3 +1,10 @@
+pub mod health;
 pub mod helper;
+pub mod idempotency;
+pub mod metrics;
+pub mod observability;
+pub mod problem;
 pub mod security;
+pub mod settings;
+pub mod validation;
 pub mod errors;
\ No newline at end of file
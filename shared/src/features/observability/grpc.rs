@@ -0,0 +1,43 @@
+use crate::features::observability::link_parent;
+use crate::features::observability::propagation::{extract, inject_current, GrpcMetadataExtractor, GrpcMetadataInjector};
+use opentelemetry::Context;
+use tonic::{Request, Status};
+
+// The caller's trace context, stored by `extract_trace_context`
+#[derive(Clone)]
+pub struct RemoteTraceContext(pub Context);
+
+// Client interceptor: sends the current trace context with every call
+//
+//     AuthClient::with_interceptor(channel, inject_trace_context)
+pub fn inject_trace_context(mut request: Request<()>) -> Result<Request<()>, Status> {
+    inject_current(&mut GrpcMetadataInjector(request.metadata_mut()));
+    Ok(request)
+}
+
+// Server interceptor: keeps the caller's trace context for `server_span`
+//
+//     AuthServer::with_interceptor(service, extract_trace_context)
+pub fn extract_trace_context(mut request: Request<()>) -> Result<Request<()>, Status> {
+    let context = extract(&GrpcMetadataExtractor(request.metadata()));
+    request.extensions_mut().insert(RemoteTraceContext(context));
+    Ok(request)
+}
+
+// A server span for one gRPC method, child of the caller's span. Handlers
+// run their body inside it with `.instrument(span)`.
+pub fn server_span<T>(request: &Request<T>, method: &str) -> tracing::Span {
+    let span = tracing::info_span!(
+        "grpc.request",
+        otel.name = %method,
+        otel.kind = "server",
+        rpc.system = "grpc",
+        rpc.method = %method,
+    );
+    let parent = match request.extensions().get::<RemoteTraceContext>() {
+        Some(remote) => remote.0.clone(),
+        None => extract(&GrpcMetadataExtractor(request.metadata())),
+    };
+    link_parent(&span, parent);
+    span
+}
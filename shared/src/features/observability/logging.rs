@@ -0,0 +1,244 @@
+use chrono::{SecondsFormat, Utc};
+use opentelemetry::trace::TraceContextExt;
+use regex::{Captures, Regex, Replacer};
+use serde_json::{Map, Value};
+use std::borrow::Cow;
+use std::fmt;
+use std::sync::LazyLock;
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Subscriber};
+use tracing_opentelemetry::OtelData;
+use tracing_subscriber::fmt::format::Writer;
+use tracing_subscriber::fmt::{FmtContext, FormatEvent, FormatFields};
+use tracing_subscriber::layer::{Context, Layer};
+use tracing_subscriber::registry::LookupSpan;
+
+// Request and user ids recorded on a span (see `TracingMiddleware` and
+// `record_user_id`); every log line inside the span carries them
+#[derive(Default)]
+struct Correlation {
+    request_id: Option<String>,
+    user_id: Option<String>,
+}
+
+impl Visit for Correlation {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        match field.name() {
+            "request_id" => self.request_id = Some(value.to_string()),
+            "user_id" => self.user_id = Some(value.to_string()),
+            _ => {}
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        match field.name() {
+            "request_id" | "user_id" => self.record_str(field, &format!("{:?}", value)),
+            _ => {}
+        }
+    }
+}
+
+// Keeps each span's correlation fields where `JsonFormat` can read them
+pub struct CorrelationLayer;
+
+impl<S> Layer<S> for CorrelationLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        let mut correlation = Correlation::default();
+        attrs.record(&mut correlation);
+        if let Some(span) = ctx.span(id) {
+            span.extensions_mut().insert(correlation);
+        }
+    }
+
+    fn on_record(&self, id: &Id, values: &Record<'_>, ctx: Context<'_, S>) {
+        if let Some(span) = ctx.span(id) {
+            if let Some(correlation) = span.extensions_mut().get_mut::<Correlation>() {
+                values.record(correlation);
+            }
+        }
+    }
+}
+
+// One JSON object per line:
+//
+//     {"timestamp":"...","level":"INFO","service":"auth-service","target":"...",
+//      "message":"...","trace_id":"...","request_id":"...","user_id":"...","fields":{...}}
+pub struct JsonFormat {
+    service: String,
+}
+
+impl JsonFormat {
+    pub fn new(service: impl Into<String>) -> Self {
+        Self { service: service.into() }
+    }
+}
+
+impl<S, N> FormatEvent<S, N> for JsonFormat
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+    N: for<'a> FormatFields<'a> + 'static,
+{
+    fn format_event(&self, ctx: &FmtContext<'_, S, N>, mut writer: Writer<'_>, event: &Event<'_>) -> fmt::Result {
+        let metadata = event.metadata();
+        let mut fields = EventFields::default();
+        event.record(&mut fields);
+
+        let mut line = Map::new();
+        line.insert("timestamp".into(), Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true).into());
+        line.insert("level".into(), metadata.level().as_str().into());
+        line.insert("service".into(), self.service.clone().into());
+        // Records forwarded from `log` name their real target in `log.target`
+        let target = fields.log_target.take().unwrap_or_else(|| metadata.target().to_string());
+        line.insert("target".into(), target.into());
+        line.insert("message".into(), fields.message.take().unwrap_or_default().into());
+
+        // Innermost span first, so the closest value wins
+        if let Some(scope) = ctx.event_scope() {
+            for span in scope {
+                let extensions = span.extensions();
+                if !line.contains_key("span") {
+                    line.insert("span".into(), span.name().into());
+                }
+                if !line.contains_key("trace_id") {
+                    if let Some(trace_id) = extensions.get::<OtelData>().and_then(trace_id) {
+                        line.insert("trace_id".into(), trace_id.into());
+                    }
+                }
+                if let Some(correlation) = extensions.get::<Correlation>() {
+                    if let (false, Some(id)) = (line.contains_key("request_id"), &correlation.request_id) {
+                        line.insert("request_id".into(), id.clone().into());
+                    }
+                    if let (false, Some(id)) = (line.contains_key("user_id"), &correlation.user_id) {
+                        line.insert("user_id".into(), id.clone().into());
+                    }
+                }
+            }
+        }
+
+        if !fields.values.is_empty() {
+            line.insert("fields".into(), Value::Object(fields.values));
+        }
+        writeln!(writer, "{}", Value::Object(line))
+    }
+}
+
+fn trace_id(otel: &OtelData) -> Option<String> {
+    let parent = otel.parent_cx.span();
+    let parent = parent.span_context();
+    if parent.is_valid() {
+        return Some(parent.trace_id().to_string());
+    }
+    otel.builder.trace_id.map(|id| id.to_string())
+}
+
+#[derive(Default)]
+struct EventFields {
+    message: Option<String>,
+    log_target: Option<String>,
+    values: Map<String, Value>,
+}
+
+impl Visit for EventFields {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.insert(field, value.into());
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.insert(field, value.into());
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.insert(field, value.into());
+    }
+
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        self.insert(field, value.into());
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.insert(field, value.into());
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.insert(field, format!("{:?}", value).into());
+    }
+}
+
+impl EventFields {
+    fn insert(&mut self, field: &Field, value: Value) {
+        match field.name() {
+            "message" => self.message = value.as_str().map(str::to_string),
+            "log.target" => self.log_target = value.as_str().map(str::to_string),
+            name if name.starts_with("log.") => {}
+            name => {
+                self.values.insert(name.to_string(), value);
+            }
+        }
+    }
+}
+
+// Wraps another event format and masks personal data and secrets in the
+// finished line, whatever field or message they ended up in
+pub struct Redacted<F>(pub F);
+
+impl<S, N, F> FormatEvent<S, N> for Redacted<F>
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+    N: for<'a> FormatFields<'a> + 'static,
+    F: FormatEvent<S, N>,
+{
+    fn format_event(&self, ctx: &FmtContext<'_, S, N>, mut writer: Writer<'_>, event: &Event<'_>) -> fmt::Result {
+        let mut line = String::new();
+        self.0.format_event(ctx, Writer::new(&mut line), event)?;
+        writer.write_str(&redact(&line))
+    }
+}
+
+static JWT: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"eyJ[A-Za-z0-9_-]+\.[A-Za-z0-9_-]+\.[A-Za-z0-9_-]*").expect("valid regex"));
+static BEARER: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)\bbearer\s+[A-Za-z0-9._~+/=-]+").expect("valid regex"));
+// API keys are `bk_<prefix>_<secret>`; the prefix names the key and is kept.
+// `_` is a word character, so `HEX_TOKEN` alone never matches the secret
+static API_KEY: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\b(bk_[0-9a-f]+_)[0-9a-f]{64}\b").expect("valid regex"));
+// Refresh tokens are 64 hex characters; trace ids are 32
+static HEX_TOKEN: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\b[0-9a-fA-F]{64}\b").expect("valid regex"));
+// Quoted values are masked up to the closing quote (argon2 hashes contain commas)
+static SECRET_VALUE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r#"(?i)\b((?:otp|otp_code|verification_code|password|new_password|password_hash|answer_hash|secret|token|access_token|refresh_token|reset_token|api_key)\\?"?\s*[:=]\s*)(?:(\\?")[^"\\]*|[^\s"\\,&}]+)"#,
+    )
+    .expect("valid regex")
+});
+static EMAIL: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\b([A-Za-z0-9])[A-Za-z0-9._%+-]*@([A-Za-z0-9.-]+\.[A-Za-z]{2,})\b").expect("valid regex"));
+// International (+2348012345678) and local (08012345678) numbers
+static PHONE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?:\+\d{8,13}|\b0\d{8,9})(\d{2})\b").expect("valid regex"));
+
+// Masks tokens, OTPs and passwords completely, and emails and phone numbers
+// down to what is needed to tell them apart (`j***@example.com`, `***78`)
+pub fn redact(line: &str) -> Cow<'_, str> {
+    let mut line = Cow::Borrowed(line);
+    replace(&mut line, &JWT, "[REDACTED_TOKEN]");
+    replace(&mut line, &BEARER, "Bearer [REDACTED_TOKEN]");
+    replace(&mut line, &API_KEY, "${1}[REDACTED_TOKEN]");
+    replace(&mut line, &HEX_TOKEN, "[REDACTED_TOKEN]");
+    replace(&mut line, &SECRET_VALUE, "${1}${2}[REDACTED]");
+    replace(&mut line, &EMAIL, |c: &Captures| format!("{}***@{}", &c[1], &c[2]));
+    replace(&mut line, &PHONE, "***${1}");
+    line
+}
+
+fn replace(line: &mut Cow<'_, str>, pattern: &Regex, replacement: impl Replacer) {
+    let replaced = match pattern.replace_all(line, replacement) {
+        Cow::Owned(replaced) => replaced,
+        Cow::Borrowed(_) => return,
+    };
+    *line = Cow::Owned(replaced);
+}
@@ -0,0 +1,129 @@
+use crate::features::observability::link_parent;
+use crate::features::observability::propagation::{extract, HttpHeaderExtractor};
+use actix_web::{
+    dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform},
+    http::header::{HeaderName, HeaderValue},
+    Error,
+};
+use futures::future::LocalBoxFuture;
+use std::future::{ready, Ready};
+use tracing::Instrument;
+use uuid::Uuid;
+
+const REQUEST_ID_HEADER: HeaderName = HeaderName::from_static("x-request-id");
+
+#[derive(Debug, Clone)]
+pub struct RequestContext {
+    pub request_id: String,
+    pub path: String,
+}
+
+tokio::task_local! {
+    static CURRENT_REQUEST: RequestContext;
+}
+
+// The request being handled, for code that has no access to it (e.g.
+// `ResponseError::error_response`). Set by `TracingMiddleware`.
+pub fn current_request() -> Option<RequestContext> {
+    CURRENT_REQUEST.try_with(RequestContext::clone).ok()
+}
+
+// Opens a server span for every request, continuing the caller's trace when
+// the request carries a `traceparent` header. Register it as the outermost
+// `wrap` so the other middleware run inside the span. The caller's
+// `X-Request-ID` is kept (or a new one generated), recorded on the span for
+// the logs and echoed in the response.
+pub struct TracingMiddleware;
+
+impl<S, B> Transform<S, ServiceRequest> for TracingMiddleware
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Transform = TracingMiddlewareService<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(TracingMiddlewareService { service }))
+    }
+}
+
+pub struct TracingMiddlewareService<S> {
+    service: S,
+}
+
+impl<S, B> Service<ServiceRequest> for TracingMiddlewareService<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        // The route pattern keeps ids out of span names
+        let route = req.match_pattern().unwrap_or_else(|| "unmatched".to_string());
+        let request_id = req
+            .headers()
+            .get(&REQUEST_ID_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .filter(|value| !value.is_empty() && value.len() <= 128)
+            .map(|value| value.to_string())
+            .unwrap_or_else(|| Uuid::new_v4().to_string());
+        let span = tracing::info_span!(
+            "http.request",
+            otel.name = %format!("{} {}", req.method(), route),
+            otel.kind = "server",
+            otel.status_code = tracing::field::Empty,
+            http.request.method = %req.method(),
+            http.route = %route,
+            url.path = %req.path(),
+            http.response.status_code = tracing::field::Empty,
+            request_id = %request_id,
+            user_id = tracing::field::Empty,
+        );
+        link_parent(&span, extract(&HttpHeaderExtractor(req.headers())));
+        let context = RequestContext {
+            request_id: request_id.clone(),
+            path: req.path().to_string(),
+        };
+
+        let fut = {
+            let _entered = span.enter();
+            CURRENT_REQUEST.sync_scope(context.clone(), || self.service.call(req))
+        };
+
+        Box::pin(CURRENT_REQUEST.scope(
+            context,
+            async move {
+                let mut result = fut.await;
+                let span = tracing::Span::current();
+                match &mut result {
+                    Ok(response) => {
+                        if let Ok(value) = HeaderValue::from_str(&request_id) {
+                            response.headers_mut().insert(REQUEST_ID_HEADER, value);
+                        }
+                        let status = response.status();
+                        span.record("http.response.status_code", status.as_u16());
+                        if status.is_server_error() {
+                            span.record("otel.status_code", "ERROR");
+                        }
+                    }
+                    Err(_) => {
+                        span.record("otel.status_code", "ERROR");
+                    }
+                }
+                result
+            }
+            .instrument(span),
+        ))
+    }
+}
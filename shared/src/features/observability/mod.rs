@@ -0,0 +1,108 @@
+pub mod grpc;
+pub mod logging;
+pub mod middleware;
+pub mod propagation;
+
+use crate::config::tracing_config::{LogFormat, TracingConfig};
+use crate::features::errors::{SystemError, SystemResult};
+use logging::{CorrelationLayer, JsonFormat, Redacted};
+use opentelemetry::trace::{TraceContextExt, TracerProvider as _};
+use opentelemetry::{global, Context};
+use opentelemetry_otlp::WithExportConfig;
+use opentelemetry_sdk::propagation::TraceContextPropagator;
+use opentelemetry_sdk::trace::{Sampler, SdkTracerProvider};
+use opentelemetry_sdk::Resource;
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::EnvFilter;
+
+pub use middleware::{current_request, RequestContext, TracingMiddleware};
+
+// Flushes buffered spans when dropped; keep it alive for the whole of `main`
+pub struct TracingGuard {
+    provider: SdkTracerProvider,
+}
+
+impl Drop for TracingGuard {
+    fn drop(&mut self) {
+        if let Err(e) = self.provider.shutdown() {
+            eprintln!("Failed to flush traces: {}", e);
+        }
+    }
+}
+
+// Installs the global `tracing` subscriber, replacing `env_logger`. `log`
+// records are forwarded to it, so existing `log::info!` calls are attached to
+// the current span. Lines are written as JSON (or plain text with
+// `LOG_FORMAT=text`) with personal data and secrets masked. Spans are exported over OTLP/gRPC (Jaeger accepts OTLP on
+// port 4317) when an endpoint is configured; without one, trace ids are still
+// generated and propagated. Must be called inside the Tokio runtime.
+pub fn init_tracing(config: &TracingConfig) -> SystemResult<TracingGuard> {
+    global::set_text_map_propagator(TraceContextPropagator::new());
+
+    let mut builder = SdkTracerProvider::builder()
+        .with_sampler(Sampler::ParentBased(Box::new(Sampler::TraceIdRatioBased(
+            config.sample_ratio,
+        ))))
+        .with_resource(Resource::builder().with_service_name(config.service_name.clone()).build());
+
+    if let Some(endpoint) = &config.otlp_endpoint {
+        let exporter = opentelemetry_otlp::SpanExporter::builder()
+            .with_tonic()
+            .with_endpoint(endpoint.clone())
+            .build()
+            .map_err(|e| SystemError::ConfigurationError(format!("Failed to create OTLP exporter: {}", e)))?;
+        builder = builder.with_batch_exporter(exporter);
+    }
+
+    let provider = builder.build();
+    let tracer = provider.tracer(config.service_name.clone());
+    global::set_tracer_provider(provider.clone());
+
+    let (json, text) = match config.log_format {
+        LogFormat::Json => (
+            Some(tracing_subscriber::fmt::layer().event_format(Redacted(JsonFormat::new(config.service_name.clone())))),
+            None,
+        ),
+        LogFormat::Text => (
+            None,
+            Some(tracing_subscriber::fmt::layer().event_format(Redacted(tracing_subscriber::fmt::format().with_ansi(false)))),
+        ),
+    };
+
+    tracing_subscriber::registry()
+        .with(EnvFilter::new(&config.log_filter))
+        .with(CorrelationLayer)
+        .with(json)
+        .with(text)
+        .with(tracing_opentelemetry::layer().with_tracer(tracer))
+        .try_init()
+        .map_err(|e| SystemError::ConfigurationError(format!("Failed to install tracing subscriber: {}", e)))?;
+
+    Ok(TracingGuard { provider })
+}
+
+// The trace id of the current span, if it belongs to a trace
+pub fn current_trace_id() -> Option<String> {
+    let context = tracing::Span::current().context();
+    let span = context.span();
+    let span_context = span.span_context();
+    span_context
+        .is_valid()
+        .then(|| span_context.trace_id().to_string())
+}
+
+// Tags the current request span with the authenticated user, so every log
+// line for the request carries `user_id`
+pub fn record_user_id(user_id: impl std::fmt::Display) {
+    tracing::Span::current().record("user_id", tracing::field::display(user_id));
+}
+
+// Makes `parent` (extracted from an incoming request or message) the parent
+// of `span`; a context without a remote span is ignored
+pub fn link_parent(span: &tracing::Span, parent: Context) {
+    if parent.span().span_context().is_valid() {
+        span.set_parent(parent);
+    }
+}
@@ -0,0 +1,137 @@
+use crate::features::errors::{FieldError, SystemError};
+use crate::features::observability::{current_request, current_trace_id};
+use actix_web::error::{JsonPayloadError, PathError, QueryPayloadError};
+use actix_web::http::header::ContentType;
+use actix_web::http::StatusCode;
+use actix_web::{web, HttpRequest, HttpResponse, ResponseError};
+use serde::{Deserialize, Serialize};
+use tonic::codegen::Bytes;
+use tonic::metadata::MetadataValue;
+use tonic::{Code, Status};
+
+pub const PROBLEM_JSON: &str = "application/problem+json";
+
+// gRPC metadata carrying `SystemError::code`
+pub const ERROR_CODE_METADATA: &str = "x-error-code";
+
+// The body of every error response (RFC 7807):
+//
+//     {"type": "urn:borough:problem:validation_failed", "title": "Bad Request", "status": 400,
+//      "detail": "The request has invalid fields", "instance": "/api/v1/auth/otp/send",
+//      "code": "validation_failed", "errors": [{"field": "identifier", ...}],
+//      "request_id": "...", "trace_id": "..."}
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProblemDetails {
+    #[serde(rename = "type")]
+    pub problem_type: String,
+    pub title: String,
+    pub status: u16,
+    pub detail: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub instance: Option<String>,
+    pub code: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<FieldError>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trace_id: Option<String>,
+}
+
+impl ProblemDetails {
+    // Picks up the request id and path from `TracingMiddleware` and the
+    // trace id from the current span, when there are any
+    pub fn from_error(err: &SystemError) -> Self {
+        let status = err.http_status();
+        let request = current_request();
+
+        Self {
+            problem_type: format!("urn:borough:problem:{}", err.code()),
+            title: status.canonical_reason().unwrap_or("Error").to_string(),
+            status: status.as_u16(),
+            detail: err.public_message(),
+            instance: request.as_ref().map(|r| r.path.clone()),
+            code: err.code().to_string(),
+            errors: err.field_errors().to_vec(),
+            request_id: request.map(|r| r.request_id),
+            trace_id: current_trace_id(),
+        }
+    }
+}
+
+impl ResponseError for SystemError {
+    fn status_code(&self) -> StatusCode {
+        self.http_status()
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        let status = self.http_status();
+        if status.is_server_error() {
+            log::error!("Request failed with {} ({}): {}", status.as_u16(), self.code(), self);
+        }
+        HttpResponse::build(status)
+            .insert_header(ContentType(PROBLEM_JSON.parse().expect("valid mime type")))
+            .json(ProblemDetails::from_error(self))
+    }
+}
+
+// The same error over gRPC. The message is the public detail; the full
+// problem document travels in the status details and the code in the
+// `x-error-code` metadata.
+impl From<SystemError> for Status {
+    fn from(err: SystemError) -> Self {
+        let code = match err.http_status() {
+            StatusCode::BAD_REQUEST => Code::InvalidArgument,
+            StatusCode::UNAUTHORIZED => Code::Unauthenticated,
+            StatusCode::FORBIDDEN => Code::PermissionDenied,
+            StatusCode::NOT_FOUND => Code::NotFound,
+            StatusCode::CONFLICT => Code::AlreadyExists,
+            StatusCode::TOO_MANY_REQUESTS => Code::ResourceExhausted,
+            StatusCode::REQUEST_TIMEOUT => Code::DeadlineExceeded,
+            StatusCode::BAD_GATEWAY => Code::Unavailable,
+            _ => Code::Internal,
+        };
+        if code == Code::Internal {
+            log::error!("gRPC call failed ({}): {}", err.code(), err);
+        }
+
+        let problem = ProblemDetails::from_error(&err);
+        let details = serde_json::to_vec(&problem).unwrap_or_default();
+        let mut status = Status::with_details(code, problem.detail, Bytes::from(details));
+        status
+            .metadata_mut()
+            .insert(ERROR_CODE_METADATA, MetadataValue::from_static(err.code()));
+        status
+    }
+}
+
+// Makes extractor failures and unknown routes answer with problem details
+// too. Register once per app:
+//
+//     App::new().configure(problem_details)
+pub fn problem_details(cfg: &mut web::ServiceConfig) {
+    cfg.app_data(web::JsonConfig::default().error_handler(json_error))
+        .app_data(web::QueryConfig::default().error_handler(query_error))
+        .app_data(web::PathConfig::default().error_handler(path_error))
+        .default_service(web::to(route_not_found));
+}
+
+fn json_error(err: JsonPayloadError, _req: &HttpRequest) -> actix_web::Error {
+    let err = match err {
+        JsonPayloadError::Deserialize(e) if e.is_data() => SystemError::ValidationError(e.to_string()),
+        e => SystemError::ParseError(e.to_string()),
+    };
+    err.into()
+}
+
+fn query_error(err: QueryPayloadError, _req: &HttpRequest) -> actix_web::Error {
+    SystemError::ValidationError(format!("Invalid query string: {}", err)).into()
+}
+
+fn path_error(err: PathError, _req: &HttpRequest) -> actix_web::Error {
+    SystemError::ValidationError(format!("Invalid path: {}", err)).into()
+}
+
+async fn route_not_found(req: HttpRequest) -> Result<HttpResponse, SystemError> {
+    Err(SystemError::NotFound(format!("No route for {} {}", req.method(), req.path())))
+}
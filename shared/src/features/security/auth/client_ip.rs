@@ -0,0 +1,82 @@
+use actix_web::HttpRequest;
+use std::net::IpAddr;
+
+// Proxies (Traefik) whose `X-Forwarded-For` entries are believed. Anyone can
+// send that header, so it is only read when the connection itself comes from
+// one of these; with none configured the peer address is the client.
+#[derive(Debug, Clone, Default)]
+pub struct TrustedProxies(Vec<(IpAddr, u8)>);
+
+impl TrustedProxies {
+    // Comma-separated addresses or CIDR ranges, e.g. "10.0.0.0/8, 127.0.0.1"
+    pub fn parse(list: &str) -> Result<Self, String> {
+        list.split(',')
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+            .map(|entry| parse_network(entry).ok_or_else(|| format!("invalid address or CIDR range: {}", entry)))
+            .collect::<Result<_, _>>()
+            .map(Self)
+    }
+
+    pub fn contains(&self, ip: IpAddr) -> bool {
+        self.0.iter().any(|&(network, prefix_len)| network_contains(network, prefix_len, ip))
+    }
+
+    // Walks `X-Forwarded-For` back from the nearest hop, skipping trusted
+    // proxies; the first untrusted address is the client. Entries further
+    // left were written by the client and are never used.
+    pub fn client_ip(&self, req: &HttpRequest) -> Option<IpAddr> {
+        let peer = req.peer_addr()?.ip();
+        if !self.contains(peer) {
+            return Some(peer);
+        }
+
+        let forwarded: Vec<IpAddr> = req
+            .headers()
+            .get_all("X-Forwarded-For")
+            .filter_map(|h| h.to_str().ok())
+            .flat_map(|h| h.split(','))
+            .map(|hop| hop.trim().parse::<IpAddr>().ok())
+            .collect::<Option<_>>()?;
+
+        let mut client = peer;
+        for hop in forwarded.into_iter().rev() {
+            client = hop;
+            if !self.contains(hop) {
+                break;
+            }
+        }
+        Some(client)
+    }
+}
+
+fn parse_network(entry: &str) -> Option<(IpAddr, u8)> {
+    let (address, prefix_len) = match entry.split_once('/') {
+        Some((address, prefix_len)) => (address, Some(prefix_len.parse::<u8>().ok()?)),
+        None => (entry, None),
+    };
+
+    let address = address.trim().parse::<IpAddr>().ok()?;
+    let max_len = if address.is_ipv4() { 32 } else { 128 };
+    let prefix_len = prefix_len.unwrap_or(max_len);
+
+    if prefix_len > max_len {
+        return None;
+    }
+
+    Some((address, prefix_len))
+}
+
+fn network_contains(network: IpAddr, prefix_len: u8, ip: IpAddr) -> bool {
+    match (network, ip) {
+        (IpAddr::V4(network), IpAddr::V4(ip)) => {
+            let mask = u32::MAX.checked_shl(32 - prefix_len as u32).unwrap_or(0);
+            u32::from(network) & mask == u32::from(ip) & mask
+        }
+        (IpAddr::V6(network), IpAddr::V6(ip)) => {
+            let mask = u128::MAX.checked_shl(128 - prefix_len as u32).unwrap_or(0);
+            u128::from(network) & mask == u128::from(ip) & mask
+        }
+        _ => false,
+    }
+}
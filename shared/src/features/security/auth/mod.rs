@@ -0,0 +1,400 @@
+mod client_ip;
+
+pub use client_ip::TrustedProxies;
+
+use actix_web::{
+    body::BoxBody,
+    dev::{forward_ready, Payload, Service, ServiceRequest, ServiceResponse, Transform},
+    http::header::AUTHORIZATION,
+    Error, FromRequest, HttpMessage, HttpRequest,
+};
+use async_trait::async_trait;
+use futures::future::LocalBoxFuture;
+use std::{
+    future::{ready, Ready},
+    rc::Rc,
+    sync::Arc,
+};
+use uuid::Uuid;
+use crate::config::jwt_config::JwtConfig;
+use crate::config::step_up_config::StepUpConfig;
+use crate::entities::enums::UserRole;
+use crate::features::errors::{map_auth_error_to_response, SystemError, SystemResult};
+use crate::features::helper::jwt_helper::JwtHelper;
+use crate::features::observability::record_user_id;
+use crate::features::security::jwt::JwtClaims;
+
+pub const API_KEY_HEADER: &str = "X-Api-Key";
+
+// Resolves an `X-Api-Key` value into the claims the caller acts with. Each
+// service plugs in its own implementation (a repository lookup in auth-service).
+#[async_trait]
+pub trait ApiKeyValidator: Send + Sync {
+    async fn validate(&self, api_key: &str, ip_address: Option<&str>) -> SystemResult<JwtClaims>;
+}
+
+// Answers whether a bearer token was revoked before it expired (auth-service
+// keeps a blacklist in Redis). Checked on every bearer request.
+#[async_trait]
+pub trait RevokedTokens: Send + Sync {
+    async fn is_revoked(&self, token: &str) -> SystemResult<bool>;
+}
+
+// Records requests made with an impersonation token. Called after the request
+// has been handled so the audit entry carries the response status.
+#[async_trait]
+pub trait ImpersonationAuditor: Send + Sync {
+    async fn record(&self, claims: &JwtClaims, request: &ImpersonatedRequest);
+}
+
+#[derive(Debug, Clone)]
+pub struct ImpersonatedRequest {
+    pub method: String,
+    pub path: String,
+    pub status: u16,
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+}
+
+// Money never moves on someone else's behalf, whichever service serves the
+// route: impersonation tokens are refused here even if a service forgets to
+// block them itself
+pub const IMPERSONATION_PAYMENT_PATHS: &[&str] = &["/api/v1/payments", "/api/v1/transactions", "/api/v1/refunds"];
+
+// Validates `Authorization: Bearer` access tokens, or `X-Api-Key` when an
+// `ApiKeyValidator` is configured, and stores the claims in the request
+// extensions. Requests without credentials pass through untouched so public
+// routes keep working; handlers that need a caller use `AuthenticatedUser`.
+pub struct AuthMiddleware {
+    config: Rc<JwtConfig>,
+    api_key_validator: Option<Arc<dyn ApiKeyValidator>>,
+    revoked_tokens: Option<Arc<dyn RevokedTokens>>,
+    impersonation_auditor: Option<Arc<dyn ImpersonationAuditor>>,
+    impersonation_blocked_paths: Rc<Vec<String>>,
+    trusted_proxies: Rc<TrustedProxies>,
+}
+
+impl AuthMiddleware {
+    pub fn new(config: JwtConfig) -> Self {
+        Self {
+            config: Rc::new(config),
+            api_key_validator: None,
+            revoked_tokens: None,
+            impersonation_auditor: None,
+            impersonation_blocked_paths: Rc::new(IMPERSONATION_PAYMENT_PATHS.iter().map(|p| p.to_string()).collect()),
+            trusted_proxies: Rc::new(TrustedProxies::default()),
+        }
+    }
+
+    pub fn with_api_key_validator(mut self, validator: Arc<dyn ApiKeyValidator>) -> Self {
+        self.api_key_validator = Some(validator);
+        self
+    }
+
+    pub fn with_revoked_tokens(mut self, revoked_tokens: Arc<dyn RevokedTokens>) -> Self {
+        self.revoked_tokens = Some(revoked_tokens);
+        self
+    }
+
+    pub fn with_impersonation_auditor(mut self, auditor: Arc<dyn ImpersonationAuditor>) -> Self {
+        self.impersonation_auditor = Some(auditor);
+        self
+    }
+
+    // Client addresses (API key allowlists, impersonation audit) come from
+    // the connection unless it is one of these proxies
+    pub fn with_trusted_proxies(mut self, trusted_proxies: TrustedProxies) -> Self {
+        self.trusted_proxies = Rc::new(trusted_proxies);
+        self
+    }
+
+    // Path prefixes an impersonation token may never reach (password changes,
+    // MFA), on top of `IMPERSONATION_PAYMENT_PATHS`. Requests are rejected
+    // before they hit the handler.
+    pub fn block_impersonation_for(mut self, path_prefixes: &[&str]) -> Self {
+        Rc::make_mut(&mut self.impersonation_blocked_paths).extend(path_prefixes.iter().map(|p| p.to_string()));
+        self
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for AuthMiddleware
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static + actix_web::body::MessageBody,
+{
+    type Response = ServiceResponse<BoxBody>;
+    type Error = Error;
+    type Transform = AuthMiddlewareService<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(AuthMiddlewareService {
+            service: Rc::new(service),
+            config: self.config.clone(),
+            api_key_validator: self.api_key_validator.clone(),
+            revoked_tokens: self.revoked_tokens.clone(),
+            impersonation_auditor: self.impersonation_auditor.clone(),
+            impersonation_blocked_paths: self.impersonation_blocked_paths.clone(),
+            trusted_proxies: self.trusted_proxies.clone(),
+        }))
+    }
+}
+
+pub struct AuthMiddlewareService<S> {
+    service: Rc<S>,
+    config: Rc<JwtConfig>,
+    api_key_validator: Option<Arc<dyn ApiKeyValidator>>,
+    revoked_tokens: Option<Arc<dyn RevokedTokens>>,
+    impersonation_auditor: Option<Arc<dyn ImpersonationAuditor>>,
+    impersonation_blocked_paths: Rc<Vec<String>>,
+    trusted_proxies: Rc<TrustedProxies>,
+}
+
+impl<S, B> Service<ServiceRequest> for AuthMiddlewareService<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static + actix_web::body::MessageBody,
+{
+    type Response = ServiceResponse<BoxBody>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        if let Some(token) = bearer_token(req.request()) {
+            let claims = match JwtHelper::validate_access_token(&token, &self.config) {
+                Ok(claims) => claims,
+                Err(err) => {
+                    let response = map_auth_error_to_response(&err);
+                    return Box::pin(async move { Ok(req.into_response(response)) });
+                }
+            };
+
+            let Some(revoked_tokens) = self.revoked_tokens.clone() else {
+                return self.call_authenticated(req, claims);
+            };
+            let service = self.clone_handle();
+            return Box::pin(async move {
+                // Fails closed: a token whose status is unknown is not accepted
+                match revoked_tokens.is_revoked(&token).await {
+                    Ok(false) => service.call_authenticated(req, claims).await,
+                    Ok(true) => Ok(req.into_response(map_auth_error_to_response(&SystemError::TokenBlacklisted))),
+                    Err(err) => Ok(req.into_response(map_auth_error_to_response(&err))),
+                }
+            });
+        } else if let (Some(api_key), Some(validator)) = (api_key(req.request()), self.api_key_validator.clone()) {
+            let service = self.service.clone();
+            let ip_address = self.trusted_proxies.client_ip(req.request()).map(|ip| ip.to_string());
+            return Box::pin(async move {
+                match validator.validate(&api_key, ip_address.as_deref()).await {
+                    Ok(claims) => {
+                        record_user_id(claims.sub);
+                        req.extensions_mut().insert(claims);
+                        service.call(req).await.map(|res| res.map_into_boxed_body())
+                    }
+                    Err(err) => Ok(req.into_response(map_auth_error_to_response(&err))),
+                }
+            });
+        }
+
+        let fut = self.service.call(req);
+        Box::pin(async move { fut.await.map(|res| res.map_into_boxed_body()) })
+    }
+}
+
+impl<S, B> AuthMiddlewareService<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static + actix_web::body::MessageBody,
+{
+    fn clone_handle(&self) -> Self {
+        Self {
+            service: self.service.clone(),
+            config: self.config.clone(),
+            api_key_validator: self.api_key_validator.clone(),
+            revoked_tokens: self.revoked_tokens.clone(),
+            impersonation_auditor: self.impersonation_auditor.clone(),
+            impersonation_blocked_paths: self.impersonation_blocked_paths.clone(),
+            trusted_proxies: self.trusted_proxies.clone(),
+        }
+    }
+
+    fn call_authenticated(
+        &self,
+        req: ServiceRequest,
+        claims: JwtClaims,
+    ) -> LocalBoxFuture<'static, Result<ServiceResponse<BoxBody>, Error>> {
+        if claims.is_impersonated() {
+            return self.call_impersonated(req, claims);
+        }
+
+        record_user_id(claims.sub);
+        req.extensions_mut().insert(claims);
+        let fut = self.service.call(req);
+        Box::pin(async move { fut.await.map(|res| res.map_into_boxed_body()) })
+    }
+
+    fn call_impersonated(
+        &self,
+        req: ServiceRequest,
+        claims: JwtClaims,
+    ) -> LocalBoxFuture<'static, Result<ServiceResponse<BoxBody>, Error>> {
+        let blocked = self
+            .impersonation_blocked_paths
+            .iter()
+            .any(|prefix| req.path().starts_with(prefix.as_str()));
+
+        let mut request = ImpersonatedRequest {
+            method: req.method().to_string(),
+            path: req.path().to_string(),
+            status: 0,
+            ip_address: self.trusted_proxies.client_ip(req.request()).map(|ip| ip.to_string()),
+            user_agent: req
+                .headers()
+                .get(actix_web::http::header::USER_AGENT)
+                .and_then(|h| h.to_str().ok())
+                .map(|s| s.to_string()),
+        };
+        let auditor = self.impersonation_auditor.clone();
+        let service = self.service.clone();
+
+        Box::pin(async move {
+            let res = if blocked {
+                let err = SystemError::PermissionDenied("Not allowed while impersonating a user".to_string());
+                req.into_response(map_auth_error_to_response(&err))
+            } else {
+                record_user_id(claims.sub);
+                req.extensions_mut().insert(claims.clone());
+                service.call(req).await?.map_into_boxed_body()
+            };
+
+            if let Some(auditor) = auditor {
+                request.status = res.status().as_u16();
+                auditor.record(&claims, &request).await;
+            }
+
+            Ok(res)
+        })
+    }
+}
+
+pub fn bearer_token(req: &HttpRequest) -> Option<String> {
+    req.headers()
+        .get(AUTHORIZATION)
+        .and_then(|h| h.to_str().ok())
+        .and_then(|h| h.strip_prefix("Bearer "))
+        .map(|t| t.trim().to_string())
+        .filter(|t| !t.is_empty())
+}
+
+pub fn api_key(req: &HttpRequest) -> Option<String> {
+    req.headers()
+        .get(API_KEY_HEADER)
+        .and_then(|h| h.to_str().ok())
+        .map(|k| k.trim().to_string())
+        .filter(|k| !k.is_empty())
+}
+
+// Extractor for handlers that require a caller authenticated by `AuthMiddleware`.
+#[derive(Debug, Clone)]
+pub struct AuthenticatedUser(pub JwtClaims);
+
+impl AuthenticatedUser {
+    pub fn claims(&self) -> &JwtClaims {
+        &self.0
+    }
+
+    pub fn user_id(&self) -> Uuid {
+        self.0.sub
+    }
+
+    pub fn require_permission(&self, permission: &str) -> SystemResult<()> {
+        if self.0.permissions.iter().any(|p| p == permission) {
+            Ok(())
+        } else {
+            Err(SystemError::PermissionDenied(format!("Missing permission: {}", permission)))
+        }
+    }
+
+    // For handlers outside the middleware's blocked paths that still must not
+    // run on behalf of an impersonating staff member.
+    pub fn forbid_impersonation(&self) -> SystemResult<()> {
+        if self.0.is_impersonated() {
+            Err(SystemError::PermissionDenied("Not allowed while impersonating a user".to_string()))
+        } else {
+            Ok(())
+        }
+    }
+
+    // For administration routes: OAuth tokens, API keys and impersonation
+    // sessions carry the user's role but only a slice of their authority
+    pub fn forbid_delegation(&self) -> SystemResult<()> {
+        if self.0.is_delegated() {
+            Err(SystemError::PermissionDenied(
+                "Not allowed with an OAuth token, API key or impersonation session".to_string(),
+            ))
+        } else {
+            Ok(())
+        }
+    }
+
+    pub fn require_any_role(&self, roles: &[UserRole]) -> SystemResult<()> {
+        if roles.contains(&self.0.role) {
+            Ok(())
+        } else {
+            Err(SystemError::PermissionDenied(format!("Role {} is not allowed", self.0.role)))
+        }
+    }
+}
+
+impl FromRequest for AuthenticatedUser {
+    type Error = Error;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _payload: &mut Payload) -> Self::Future {
+        let claims = req.extensions().get::<JwtClaims>().cloned();
+        ready(claims.map(AuthenticatedUser).ok_or_else(|| reject(SystemError::InvalidToken)))
+    }
+}
+
+// Extractor for sensitive operations: the caller must have authenticated
+// within the `StepUpConfig` window registered as app data (5 minutes when
+// none is). Stale sessions get 401 and should call the re-authenticate
+// endpoint for an elevated token.
+#[derive(Debug, Clone)]
+pub struct RecentlyAuthenticated(pub JwtClaims);
+
+impl RecentlyAuthenticated {
+    pub fn claims(&self) -> &JwtClaims {
+        &self.0
+    }
+}
+
+impl FromRequest for RecentlyAuthenticated {
+    type Error = Error;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _payload: &mut Payload) -> Self::Future {
+        let max_age = req
+            .app_data::<StepUpConfig>()
+            .cloned()
+            .unwrap_or_default()
+            .max_age_seconds;
+
+        let result = match req.extensions().get::<JwtClaims>() {
+            None => Err(reject(SystemError::InvalidToken)),
+            Some(claims) if claims.authenticated_within(max_age) => Ok(RecentlyAuthenticated(claims.clone())),
+            Some(_) => Err(reject(SystemError::ReauthenticationRequired)),
+        };
+        ready(result)
+    }
+}
+
+pub fn reject(err: SystemError) -> Error {
+    err.into()
+}
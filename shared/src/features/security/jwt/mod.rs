@@ -1,7 +1,7 @@
 use chrono::Utc;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
-use crate::entities::enums::UserRole;
+use crate::entities::enums::{OrganisationRole, UserRole};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct JwtClaims {
@@ -14,6 +14,33 @@ pub struct JwtClaims {
     pub iss: String, // issuer
     pub aud: String, // audience
     pub jti: Uuid, // JWT ID for blacklisting
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_id: Option<String>, // OAuth client acting on the user's behalf
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api_key_id: Option<Uuid>, // set when the caller authenticated with an API key
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub organisation_id: Option<Uuid>, // active organisation context
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub organisation_role: Option<OrganisationRole>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub act: Option<ActorClaims>, // staff member impersonating `sub` (RFC 8693)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auth_time: Option<usize>, // when the user last proved their identity
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub amr: Vec<String>, // how they proved it, see `amr`
+}
+
+// Authentication method references (RFC 8176)
+pub mod amr {
+    pub const PASSWORD: &str = "pwd";
+    pub const OTP: &str = "otp";
+    pub const TOTP: &str = "totp";
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ActorClaims {
+    pub sub: Uuid,
+    pub email: String,
 }
 
 impl JwtClaims {
@@ -38,7 +65,58 @@ impl JwtClaims {
             exp: now + exp,
             iss: issuer,
             aud: audience,
-            jti
+            jti,
+            client_id: None,
+            api_key_id: None,
+            organisation_id: None,
+            organisation_role: None,
+            act: None,
+            auth_time: None,
+            amr: Vec::new(),
         }
     }
+
+    pub fn with_client_id(mut self, client_id: String) -> Self {
+        self.client_id = Some(client_id);
+        self
+    }
+
+    pub fn with_api_key_id(mut self, api_key_id: Uuid) -> Self {
+        self.api_key_id = Some(api_key_id);
+        self
+    }
+
+    pub fn with_organisation(mut self, organisation_id: Uuid, role: OrganisationRole) -> Self {
+        self.organisation_id = Some(organisation_id);
+        self.organisation_role = Some(role);
+        self
+    }
+
+    pub fn with_actor(mut self, actor: ActorClaims) -> Self {
+        self.act = Some(actor);
+        self
+    }
+
+    // Marks the token as issued right after the user authenticated with `method`
+    pub fn with_authentication(mut self, method: &str) -> Self {
+        self.auth_time = Some(self.iat);
+        self.amr = vec![method.to_string()];
+        self
+    }
+
+    pub fn authenticated_within(&self, max_age_seconds: u64) -> bool {
+        let now = Utc::now().timestamp() as usize;
+        self.auth_time
+            .is_some_and(|auth_time| now.saturating_sub(auth_time) <= max_age_seconds as usize)
+    }
+
+    pub fn is_impersonated(&self) -> bool {
+        self.act.is_some()
+    }
+
+    // Delegated callers (OAuth clients, API keys, impersonation sessions) act
+    // with a restricted permission set and must not manage credentials themselves.
+    pub fn is_delegated(&self) -> bool {
+        self.client_id.is_some() || self.api_key_id.is_some() || self.act.is_some()
+    }
 }
\ No newline at end of file
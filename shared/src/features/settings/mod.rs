@@ -0,0 +1,144 @@
+pub mod routes;
+mod store;
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+pub use store::RuntimeSettings;
+
+// Table the settings store reads and writes. Each service that uses runtime
+// settings adds this to its own migrations.
+pub const SETTINGS_SCHEMA_SQL: &str = include_str!("schema.sql");
+
+// Channel an instance notifies after changing a value, so the others reload
+const CHANGED_CHANNEL: &str = "runtime_settings_changed";
+
+// A value operators can change while the service runs. `default` applies
+// until someone stores an override:
+//
+//     pub const MAX_FAILED_LOGINS: Setting<i32> =
+//         Setting::new("auth.login.max_failed_attempts", 5).with_range(1, 20);
+//
+//     let max = settings.get(&MAX_FAILED_LOGINS);
+#[derive(Debug, Clone)]
+pub struct Setting<T> {
+    key: &'static str,
+    default: T,
+    range: Option<(T, T)>,
+}
+
+impl<T> Setting<T> {
+    pub const fn new(key: &'static str, default: T) -> Self {
+        Self { key, default, range: None }
+    }
+
+    pub fn key(&self) -> &'static str {
+        self.key
+    }
+
+    pub fn default_value(&self) -> &T {
+        &self.default
+    }
+
+    // Inclusive bounds a new value must fall within, if any
+    pub fn range(&self) -> Option<&(T, T)> {
+        self.range.as_ref()
+    }
+}
+
+impl<T: Copy> Setting<T> {
+    // Values outside `min..=max` are refused when set, and ignored if
+    // found stored
+    pub const fn with_range(self, min: T, max: T) -> Self {
+        Self {
+            key: self.key,
+            default: self.default,
+            range: Some((min, max)),
+        }
+    }
+}
+
+// A feature that can be switched on for everyone, a share of users, or
+// certain roles. `default` is the state while no rule is stored.
+#[derive(Debug, Clone, Copy)]
+pub struct Flag {
+    key: &'static str,
+    default: bool,
+}
+
+impl Flag {
+    pub const fn new(key: &'static str, default: bool) -> Self {
+        Self { key, default }
+    }
+
+    pub fn key(&self) -> &'static str {
+        self.key
+    }
+
+    pub fn default_rule(&self) -> FlagRule {
+        FlagRule {
+            enabled: self.default,
+            percentage: 100,
+            roles: Vec::new(),
+        }
+    }
+}
+
+// The stored state of a flag. It is on for a subject when it is enabled, the
+// subject's role is listed (or no roles are), and the subject falls inside
+// the rollout percentage.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FlagRule {
+    pub enabled: bool,
+    #[serde(default = "full_rollout")]
+    pub percentage: u8,
+    #[serde(default)]
+    pub roles: Vec<String>,
+}
+
+fn full_rollout() -> u8 {
+    100
+}
+
+// Who a flag is being evaluated for. `subject` is any stable id (a user id,
+// an email); the same subject always lands in the same rollout bucket.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FlagContext<'a> {
+    pub subject: Option<&'a str>,
+    pub role: Option<&'a str>,
+}
+
+impl<'a> FlagContext<'a> {
+    pub fn subject(subject: &'a str) -> Self {
+        Self { subject: Some(subject), role: None }
+    }
+
+    pub fn with_role(mut self, role: &'a str) -> Self {
+        self.role = Some(role);
+        self
+    }
+}
+
+impl FlagRule {
+    pub fn evaluate(&self, key: &str, context: FlagContext<'_>) -> bool {
+        if !self.enabled {
+            return false;
+        }
+        if !self.roles.is_empty() && !context.role.is_some_and(|role| self.roles.iter().any(|r| r == role)) {
+            return false;
+        }
+        match (self.percentage, context.subject) {
+            (p, _) if p >= 100 => true,
+            (p, Some(subject)) => bucket(key, subject) < p,
+            // Without a subject there is nothing to bucket on
+            (_, None) => false,
+        }
+    }
+}
+
+// 0..100, stable for a flag and subject. Hashing the key in as well means a
+// subject in the first 10% of one rollout is not in the first 10% of all.
+fn bucket(key: &str, subject: &str) -> u8 {
+    let digest = Sha256::digest(format!("{}:{}", key, subject));
+    (u16::from_be_bytes([digest[0], digest[1]]) % 100) as u8
+}
@@ -0,0 +1,76 @@
+use crate::entities::enums::UserRole;
+use crate::features::errors::{map_auth_error_to_response, map_success_to_response, SuccessResponse, SystemResult};
+use crate::features::security::auth::AuthenticatedUser;
+use crate::features::settings::RuntimeSettings;
+use actix_web::{web, HttpResponse, Result, Scope};
+use serde::Deserialize;
+use serde_json::Value;
+
+const ALLOWED_ROLES: [UserRole; 2] = [UserRole::Admin, UserRole::SuperAdmin];
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateSettingRequest {
+    pub value: Value,
+}
+
+// Runtime settings and feature flags, restricted to admins signed in as
+// themselves. Needs the
+// settings registered as app data:
+//
+//     .app_data(web::Data::from(settings.clone()))
+//     .service(settings_routes())
+//
+//     PUT /settings/auth.login.max_failed_attempts  {"value": 3}
+//     PUT /settings/auth.otp.sms                    {"value": {"enabled": true, "percentage": 25}}
+//     DELETE /settings/auth.otp.sms                 back to the default
+pub fn settings_routes() -> Scope {
+    web::scope("/settings")
+        .route("", web::get().to(list_settings))
+        .route("/{key}", web::put().to(update_setting))
+        .route("/{key}", web::delete().to(reset_setting))
+}
+
+async fn list_settings(user: AuthenticatedUser, settings: web::Data<RuntimeSettings>) -> Result<HttpResponse> {
+    if let Err(err) = require_admin(&user) {
+        return Ok(map_auth_error_to_response(&err));
+    }
+
+    Ok(map_success_to_response(SuccessResponse::Fetched, Some(settings.list()), None))
+}
+
+async fn update_setting(
+    user: AuthenticatedUser,
+    settings: web::Data<RuntimeSettings>,
+    path: web::Path<String>,
+    body: web::Json<UpdateSettingRequest>,
+) -> Result<HttpResponse> {
+    if let Err(err) = require_admin(&user) {
+        return Ok(map_auth_error_to_response(&err));
+    }
+
+    match settings.set(&path.into_inner(), body.into_inner().value, user.user_id()).await {
+        Ok(setting) => Ok(map_success_to_response(SuccessResponse::Updated, Some(setting), None)),
+        Err(err) => Ok(map_auth_error_to_response(&err)),
+    }
+}
+
+async fn reset_setting(
+    user: AuthenticatedUser,
+    settings: web::Data<RuntimeSettings>,
+    path: web::Path<String>,
+) -> Result<HttpResponse> {
+    if let Err(err) = require_admin(&user) {
+        return Ok(map_auth_error_to_response(&err));
+    }
+
+    match settings.reset(&path.into_inner(), user.user_id()).await {
+        Ok(setting) => Ok(map_success_to_response(SuccessResponse::Updated, Some(setting), None)),
+        Err(err) => Ok(map_auth_error_to_response(&err)),
+    }
+}
+
+// The role alone is not enough: an admin's API key or OAuth token carries it too
+fn require_admin(user: &AuthenticatedUser) -> SystemResult<()> {
+    user.require_any_role(&ALLOWED_ROLES)?;
+    user.forbid_delegation()
+}
@@ -0,0 +1,314 @@
+use crate::entities::enums::UserRole;
+use crate::features::errors::{SystemError, SystemResult};
+use crate::features::settings::{Flag, FlagContext, FlagRule, Setting, CHANGED_CHANNEL};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+use sqlx::postgres::{PgListener, PgNotification};
+use sqlx::{Pool, Postgres, Row};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Display;
+use std::sync::{Arc, PoisonError, RwLock};
+use std::time::Duration;
+use tokio::sync::broadcast;
+use tokio::task::JoinHandle;
+use uuid::Uuid;
+
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+
+type Check = Box<dyn Fn(&Value) -> Result<(), String> + Send + Sync>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SettingKind {
+    Setting,
+    Flag,
+}
+
+struct Definition {
+    kind: SettingKind,
+    default: Value,
+    check: Check,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SettingView {
+    pub key: String,
+    pub kind: SettingKind,
+    pub value: Value,
+    pub default: Value,
+    pub overridden: bool,
+}
+
+// Runtime settings and feature flags, stored in `runtime_settings` (see
+// `SETTINGS_SCHEMA_SQL`) and read from an in-memory copy, so `get` and
+// `is_enabled` never touch the database. A change made on one instance is
+// announced with NOTIFY and every instance reloads; a periodic reload covers
+// notifications lost while the listener was reconnecting.
+//
+// Only registered keys can be changed, and only to values of their type
+// within their range:
+//
+//     let settings = Arc::new(
+//         RuntimeSettings::new(pool.clone())
+//             .with_setting(&MAX_FAILED_LOGINS)
+//             .with_flag(&SMS_OTP),
+//     );
+//     settings.refresh().await?;
+//     settings.spawn_listener(Duration::from_secs(60), shutdown_tx.subscribe());
+pub struct RuntimeSettings {
+    pool: Pool<Postgres>,
+    definitions: BTreeMap<&'static str, Definition>,
+    values: RwLock<HashMap<String, Value>>,
+}
+
+impl RuntimeSettings {
+    pub fn new(pool: Pool<Postgres>) -> Self {
+        Self {
+            pool,
+            definitions: BTreeMap::new(),
+            values: RwLock::new(HashMap::new()),
+        }
+    }
+
+    pub fn with_setting<T>(mut self, setting: &Setting<T>) -> Self
+    where
+        T: Serialize + DeserializeOwned + PartialOrd + Display + Clone + Send + Sync + 'static,
+    {
+        let default = serde_json::to_value(setting.default_value()).expect("setting defaults must serialize");
+        let range = setting.range().cloned();
+        self.definitions.insert(
+            setting.key(),
+            Definition {
+                kind: SettingKind::Setting,
+                default,
+                check: Box::new(move |value| check_setting::<T>(value, range.as_ref())),
+            },
+        );
+        self
+    }
+
+    pub fn with_flag(mut self, flag: &Flag) -> Self {
+        let default = serde_json::to_value(flag.default_rule()).expect("flag rules must serialize");
+        self.definitions.insert(
+            flag.key(),
+            Definition { kind: SettingKind::Flag, default, check: Box::new(check_flag) },
+        );
+        self
+    }
+
+    pub fn get<T: DeserializeOwned + Clone>(&self, setting: &Setting<T>) -> T {
+        let values = self.values.read().unwrap_or_else(PoisonError::into_inner);
+        match values.get(setting.key()) {
+            Some(value) => serde_json::from_value(value.clone()).unwrap_or_else(|e| {
+                log::warn!("Runtime setting {} has an invalid value, using the default: {}", setting.key(), e);
+                setting.default_value().clone()
+            }),
+            None => setting.default_value().clone(),
+        }
+    }
+
+    pub fn is_enabled(&self, flag: &Flag, context: FlagContext<'_>) -> bool {
+        let values = self.values.read().unwrap_or_else(PoisonError::into_inner);
+        let rule = values
+            .get(flag.key())
+            .and_then(|value| serde_json::from_value::<FlagRule>(value.clone()).ok())
+            .unwrap_or_else(|| flag.default_rule());
+        rule.evaluate(flag.key(), context)
+    }
+
+    // Every registered key with its current and default value
+    pub fn list(&self) -> Vec<SettingView> {
+        let values = self.values.read().unwrap_or_else(PoisonError::into_inner);
+        self.definitions
+            .iter()
+            .map(|(key, definition)| view(key, definition, values.get(*key)))
+            .collect()
+    }
+
+    #[tracing::instrument(name = "RuntimeSettings::set", skip_all, fields(db.system = "postgresql"))]
+    pub async fn set(&self, key: &str, value: Value, updated_by: Uuid) -> SystemResult<SettingView> {
+        log::info!("set() called with key: {}, updated_by: {}", key, updated_by);
+        let definition = self.definition(key)?;
+        (definition.check)(&value).map_err(|e| SystemError::ValidationError(format!("{}: {}", key, e)))?;
+
+        let mut tx = self.pool.begin().await?;
+        sqlx::query(
+            r#"
+            INSERT INTO runtime_settings (key, value, updated_by, updated_at)
+            VALUES ($1, $2::jsonb, $3, NOW())
+            ON CONFLICT (key) DO UPDATE SET value = EXCLUDED.value, updated_by = EXCLUDED.updated_by, updated_at = NOW()
+            "#
+        )
+        .bind(key)
+        .bind(value.to_string())
+        .bind(updated_by)
+        .execute(&mut *tx)
+        .await?;
+        notify(&mut tx, key).await?;
+        tx.commit().await?;
+
+        self.values
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(key.to_string(), value.clone());
+        log::warn!("Runtime setting {} changed to {} by {}", key, value, updated_by);
+        Ok(view(key, definition, Some(&value)))
+    }
+
+    // Drops the override so the default applies again
+    #[tracing::instrument(name = "RuntimeSettings::reset", skip_all, fields(db.system = "postgresql"))]
+    pub async fn reset(&self, key: &str, updated_by: Uuid) -> SystemResult<SettingView> {
+        log::info!("reset() called with key: {}, updated_by: {}", key, updated_by);
+        let definition = self.definition(key)?;
+
+        let mut tx = self.pool.begin().await?;
+        sqlx::query("DELETE FROM runtime_settings WHERE key = $1")
+            .bind(key)
+            .execute(&mut *tx)
+            .await?;
+        notify(&mut tx, key).await?;
+        tx.commit().await?;
+
+        self.values.write().unwrap_or_else(PoisonError::into_inner).remove(key);
+        log::warn!("Runtime setting {} reset to its default by {}", key, updated_by);
+        Ok(view(key, definition, None))
+    }
+
+    // Replaces the in-memory copy with what is stored. Values that no longer
+    // fit their type (e.g. after a deploy changed it) are ignored.
+    #[tracing::instrument(name = "RuntimeSettings::refresh", skip_all, fields(db.system = "postgresql"))]
+    pub async fn refresh(&self) -> SystemResult<()> {
+        let rows = sqlx::query("SELECT key, value::text AS value FROM runtime_settings")
+            .fetch_all(&self.pool)
+            .await?;
+
+        let mut loaded = HashMap::new();
+        for row in rows {
+            let key: String = row.try_get("key")?;
+            let raw: String = row.try_get("value")?;
+            let Some(definition) = self.definitions.get(key.as_str()) else {
+                continue;
+            };
+            let checked = serde_json::from_str::<Value>(&raw)
+                .map_err(|e| e.to_string())
+                .and_then(|value| (definition.check)(&value).map(|_| value));
+            match checked {
+                Ok(value) => {
+                    loaded.insert(key, value);
+                }
+                Err(e) => log::warn!("Ignoring stored runtime setting {}: {}", key, e),
+            }
+        }
+
+        *self.values.write().unwrap_or_else(PoisonError::into_inner) = loaded;
+        Ok(())
+    }
+
+    // Reloads whenever any instance changes a value, and every `resync`
+    pub fn spawn_listener(self: &Arc<Self>, resync: Duration, mut shutdown_rx: broadcast::Receiver<()>) -> JoinHandle<()> {
+        let settings = self.clone();
+        tokio::spawn(async move {
+            let mut listener: Option<PgListener> = None;
+            let mut ticker = tokio::time::interval(resync);
+
+            loop {
+                if listener.is_none() {
+                    listener = settings.listen().await;
+                }
+
+                tokio::select! {
+                    _ = ticker.tick() => settings.reload().await,
+                    notification = next_notification(&mut listener) => match notification {
+                        Ok(Some(notification)) => {
+                            log::debug!("Runtime setting {} changed elsewhere", notification.payload());
+                            settings.reload().await;
+                        }
+                        // The connection dropped; notifications sent meanwhile are lost
+                        Ok(None) => settings.reload().await,
+                        Err(e) => {
+                            log::warn!("Runtime settings listener failed: {}", e);
+                            listener = None;
+                            tokio::time::sleep(RECONNECT_DELAY).await;
+                        }
+                    },
+                    _ = shutdown_rx.recv() => break,
+                }
+            }
+        })
+    }
+
+    async fn listen(&self) -> Option<PgListener> {
+        let mut listener = match PgListener::connect_with(&self.pool).await {
+            Ok(listener) => listener,
+            Err(e) => {
+                log::warn!("Cannot listen for runtime setting changes: {}", e);
+                return None;
+            }
+        };
+        if let Err(e) = listener.listen(CHANGED_CHANNEL).await {
+            log::warn!("Cannot listen for runtime setting changes: {}", e);
+            return None;
+        }
+        Some(listener)
+    }
+
+    async fn reload(&self) {
+        if let Err(e) = self.refresh().await {
+            log::warn!("Failed to reload runtime settings: {}", e);
+        }
+    }
+
+    fn definition(&self, key: &str) -> SystemResult<&Definition> {
+        self.definitions
+            .get(key)
+            .ok_or_else(|| SystemError::NotFound(format!("Unknown runtime setting {}", key)))
+    }
+}
+
+async fn next_notification(listener: &mut Option<PgListener>) -> Result<Option<PgNotification>, sqlx::Error> {
+    match listener {
+        Some(listener) => listener.try_recv().await,
+        None => std::future::pending().await,
+    }
+}
+
+async fn notify(tx: &mut sqlx::Transaction<'_, Postgres>, key: &str) -> SystemResult<()> {
+    sqlx::query("SELECT pg_notify($1, $2)")
+        .bind(CHANGED_CHANNEL)
+        .bind(key)
+        .execute(&mut **tx)
+        .await?;
+    Ok(())
+}
+
+fn view(key: &str, definition: &Definition, value: Option<&Value>) -> SettingView {
+    SettingView {
+        key: key.to_string(),
+        kind: definition.kind,
+        value: value.cloned().unwrap_or_else(|| definition.default.clone()),
+        default: definition.default.clone(),
+        overridden: value.is_some(),
+    }
+}
+
+fn check_setting<T: DeserializeOwned + PartialOrd + Display>(value: &Value, range: Option<&(T, T)>) -> Result<(), String> {
+    let value = serde_json::from_value::<T>(value.clone()).map_err(|e| e.to_string())?;
+    match range {
+        Some((min, max)) if value < *min || value > *max => {
+            Err(format!("must be between {} and {}", min, max))
+        }
+        _ => Ok(()),
+    }
+}
+
+fn check_flag(value: &Value) -> Result<(), String> {
+    let rule: FlagRule = serde_json::from_value(value.clone()).map_err(|e| e.to_string())?;
+    if rule.percentage > 100 {
+        return Err("percentage must be between 0 and 100".to_string());
+    }
+    match rule.roles.iter().find(|role| role.parse::<UserRole>().is_err()) {
+        Some(role) => Err(format!("unknown role {}", role)),
+        None => Ok(()),
+    }
+}
@@ -0,0 +1,57 @@
+use crate::features::errors::SystemError;
+use actix_web::dev::Payload;
+use actix_web::{web, FromRequest, HttpRequest};
+use serde::de::DeserializeOwned;
+use std::future::Future;
+use std::ops::{Deref, DerefMut};
+use std::pin::Pin;
+use validator::Validate;
+
+// `web::Json<T>` that also runs `T::validate()`, so handlers only ever see
+// well-formed input:
+//
+//     #[post("/send")]
+//     pub async fn send_otp(req: ValidatedJson<SendOtpRequest>) -> ...
+//
+// Bodies that fail to parse go through the app's `JsonConfig` error handler
+// as before; bodies that parse but break a rule are answered with a 400
+// listing every offending field.
+#[derive(Debug)]
+pub struct ValidatedJson<T>(pub T);
+
+impl<T> ValidatedJson<T> {
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> Deref for ValidatedJson<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for ValidatedJson<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
+
+impl<T> FromRequest for ValidatedJson<T>
+where
+    T: DeserializeOwned + Validate + 'static,
+{
+    type Error = actix_web::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self, Self::Error>>>>;
+
+    fn from_request(req: &HttpRequest, payload: &mut Payload) -> Self::Future {
+        let json = web::Json::<T>::from_request(req, payload);
+        Box::pin(async move {
+            let value = json.await?.into_inner();
+            value.validate().map_err(SystemError::from)?;
+            Ok(ValidatedJson(value))
+        })
+    }
+}
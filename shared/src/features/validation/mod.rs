@@ -0,0 +1,96 @@
+mod extractor;
+mod validators;
+
+pub use extractor::ValidatedJson;
+pub use validators::{date_range, e164_phone, email, identifier, money_amount, uuid_str};
+
+use crate::features::errors::{FieldError, SystemError};
+use std::borrow::Cow;
+use validator::{ValidationError, ValidationErrors, ValidationErrorsKind};
+
+// Key validator uses for struct-level (`#[validate(schema(...))]`) errors
+const STRUCT_LEVEL: &str = "__all__";
+
+// Lets a struct-level rule blame one field rather than the whole body
+const FIELD_PARAM: &str = "field";
+
+// For `#[validate(schema(...))]` rules that check one field against another:
+//
+//     fn validate_send_otp(req: &SendOtpRequest) -> Result<(), ValidationError> {
+//         identifier(&req.identifier, &req.identifier_type).map_err(|e| on_field("identifier", e))
+//     }
+pub fn on_field(field: &'static str, mut error: ValidationError) -> ValidationError {
+    error.add_param(Cow::Borrowed(FIELD_PARAM), &field);
+    error
+}
+
+// Flattens nested validator errors into `SystemError::InvalidFields`, with
+// paths like `questions[1].answer`, sorted so responses are stable
+pub fn into_system_error(errors: &ValidationErrors) -> SystemError {
+    let mut fields = Vec::new();
+    flatten(errors, "", &mut fields);
+    fields.sort_by(|a, b| a.field.cmp(&b.field));
+    SystemError::InvalidFields(fields)
+}
+
+impl From<ValidationErrors> for SystemError {
+    fn from(errors: ValidationErrors) -> Self {
+        into_system_error(&errors)
+    }
+}
+
+fn flatten(errors: &ValidationErrors, prefix: &str, out: &mut Vec<FieldError>) {
+    for (field, kind) in errors.errors() {
+        let path = match (prefix.is_empty(), *field == STRUCT_LEVEL) {
+            (true, true) => "body".to_string(),
+            (false, true) => prefix.to_string(),
+            (true, false) => field.to_string(),
+            (false, false) => format!("{}.{}", prefix, field),
+        };
+        match kind {
+            ValidationErrorsKind::Field(errors) => {
+                out.extend(errors.iter().map(|e| {
+                    let path = match e.params.get(FIELD_PARAM).and_then(|f| f.as_str()) {
+                        Some(field) if prefix.is_empty() => field.to_string(),
+                        Some(field) => format!("{}.{}", prefix, field),
+                        None => path.clone(),
+                    };
+                    FieldError::new(path, e.code.to_string(), describe(e))
+                }));
+            }
+            ValidationErrorsKind::Struct(nested) => flatten(nested, &path, out),
+            ValidationErrorsKind::List(items) => {
+                for (index, nested) in items {
+                    flatten(nested, &format!("{}[{}]", path, index), out);
+                }
+            }
+        }
+    }
+}
+
+// The rule's own message, or one built from the built-in rule's params
+fn describe(error: &ValidationError) -> String {
+    if let Some(message) = &error.message {
+        return message.to_string();
+    }
+    let param = |name: &str| error.params.get(name).map(|v| v.to_string());
+    match error.code.as_ref() {
+        "length" => match (param("min"), param("max"), param("equal")) {
+            (_, _, Some(equal)) => format!("length must be exactly {}", equal),
+            (Some(min), Some(max), _) => format!("length must be between {} and {}", min, max),
+            (Some(min), None, _) => format!("length must be at least {}", min),
+            (None, Some(max), _) => format!("length must be at most {}", max),
+            _ => "has an invalid length".to_string(),
+        },
+        "range" => match (param("min"), param("max")) {
+            (Some(min), Some(max)) => format!("must be between {} and {}", min, max),
+            (Some(min), None) => format!("must be at least {}", min),
+            (None, Some(max)) => format!("must be at most {}", max),
+            _ => "is out of range".to_string(),
+        },
+        "required" => "is required".to_string(),
+        "email" => "must be a valid email address".to_string(),
+        "url" => "must be a valid URL".to_string(),
+        code => format!("is invalid ({})", code),
+    }
+}
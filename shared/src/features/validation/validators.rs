@@ -0,0 +1,81 @@
+use crate::entities::enums::IdentifierType;
+use chrono::{DateTime, Utc};
+use regex::Regex;
+use std::borrow::Cow;
+use std::sync::LazyLock;
+use uuid::Uuid;
+use validator::{ValidateEmail, ValidationError};
+
+// `+` then up to 15 digits, no leading zero in the country code
+static E164: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^\+[1-9][0-9]{6,14}$").expect("valid regex"));
+
+// A positive decimal with at most two places: "10", "10.5", "1999.99"
+static MONEY: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^(0|[1-9][0-9]{0,14})(\.[0-9]{1,2})?$").expect("valid regex"));
+
+// Longest address SMTP allows
+const MAX_EMAIL_LENGTH: usize = 254;
+
+// Shared rules for `#[validate(custom(function = ...))]`:
+//
+//     #[validate(custom(function = "shared::features::validation::e164_phone"))]
+//     pub phone: String,
+
+pub fn e164_phone(value: &str) -> Result<(), ValidationError> {
+    if E164.is_match(value) {
+        Ok(())
+    } else {
+        Err(invalid("phone", "must be an E.164 phone number, e.g. +2348012345678"))
+    }
+}
+
+pub fn email(value: &str) -> Result<(), ValidationError> {
+    if value.len() <= MAX_EMAIL_LENGTH && value.validate_email() {
+        Ok(())
+    } else {
+        Err(invalid("email", "must be a valid email address"))
+    }
+}
+
+// An email or phone, as `SendOtpRequest` and friends carry them
+pub fn identifier(value: &str, identifier_type: &IdentifierType) -> Result<(), ValidationError> {
+    match identifier_type {
+        IdentifierType::Email => email(value),
+        IdentifierType::Phone => e164_phone(value),
+    }
+}
+
+pub fn uuid_str(value: &str) -> Result<(), ValidationError> {
+    match Uuid::parse_str(value) {
+        Ok(_) => Ok(()),
+        Err(_) => Err(invalid("uuid", "must be a UUID")),
+    }
+}
+
+// Amounts travel as strings so no precision is lost on the way in
+pub fn money_amount(value: &str) -> Result<(), ValidationError> {
+    if !MONEY.is_match(value) {
+        return Err(invalid("money", "must be an amount with at most 2 decimal places"));
+    }
+    if value.bytes().all(|b| b == b'0' || b == b'.') {
+        return Err(invalid("money", "must be greater than zero"));
+    }
+    Ok(())
+}
+
+// For struct-level rules; open-ended ranges pass
+//
+//     #[validate(schema(function = "validate_period"))]
+//     fn validate_period(req: &ReportRequest) -> Result<(), ValidationError> {
+//         date_range(req.from.as_ref(), req.to.as_ref())
+//     }
+pub fn date_range(start: Option<&DateTime<Utc>>, end: Option<&DateTime<Utc>>) -> Result<(), ValidationError> {
+    match (start, end) {
+        (Some(start), Some(end)) if start > end => Err(invalid("date_range", "start must not be after end")),
+        _ => Ok(()),
+    }
+}
+
+fn invalid(code: &'static str, message: &'static str) -> ValidationError {
+    ValidationError::new(code).with_message(Cow::Borrowed(message))
+}
@@ -1,12 +1,20 @@
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
+use validator::Validate;
+use crate::features::validation::{e164_phone, email};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+// Extract with `ValidatedJson<RegisterRequest>`, never `web::Json`
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
 pub struct RegisterRequest {
+    #[validate(custom(function = "email"))]
     pub email: String,
+    #[validate(custom(function = "e164_phone"))]
     pub phone_number: Option<String>,
+    #[validate(length(min = 8, max = 128))]
     pub password: String,
+    #[validate(length(min = 1, max = 100))]
     pub first_name: String,
+    #[validate(length(min = 1, max = 100))]
     pub last_name: String,
 }
 
@@ -14,4 +22,4 @@ pub struct RegisterRequest {
 pub struct RegisterResponse {
     pub user_id: Uuid,
     pub verification_required: bool,
-}
\ No newline at end of file
+}
@@ -0,0 +1,44 @@
+use std::fmt::Display;
+
+// Builds every key a service writes, so services sharing one Redis never
+// collide and a change to a cached type can be rolled out by bumping the
+// version instead of flushing:
+//
+//     const KEYS: CacheKeys = CacheKeys::new("auth", 1);
+//
+//     KEYS.key("session", user_id)          // "auth:v1:session:<user_id>"
+//     KEYS.key_parts("inbox", &[a, b])      // "auth:v1:inbox:<a>:<b>"
+//     KEYS.tag("user", user_id)             // "auth:v1:tag:user:<user_id>"
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CacheKeys {
+    service: &'static str,
+    version: u32,
+}
+
+impl CacheKeys {
+    pub const fn new(service: &'static str, version: u32) -> Self {
+        Self { service, version }
+    }
+
+    pub fn prefix(&self) -> String {
+        format!("{}:v{}", self.service, self.version)
+    }
+
+    pub fn key(&self, kind: &str, id: impl Display) -> String {
+        format!("{}:{}:{}", self.prefix(), kind, id)
+    }
+
+    pub fn key_parts(&self, kind: &str, parts: &[&dyn Display]) -> String {
+        let mut key = format!("{}:{}", self.prefix(), kind);
+        for part in parts {
+            key.push(':');
+            key.push_str(&part.to_string());
+        }
+        key
+    }
+
+    // The set holding every key stored under a tag; see `CacheService::invalidate_tag`
+    pub fn tag(&self, kind: &str, id: impl Display) -> String {
+        format!("{}:tag:{}:{}", self.prefix(), kind, id)
+    }
+}
@@ -0,0 +1,93 @@
+use std::collections::HashMap;
+use std::sync::{Mutex, PoisonError};
+use std::time::{Duration, Instant};
+use uuid::Uuid;
+
+// Redis channel instances announce changed keys on
+pub const INVALIDATION_CHANNEL: &str = "cache:invalidate";
+
+// An in-process (L1) copy of hot JSON values in front of Redis. Entries live
+// for at most `ttl`, and instances drop them as soon as any instance writes
+// or deletes the key (see `CacheService::spawn_invalidation_listener`), so a
+// stale read is bounded by the pub/sub delay, or by `ttl` if a message is
+// lost.
+pub struct LocalCache {
+    capacity: usize,
+    ttl: Duration,
+    // Lets an instance skip its own invalidation messages
+    instance_id: Uuid,
+    entries: Mutex<HashMap<String, Entry>>,
+}
+
+struct Entry {
+    value: String,
+    expires_at: Instant,
+}
+
+impl LocalCache {
+    pub fn new(capacity: usize, ttl: Duration) -> Self {
+        Self {
+            capacity,
+            ttl,
+            instance_id: Uuid::new_v4(),
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub(crate) fn instance_id(&self) -> Uuid {
+        self.instance_id
+    }
+
+    pub(crate) fn get(&self, key: &str) -> Option<String> {
+        let mut entries = self.entries.lock().unwrap_or_else(PoisonError::into_inner);
+        match entries.get(key) {
+            Some(entry) if entry.expires_at > Instant::now() => Some(entry.value.clone()),
+            Some(_) => {
+                entries.remove(key);
+                None
+            }
+            None => None,
+        }
+    }
+
+    // Never keeps a value longer than Redis does, when its TTL is known
+    pub(crate) fn insert(&self, key: &str, value: String, redis_ttl: Option<Duration>) {
+        let mut entries = self.entries.lock().unwrap_or_else(PoisonError::into_inner);
+        if entries.len() >= self.capacity && !entries.contains_key(key) {
+            let now = Instant::now();
+            entries.retain(|_, entry| entry.expires_at > now);
+            if entries.len() >= self.capacity {
+                // Still full: make room by dropping whatever expires first
+                if let Some(oldest) = entries.iter().min_by_key(|(_, e)| e.expires_at).map(|(k, _)| k.clone()) {
+                    entries.remove(&oldest);
+                }
+            }
+        }
+        entries.insert(
+            key.to_string(),
+            Entry {
+                value,
+                expires_at: Instant::now() + redis_ttl.map_or(self.ttl, |ttl| ttl.min(self.ttl)),
+            },
+        );
+    }
+
+    pub(crate) fn remove(&self, keys: &[String]) {
+        let mut entries = self.entries.lock().unwrap_or_else(PoisonError::into_inner);
+        for key in keys {
+            entries.remove(key);
+        }
+    }
+
+    pub fn clear(&self) {
+        self.entries.lock().unwrap_or_else(PoisonError::into_inner).clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.lock().unwrap_or_else(PoisonError::into_inner).len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
@@ -1,20 +1,52 @@
-use deadpool_redis::{redis::AsyncCommands, Pool};
+mod keys;
+mod local;
+mod single_flight;
+
+pub use keys::CacheKeys;
+pub use local::{LocalCache, INVALIDATION_CHANNEL};
+
+use deadpool_redis::{redis, redis::AsyncCommands, Pool};
+use futures::StreamExt;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::future::Future;
 use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::broadcast;
+use tokio::task::JoinHandle;
+use uuid::Uuid;
 use crate::config::redis_config::RedisFigureConfig;
 use crate::features::errors::{SystemError, SystemResult};
+use single_flight::SingleFlight;
 
 #[derive(Clone)]
 pub struct CacheService {
     redis_client: Arc<Pool>,
     config: RedisFigureConfig,
+    local: Option<Arc<LocalCache>>,
+    flights: Arc<SingleFlight>,
 }
 
+// Every Redis call gets a client span; keys are left out as they can hold
+// emails and phone numbers
 impl CacheService {
     fn get_client(&self) -> &Pool {
         self.redis_client.as_ref()
     }
     pub fn new(redis_client: Arc<Pool>, config: RedisFigureConfig) -> Self {
-        Self { redis_client, config }
+        Self {
+            redis_client,
+            config,
+            local: None,
+            flights: Arc::new(SingleFlight::default()),
+        }
+    }
+
+    // Serves `get_json` from memory when it can; pair with
+    // `spawn_invalidation_listener` so other instances' writes evict entries
+    pub fn with_local_cache(mut self, local: Arc<LocalCache>) -> Self {
+        self.local = Some(local);
+        self
     }
 
     async fn get_connection(&self) -> SystemResult<deadpool_redis::Connection> {
@@ -25,6 +57,7 @@ impl CacheService {
     }
 
     // String operations
+    #[tracing::instrument(name = "redis.set", skip_all, fields(db.system = "redis", db.operation = "SETEX"))]
     pub async fn set<T: Send + Sync + deadpool_redis::redis::ToRedisArgs>(
         &self,
         key: &str,
@@ -39,6 +72,7 @@ impl CacheService {
     }
 
 
+    #[tracing::instrument(name = "redis.get", skip_all, fields(db.system = "redis", db.operation = "GET"))]
     pub async fn get<T: deadpool_redis::redis::FromRedisValue + Send + Sync>(
         &self,
         key: &str,
@@ -49,13 +83,38 @@ impl CacheService {
             .map_err(|e| SystemError::RedisError(e.to_string()))
     }
 
+    // SET NX EX: stores the value only if the key does not exist yet.
+    // Returns whether it was stored.
+    #[tracing::instrument(name = "redis.set_if_absent", skip_all, fields(db.system = "redis", db.operation = "SET NX"))]
+    pub async fn set_if_absent<T: Send + Sync + deadpool_redis::redis::ToRedisArgs>(
+        &self,
+        key: &str,
+        value: T,
+        ttl_seconds: u64,
+    ) -> SystemResult<bool> {
+        let mut conn = self.get_connection().await?;
+        let stored: Option<String> = deadpool_redis::redis::cmd("SET")
+            .arg(key)
+            .arg(value)
+            .arg("NX")
+            .arg("EX")
+            .arg(ttl_seconds)
+            .query_async(&mut conn)
+            .await
+            .map_err(|e| SystemError::RedisError(e.to_string()))?;
+        Ok(stored.is_some())
+    }
+
+    #[tracing::instrument(name = "redis.delete", skip_all, fields(db.system = "redis", db.operation = "DEL"))]
     pub async fn delete(&self, key: &str) -> SystemResult<()> {
         let mut conn = self.get_connection().await?;
-        conn.del(key)
+        conn.del::<_, ()>(key)
             .await
-            .map_err(|e| SystemError::RedisError(e.to_string()))
+            .map_err(|e| SystemError::RedisError(e.to_string()))?;
+        self.invalidate_local(&[key.to_string()]).await
     }
 
+    #[tracing::instrument(name = "redis.expire", skip_all, fields(db.system = "redis", db.operation = "EXPIRE"))]
     pub async fn expire(&self, key: &str, ttl_seconds: i64) -> SystemResult<()> {
         let mut conn = self.get_connection().await?;
         conn.expire(key, ttl_seconds)
@@ -63,6 +122,7 @@ impl CacheService {
             .map_err(|e| SystemError::RedisError(e.to_string()))
     }
 
+    #[tracing::instrument(name = "redis.exists", skip_all, fields(db.system = "redis", db.operation = "EXISTS"))]
     pub async fn exists(&self, key: &str) -> SystemResult<bool> {
         let mut conn = self.get_connection().await?;
         conn.exists(key)
@@ -70,6 +130,7 @@ impl CacheService {
             .map_err(|e| SystemError::RedisError(e.to_string()))
     }
 
+    #[tracing::instrument(name = "redis.increment", skip_all, fields(db.system = "redis", db.operation = "INCR"))]
     pub async fn increment(&self, key: &str) -> SystemResult<i32> {
         let mut conn = self.get_connection().await?;
         conn.incr(key, 1)
@@ -78,6 +139,7 @@ impl CacheService {
     }
 
     // Hash operations
+    #[tracing::instrument(name = "redis.set_hash_field", skip_all, fields(db.system = "redis", db.operation = "HSET"))]
     pub async fn set_hash_field<T: deadpool_redis::redis::ToRedisArgs + Send + Sync>(
         &self,
         key: &str,
@@ -98,6 +160,7 @@ impl CacheService {
         Ok(())
     }
 
+    #[tracing::instrument(name = "redis.get_hash_field", skip_all, fields(db.system = "redis", db.operation = "HGET"))]
     pub async fn get_hash_field<T: deadpool_redis::redis::FromRedisValue + Send + Sync>(
         &self,
         key: &str,
@@ -109,6 +172,7 @@ impl CacheService {
             .map_err(|e| SystemError::RedisError(e.to_string()))
     }
 
+    #[tracing::instrument(name = "redis.get_all_hash_fields", skip_all, fields(db.system = "redis", db.operation = "HGETALL"))]
     pub async fn get_all_hash_fields(
         &self,
         key: &str,
@@ -119,6 +183,7 @@ impl CacheService {
             .map_err(|e| SystemError::RedisError(e.to_string()))
     }
 
+    #[tracing::instrument(name = "redis.delete_hash_field", skip_all, fields(db.system = "redis", db.operation = "HDEL"))]
     pub async fn delete_hash_field(
         &self,
         key: &str,
@@ -149,4 +214,277 @@ impl CacheService {
             }
         }
     }
-}
\ No newline at end of file
+}
+// Typed values, stored as JSON
+impl CacheService {
+    #[tracing::instrument(name = "redis.get_json", skip_all, fields(db.system = "redis", db.operation = "GET"))]
+    pub async fn get_json<T: DeserializeOwned>(&self, key: &str) -> SystemResult<Option<T>> {
+        if let Some(raw) = self.local.as_ref().and_then(|local| local.get(key)) {
+            return Ok(decode(key, &raw));
+        }
+
+        let raw: Option<String> = self.get(key).await?;
+        Ok(raw.and_then(|raw| {
+            let value = decode(key, &raw);
+            if let (Some(local), Some(_)) = (&self.local, &value) {
+                local.insert(key, raw, None);
+            }
+            value
+        }))
+    }
+
+    #[tracing::instrument(name = "redis.set_json", skip_all, fields(db.system = "redis", db.operation = "SETEX"))]
+    pub async fn set_json<T: Serialize>(&self, key: &str, value: &T, ttl_seconds: Option<u64>) -> SystemResult<()> {
+        let raw = encode(value)?;
+        let ttl = ttl_seconds.unwrap_or(self.config.default_ttl_seconds);
+        self.set(key, raw.as_str(), Some(ttl)).await?;
+        self.store_local(key, raw, ttl).await
+    }
+
+    // One round trip for many keys; missing or undecodable ones come back as None
+    #[tracing::instrument(name = "redis.mget_json", skip_all, fields(db.system = "redis", db.operation = "MGET"))]
+    pub async fn mget_json<T: DeserializeOwned>(&self, keys: &[String]) -> SystemResult<Vec<Option<T>>> {
+        if keys.is_empty() {
+            return Ok(Vec::new());
+        }
+        let mut conn = self.get_connection().await?;
+        let raw: Vec<Option<String>> = redis::cmd("MGET")
+            .arg(keys)
+            .query_async(&mut conn)
+            .await
+            .map_err(|e| SystemError::RedisError(e.to_string()))?;
+        Ok(keys
+            .iter()
+            .zip(raw)
+            .map(|(key, raw)| raw.and_then(|raw| decode(key, &raw)))
+            .collect())
+    }
+
+    #[tracing::instrument(name = "redis.set_many_json", skip_all, fields(db.system = "redis", db.operation = "SETEX"))]
+    pub async fn set_many_json<T: Serialize>(&self, entries: &[(String, T)], ttl_seconds: Option<u64>) -> SystemResult<()> {
+        if entries.is_empty() {
+            return Ok(());
+        }
+        let ttl = ttl_seconds.unwrap_or(self.config.default_ttl_seconds);
+        let encoded = entries
+            .iter()
+            .map(|(key, value)| Ok((key.clone(), encode(value)?)))
+            .collect::<SystemResult<Vec<_>>>()?;
+
+        let mut pipe = redis::pipe();
+        for (key, raw) in &encoded {
+            pipe.set_ex(key, raw, ttl).ignore();
+        }
+        let mut conn = self.get_connection().await?;
+        pipe.query_async::<()>(&mut conn)
+            .await
+            .map_err(|e| SystemError::RedisError(e.to_string()))?;
+
+        let keys: Vec<String> = encoded.iter().map(|(key, _)| key.clone()).collect();
+        self.invalidate_local(&keys).await
+    }
+
+    #[tracing::instrument(name = "redis.delete_many", skip_all, fields(db.system = "redis", db.operation = "DEL"))]
+    pub async fn delete_many(&self, keys: &[String]) -> SystemResult<()> {
+        if keys.is_empty() {
+            return Ok(());
+        }
+        let mut conn = self.get_connection().await?;
+        conn.del::<_, ()>(keys)
+            .await
+            .map_err(|e| SystemError::RedisError(e.to_string()))?;
+        self.invalidate_local(keys).await
+    }
+
+    // Cache-aside: returns the cached value, or loads, stores and returns
+    // it. Concurrent misses on one key in this process share a single load.
+    //
+    //     let user = cache
+    //         .get_or_load(&KEYS.key("user", id), Some(300), || repo.find_by_id(id))
+    //         .await?;
+    //
+    // A loader returning `Option` caches "not found" too.
+    pub async fn get_or_load<T, F, Fut>(&self, key: &str, ttl_seconds: Option<u64>, loader: F) -> SystemResult<T>
+    where
+        T: Serialize + DeserializeOwned,
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = SystemResult<T>>,
+    {
+        if let Some(value) = self.get_json(key).await? {
+            return Ok(value);
+        }
+
+        let _flight = self.flights.acquire(key).await;
+        // Whoever held the flight before us has probably stored it
+        if let Some(value) = self.get_json(key).await? {
+            return Ok(value);
+        }
+
+        let value = loader().await?;
+        if let Err(e) = self.set_json(key, &value, ttl_seconds).await {
+            // The caller still gets the value; the next miss loads it again
+            log::warn!("Failed to cache loaded value: {}", e);
+        }
+        Ok(value)
+    }
+
+    // Like `set_json`, also recording the key under each tag (a Redis set)
+    // so `invalidate_tag` can drop everything cached about, say, one user.
+    // A tag lives as long as its longest-lived key (EXPIRE NX/GT, Redis 7+).
+    #[tracing::instrument(name = "redis.set_json_tagged", skip_all, fields(db.system = "redis", db.operation = "SETEX"))]
+    pub async fn set_json_tagged<T: Serialize>(
+        &self,
+        key: &str,
+        value: &T,
+        ttl_seconds: Option<u64>,
+        tags: &[String],
+    ) -> SystemResult<()> {
+        let raw = encode(value)?;
+        let ttl = ttl_seconds.unwrap_or(self.config.default_ttl_seconds);
+
+        let mut pipe = redis::pipe();
+        pipe.atomic().set_ex(key, raw.as_str(), ttl).ignore();
+        for tag in tags {
+            pipe.sadd(tag, key).ignore();
+            pipe.cmd("EXPIRE").arg(tag).arg(ttl).arg("NX").ignore();
+            pipe.cmd("EXPIRE").arg(tag).arg(ttl).arg("GT").ignore();
+        }
+        let mut conn = self.get_connection().await?;
+        pipe.query_async::<()>(&mut conn)
+            .await
+            .map_err(|e| SystemError::RedisError(e.to_string()))?;
+
+        self.store_local(key, raw, ttl).await
+    }
+
+    // Deletes every key stored under `tag`, and the tag. Returns how many
+    // keys were dropped.
+    #[tracing::instrument(name = "redis.invalidate_tag", skip_all, fields(db.system = "redis", db.operation = "SMEMBERS"))]
+    pub async fn invalidate_tag(&self, tag: &str) -> SystemResult<usize> {
+        let mut conn = self.get_connection().await?;
+        let keys: Vec<String> = conn
+            .smembers(tag)
+            .await
+            .map_err(|e| SystemError::RedisError(e.to_string()))?;
+
+        let mut doomed = keys.clone();
+        doomed.push(tag.to_string());
+        conn.del::<_, ()>(&doomed)
+            .await
+            .map_err(|e| SystemError::RedisError(e.to_string()))?;
+
+        self.invalidate_local(&keys).await?;
+        Ok(keys.len())
+    }
+
+    // Keeps the L1 copy of every instance subscribed to `INVALIDATION_CHANNEL`
+    // in step with writes made through any `CacheService`. Reconnects on its
+    // own; entries written while disconnected are dropped on reconnect.
+    pub fn spawn_invalidation_listener(&self, redis_url: &str, mut shutdown_rx: broadcast::Receiver<()>) -> JoinHandle<()> {
+        let local = self.local.clone();
+        let redis_url = redis_url.to_string();
+        tokio::spawn(async move {
+            let Some(local) = local else {
+                return;
+            };
+            loop {
+                tokio::select! {
+                    _ = shutdown_rx.recv() => break,
+                    result = listen_for_invalidations(&redis_url, &local) => {
+                        if let Err(e) = result {
+                            log::warn!("Cache invalidation listener disconnected: {}", e);
+                        }
+                        local.clear();
+                    }
+                }
+                tokio::select! {
+                    _ = shutdown_rx.recv() => break,
+                    _ = tokio::time::sleep(INVALIDATION_RECONNECT_DELAY) => {}
+                }
+            }
+            log::info!("Cache invalidation listener stopped");
+        })
+    }
+
+    async fn store_local(&self, key: &str, raw: String, ttl_seconds: u64) -> SystemResult<()> {
+        if let Some(local) = &self.local {
+            local.insert(key, raw, Some(Duration::from_secs(ttl_seconds)));
+        }
+        self.publish_invalidation(&[key.to_string()]).await
+    }
+
+    async fn invalidate_local(&self, keys: &[String]) -> SystemResult<()> {
+        if let Some(local) = &self.local {
+            local.remove(keys);
+        }
+        self.publish_invalidation(keys).await
+    }
+
+    // Only instances running an L1 publish; the cache must be enabled on all
+    // replicas of a service or on none
+    async fn publish_invalidation(&self, keys: &[String]) -> SystemResult<()> {
+        let Some(local) = &self.local else {
+            return Ok(());
+        };
+        let message = Invalidation {
+            origin: local.instance_id(),
+            keys: keys.to_vec(),
+        };
+        let payload = serde_json::to_string(&message).map_err(|e| SystemError::InternalError(e.to_string()))?;
+        let mut conn = self.get_connection().await?;
+        conn.publish::<_, _, ()>(INVALIDATION_CHANNEL, payload)
+            .await
+            .map_err(|e| SystemError::RedisError(e.to_string()))
+    }
+}
+
+const INVALIDATION_RECONNECT_DELAY: Duration = Duration::from_secs(5);
+
+#[derive(Serialize, Deserialize)]
+struct Invalidation {
+    origin: Uuid,
+    keys: Vec<String>,
+}
+
+// Pub/sub needs a dedicated connection, so this one is not from the pool
+async fn listen_for_invalidations(redis_url: &str, local: &LocalCache) -> SystemResult<()> {
+    let client = redis::Client::open(redis_url).map_err(|e| SystemError::RedisError(e.to_string()))?;
+    let mut pubsub = client
+        .get_async_pubsub()
+        .await
+        .map_err(|e| SystemError::RedisError(e.to_string()))?;
+    pubsub
+        .subscribe(INVALIDATION_CHANNEL)
+        .await
+        .map_err(|e| SystemError::RedisError(e.to_string()))?;
+    log::info!("Listening for cache invalidations on {}", INVALIDATION_CHANNEL);
+
+    let mut messages = pubsub.on_message();
+    while let Some(message) = messages.next().await {
+        let payload: String = match message.get_payload() {
+            Ok(payload) => payload,
+            Err(e) => {
+                log::warn!("Ignoring unreadable cache invalidation: {}", e);
+                continue;
+            }
+        };
+        match serde_json::from_str::<Invalidation>(&payload) {
+            Ok(message) if message.origin == local.instance_id() => {}
+            Ok(message) => local.remove(&message.keys),
+            Err(e) => log::warn!("Ignoring malformed cache invalidation: {}", e),
+        }
+    }
+    Err(SystemError::RedisError("subscription closed".to_string()))
+}
+
+fn encode<T: Serialize>(value: &T) -> SystemResult<String> {
+    serde_json::to_string(value).map_err(|e| SystemError::InternalError(format!("Failed to encode cached value: {}", e)))
+}
+
+// A value that no longer matches its type (e.g. after a deploy that forgot
+// to bump the key version) is treated as a miss
+fn decode<T: DeserializeOwned>(key: &str, raw: &str) -> Option<T> {
+    serde_json::from_str(raw)
+        .map_err(|e| log::warn!("Ignoring undecodable cached value at {}: {}", key, e))
+        .ok()
+}
@@ -0,0 +1,44 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, PoisonError};
+use tokio::sync::{Mutex as AsyncMutex, OwnedMutexGuard};
+
+// One in-flight load per key within this process. Callers that arrive while
+// a load is running wait for it and then find the value already cached, so
+// an expired hot key costs one database query instead of one per request.
+#[derive(Default)]
+pub(crate) struct SingleFlight {
+    locks: Mutex<HashMap<String, Arc<AsyncMutex<()>>>>,
+}
+
+pub(crate) struct Flight<'a> {
+    group: &'a SingleFlight,
+    key: String,
+    _guard: OwnedMutexGuard<()>,
+}
+
+impl SingleFlight {
+    pub(crate) async fn acquire(&self, key: &str) -> Flight<'_> {
+        let lock = {
+            let mut locks = self.locks.lock().unwrap_or_else(PoisonError::into_inner);
+            locks.entry(key.to_string()).or_default().clone()
+        };
+        Flight {
+            group: self,
+            key: key.to_string(),
+            _guard: lock.lock_owned().await,
+        }
+    }
+}
+
+// The last caller out removes the key's lock so the map does not grow
+impl Drop for Flight<'_> {
+    fn drop(&mut self) {
+        let mut locks = self.group.locks.lock().unwrap_or_else(PoisonError::into_inner);
+        if let Some(lock) = locks.get(&self.key) {
+            // Ours, plus the map's
+            if Arc::strong_count(lock) <= 2 {
+                locks.remove(&self.key);
+            }
+        }
+    }
+}
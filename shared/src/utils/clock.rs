@@ -0,0 +1,50 @@
+use chrono::{DateTime, Utc};
+use std::cell::RefCell;
+use std::sync::Arc;
+
+// Where "now" comes from. Entities and use cases read the time through
+// `clock::now()` rather than `Utc::now()` so tests can pin or advance it.
+pub trait Clock: Send + Sync {
+    fn now(&self) -> DateTime<Utc>;
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+thread_local! {
+    static CURRENT: RefCell<Option<Arc<dyn Clock>>> = const { RefCell::new(None) };
+}
+
+// The current time: the system clock unless a test installed another clock
+// on this thread
+pub fn now() -> DateTime<Utc> {
+    CURRENT
+        .with(|current| current.borrow().as_ref().map(|clock| clock.now()))
+        .unwrap_or_else(Utc::now)
+}
+
+// Replaces the clock for the current thread until the guard is dropped. The
+// override is per thread, so it suits `#[tokio::test]`'s single-threaded
+// runtime but is not seen by work spawned onto other threads.
+pub fn install(clock: Arc<dyn Clock>) -> ClockGuard {
+    let previous = CURRENT.with(|current| current.borrow_mut().replace(clock));
+    ClockGuard { previous }
+}
+
+#[must_use = "the clock is restored as soon as the guard is dropped"]
+pub struct ClockGuard {
+    previous: Option<Arc<dyn Clock>>,
+}
+
+impl Drop for ClockGuard {
+    fn drop(&mut self) {
+        let previous = self.previous.take();
+        CURRENT.with(|current| *current.borrow_mut() = previous);
+    }
+}
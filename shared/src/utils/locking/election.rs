@@ -0,0 +1,127 @@
+use super::{wait_until_lost, LockProvider};
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{broadcast, watch};
+use tokio::task::JoinHandle;
+
+// Runs a background task on exactly one replica at a time:
+//
+//     LeaderElection::new(locks, "booking.expire-holds", Duration::from_secs(15))
+//         .spawn(shutdown_tx.subscribe(), |leadership| async move {
+//             loop {
+//                 expire_holds(leadership.fencing_token()).await;
+//                 tokio::time::sleep(Duration::from_secs(60)).await;
+//             }
+//         });
+//
+// Every replica campaigns; the one holding the lock runs the task. When the
+// lock is lost the task's future is dropped mid-flight, so it should only
+// make progress through writes that are safe to repeat or fenced with
+// `fencing_token()`. A task that returns gives leadership up; the replicas
+// then campaign again.
+pub struct LeaderElection {
+    locks: Arc<dyn LockProvider>,
+    name: String,
+    ttl: Duration,
+    retry_interval: Duration,
+    is_leader: Arc<AtomicBool>,
+}
+
+pub struct Leadership {
+    fencing_token: i64,
+    held: watch::Receiver<bool>,
+}
+
+impl Leadership {
+    pub fn fencing_token(&self) -> i64 {
+        self.fencing_token
+    }
+
+    pub fn is_leader(&self) -> bool {
+        *self.held.borrow()
+    }
+}
+
+impl LeaderElection {
+    // Followers try to take over every `ttl`
+    pub fn new(locks: Arc<dyn LockProvider>, name: impl Into<String>, ttl: Duration) -> Self {
+        Self {
+            locks,
+            name: name.into(),
+            ttl,
+            retry_interval: ttl,
+            is_leader: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    pub fn with_retry_interval(mut self, retry_interval: Duration) -> Self {
+        self.retry_interval = retry_interval;
+        self
+    }
+
+    // For health checks and metrics; keep it from `spawn` consuming self
+    pub fn leader_flag(&self) -> Arc<AtomicBool> {
+        self.is_leader.clone()
+    }
+
+    pub fn is_leader(&self) -> bool {
+        self.is_leader.load(Ordering::Relaxed)
+    }
+
+    pub fn spawn<F, Fut>(self, mut shutdown_rx: broadcast::Receiver<()>, task: F) -> JoinHandle<()>
+    where
+        F: Fn(Leadership) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = ()> + Send,
+    {
+        tokio::spawn(async move {
+            loop {
+                let acquired = tokio::select! {
+                    _ = shutdown_rx.recv() => break,
+                    acquired = self.locks.try_acquire(&self.name, self.ttl) => acquired,
+                };
+
+                match acquired {
+                    Ok(Some(lease)) => {
+                        log::info!("Became leader for {} (fencing token {})", self.name, lease.fencing_token());
+                        self.is_leader.store(true, Ordering::Relaxed);
+                        let leadership = Leadership {
+                            fencing_token: lease.fencing_token(),
+                            held: lease.watch(),
+                        };
+                        let mut held = lease.watch();
+
+                        let shutting_down = tokio::select! {
+                            _ = shutdown_rx.recv() => true,
+                            _ = task(leadership) => {
+                                log::info!("Leader task for {} finished, stepping down", self.name);
+                                false
+                            }
+                            _ = wait_until_lost(&mut held) => {
+                                log::warn!("Lost leadership for {}", self.name);
+                                false
+                            }
+                        };
+
+                        self.is_leader.store(false, Ordering::Relaxed);
+                        if let Err(e) = lease.release().await {
+                            log::warn!("Failed to release leadership for {}: {}", self.name, e);
+                        }
+                        if shutting_down {
+                            break;
+                        }
+                    }
+                    Ok(None) => {}
+                    Err(e) => log::warn!("Leader election for {} failed: {}", self.name, e),
+                }
+
+                tokio::select! {
+                    _ = shutdown_rx.recv() => break,
+                    _ = tokio::time::sleep(self.retry_interval) => {}
+                }
+            }
+            log::info!("Leader election for {} stopped", self.name);
+        })
+    }
+}
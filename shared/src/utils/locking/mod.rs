@@ -0,0 +1,95 @@
+mod election;
+mod postgres;
+mod redis;
+
+pub use election::{LeaderElection, Leadership};
+pub use postgres::PgAdvisoryLock;
+pub use redis::RedisLock;
+
+use crate::features::errors::{SystemError, SystemResult};
+use async_trait::async_trait;
+use std::time::Duration;
+use tokio::sync::watch;
+
+// Mutual exclusion across replicas. Two backends:
+//
+// - `RedisLock`: leases that expire unless renewed (renewal is automatic
+//   while the lease is held), for short critical sections such as holding a
+//   booking slot or capturing a payment.
+// - `PgAdvisoryLock`: a session advisory lock held on a dedicated
+//   connection, for services that already depend on Postgres and would
+//   rather not depend on Redis for correctness.
+//
+//     let locks = RedisLock::new(redis_pool, KEYS);
+//     let lease = locks.acquire("booking:42", Duration::from_secs(10), Duration::from_secs(2)).await?;
+//     repo.hold_slot(slot, lease.fencing_token()).await?;
+//     lease.release().await?;
+//
+// A lease can be lost (a long GC-like pause, a network partition, Redis
+// failing over), so work that writes to another system should pass
+// `fencing_token()` along and have that system reject tokens older than the
+// last one it saw.
+#[async_trait]
+pub trait LockProvider: Send + Sync {
+    // Returns None when someone else holds the lock
+    async fn try_acquire(&self, name: &str, ttl: Duration) -> SystemResult<Option<Box<dyn Lease>>>;
+
+    // Retries `try_acquire` until `wait` runs out
+    async fn acquire(&self, name: &str, ttl: Duration, wait: Duration) -> SystemResult<Box<dyn Lease>> {
+        let deadline = tokio::time::Instant::now() + wait;
+        let mut backoff = ACQUIRE_BACKOFF_MIN;
+        loop {
+            if let Some(lease) = self.try_acquire(name, ttl).await? {
+                return Ok(lease);
+            }
+            let now = tokio::time::Instant::now();
+            if now >= deadline {
+                return Err(SystemError::TimeoutError(format!("Timed out waiting for lock {}", name)));
+            }
+            tokio::time::sleep(backoff.min(deadline - now)).await;
+            backoff = (backoff * 2).min(ACQUIRE_BACKOFF_MAX);
+        }
+    }
+}
+
+// Leases are renewed every third of their TTL, so anything shorter would
+// have replicas doing little but renewing
+pub const MIN_LEASE_TTL: Duration = Duration::from_millis(100);
+
+pub(crate) fn check_ttl(name: &str, ttl: Duration) -> SystemResult<()> {
+    if ttl < MIN_LEASE_TTL {
+        return Err(SystemError::ValidationError(format!(
+            "Lock {} TTL of {:?} is below the minimum of {:?}",
+            name, ttl, MIN_LEASE_TTL
+        )));
+    }
+    Ok(())
+}
+
+const ACQUIRE_BACKOFF_MIN: Duration = Duration::from_millis(25);
+const ACQUIRE_BACKOFF_MAX: Duration = Duration::from_millis(500);
+
+#[async_trait]
+pub trait Lease: Send + Sync {
+    fn name(&self) -> &str;
+
+    // Strictly increases with every acquisition of the same lock
+    fn fencing_token(&self) -> i64;
+
+    // False once renewal found the lock gone
+    fn is_held(&self) -> bool;
+
+    // Resolves with false when the lease is lost
+    fn watch(&self) -> watch::Receiver<bool>;
+
+    async fn release(self: Box<Self>) -> SystemResult<()>;
+}
+
+// Resolves once `held` turns false, or never if the lease is released first
+pub(crate) async fn wait_until_lost(held: &mut watch::Receiver<bool>) {
+    while *held.borrow_and_update() {
+        if held.changed().await.is_err() {
+            std::future::pending::<()>().await;
+        }
+    }
+}
@@ -0,0 +1,179 @@
+use super::{check_ttl, Lease, LockProvider};
+use crate::features::errors::SystemResult;
+use async_trait::async_trait;
+use sha2::{Digest, Sha256};
+use sqlx::pool::PoolConnection;
+use sqlx::{Pool, Postgres};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{watch, Mutex};
+use tokio::task::JoinHandle;
+
+// Postgres session advisory locks. A lease keeps one pooled connection for
+// as long as it is held and pings it every third of `ttl`; if the
+// connection dies, Postgres has already released the lock and the lease
+// reports itself lost. Fencing tokens are transaction ids, which only grow.
+#[derive(Clone)]
+pub struct PgAdvisoryLock {
+    pool: Pool<Postgres>,
+    namespace: &'static str,
+}
+
+impl PgAdvisoryLock {
+    // `namespace` keeps lock names of different services apart, as they
+    // share one key space per database
+    pub fn new(pool: Pool<Postgres>, namespace: &'static str) -> Self {
+        Self { pool, namespace }
+    }
+
+    fn lock_key(&self, name: &str) -> i64 {
+        let digest = Sha256::digest(format!("{}:{}", self.namespace, name).as_bytes());
+        i64::from_be_bytes(digest[..8].try_into().expect("sha256 digests are 32 bytes"))
+    }
+}
+
+#[async_trait]
+impl LockProvider for PgAdvisoryLock {
+    #[tracing::instrument(name = "PgAdvisoryLock::try_acquire", skip_all, fields(db.system = "postgresql", lock.name = %name))]
+    async fn try_acquire(&self, name: &str, ttl: Duration) -> SystemResult<Option<Box<dyn Lease>>> {
+        check_ttl(name, ttl)?;
+        let lock_key = self.lock_key(name);
+        let mut conn = self.pool.acquire().await?;
+
+        let locked: bool = sqlx::query_scalar("SELECT pg_try_advisory_lock($1)")
+            .bind(lock_key)
+            .fetch_one(&mut *conn)
+            .await?;
+        if !locked {
+            return Ok(None);
+        }
+
+        let fencing_token: i64 = match sqlx::query_scalar("SELECT txid_current()").fetch_one(&mut *conn).await {
+            Ok(token) => token,
+            Err(e) => {
+                // Closing the session drops the lock with it
+                drop(conn.detach());
+                return Err(e.into());
+            }
+        };
+
+        let conn = Arc::new(Mutex::new(Some(conn)));
+        let (held_tx, held) = watch::channel(true);
+        let monitor = spawn_monitor(conn.clone(), ttl, held_tx);
+        log::debug!("Acquired advisory lock {} (fencing token {})", name, fencing_token);
+        Ok(Some(Box::new(PgLease {
+            name: name.to_string(),
+            lock_key,
+            fencing_token,
+            conn,
+            held,
+            monitor,
+            released: false,
+        })))
+    }
+}
+
+struct PgLease {
+    name: String,
+    lock_key: i64,
+    fencing_token: i64,
+    conn: Arc<Mutex<Option<PoolConnection<Postgres>>>>,
+    held: watch::Receiver<bool>,
+    monitor: JoinHandle<()>,
+    released: bool,
+}
+
+#[async_trait]
+impl Lease for PgLease {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn fencing_token(&self) -> i64 {
+        self.fencing_token
+    }
+
+    fn is_held(&self) -> bool {
+        *self.held.borrow()
+    }
+
+    fn watch(&self) -> watch::Receiver<bool> {
+        self.held.clone()
+    }
+
+    async fn release(mut self: Box<Self>) -> SystemResult<()> {
+        self.released = true;
+        self.monitor.abort();
+        let conn = self.conn.lock().await.take();
+        unlock(conn, self.lock_key).await
+    }
+}
+
+impl Drop for PgLease {
+    fn drop(&mut self) {
+        if self.released {
+            return;
+        }
+        self.monitor.abort();
+        let (conn, lock_key) = (self.conn.clone(), self.lock_key);
+        match tokio::runtime::Handle::try_current() {
+            Ok(runtime) => {
+                runtime.spawn(async move {
+                    let conn = conn.lock().await.take();
+                    if let Err(e) = unlock(conn, lock_key).await {
+                        log::warn!("Failed to release dropped advisory lock: {}", e);
+                    }
+                });
+            }
+            Err(_) => {
+                if let Some(conn) = conn.try_lock().ok().and_then(|mut conn| conn.take()) {
+                    drop(conn.detach());
+                }
+            }
+        }
+    }
+}
+
+// Hands the connection back to the pool only once the lock is gone from
+// its session; otherwise closes it, which releases the lock too
+async fn unlock(conn: Option<PoolConnection<Postgres>>, lock_key: i64) -> SystemResult<()> {
+    let Some(mut conn) = conn else {
+        return Ok(());
+    };
+    let result = sqlx::query_scalar::<_, bool>("SELECT pg_advisory_unlock($1)")
+        .bind(lock_key)
+        .fetch_one(&mut *conn)
+        .await;
+    if let Err(e) = result {
+        drop(conn.detach());
+        return Err(e.into());
+    }
+    Ok(())
+}
+
+fn spawn_monitor(
+    conn: Arc<Mutex<Option<PoolConnection<Postgres>>>>,
+    ttl: Duration,
+    held: watch::Sender<bool>,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(ttl / 3);
+        interval.tick().await;
+
+        loop {
+            interval.tick().await;
+            let mut guard = conn.lock().await;
+            let Some(session) = guard.as_mut() else {
+                return;
+            };
+            if let Err(e) = sqlx::query("SELECT 1").execute(&mut **session).await {
+                log::warn!("Advisory lock connection failed, the lock is lost: {}", e);
+                if let Some(session) = guard.take() {
+                    drop(session.detach());
+                }
+                let _ = held.send(false);
+                return;
+            }
+        }
+    })
+}
@@ -0,0 +1,199 @@
+use super::{check_ttl, Lease, LockProvider};
+use crate::features::errors::{SystemError, SystemResult};
+use crate::utils::caching::CacheKeys;
+use async_trait::async_trait;
+use deadpool_redis::redis;
+use deadpool_redis::Pool;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+use tokio::time::Instant;
+use uuid::Uuid;
+
+// Takes the lock and, only if it did, bumps the lock's fencing counter.
+// KEYS: lock, fence counter; ARGV: owner token, ttl in ms
+const ACQUIRE: &str = r#"
+if redis.call('SET', KEYS[1], ARGV[1], 'NX', 'PX', ARGV[2]) then
+    return redis.call('INCR', KEYS[2])
+end
+return 0
+"#;
+
+// Both only touch the lock while it is still ours, so a lease that expired
+// and was taken by another replica is left alone.
+// KEYS: lock; ARGV: owner token[, ttl in ms]
+const RENEW: &str = r#"
+if redis.call('GET', KEYS[1]) == ARGV[1] then
+    return redis.call('PEXPIRE', KEYS[1], ARGV[2])
+end
+return 0
+"#;
+
+const RELEASE: &str = r#"
+if redis.call('GET', KEYS[1]) == ARGV[1] then
+    return redis.call('DEL', KEYS[1])
+end
+return 0
+"#;
+
+// Redis leases (SET NX PX), renewed every third of their TTL while held
+#[derive(Clone)]
+pub struct RedisLock {
+    pool: Arc<Pool>,
+    keys: CacheKeys,
+}
+
+impl RedisLock {
+    pub fn new(pool: Arc<Pool>, keys: CacheKeys) -> Self {
+        Self { pool, keys }
+    }
+}
+
+#[async_trait]
+impl LockProvider for RedisLock {
+    #[tracing::instrument(name = "redis.lock_acquire", skip_all, fields(db.system = "redis", lock.name = %name))]
+    async fn try_acquire(&self, name: &str, ttl: Duration) -> SystemResult<Option<Box<dyn Lease>>> {
+        check_ttl(name, ttl)?;
+        let key = self.keys.key("lock", name);
+        let token = Uuid::new_v4().to_string();
+
+        let mut conn = self.pool.get().await.map_err(|e| SystemError::RedisError(e.to_string()))?;
+        let fencing_token: i64 = redis::cmd("EVAL")
+            .arg(ACQUIRE)
+            .arg(2)
+            .arg(&key)
+            .arg(self.keys.key("lock_fence", name))
+            .arg(&token)
+            .arg(ttl.as_millis() as u64)
+            .query_async(&mut conn)
+            .await
+            .map_err(|e| SystemError::RedisError(e.to_string()))?;
+        if fencing_token == 0 {
+            return Ok(None);
+        }
+
+        let (held_tx, held) = watch::channel(true);
+        let renewal = spawn_renewal(self.pool.clone(), key.clone(), token.clone(), ttl, held_tx);
+        log::debug!("Acquired lock {} (fencing token {})", name, fencing_token);
+        Ok(Some(Box::new(RedisLease {
+            name: name.to_string(),
+            key,
+            token,
+            fencing_token,
+            pool: self.pool.clone(),
+            held,
+            renewal,
+            released: false,
+        })))
+    }
+}
+
+struct RedisLease {
+    name: String,
+    key: String,
+    token: String,
+    fencing_token: i64,
+    pool: Arc<Pool>,
+    held: watch::Receiver<bool>,
+    renewal: JoinHandle<()>,
+    released: bool,
+}
+
+#[async_trait]
+impl Lease for RedisLease {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn fencing_token(&self) -> i64 {
+        self.fencing_token
+    }
+
+    fn is_held(&self) -> bool {
+        *self.held.borrow()
+    }
+
+    fn watch(&self) -> watch::Receiver<bool> {
+        self.held.clone()
+    }
+
+    async fn release(mut self: Box<Self>) -> SystemResult<()> {
+        self.released = true;
+        self.renewal.abort();
+        release(&self.pool, &self.key, &self.token).await
+    }
+}
+
+// A lease dropped without `release` is released in the background rather
+// than left to expire
+impl Drop for RedisLease {
+    fn drop(&mut self) {
+        if self.released {
+            return;
+        }
+        self.renewal.abort();
+        if let Ok(runtime) = tokio::runtime::Handle::try_current() {
+            let (pool, key, token) = (self.pool.clone(), self.key.clone(), self.token.clone());
+            runtime.spawn(async move {
+                if let Err(e) = release(&pool, &key, &token).await {
+                    log::warn!("Failed to release dropped lock {}: {}", key, e);
+                }
+            });
+        }
+    }
+}
+
+async fn release(pool: &Pool, key: &str, token: &str) -> SystemResult<()> {
+    let mut conn = pool.get().await.map_err(|e| SystemError::RedisError(e.to_string()))?;
+    let _: i64 = redis::cmd("EVAL")
+        .arg(RELEASE)
+        .arg(1)
+        .arg(key)
+        .arg(token)
+        .query_async(&mut conn)
+        .await
+        .map_err(|e| SystemError::RedisError(e.to_string()))?;
+    Ok(())
+}
+
+// Keeps extending the lease. It counts as lost when the key is no longer
+// ours, or when Redis could not be reached for a whole TTL, after which
+// another replica may already hold it.
+fn spawn_renewal(pool: Arc<Pool>, key: String, token: String, ttl: Duration, held: watch::Sender<bool>) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut last_renewed = Instant::now();
+        let mut interval = tokio::time::interval(ttl / 3);
+        interval.tick().await;
+
+        loop {
+            interval.tick().await;
+            let renewed = match pool.get().await {
+                Ok(mut conn) => redis::cmd("EVAL")
+                    .arg(RENEW)
+                    .arg(1)
+                    .arg(&key)
+                    .arg(&token)
+                    .arg(ttl.as_millis() as u64)
+                    .query_async::<i64>(&mut conn)
+                    .await
+                    .map_err(|e| e.to_string()),
+                Err(e) => Err(e.to_string()),
+            };
+            match renewed {
+                Ok(1) => last_renewed = Instant::now(),
+                Ok(_) => {
+                    log::warn!("Lock {} was lost: it expired and may be held elsewhere", key);
+                    let _ = held.send(false);
+                    return;
+                }
+                Err(e) if last_renewed.elapsed() >= ttl => {
+                    log::warn!("Lock {} was lost: could not renew it within its TTL: {}", key, e);
+                    let _ = held.send(false);
+                    return;
+                }
+                Err(e) => log::warn!("Failed to renew lock {}, retrying: {}", key, e),
+            }
+        }
+    })
+}
@@ -0,0 +1,302 @@
+use crate::config::messaging_config::MessagingConfig;
+use crate::features::errors::{SystemError, SystemResult};
+use crate::utils::messaging::retry::RetryPolicy;
+use crate::utils::messaging::topology::{declare_exchanges, declare_queue, QueueTopology};
+use lapin::options::{BasicQosOptions, ConfirmSelectOptions, QueueDeclareOptions};
+use lapin::types::FieldTable;
+use lapin::{Channel, Connection, ConnectionProperties};
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::{watch, Notify, RwLock};
+
+const MIN_RECONNECT_DELAY: Duration = Duration::from_secs(1);
+const MAX_RECONNECT_DELAY: Duration = Duration::from_secs(30);
+const CONNECTION_CHECK_INTERVAL: Duration = Duration::from_secs(5);
+
+// Broker connectivity as reported by health checks
+#[derive(Debug, Clone, Serialize)]
+pub struct BrokerHealth {
+    pub connected: bool,
+    pub reconnects: u64,
+    pub last_error: Option<String>,
+}
+
+struct Connected {
+    connection: Connection,
+    channels: Vec<Channel>, // publisher pool, all in confirm mode
+}
+
+// Owns the AMQP connection. A supervisor task reconnects with exponential
+// backoff whenever the connection drops and then declares the exchanges and
+// every queue set up so far again. Consumers watch `generation` to know when
+// to resubscribe.
+pub(crate) struct ConnectionManager {
+    config: MessagingConfig,
+    retry_policy: RetryPolicy,
+    state: RwLock<Option<Connected>>,
+    topology: Mutex<Vec<QueueTopology>>,
+    reconnect_lock: tokio::sync::Mutex<()>,
+    generation: watch::Sender<u64>,
+    next_channel: AtomicUsize,
+    reconnects: AtomicU64,
+    last_error: Mutex<Option<String>>,
+    disconnected: Notify,
+    closed: AtomicBool,
+}
+
+fn broker_error(e: lapin::Error) -> SystemError {
+    SystemError::MessageBrokerError(e.to_string())
+}
+
+impl ConnectionManager {
+    pub async fn connect(config: &MessagingConfig, retry_policy: RetryPolicy) -> SystemResult<Arc<Self>> {
+        let (generation, _) = watch::channel(0);
+        let manager = Arc::new(Self {
+            config: config.clone(),
+            retry_policy,
+            state: RwLock::new(None),
+            topology: Mutex::new(Vec::new()),
+            reconnect_lock: tokio::sync::Mutex::new(()),
+            generation,
+            next_channel: AtomicUsize::new(0),
+            reconnects: AtomicU64::new(0),
+            last_error: Mutex::new(None),
+            disconnected: Notify::new(),
+            closed: AtomicBool::new(false),
+        });
+
+        // The first connection must succeed; later outages are recovered
+        manager.try_reconnect().await?;
+        Self::spawn_supervisor(manager.clone());
+        Ok(manager)
+    }
+
+    pub fn retry_policy(&self) -> &RetryPolicy {
+        &self.retry_policy
+    }
+
+    pub async fn is_connected(&self) -> bool {
+        self.state
+            .read()
+            .await
+            .as_ref()
+            .map(|state| state.connection.status().connected())
+            .unwrap_or(false)
+    }
+
+    pub async fn health(&self) -> BrokerHealth {
+        BrokerHealth {
+            connected: self.is_connected().await,
+            reconnects: self.reconnects.load(Ordering::Relaxed),
+            last_error: self.last_error.lock().ok().and_then(|e| e.clone()),
+        }
+    }
+
+    pub fn subscribe_generation(&self) -> watch::Receiver<u64> {
+        self.generation.subscribe()
+    }
+
+    // A pooled publisher channel. If the connection is down, one reconnect
+    // attempt is made before giving up, so callers fail fast while the
+    // supervisor keeps retrying in the background.
+    pub async fn channel(&self) -> SystemResult<Channel> {
+        if let Some(channel) = self.pooled_channel().await {
+            return Ok(channel);
+        }
+        self.try_reconnect().await?;
+        self.pooled_channel()
+            .await
+            .ok_or_else(|| SystemError::MessageBrokerError("Not connected to RabbitMQ".to_string()))
+    }
+
+    async fn pooled_channel(&self) -> Option<Channel> {
+        let state = self.state.read().await;
+        let state = state.as_ref().filter(|s| s.connection.status().connected())?;
+        let start = self.next_channel.fetch_add(1, Ordering::Relaxed);
+        (0..state.channels.len())
+            .map(|offset| &state.channels[(start + offset) % state.channels.len()])
+            .find(|channel| channel.status().connected())
+            .cloned()
+    }
+
+    // A dedicated channel for one consumer, with the configured prefetch
+    pub async fn consumer_channel(&self) -> SystemResult<Channel> {
+        let state = self.state.read().await;
+        let state = state
+            .as_ref()
+            .filter(|s| s.connection.status().connected())
+            .ok_or_else(|| SystemError::MessageBrokerError("Not connected to RabbitMQ".to_string()))?;
+
+        let channel = state.connection.create_channel().await.map_err(broker_error)?;
+        channel
+            .basic_qos(self.config.prefetch_count, BasicQosOptions::default())
+            .await
+            .map_err(broker_error)?;
+        Ok(channel)
+    }
+
+    // Declares the queue now and again after every reconnect
+    pub async fn declare_queue(&self, topology: QueueTopology) -> SystemResult<()> {
+        if let Ok(mut declared) = self.topology.lock() {
+            if !declared.contains(&topology) {
+                declared.retain(|t| t.queue_name != topology.queue_name);
+                declared.push(topology.clone());
+            }
+        }
+        let channel = self.channel().await?;
+        declare_queue(&channel, &topology, &self.retry_policy).await
+    }
+
+    pub fn declared_queues(&self) -> Vec<String> {
+        self.topology
+            .lock()
+            .map(|topology| topology.iter().map(|t| t.queue_name.clone()).collect())
+            .unwrap_or_default()
+    }
+
+    // Ready messages and consumers of a queue. Uses a throwaway channel since
+    // a passive declare of a missing queue closes the channel.
+    pub async fn queue_stats(&self, queue_name: &str) -> SystemResult<(u32, u32)> {
+        let channel = self.consumer_channel().await?;
+        let queue = channel
+            .queue_declare(
+                queue_name,
+                QueueDeclareOptions {
+                    passive: true,
+                    ..Default::default()
+                },
+                FieldTable::default(),
+            )
+            .await
+            .map_err(broker_error)?;
+        if channel.status().connected() {
+            let _ = channel.close(200, "Queue sampled").await;
+        }
+        Ok((queue.message_count(), queue.consumer_count()))
+    }
+
+    async fn try_reconnect(&self) -> SystemResult<()> {
+        let _guard = self.reconnect_lock.lock().await;
+        if self.closed.load(Ordering::Relaxed) {
+            return Err(SystemError::MessageBrokerError("MessageBroker is closed".to_string()));
+        }
+        if self.is_connected().await {
+            return Ok(());
+        }
+
+        match self.open().await {
+            Ok(connected) => {
+                let first = self.state.write().await.replace(connected).is_none();
+                if !first {
+                    self.reconnects.fetch_add(1, Ordering::Relaxed);
+                    log::info!("Reconnected to RabbitMQ");
+                }
+                if let Ok(mut last_error) = self.last_error.lock() {
+                    *last_error = None;
+                }
+                self.generation.send_modify(|generation| *generation += 1);
+                Ok(())
+            }
+            Err(e) => {
+                if let Ok(mut last_error) = self.last_error.lock() {
+                    *last_error = Some(e.to_string());
+                }
+                Err(e)
+            }
+        }
+    }
+
+    async fn open(&self) -> SystemResult<Connected> {
+        let uri = self.uri();
+        let connect = Connection::connect(&uri, ConnectionProperties::default());
+        let connection = tokio::time::timeout(Duration::from_secs(self.config.connection_timeout), connect)
+            .await
+            .map_err(|_| {
+                SystemError::MessageBrokerError(format!(
+                    "Timed out connecting to RabbitMQ after {}s",
+                    self.config.connection_timeout
+                ))
+            })?
+            .map_err(broker_error)?;
+
+        let mut channels = Vec::with_capacity(self.config.channel_pool_size.max(1));
+        for _ in 0..self.config.channel_pool_size.max(1) {
+            let channel = connection.create_channel().await.map_err(broker_error)?;
+            // Publisher confirms, so a publish only succeeds once the broker has the message
+            channel
+                .confirm_select(ConfirmSelectOptions::default())
+                .await
+                .map_err(broker_error)?;
+            channels.push(channel);
+        }
+
+        declare_exchanges(&channels[0]).await?;
+        let topology = self.topology.lock().map(|t| t.clone()).unwrap_or_default();
+        for queue in &topology {
+            declare_queue(&channels[0], queue, &self.retry_policy).await?;
+        }
+
+        Ok(Connected { connection, channels })
+    }
+
+    // Heartbeat is negotiated through the AMQP URI
+    fn uri(&self) -> String {
+        let url = &self.config.rabbitmq_url;
+        if url.contains("heartbeat=") {
+            return url.clone();
+        }
+        let separator = if url.contains('?') { '&' } else { '?' };
+        format!("{}{}heartbeat={}", url, separator, self.config.heartbeat)
+    }
+
+    fn spawn_supervisor(manager: Arc<Self>) {
+        tokio::spawn(async move {
+            let mut delay = MIN_RECONNECT_DELAY;
+            loop {
+                tokio::select! {
+                    _ = manager.disconnected.notified() => {}
+                    _ = tokio::time::sleep(CONNECTION_CHECK_INTERVAL) => {}
+                }
+                if manager.closed.load(Ordering::Relaxed) {
+                    break;
+                }
+
+                while !manager.is_connected().await && !manager.closed.load(Ordering::Relaxed) {
+                    match manager.try_reconnect().await {
+                        Ok(()) => delay = MIN_RECONNECT_DELAY,
+                        Err(e) => {
+                            log::warn!("RabbitMQ reconnect failed, retrying in {:?}: {}", delay, e);
+                            tokio::time::sleep(delay).await;
+                            delay = (delay * 2).min(MAX_RECONNECT_DELAY);
+                        }
+                    }
+                }
+            }
+        });
+    }
+
+    // Consumers call this when their channel goes away, so the supervisor
+    // does not wait for its next periodic check
+    pub fn report_disconnect(&self) {
+        self.disconnected.notify_one();
+    }
+
+    pub async fn close(&self) -> SystemResult<()> {
+        self.closed.store(true, Ordering::Relaxed);
+        self.disconnected.notify_one();
+
+        if let Some(state) = self.state.write().await.take() {
+            for channel in &state.channels {
+                if channel.status().connected() {
+                    channel.close(200, "Normal shutdown").await.map_err(broker_error)?;
+                }
+            }
+            if state.connection.status().connected() {
+                state.connection.close(200, "Normal shutdown").await.map_err(broker_error)?;
+            }
+        }
+        Ok(())
+    }
+}
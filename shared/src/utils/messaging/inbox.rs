@@ -0,0 +1,198 @@
+use crate::events::{DomainEvent, EventEnvelope};
+use crate::features::errors::SystemResult;
+use crate::utils::caching::CacheService;
+use crate::utils::messaging::retry::MessageError;
+use async_trait::async_trait;
+use futures::future::BoxFuture;
+use sqlx::{Pool, Postgres, Transaction};
+use std::future::Future;
+use std::sync::Arc;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LedgerClaim {
+    // First time this consumer sees the event; it must call `complete` or `release`
+    Acquired,
+    // Already handled; the delivery is a duplicate and can be acked
+    AlreadyProcessed,
+    // Another delivery of the same event is being handled right now
+    InProgress,
+}
+
+// Records which events a consumer has processed, so redeliveries of an event
+// (RabbitMQ is at-least-once) do not repeat its side effects
+#[async_trait]
+pub trait ProcessedEventLedger: Send + Sync {
+    async fn claim(&self, consumer: &str, event_id: Uuid) -> SystemResult<LedgerClaim>;
+    async fn complete(&self, consumer: &str, event_id: Uuid) -> SystemResult<()>;
+    // Gives up a claim after a failure so the retried delivery is handled again
+    async fn release(&self, consumer: &str, event_id: Uuid) -> SystemResult<()>;
+}
+
+// Redis ledger for handlers without database side effects. A claim is held
+// for `processing_ttl_seconds` so a crashed handler doesn't block the event
+// forever; completed events are remembered for `processed_ttl_seconds`, which
+// must outlast the longest retry backoff.
+#[derive(Clone)]
+pub struct RedisEventLedger {
+    cache: CacheService,
+    processing_ttl_seconds: u64,
+    processed_ttl_seconds: u64,
+}
+
+const PROCESSING: &str = "processing";
+const PROCESSED: &str = "processed";
+
+impl RedisEventLedger {
+    pub fn new(cache: CacheService, processing_ttl_seconds: u64, processed_ttl_seconds: u64) -> Self {
+        Self {
+            cache,
+            processing_ttl_seconds,
+            processed_ttl_seconds,
+        }
+    }
+
+    fn key(consumer: &str, event_id: Uuid) -> String {
+        format!("inbox:{}:{}", consumer, event_id)
+    }
+}
+
+#[async_trait]
+impl ProcessedEventLedger for RedisEventLedger {
+    async fn claim(&self, consumer: &str, event_id: Uuid) -> SystemResult<LedgerClaim> {
+        let key = Self::key(consumer, event_id);
+        if self
+            .cache
+            .set_if_absent(&key, PROCESSING, self.processing_ttl_seconds)
+            .await?
+        {
+            return Ok(LedgerClaim::Acquired);
+        }
+
+        match self.cache.get::<String>(&key).await?.as_deref() {
+            Some(PROCESSED) => Ok(LedgerClaim::AlreadyProcessed),
+            Some(_) => Ok(LedgerClaim::InProgress),
+            // Expired between the two calls; let the retry claim it
+            None => Ok(LedgerClaim::InProgress),
+        }
+    }
+
+    async fn complete(&self, consumer: &str, event_id: Uuid) -> SystemResult<()> {
+        self.cache
+            .set(&Self::key(consumer, event_id), PROCESSED, Some(self.processed_ttl_seconds))
+            .await
+    }
+
+    async fn release(&self, consumer: &str, event_id: Uuid) -> SystemResult<()> {
+        self.cache.delete(&Self::key(consumer, event_id)).await
+    }
+}
+
+// Wraps a `subscribe` handler so each event is handled at most once per
+// consumer. Duplicates are acked without calling the handler; a delivery that
+// races another one of the same event is retried later.
+pub fn deduplicate<E, F, Fut>(
+    ledger: Arc<dyn ProcessedEventLedger>,
+    consumer: &str,
+    handler: F,
+) -> impl Fn(EventEnvelope<E>) -> BoxFuture<'static, Result<(), MessageError>> + Send + Sync + 'static
+where
+    E: DomainEvent,
+    F: Fn(EventEnvelope<E>) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = Result<(), MessageError>> + Send + 'static,
+{
+    let consumer = consumer.to_string();
+    let handler = Arc::new(handler);
+
+    move |envelope: EventEnvelope<E>| {
+        let ledger = ledger.clone();
+        let consumer = consumer.clone();
+        let handler = handler.clone();
+
+        Box::pin(async move {
+            let event_id = envelope.event_id;
+            match ledger.claim(&consumer, event_id).await? {
+                LedgerClaim::Acquired => {}
+                LedgerClaim::AlreadyProcessed => {
+                    log::info!("Skipping duplicate event {} for {}", event_id, consumer);
+                    return Ok(());
+                }
+                LedgerClaim::InProgress => {
+                    return Err(MessageError::Transient(format!(
+                        "Event {} is already being handled by {}",
+                        event_id, consumer
+                    )));
+                }
+            }
+
+            match handler(envelope).await {
+                Ok(()) => {
+                    ledger.complete(&consumer, event_id).await?;
+                    Ok(())
+                }
+                Err(error) => {
+                    if let Err(e) = ledger.release(&consumer, event_id).await {
+                        log::warn!("Failed to release event {} for {}: {}", event_id, consumer, e);
+                    }
+                    Err(error)
+                }
+            }
+        })
+    }
+}
+
+// Postgres inbox for handlers whose side effects are database writes. The
+// inbox row and the side effects commit in one transaction:
+//
+//     let Some(mut tx) = inbox.begin("billing", envelope.event_id).await? else {
+//         return Ok(()); // duplicate
+//     };
+//     sqlx::query("...").execute(&mut *tx).await?;
+//     tx.commit().await?;
+//
+// A concurrent delivery of the same event blocks on the primary key until the
+// first transaction ends, then sees the row and skips.
+#[derive(Clone)]
+pub struct PostgresInbox {
+    pool: Pool<Postgres>,
+}
+
+impl PostgresInbox {
+    pub fn new(pool: Pool<Postgres>) -> Self {
+        Self { pool }
+    }
+
+    // Returns `None` if the consumer already processed the event
+    #[tracing::instrument(name = "PostgresInbox::begin", skip_all, fields(db.system = "postgresql"))]
+    pub async fn begin(&self, consumer: &str, event_id: Uuid) -> SystemResult<Option<Transaction<'static, Postgres>>> {
+        let mut tx = self.pool.begin().await?;
+
+        let inserted = sqlx::query(
+            r#"
+            INSERT INTO inbox (consumer, event_id)
+            VALUES ($1, $2)
+            ON CONFLICT (consumer, event_id) DO NOTHING
+            "#
+        )
+        .bind(consumer)
+        .bind(event_id)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+
+        if inserted == 0 {
+            tx.rollback().await?;
+            return Ok(None);
+        }
+        Ok(Some(tx))
+    }
+
+    #[tracing::instrument(name = "PostgresInbox::purge_older_than", skip_all, fields(db.system = "postgresql"))]
+    pub async fn purge_older_than(&self, days: i32) -> SystemResult<u64> {
+        let result = sqlx::query("DELETE FROM inbox WHERE processed_at < NOW() - make_interval(days => $1)")
+            .bind(days)
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
@@ -1,117 +1,160 @@
-use lapin::{options::*, types::FieldTable, BasicProperties, Channel, Connection, ConnectionProperties};
+mod connection;
+pub mod inbox;
+pub mod outbox;
+mod publisher;
+pub mod retry;
+mod topology;
+
+use lapin::{
+    message::Delivery,
+    options::*,
+    publisher_confirm::Confirmation,
+    types::{AMQPValue, FieldTable},
+    BasicProperties, Channel, Consumer,
+};
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::broadcast;
 use futures::stream::StreamExt;
+use tracing::Instrument;
 use crate::config::messaging_config::MessagingConfig;
-use crate::events::{ExchangeType, RoutingKey};
+use crate::events::{DomainEvent, EventEnvelope, ExchangeType};
 use crate::features::errors::{SystemError, SystemResult};
+use crate::features::metrics::{MESSAGES_CONSUMED_TOTAL, MESSAGES_PUBLISHED_TOTAL, QUEUE_CONSUMERS, QUEUE_MESSAGES};
+use crate::features::observability::link_parent;
+use crate::features::observability::propagation::{extract, inject_current, AmqpHeaderExtractor, AmqpHeaderInjector};
+use connection::ConnectionManager;
+pub use connection::BrokerHealth;
+pub use publisher::Publisher;
+use retry::{
+    dead_letter_exchange, dead_letter_queue, header_str, header_u32, retry_queue, string_value, DeadLetter,
+    MessageError, ReceivedMessage, RetryPolicy, LAST_ERROR_HEADER, ORIGINAL_EXCHANGE_HEADER,
+    ORIGINAL_ROUTING_KEY_HEADER, RETRY_COUNT_HEADER,
+};
+use topology::QueueTopology;
+
+// How long a consumer waits before trying to resubscribe when the connection
+// is up but its channel could not be opened (e.g. the queue was deleted)
+const RESUBSCRIBE_DELAY: Duration = Duration::from_secs(5);
 
+// Cloning is cheap; clones share the connection, channel pool and topology
 #[derive(Clone)]
 pub struct MessageBroker {
-    channel: Arc<Channel>,
-    connection: Arc<Connection>,
+    connection: Arc<ConnectionManager>,
 }
 
 impl MessageBroker {
-    fn get_channel(&self) -> &Channel {
-        self.channel.as_ref()
+    pub async fn new(config: &MessagingConfig) -> SystemResult<Self> {
+        let connection = ConnectionManager::connect(config, RetryPolicy::from_config(config)).await?;
+        Ok(Self { connection })
     }
 
-    fn get_connection(&self) -> &Connection {
-        self.connection.as_ref()
+    fn retry_policy(&self) -> &RetryPolicy {
+        self.connection.retry_policy()
     }
 
-    pub async fn new(config: &MessagingConfig) -> SystemResult<Self> {
-        let connection = Connection::connect((&config.rabbitmq_url).as_ref(), ConnectionProperties::default())
-            .await
-            .map_err(|e| SystemError::MessageBrokerError(e.to_string()))?;
+    pub async fn health(&self) -> BrokerHealth {
+        self.connection.health().await
+    }
 
-        let channel = connection
-            .create_channel()
+    // Declares the queue together with its retry queues and dead-letter
+    // exchange/queue (see `retry` for the layout). The declaration is repeated
+    // after every reconnect. Queue arguments cannot be changed on an existing
+    // queue, so a queue declared before dead-lettering was added, or with a
+    // different retry policy, must be deleted first.
+    pub async fn setup_queue(&self, queue_name: &str, routing_keys: &[&str], exchange_type: ExchangeType) -> SystemResult<()> {
+        self.connection
+            .declare_queue(QueueTopology {
+                queue_name: queue_name.to_string(),
+                routing_keys: routing_keys.iter().map(|key| key.to_string()).collect(),
+                exchange_type,
+            })
             .await
-            .map_err(|e| SystemError::MessageBrokerError(e.to_string()))?;
+    }
 
-        // Declare custom exchanges
-        for exchange_type in [ExchangeType::Topic, ExchangeType::Fanout, ExchangeType::Direct, ExchangeType::Headers] {
-            channel
-                .exchange_declare(
-                    &exchange_type.to_string(),
-                    exchange_type.into(),
-                    ExchangeDeclareOptions {
-                        durable: true,
-                        ..Default::default()
-                    },
-                    FieldTable::default(),
-                )
-                .await
-                .map_err(|e| SystemError::MessageBrokerError(e.to_string()))?;
-        }
+    pub async fn publish<E: DomainEvent>(&self, envelope: &EventEnvelope<E>) -> SystemResult<()> {
+        let payload = envelope.to_bytes().map_err(SystemError::MessageBrokerError)?;
+        log::debug!("Publishing {} to {}", envelope.event_id, envelope.routing_key());
 
-        Ok(Self {
-            channel: Arc::new(channel),
-            connection: Arc::new(connection),
-        })
+        self.publish_raw(&envelope.exchange().to_string(), envelope.routing_key(), payload.as_ref())
+            .await
     }
 
-    pub async fn setup_queue(&self, queue_name: &str, routing_keys: &[RoutingKey], exchange_type: ExchangeType) -> SystemResult<()> {
-        self.get_channel()
-            .queue_declare(
-                queue_name,
-                QueueDeclareOptions {
-                    durable: true,
-                    ..Default::default()
-                },
-                FieldTable::default(),
-            )
+    // Publishes on `E`'s exchange with a routing key chosen at runtime, for
+    // messages addressed per instance such as saga commands and replies
+    pub async fn publish_to<E: DomainEvent>(&self, envelope: &EventEnvelope<E>, routing_key: &str) -> SystemResult<()> {
+        let payload = envelope.to_bytes().map_err(SystemError::MessageBrokerError)?;
+        self.publish_raw(&envelope.exchange().to_string(), routing_key, payload.as_ref())
             .await
-            .map_err(|e| SystemError::MessageBrokerError(e.to_string()))?;
+    }
 
-        // Skip binding for fanout exchange if no routing keys are provided
-        if exchange_type != ExchangeType::Fanout || !routing_keys.is_empty() {
-            for routing_key in routing_keys {
-                self.get_channel()
-                    .queue_bind(
-                        queue_name,
-                        &exchange_type.to_string(),
-                        &routing_key.to_string(),
-                        QueueBindOptions::default(),
-                        FieldTable::default(),
-                    )
-                    .await
-                    .map_err(|e| SystemError::MessageBrokerError(e.to_string()))?;
-            }
-        }
-        Ok(())
+    // Publishes an already serialized payload and waits for the broker's
+    // confirm. The current trace context travels in the message headers.
+    pub async fn publish_raw(&self, exchange: &str, routing_key: &str, payload: &[u8]) -> SystemResult<()> {
+        let span = tracing::info_span!(
+            "amqp.publish",
+            otel.name = %format!("{} publish", routing_key),
+            otel.kind = "producer",
+            messaging.system = "rabbitmq",
+            messaging.destination.name = %exchange,
+            messaging.rabbitmq.routing_key = %routing_key,
+        );
+        let mut headers = FieldTable::default();
+        span.in_scope(|| inject_current(&mut AmqpHeaderInjector(&mut headers)));
+
+        let properties = BasicProperties::default()
+            .with_delivery_mode(2)
+            .with_content_type("application/json".into())
+            .with_headers(headers);
+        self.publish_with_properties(exchange, routing_key, payload, properties)
+            .instrument(span)
+            .await
     }
 
-    pub async fn publish<T: serde::Serialize>(
+    async fn publish_with_properties(
         &self,
-        routing_key: RoutingKey,
-        payload: T,
-        exchange_type: ExchangeType,
+        exchange: &str,
+        routing_key: &str,
+        payload: &[u8],
+        properties: BasicProperties,
     ) -> SystemResult<()> {
-        let payload = serde_json::to_vec(&payload)
-            .map_err(|e| SystemError::MessageBrokerError(format!("Failed to serialize payload: {}", e)))?;
-        println!("Publishing to {}: {:?}", routing_key.to_string(), String::from_utf8_lossy(payload.as_ref()));
-
-        let confirm = self
-            .get_channel()
-            .basic_publish(
-                exchange_type.to_string().as_ref(),
-                routing_key.to_string().as_ref(),
-                BasicPublishOptions::default(),
-                payload.as_ref(),
-                BasicProperties::default().with_delivery_mode(2),
-            )
-            .await
-            .map_err(|e| SystemError::MessageBrokerError(e.to_string()))?;
+        let confirmation = async {
+            self.connection
+                .channel()
+                .await?
+                .basic_publish(
+                    exchange,
+                    routing_key,
+                    BasicPublishOptions::default(),
+                    payload,
+                    properties,
+                )
+                .await
+                .map_err(|e| SystemError::MessageBrokerError(e.to_string()))?
+                .await
+                .map_err(|e| SystemError::MessageBrokerError(e.to_string()))
+        }
+        .await;
 
-        confirm
-            .await
-            .map_err(|e| SystemError::MessageBrokerError(e.to_string()))?;
-        Ok(())
+        let (outcome, result) = match confirmation {
+            Ok(Confirmation::Nack(_)) => (
+                "rejected",
+                Err(SystemError::MessageBrokerError(format!(
+                    "Broker rejected message for {}",
+                    routing_key
+                ))),
+            ),
+            Ok(_) => ("confirmed", Ok(())),
+            Err(e) => ("failed", Err(e)),
+        };
+        MESSAGES_PUBLISHED_TOTAL.with_label_values(&[exchange, outcome]).inc();
+        result
     }
 
+    // Runs `handler` for every delivery until shutdown. A failing message
+    // never stops the loop: transient failures are retried through the
+    // delayed retry queues with exponential backoff, and poison messages or
+    // messages out of retries are dead-lettered.
     pub async fn consume<F, Fut>(
         &self,
         queue_name: &str,
@@ -120,11 +163,70 @@ impl MessageBroker {
         handler: F,
     ) -> SystemResult<()>
     where
-        F: Fn(lapin::message::Delivery) -> Fut + Send + Sync + 'static,
-        Fut: std::future::Future<Output = SystemResult<()>> + Send,
+        F: Fn(ReceivedMessage) -> Fut + Send + Sync + 'static,
+        Fut: std::future::Future<Output = Result<(), MessageError>> + Send,
     {
-        let mut consumer = self
-            .get_channel()
+        let mut generation = self.connection.subscribe_generation();
+
+        // Resubscribes whenever the channel or connection is lost, until shutdown
+        loop {
+            let (channel, mut consumer) = match self.open_consumer(queue_name, consumer_tag).await {
+                Ok(subscription) => subscription,
+                Err(e) => {
+                    log::warn!("Failed to subscribe {} to {}: {}", consumer_tag, queue_name, e);
+                    self.connection.report_disconnect();
+                    tokio::select! {
+                        _ = shutdown_rx.recv() => return Ok(()),
+                        _ = generation.changed() => {}
+                        _ = tokio::time::sleep(RESUBSCRIBE_DELAY) => {}
+                    }
+                    continue;
+                }
+            };
+
+            loop {
+                tokio::select! {
+                    _ = shutdown_rx.recv() => {
+                        log::info!("Shutdown signal received, stopping consumer {}", consumer_tag);
+                        if channel.status().connected() {
+                            channel
+                                .basic_cancel(consumer_tag, BasicCancelOptions::default())
+                                .await
+                                .map_err(|e| SystemError::MessageBrokerError(e.to_string()))?;
+                        }
+                        log::info!("Consumer {} canceled", consumer_tag);
+                        return Ok(());
+                    }
+                    delivery_result = consumer.next() => {
+                        match delivery_result {
+                            Some(Ok(delivery)) => {
+                                let message = Self::received_message(&delivery);
+                                let span = Self::consume_span(queue_name, &delivery, &message);
+                                let result = handler(message.clone()).instrument(span.clone()).await;
+                                self.settle(queue_name, delivery, &message, result)
+                                    .instrument(span)
+                                    .await;
+                            }
+                            Some(Err(e)) => {
+                                log::error!("Error receiving message on {}: {}", queue_name, e);
+                                if !channel.status().connected() {
+                                    break;
+                                }
+                            }
+                            None => break,
+                        }
+                    }
+                }
+            }
+
+            log::warn!("Consumer {} lost its channel, resubscribing to {}", consumer_tag, queue_name);
+            self.connection.report_disconnect();
+        }
+    }
+
+    async fn open_consumer(&self, queue_name: &str, consumer_tag: &str) -> SystemResult<(Channel, Consumer)> {
+        let channel = self.connection.consumer_channel().await?;
+        let consumer = channel
             .basic_consume(
                 queue_name,
                 consumer_tag,
@@ -133,44 +235,254 @@ impl MessageBroker {
             )
             .await
             .map_err(|e| SystemError::MessageBrokerError(e.to_string()))?;
+        Ok((channel, consumer))
+    }
 
-        loop {
-            tokio::select! {
-                _ = shutdown_rx.recv() => {
-                    println!("Shutdown signal received, stopping consumer");
-                    break;
+    // Declares `queue_name` bound to `E`'s routing key and consumes it,
+    // decoding (and upcasting) every message into an `EventEnvelope<E>`.
+    // Messages that are not a valid `E` are dead-lettered.
+    pub async fn subscribe<E, F, Fut>(
+        &self,
+        queue_name: &str,
+        shutdown_rx: broadcast::Receiver<()>,
+        handler: F,
+    ) -> SystemResult<()>
+    where
+        E: DomainEvent,
+        F: Fn(EventEnvelope<E>) -> Fut + Send + Sync + 'static,
+        Fut: std::future::Future<Output = Result<(), MessageError>> + Send,
+    {
+        self.setup_queue(queue_name, &[E::ROUTING_KEY], E::EXCHANGE).await?;
+
+        let consumer_tag = format!("{}.consumer", queue_name);
+        let handler = Arc::new(handler);
+        self.consume(queue_name, &consumer_tag, shutdown_rx, move |message| {
+            let handler = handler.clone();
+            async move {
+                let envelope = EventEnvelope::<E>::decode(&message.data).map_err(MessageError::Poison)?;
+                handler(envelope).await
+            }
+        })
+        .await
+    }
+
+    // A consumer span continuing the publisher's trace
+    fn consume_span(queue_name: &str, delivery: &Delivery, message: &ReceivedMessage) -> tracing::Span {
+        let span = tracing::info_span!(
+            "amqp.consume",
+            otel.name = %format!("{} process", queue_name),
+            otel.kind = "consumer",
+            messaging.system = "rabbitmq",
+            messaging.destination.name = %queue_name,
+            messaging.rabbitmq.routing_key = %message.routing_key,
+            messaging.retry_count = message.retry_count,
+        );
+        if let Some(headers) = delivery.properties.headers() {
+            link_parent(&span, extract(&AmqpHeaderExtractor(headers)));
+        }
+        span
+    }
+
+    fn received_message(delivery: &Delivery) -> ReceivedMessage {
+        let headers = delivery.properties.headers().as_ref();
+        ReceivedMessage {
+            exchange: header_str(headers, ORIGINAL_EXCHANGE_HEADER)
+                .unwrap_or_else(|| delivery.exchange.to_string()),
+            routing_key: header_str(headers, ORIGINAL_ROUTING_KEY_HEADER)
+                .unwrap_or_else(|| delivery.routing_key.to_string()),
+            data: delivery.data.clone(),
+            retry_count: header_u32(headers, RETRY_COUNT_HEADER).unwrap_or(0),
+        }
+    }
+
+    // Acks, schedules a retry or dead-letters the delivery. Failures here are
+    // logged rather than returned so the consumer loop keeps running.
+    async fn settle(
+        &self,
+        queue_name: &str,
+        delivery: Delivery,
+        message: &ReceivedMessage,
+        result: Result<(), MessageError>,
+    ) {
+        let error = match result {
+            Ok(()) => {
+                if let Err(e) = delivery.ack(BasicAckOptions::default()).await {
+                    log::error!("Failed to ack message on {}: {}", queue_name, e);
                 }
-                Some(delivery_result) = consumer.next() => {
-                    match delivery_result {
-                        Ok(delivery) => {
-                            handler(delivery).await?;
-                        }
-                        Err(e) => {
-                            eprintln!("Error receiving message: {}", e);
-                        }
-                    }
+                MESSAGES_CONSUMED_TOTAL.with_label_values(&[queue_name, "acked"]).inc();
+                return;
+            }
+            Err(error) => error,
+        };
+
+        let attempt = message.retry_count + 1;
+        let (target, error_text, outcome) = match &error {
+            MessageError::Transient(e) if attempt <= self.retry_policy().max_retries => {
+                log::warn!(
+                    "Message {} on {} failed (attempt {} of {}), retrying in {:?}: {}",
+                    message.routing_key,
+                    queue_name,
+                    attempt,
+                    self.retry_policy().max_retries,
+                    self.retry_policy().delay_for(attempt),
+                    e
+                );
+                (("".to_string(), retry_queue(queue_name, attempt)), e, "retried")
+            }
+            MessageError::Transient(e) | MessageError::Poison(e) => {
+                log::error!(
+                    "Dead-lettering message {} on {} after {} retries: {}",
+                    message.routing_key,
+                    queue_name,
+                    message.retry_count,
+                    error
+                );
+                ((dead_letter_exchange(queue_name), "".to_string()), e, "dead_lettered")
+            }
+        };
+
+        let mut headers = delivery.properties.headers().clone().unwrap_or_default();
+        headers.insert(RETRY_COUNT_HEADER.into(), AMQPValue::LongLongInt(attempt.into()));
+        headers.insert(ORIGINAL_EXCHANGE_HEADER.into(), string_value(&message.exchange));
+        headers.insert(ORIGINAL_ROUTING_KEY_HEADER.into(), string_value(&message.routing_key));
+        headers.insert(LAST_ERROR_HEADER.into(), string_value(error_text));
+        let properties = delivery.properties.clone().with_headers(headers);
+
+        // The copy must be confirmed before the original is acked; if it cannot
+        // be written, the broker dead-letters the original instead.
+        let (exchange, routing_key) = target;
+        match self
+            .publish_with_properties(&exchange, &routing_key, &delivery.data, properties)
+            .await
+        {
+            Ok(()) => {
+                if let Err(e) = delivery.ack(BasicAckOptions::default()).await {
+                    log::error!("Failed to ack message on {}: {}", queue_name, e);
+                }
+                MESSAGES_CONSUMED_TOTAL.with_label_values(&[queue_name, outcome]).inc();
+            }
+            Err(e) => {
+                log::error!("Failed to reroute message on {}: {}", queue_name, e);
+                MESSAGES_CONSUMED_TOTAL.with_label_values(&[queue_name, "rejected"]).inc();
+                let options = BasicNackOptions {
+                    requeue: false,
+                    ..Default::default()
+                };
+                if let Err(e) = delivery.nack(options).await {
+                    log::error!("Failed to nack message on {}: {}", queue_name, e);
                 }
             }
         }
+    }
 
-        self.get_channel()
-            .basic_cancel(consumer_tag, BasicCancelOptions::default())
-            .await
-            .map_err(|e| SystemError::MessageBrokerError(e.to_string()))?;
-        println!("Consumer canceled");
-        Ok(())
+    // Lists up to `limit` messages in the queue's dead-letter queue without
+    // removing them. The messages are held unacked while listing and then
+    // returned to the queue.
+    pub async fn inspect_dead_letters(&self, queue_name: &str, limit: usize) -> SystemResult<Vec<DeadLetter>> {
+        let dlq = dead_letter_queue(queue_name);
+        let channel = self.connection.channel().await?;
+        let mut held = Vec::new();
+        let mut dead_letters = Vec::new();
+
+        while dead_letters.len() < limit {
+            let Some(message) = channel
+                .basic_get(&dlq, BasicGetOptions::default())
+                .await
+                .map_err(|e| SystemError::MessageBrokerError(e.to_string()))?
+            else {
+                break;
+            };
+
+            let delivery = message.delivery;
+            let received = Self::received_message(&delivery);
+            dead_letters.push(DeadLetter {
+                queue: queue_name.to_string(),
+                exchange: received.exchange,
+                routing_key: received.routing_key,
+                retry_count: received.retry_count,
+                last_error: header_str(delivery.properties.headers().as_ref(), LAST_ERROR_HEADER),
+                payload: String::from_utf8_lossy(&delivery.data).into_owned(),
+            });
+            held.push(delivery);
+        }
+
+        for delivery in held {
+            delivery
+                .nack(BasicNackOptions {
+                    requeue: true,
+                    ..Default::default()
+                })
+                .await
+                .map_err(|e| SystemError::MessageBrokerError(e.to_string()))?;
+        }
+        Ok(dead_letters)
+    }
+
+    // Moves up to `limit` dead letters back onto the work queue with a fresh
+    // retry budget. Returns how many were replayed.
+    pub async fn replay_dead_letters(&self, queue_name: &str, limit: usize) -> SystemResult<usize> {
+        let dlq = dead_letter_queue(queue_name);
+        let channel = self.connection.channel().await?;
+        let mut replayed = 0;
+
+        while replayed < limit {
+            let Some(message) = channel
+                .basic_get(&dlq, BasicGetOptions::default())
+                .await
+                .map_err(|e| SystemError::MessageBrokerError(e.to_string()))?
+            else {
+                break;
+            };
+
+            let delivery = message.delivery;
+            let received = Self::received_message(&delivery);
+            let mut headers = delivery.properties.headers().clone().unwrap_or_default();
+            headers.insert(RETRY_COUNT_HEADER.into(), AMQPValue::LongLongInt(0));
+            headers.insert(ORIGINAL_EXCHANGE_HEADER.into(), string_value(&received.exchange));
+            headers.insert(ORIGINAL_ROUTING_KEY_HEADER.into(), string_value(&received.routing_key));
+            let properties = delivery.properties.clone().with_headers(headers);
+
+            // Straight to the queue through the default exchange, so other
+            // queues bound to the original routing key don't see it twice
+            self.publish_with_properties("", queue_name, &delivery.data, properties)
+                .await?;
+            delivery
+                .ack(BasicAckOptions::default())
+                .await
+                .map_err(|e| SystemError::MessageBrokerError(e.to_string()))?;
+            replayed += 1;
+        }
+        Ok(replayed)
+    }
+
+    // Samples the depth and consumer count of every declared queue into the
+    // `rabbitmq_queue_*` gauges until shutdown
+    pub fn spawn_queue_metrics(&self, interval: Duration, mut shutdown_rx: broadcast::Receiver<()>) {
+        let connection = self.connection.clone();
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                tokio::select! {
+                    _ = shutdown_rx.recv() => break,
+                    _ = ticker.tick() => {
+                        for queue_name in connection.declared_queues() {
+                            match connection.queue_stats(&queue_name).await {
+                                Ok((messages, consumers)) => {
+                                    QUEUE_MESSAGES.with_label_values(&[queue_name.as_str()]).set(messages.into());
+                                    QUEUE_CONSUMERS.with_label_values(&[queue_name.as_str()]).set(consumers.into());
+                                }
+                                Err(e) => log::debug!("Failed to sample queue {}: {}", queue_name, e),
+                            }
+                        }
+                    }
+                }
+            }
+        });
     }
 
     pub async fn close(&self) -> SystemResult<()> {
-        self.get_channel()
-            .close(200, "Normal shutdown")
-            .await
-            .map_err(|e| SystemError::MessageBrokerError(e.to_string()))?;
-        self.get_connection()
-            .close(200, "Normal shutdown")
-            .await
-            .map_err(|e| SystemError::MessageBrokerError(e.to_string()))?;
-        println!("MessageBroker closed cleanly");
+        self.connection.close().await?;
+        log::info!("MessageBroker closed cleanly");
         Ok(())
     }
-}
\ No newline at end of file
+}
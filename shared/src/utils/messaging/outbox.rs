@@ -0,0 +1,422 @@
+use crate::events::{DomainEvent, EventEnvelope};
+use crate::features::errors::{SystemError, SystemResult};
+use crate::utils::messaging::Publisher;
+use chrono::{DateTime, Duration as ChronoDuration, Utc};
+use sqlx::{PgConnection, Pool, Postgres, Row};
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicI64, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::broadcast;
+use tokio::task::JoinHandle;
+use uuid::Uuid;
+
+// Only one relay per database claims events at a time; the others skip that
+// poll. The key is arbitrary but must be stable.
+const RELAY_LOCK_KEY: i64 = 0x6f75_7462_6f78;
+
+// An event waiting to be published. Written with `Outbox::enqueue` in the same
+// transaction as the domain change it describes, so either both are persisted
+// or neither is.
+#[derive(Debug, Clone)]
+pub struct OutboxEvent {
+    pub id: Uuid,
+    pub aggregate_type: String,
+    pub aggregate_id: String,
+    pub exchange: String,
+    pub routing_key: String,
+    pub payload: serde_json::Value,
+}
+
+impl OutboxEvent {
+    pub fn new<E: DomainEvent>(
+        aggregate_type: &str,
+        aggregate_id: impl ToString,
+        envelope: &EventEnvelope<E>,
+    ) -> SystemResult<Self> {
+        let payload = serde_json::to_value(envelope)
+            .map_err(|e| SystemError::MessageBrokerError(format!("Failed to serialize payload: {}", e)))?;
+
+        Ok(Self {
+            id: envelope.event_id,
+            aggregate_type: aggregate_type.to_string(),
+            aggregate_id: aggregate_id.to_string(),
+            exchange: envelope.exchange().to_string(),
+            routing_key: envelope.routing_key().to_string(),
+            payload,
+        })
+    }
+}
+
+pub struct Outbox;
+
+impl Outbox {
+    // Pass the open transaction (`&mut *tx`) of the domain write
+    #[tracing::instrument(name = "Outbox::enqueue", skip_all, fields(db.system = "postgresql"))]
+    pub async fn enqueue(conn: &mut PgConnection, event: &OutboxEvent) -> SystemResult<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO outbox (id, aggregate_type, aggregate_id, exchange, routing_key, payload)
+            VALUES ($1, $2, $3, $4, $5, $6::jsonb)
+            "#
+        )
+        .bind(event.id)
+        .bind(&event.aggregate_type)
+        .bind(&event.aggregate_id)
+        .bind(&event.exchange)
+        .bind(&event.routing_key)
+        .bind(event.payload.to_string())
+        .execute(conn)
+        .await?;
+
+        Ok(())
+    }
+}
+
+// Counters exposed by the relay. `oldest_pending_age_seconds` is the outbox
+// lag: how long the oldest unpublished event has been waiting.
+#[derive(Debug, Default)]
+pub struct OutboxMetrics {
+    pub pending: AtomicI64,
+    pub dead_lettered: AtomicI64,
+    pub oldest_pending_age_seconds: AtomicI64,
+    pub published_total: AtomicU64,
+    pub failed_total: AtomicU64,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct OutboxMetricsSnapshot {
+    pub pending: i64,
+    pub dead_lettered: i64,
+    pub oldest_pending_age_seconds: i64,
+    pub published_total: u64,
+    pub failed_total: u64,
+}
+
+impl OutboxMetrics {
+    pub fn snapshot(&self) -> OutboxMetricsSnapshot {
+        OutboxMetricsSnapshot {
+            pending: self.pending.load(Ordering::Relaxed),
+            dead_lettered: self.dead_lettered.load(Ordering::Relaxed),
+            oldest_pending_age_seconds: self.oldest_pending_age_seconds.load(Ordering::Relaxed),
+            published_total: self.published_total.load(Ordering::Relaxed),
+            failed_total: self.failed_total.load(Ordering::Relaxed),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct OutboxRelayConfig {
+    pub batch_size: i64,
+    pub poll_interval: Duration,
+    pub max_backoff: Duration,
+    pub max_attempts: i32, // then the event is dead-lettered (`failed_at` set)
+    pub publish_timeout: Duration,
+    pub claim_ttl: Duration, // how long claimed events are left to this relay
+    pub retention: ChronoDuration, // how long published rows are kept
+}
+
+impl Default for OutboxRelayConfig {
+    fn default() -> Self {
+        Self {
+            batch_size: 100,
+            poll_interval: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(300),
+            max_attempts: 20,
+            publish_timeout: Duration::from_secs(10),
+            claim_ttl: Duration::from_secs(300),
+            retention: ChronoDuration::days(7),
+        }
+    }
+}
+
+struct PendingEvent {
+    sequence: i64,
+    aggregate_key: String,
+    exchange: String,
+    routing_key: String,
+    payload: String,
+    attempts: i32,
+}
+
+// Publishes pending outbox rows in insertion order and marks them sent once
+// the broker confirms them. Events of one aggregate are never reordered: if
+// one fails or is backing off, later events of that aggregate wait for it,
+// until it is dead-lettered after `max_attempts`. Delivery is at-least-once;
+// consumers must tolerate duplicates.
+pub struct OutboxRelay {
+    pool: Pool<Postgres>,
+    publisher: Arc<dyn Publisher>,
+    config: OutboxRelayConfig,
+    metrics: Arc<OutboxMetrics>,
+}
+
+impl OutboxRelay {
+    pub fn new(pool: Pool<Postgres>, publisher: Arc<dyn Publisher>, config: OutboxRelayConfig) -> Self {
+        Self {
+            pool,
+            publisher,
+            config,
+            metrics: Arc::new(OutboxMetrics::default()),
+        }
+    }
+
+    pub fn metrics(&self) -> Arc<OutboxMetrics> {
+        self.metrics.clone()
+    }
+
+    pub fn spawn(self, mut shutdown_rx: broadcast::Receiver<()>) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut last_purge = Utc::now();
+
+            loop {
+                let published_full_batch = match self.relay_batch().await {
+                    Ok(count) => count as i64 >= self.config.batch_size,
+                    Err(e) => {
+                        log::error!("Outbox relay failed: {}", e);
+                        false
+                    }
+                };
+
+                if let Err(e) = self.refresh_metrics().await {
+                    log::warn!("Failed to refresh outbox metrics: {}", e);
+                }
+
+                if Utc::now() - last_purge > ChronoDuration::hours(1) {
+                    match self.purge_published().await {
+                        Ok(purged) if purged > 0 => log::info!("Purged {} published outbox events", purged),
+                        Ok(_) => {}
+                        Err(e) => log::warn!("Failed to purge outbox: {}", e),
+                    }
+                    last_purge = Utc::now();
+                }
+
+                // Drain a backlog without waiting between batches
+                if published_full_batch {
+                    continue;
+                }
+
+                tokio::select! {
+                    _ = shutdown_rx.recv() => {
+                        log::info!("Outbox relay stopped");
+                        break;
+                    }
+                    _ = tokio::time::sleep(self.config.poll_interval) => {}
+                }
+            }
+        })
+    }
+
+    // Returns how many events were published. Due events are claimed by
+    // pushing `next_attempt_at` out by `claim_ttl` and committing, so the
+    // broker is never waited on with a transaction or the relay lock held.
+    pub async fn relay_batch(&self) -> SystemResult<usize> {
+        let claimed_until = Utc::now() + ChronoDuration::from_std(self.config.claim_ttl).unwrap_or(ChronoDuration::MAX);
+        let events = self.claim(claimed_until).await?;
+
+        let mut blocked_aggregates = HashSet::new();
+        let mut unattempted = Vec::new();
+        let mut published = 0;
+
+        for event in events {
+            // Past the claim another relay may take the event over
+            let claim_ending = Utc::now() + self.config.publish_timeout > claimed_until;
+            if claim_ending || blocked_aggregates.contains(&event.aggregate_key) {
+                unattempted.push(event.sequence);
+                continue;
+            }
+
+            let publish = self
+                .publisher
+                .publish_raw(&event.exchange, &event.routing_key, event.payload.as_bytes());
+            let result = match tokio::time::timeout(self.config.publish_timeout, publish).await {
+                Ok(result) => result,
+                Err(_) => Err(SystemError::MessageBrokerError(format!(
+                    "Publish timed out after {}s",
+                    self.config.publish_timeout.as_secs()
+                ))),
+            };
+
+            match result {
+                Ok(()) => {
+                    sqlx::query("UPDATE outbox SET published_at = NOW(), last_error = NULL WHERE sequence = $1")
+                        .bind(event.sequence)
+                        .execute(&self.pool)
+                        .await?;
+                    published += 1;
+                    self.metrics.published_total.fetch_add(1, Ordering::Relaxed);
+                }
+                Err(e) => {
+                    self.record_failure(&event, e).await?;
+                    blocked_aggregates.insert(event.aggregate_key);
+                }
+            }
+        }
+
+        // Hand back what was skipped so the next poll can take it
+        if !unattempted.is_empty() {
+            sqlx::query("UPDATE outbox SET next_attempt_at = NOW() WHERE sequence = ANY($1)")
+                .bind(&unattempted)
+                .execute(&self.pool)
+                .await?;
+        }
+
+        Ok(published)
+    }
+
+    // Due events, oldest first, skipping any aggregate with an earlier event
+    // still waiting (backing off, or claimed by an earlier poll)
+    async fn claim(&self, claimed_until: DateTime<Utc>) -> SystemResult<Vec<PendingEvent>> {
+        let mut tx = self.pool.begin().await?;
+
+        let locked: bool = sqlx::query_scalar("SELECT pg_try_advisory_xact_lock($1)")
+            .bind(RELAY_LOCK_KEY)
+            .fetch_one(&mut *tx)
+            .await?;
+        if !locked {
+            return Ok(Vec::new());
+        }
+
+        let rows = sqlx::query(
+            r#"
+            WITH due AS (
+                SELECT o.sequence
+                FROM outbox o
+                WHERE o.published_at IS NULL AND o.failed_at IS NULL AND o.next_attempt_at <= NOW()
+                    AND NOT EXISTS (
+                        SELECT 1
+                        FROM outbox earlier
+                        WHERE earlier.aggregate_type = o.aggregate_type
+                            AND earlier.aggregate_id = o.aggregate_id
+                            AND earlier.sequence < o.sequence
+                            AND earlier.published_at IS NULL
+                            AND earlier.failed_at IS NULL
+                            AND earlier.next_attempt_at > NOW()
+                    )
+                ORDER BY o.sequence
+                LIMIT $1
+            )
+            UPDATE outbox
+            SET next_attempt_at = $2
+            FROM due
+            WHERE outbox.sequence = due.sequence
+            RETURNING outbox.sequence, aggregate_type, aggregate_id, exchange, routing_key,
+                payload::text AS payload, attempts
+            "#
+        )
+        .bind(self.config.batch_size)
+        .bind(claimed_until)
+        .fetch_all(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        let mut events: Vec<PendingEvent> = rows
+            .into_iter()
+            .map(|row| PendingEvent {
+                sequence: row.get("sequence"),
+                aggregate_key: format!(
+                    "{}:{}",
+                    row.get::<String, _>("aggregate_type"),
+                    row.get::<String, _>("aggregate_id")
+                ),
+                exchange: row.get("exchange"),
+                routing_key: row.get("routing_key"),
+                payload: row.get("payload"),
+                attempts: row.get("attempts"),
+            })
+            .collect();
+        // RETURNING has no order
+        events.sort_by_key(|event| event.sequence);
+        Ok(events)
+    }
+
+    async fn record_failure(&self, event: &PendingEvent, error: SystemError) -> SystemResult<()> {
+        let attempts = event.attempts + 1;
+        self.metrics.failed_total.fetch_add(1, Ordering::Relaxed);
+
+        if attempts >= self.config.max_attempts {
+            log::error!(
+                "Dead-lettering outbox event {} to {} after {} attempts: {}",
+                event.sequence,
+                event.routing_key,
+                attempts,
+                error
+            );
+            sqlx::query("UPDATE outbox SET attempts = $1, last_error = $2, failed_at = NOW() WHERE sequence = $3")
+                .bind(attempts)
+                .bind(error.to_string())
+                .bind(event.sequence)
+                .execute(&self.pool)
+                .await?;
+            return Ok(());
+        }
+
+        log::warn!(
+            "Failed to publish outbox event {} to {} (attempt {}): {}",
+            event.sequence,
+            event.routing_key,
+            attempts,
+            error
+        );
+        sqlx::query(
+            r#"
+            UPDATE outbox
+            SET attempts = $1, last_error = $2, next_attempt_at = $3
+            WHERE sequence = $4
+            "#
+        )
+        .bind(attempts)
+        .bind(error.to_string())
+        .bind(Utc::now() + self.backoff(attempts))
+        .bind(event.sequence)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn refresh_metrics(&self) -> SystemResult<()> {
+        let row = sqlx::query(
+            r#"
+            SELECT COUNT(*) FILTER (WHERE failed_at IS NULL) AS pending,
+                COUNT(*) FILTER (WHERE failed_at IS NOT NULL) AS dead_lettered,
+                COALESCE(EXTRACT(EPOCH FROM NOW() - MIN(created_at) FILTER (WHERE failed_at IS NULL)), 0)::BIGINT
+                    AS oldest_age
+            FROM outbox
+            WHERE published_at IS NULL
+            "#
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        let pending: i64 = row.get("pending");
+        let dead_lettered: i64 = row.get("dead_lettered");
+        let oldest_age: i64 = row.get("oldest_age");
+        self.metrics.pending.store(pending, Ordering::Relaxed);
+        self.metrics.dead_lettered.store(dead_lettered, Ordering::Relaxed);
+        self.metrics.oldest_pending_age_seconds.store(oldest_age, Ordering::Relaxed);
+
+        if oldest_age > self.config.max_backoff.as_secs() as i64 {
+            log::warn!("Outbox lag is {}s with {} pending events", oldest_age, pending);
+        }
+        if dead_lettered > 0 {
+            log::warn!("{} outbox events were dead-lettered and need attention", dead_lettered);
+        }
+        Ok(())
+    }
+
+    async fn purge_published(&self) -> SystemResult<u64> {
+        let result = sqlx::query("DELETE FROM outbox WHERE published_at < $1")
+            .bind(Utc::now() - self.config.retention)
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+
+    // Exponential backoff: 2s, 4s, 8s ... capped at `max_backoff`
+    fn backoff(&self, attempts: i32) -> ChronoDuration {
+        let seconds = 2u64.saturating_pow(attempts.clamp(1, 30) as u32);
+        let capped = seconds.min(self.config.max_backoff.as_secs());
+        ChronoDuration::seconds(capped as i64)
+    }
+}
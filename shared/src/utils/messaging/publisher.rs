@@ -0,0 +1,33 @@
+use super::MessageBroker;
+use crate::events::{DomainEvent, EventEnvelope};
+use crate::features::errors::{SystemError, SystemResult};
+use async_trait::async_trait;
+
+// Where outgoing events go. `MessageBroker` publishes to RabbitMQ; code that
+// only sends events should depend on this so tests can record them instead.
+#[async_trait]
+pub trait Publisher: Send + Sync {
+    async fn publish_raw(&self, exchange: &str, routing_key: &str, payload: &[u8]) -> SystemResult<()>;
+}
+
+impl dyn Publisher {
+    pub async fn publish<E: DomainEvent>(&self, envelope: &EventEnvelope<E>) -> SystemResult<()> {
+        let payload = envelope.to_bytes().map_err(SystemError::MessageBrokerError)?;
+        self.publish_raw(&envelope.exchange().to_string(), envelope.routing_key(), &payload)
+            .await
+    }
+
+    // As `MessageBroker::publish_to`
+    pub async fn publish_to<E: DomainEvent>(&self, envelope: &EventEnvelope<E>, routing_key: &str) -> SystemResult<()> {
+        let payload = envelope.to_bytes().map_err(SystemError::MessageBrokerError)?;
+        self.publish_raw(&envelope.exchange().to_string(), routing_key, &payload)
+            .await
+    }
+}
+
+#[async_trait]
+impl Publisher for MessageBroker {
+    async fn publish_raw(&self, exchange: &str, routing_key: &str, payload: &[u8]) -> SystemResult<()> {
+        MessageBroker::publish_raw(self, exchange, routing_key, payload).await
+    }
+}
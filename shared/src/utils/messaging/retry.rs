@@ -0,0 +1,138 @@
+use crate::config::messaging_config::MessagingConfig;
+use crate::features::errors::SystemError;
+use lapin::types::{AMQPValue, FieldTable};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::fmt;
+use std::time::Duration;
+
+pub const RETRY_COUNT_HEADER: &str = "x-retry-count";
+pub const ORIGINAL_EXCHANGE_HEADER: &str = "x-original-exchange";
+pub const ORIGINAL_ROUTING_KEY_HEADER: &str = "x-original-routing-key";
+pub const LAST_ERROR_HEADER: &str = "x-last-error";
+
+// Topology declared by `MessageBroker::setup_queue` for every queue:
+//
+//   <queue>           bound to the exchange, dead-letters to <queue>.dlx
+//   <queue>.retry.N   holds a failed message for the N-th backoff delay, then
+//                     dead-letters it back to <queue> through the default exchange
+//   <queue>.dlx       fanout exchange feeding <queue>.dlq
+//   <queue>.dlq       messages that exhausted their retries or can never succeed
+pub fn dead_letter_exchange(queue_name: &str) -> String {
+    format!("{}.dlx", queue_name)
+}
+
+pub fn dead_letter_queue(queue_name: &str) -> String {
+    format!("{}.dlq", queue_name)
+}
+
+pub fn retry_queue(queue_name: &str, attempt: u32) -> String {
+    format!("{}.retry.{}", queue_name, attempt)
+}
+
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    pub max_retries: u32,
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 5,
+            base_delay: Duration::from_secs(5),
+            max_delay: Duration::from_secs(600),
+        }
+    }
+}
+
+impl RetryPolicy {
+    pub fn from_config(config: &MessagingConfig) -> Self {
+        Self {
+            max_retries: config.max_retries,
+            base_delay: Duration::from_secs(config.retry_delay),
+            max_delay: Duration::from_secs(config.retry_max_delay),
+        }
+    }
+
+    // Exponential backoff: base, 2x base, 4x base ... capped at `max_delay`.
+    // `attempt` starts at 1.
+    pub fn delay_for(&self, attempt: u32) -> Duration {
+        let factor = 2u32.saturating_pow(attempt.saturating_sub(1).min(30));
+        self.base_delay.saturating_mul(factor).min(self.max_delay)
+    }
+}
+
+// What a consumer handler reports when it cannot process a message
+#[derive(Debug)]
+pub enum MessageError {
+    // Might succeed later (database down, downstream timeout); retried with backoff
+    Transient(String),
+    // Can never succeed (unparsable or unknown payload); dead-lettered at once
+    Poison(String),
+}
+
+impl fmt::Display for MessageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MessageError::Transient(e) => write!(f, "transient failure: {}", e),
+            MessageError::Poison(e) => write!(f, "poison message: {}", e),
+        }
+    }
+}
+
+impl From<SystemError> for MessageError {
+    fn from(error: SystemError) -> Self {
+        MessageError::Transient(error.to_string())
+    }
+}
+
+// A delivery as seen by a handler. Acknowledgement is left to the broker,
+// which acks, retries or dead-letters based on the handler's result.
+#[derive(Debug, Clone)]
+pub struct ReceivedMessage {
+    pub exchange: String,
+    pub routing_key: String, // the key the message was originally published with
+    pub data: Vec<u8>,
+    pub retry_count: u32,
+}
+
+impl ReceivedMessage {
+    pub fn json<T: DeserializeOwned>(&self) -> Result<T, MessageError> {
+        serde_json::from_slice(&self.data).map_err(|e| MessageError::Poison(format!("Invalid payload: {}", e)))
+    }
+}
+
+// A message sitting in a dead-letter queue, as listed by the DLQ admin command
+#[derive(Debug, Clone, Serialize)]
+pub struct DeadLetter {
+    pub queue: String,
+    pub exchange: String,
+    pub routing_key: String,
+    pub retry_count: u32,
+    pub last_error: Option<String>,
+    pub payload: String,
+}
+
+pub(crate) fn header_str(headers: Option<&FieldTable>, key: &str) -> Option<String> {
+    match headers?.inner().get(key)? {
+        AMQPValue::LongString(value) => Some(String::from_utf8_lossy(value.as_bytes()).into_owned()),
+        AMQPValue::ShortString(value) => Some(value.to_string()),
+        _ => None,
+    }
+}
+
+pub(crate) fn header_u32(headers: Option<&FieldTable>, key: &str) -> Option<u32> {
+    match headers?.inner().get(key)? {
+        AMQPValue::LongLongInt(value) => u32::try_from(*value).ok(),
+        AMQPValue::LongInt(value) => u32::try_from(*value).ok(),
+        AMQPValue::LongUInt(value) => Some(*value),
+        AMQPValue::ShortInt(value) => u32::try_from(*value).ok(),
+        _ => None,
+    }
+}
+
+pub(crate) fn string_value(value: &str) -> AMQPValue {
+    AMQPValue::LongString(value.into())
+}
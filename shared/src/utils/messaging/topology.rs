@@ -0,0 +1,109 @@
+use crate::events::ExchangeType;
+use crate::features::errors::{SystemError, SystemResult};
+use crate::utils::messaging::retry::{dead_letter_exchange, dead_letter_queue, retry_queue, string_value, RetryPolicy};
+use lapin::options::{ExchangeDeclareOptions, QueueBindOptions, QueueDeclareOptions};
+use lapin::types::{AMQPValue, FieldTable};
+use lapin::{Channel, ExchangeKind};
+
+// A queue declared through `MessageBroker::setup_queue`. Remembered so the
+// whole topology can be declared again after a reconnect.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct QueueTopology {
+    pub queue_name: String,
+    pub routing_keys: Vec<String>,
+    pub exchange_type: ExchangeType,
+}
+
+fn broker_error(e: lapin::Error) -> SystemError {
+    SystemError::MessageBrokerError(e.to_string())
+}
+
+pub(crate) async fn declare_exchanges(channel: &Channel) -> SystemResult<()> {
+    for exchange_type in [ExchangeType::Topic, ExchangeType::Fanout, ExchangeType::Direct, ExchangeType::Headers] {
+        channel
+            .exchange_declare(
+                &exchange_type.to_string(),
+                exchange_type.into(),
+                ExchangeDeclareOptions {
+                    durable: true,
+                    ..Default::default()
+                },
+                FieldTable::default(),
+            )
+            .await
+            .map_err(broker_error)?;
+    }
+    Ok(())
+}
+
+// Declares the queue together with its retry queues and dead-letter
+// exchange/queue (see `retry` for the layout)
+pub(crate) async fn declare_queue(channel: &Channel, topology: &QueueTopology, retry_policy: &RetryPolicy) -> SystemResult<()> {
+    let queue_name = topology.queue_name.as_str();
+    let dlx = dead_letter_exchange(queue_name);
+    let dlq = dead_letter_queue(queue_name);
+
+    channel
+        .exchange_declare(
+            &dlx,
+            ExchangeKind::Fanout,
+            ExchangeDeclareOptions {
+                durable: true,
+                ..Default::default()
+            },
+            FieldTable::default(),
+        )
+        .await
+        .map_err(broker_error)?;
+    declare_durable_queue(channel, &dlq, FieldTable::default()).await?;
+    channel
+        .queue_bind(&dlq, &dlx, "", QueueBindOptions::default(), FieldTable::default())
+        .await
+        .map_err(broker_error)?;
+
+    let mut arguments = FieldTable::default();
+    arguments.insert("x-dead-letter-exchange".into(), string_value(&dlx));
+    declare_durable_queue(channel, queue_name, arguments).await?;
+
+    // Expired retries go back to the work queue through the default exchange
+    for attempt in 1..=retry_policy.max_retries {
+        let delay = retry_policy.delay_for(attempt);
+        let mut arguments = FieldTable::default();
+        arguments.insert("x-message-ttl".into(), AMQPValue::LongLongInt(delay.as_millis() as i64));
+        arguments.insert("x-dead-letter-exchange".into(), string_value(""));
+        arguments.insert("x-dead-letter-routing-key".into(), string_value(queue_name));
+        declare_durable_queue(channel, &retry_queue(queue_name, attempt), arguments).await?;
+    }
+
+    // Skip binding for fanout exchange if no routing keys are provided
+    if topology.exchange_type != ExchangeType::Fanout || !topology.routing_keys.is_empty() {
+        for routing_key in &topology.routing_keys {
+            channel
+                .queue_bind(
+                    queue_name,
+                    &topology.exchange_type.to_string(),
+                    routing_key,
+                    QueueBindOptions::default(),
+                    FieldTable::default(),
+                )
+                .await
+                .map_err(broker_error)?;
+        }
+    }
+    Ok(())
+}
+
+async fn declare_durable_queue(channel: &Channel, queue_name: &str, arguments: FieldTable) -> SystemResult<()> {
+    channel
+        .queue_declare(
+            queue_name,
+            QueueDeclareOptions {
+                durable: true,
+                ..Default::default()
+            },
+            arguments,
+        )
+        .await
+        .map_err(broker_error)?;
+    Ok(())
+}
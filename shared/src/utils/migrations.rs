@@ -0,0 +1,217 @@
+use crate::features::errors::{SystemError, SystemResult};
+use serde::Serialize;
+use sqlx::migrate::{MigrateError, Migrator};
+use sqlx::{Pool, Postgres, Row};
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MigrationState {
+    Applied,
+    Pending,
+    // Applied, but the file has changed since
+    Modified,
+    // Started and did not finish; needs a human
+    Failed,
+    // Applied by a newer binary
+    Unknown,
+}
+
+impl MigrationState {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MigrationState::Applied => "applied",
+            MigrationState::Pending => "pending",
+            MigrationState::Modified => "modified",
+            MigrationState::Failed => "failed",
+            MigrationState::Unknown => "unknown",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MigrationStatus {
+    pub version: i64,
+    pub description: String,
+    pub state: MigrationState,
+}
+
+// A service's migrations, embedded in its binary:
+//
+//     static MIGRATOR: Migrator = sqlx::migrate!("./migrations");
+//
+//     let migrations = Migrations::new("auth-service", &MIGRATOR);
+//     migrations.on_startup(&pool, config.database.run_migrations).await?;
+//
+// Applying takes a Postgres advisory lock on the database (sqlx's migrator
+// does this), so replicas starting together apply each migration once.
+pub struct Migrations {
+    service: &'static str,
+    migrator: &'static Migrator,
+}
+
+impl Migrations {
+    pub const fn new(service: &'static str, migrator: &'static Migrator) -> Self {
+        Self { service, migrator }
+    }
+
+    pub fn latest_version(&self) -> Option<i64> {
+        self.migrator.iter().map(|m| m.version).max()
+    }
+
+    // Refuses to start against a schema newer than this binary, then applies
+    // pending migrations. With `apply` off (e.g. production, where migrations
+    // run as a separate step) pending ones are only reported.
+    pub async fn on_startup(&self, pool: &Pool<Postgres>, apply: bool) -> SystemResult<()> {
+        let status = self.status(pool).await?;
+        let unknown: Vec<i64> = status
+            .iter()
+            .filter(|m| m.state == MigrationState::Unknown)
+            .map(|m| m.version)
+            .collect();
+        if !unknown.is_empty() {
+            return Err(SystemError::DatabaseError(format!(
+                "Database schema is ahead of {}: migration(s) {:?} were applied by a newer version (this binary knows up to {}). Refusing to start",
+                self.service,
+                unknown,
+                self.latest_version().unwrap_or_default()
+            )));
+        }
+
+        let pending = status.iter().filter(|m| m.state == MigrationState::Pending).count();
+        if pending == 0 {
+            return Ok(());
+        }
+        if !apply {
+            log::warn!(
+                "{} pending migration(s) not applied; run `{} migrate up`",
+                pending,
+                self.service
+            );
+            return Ok(());
+        }
+
+        log::info!("Applying {} pending migration(s)", pending);
+        self.up(pool).await
+    }
+
+    pub async fn up(&self, pool: &Pool<Postgres>) -> SystemResult<()> {
+        self.migrator.run(pool).await.map_err(migrate_error)
+    }
+
+    // Reverts every applied migration newer than `target`; without a target,
+    // only the latest one. Returns the reverted versions.
+    pub async fn down(&self, pool: &Pool<Postgres>, target: Option<i64>) -> SystemResult<Vec<i64>> {
+        let applied: Vec<i64> = self
+            .status(pool)
+            .await?
+            .into_iter()
+            .filter(|m| m.state == MigrationState::Applied)
+            .map(|m| m.version)
+            .collect();
+        let target = match target {
+            Some(target) => target,
+            None => match applied.len() {
+                0 => return Ok(Vec::new()),
+                n if n >= 2 => applied[n - 2],
+                _ => 0,
+            },
+        };
+
+        self.migrator.undo(pool, target).await.map_err(migrate_error)?;
+        Ok(applied.into_iter().filter(|version| *version > target).collect())
+    }
+
+    // Every migration this binary knows plus any applied by another version,
+    // oldest first
+    pub async fn status(&self, pool: &Pool<Postgres>) -> SystemResult<Vec<MigrationStatus>> {
+        let applied = applied_migrations(pool).await?;
+
+        let mut status: BTreeMap<i64, MigrationStatus> = BTreeMap::new();
+        for migration in self.migrator.iter().filter(|m| !m.migration_type.is_down_migration()) {
+            let state = match applied.get(&migration.version) {
+                None => MigrationState::Pending,
+                Some((false, _)) => MigrationState::Failed,
+                Some((true, checksum)) if checksum.as_slice() != &*migration.checksum => MigrationState::Modified,
+                Some(_) => MigrationState::Applied,
+            };
+            status.insert(
+                migration.version,
+                MigrationStatus {
+                    version: migration.version,
+                    description: migration.description.to_string(),
+                    state,
+                },
+            );
+        }
+        for (version, (success, _)) in applied {
+            status.entry(version).or_insert(MigrationStatus {
+                version,
+                description: String::new(),
+                state: if success { MigrationState::Unknown } else { MigrationState::Failed },
+            });
+        }
+        Ok(status.into_values().collect())
+    }
+
+    // `<service> migrate <up|down [version]|status>`
+    pub async fn run_command(&self, pool: &Pool<Postgres>, args: &[String]) -> SystemResult<()> {
+        match args.first().map(String::as_str) {
+            Some("up") => {
+                self.up(pool).await?;
+                println!("Migrated {} to version {}", self.service, self.latest_version().unwrap_or_default());
+            }
+            Some("down") => {
+                let target = args
+                    .get(1)
+                    .map(|v| v.parse().map_err(|_| SystemError::ValidationError(format!("Invalid version: {}", v))))
+                    .transpose()?;
+                let reverted = self.down(pool, target).await?;
+                if reverted.is_empty() {
+                    println!("Nothing to revert");
+                }
+                for version in reverted.iter().rev() {
+                    println!("Reverted {}", version);
+                }
+            }
+            Some("status") => {
+                for migration in self.status(pool).await? {
+                    println!(
+                        "{:<16} {:<10} {}",
+                        migration.version,
+                        migration.state.as_str(),
+                        migration.description
+                    );
+                }
+            }
+            _ => {
+                return Err(SystemError::ValidationError(format!(
+                    "usage: {} migrate <up|down [version]|status>",
+                    self.service
+                )))
+            }
+        }
+        Ok(())
+    }
+}
+
+// version -> (success, checksum); empty before the first migration ran
+async fn applied_migrations(pool: &Pool<Postgres>) -> SystemResult<BTreeMap<i64, (bool, Vec<u8>)>> {
+    let exists: bool = sqlx::query_scalar("SELECT to_regclass('_sqlx_migrations') IS NOT NULL")
+        .fetch_one(pool)
+        .await?;
+    if !exists {
+        return Ok(BTreeMap::new());
+    }
+
+    let rows = sqlx::query("SELECT version, success, checksum FROM _sqlx_migrations")
+        .fetch_all(pool)
+        .await?;
+    rows.iter()
+        .map(|row| Ok((row.try_get("version")?, (row.try_get("success")?, row.try_get("checksum")?))))
+        .collect()
+}
+
+fn migrate_error(err: MigrateError) -> SystemError {
+    SystemError::DatabaseError(format!("Migration failed: {}", err))
+}
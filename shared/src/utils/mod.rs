@@ -1,2 +1,6 @@
 pub mod caching;
-pub mod messaging;
\ No newline at end of file
+pub mod clock;
+pub mod locking;
+pub mod messaging;
+pub mod migrations;
+pub mod saga;
@@ -0,0 +1,250 @@
+mod orchestrator;
+pub mod routes;
+mod store;
+
+use crate::events::{DomainEvent, EventEnvelope};
+use crate::features::errors::{SystemError, SystemResult};
+use crate::utils::messaging::MessageBroker;
+use chrono::{DateTime, Utc};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+use uuid::Uuid;
+
+pub use orchestrator::SagaOrchestrator;
+pub use store::PostgresSagaStore;
+
+// Table the saga store writes to. Each service that orchestrates sagas adds
+// this to its own migrations.
+pub const SAGA_SCHEMA_SQL: &str = include_str!("schema.sql");
+
+// A saga runs its steps in order by sending each one as a command and waiting
+// for the reply. When a step fails or times out, the compensations of the
+// steps that already ran are sent in reverse order:
+//
+//   running       step N sent, waiting for its reply
+//   completed     every step succeeded
+//   compensating  step N failed; undoing N-1 .. 0
+//   compensated   every completed step was undone
+//   failed        a compensation failed or kept timing out; needs a human
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SagaStatus {
+    Running,
+    Compensating,
+    Completed,
+    Compensated,
+    Failed,
+}
+
+impl SagaStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SagaStatus::Running => "running",
+            SagaStatus::Compensating => "compensating",
+            SagaStatus::Completed => "completed",
+            SagaStatus::Compensated => "compensated",
+            SagaStatus::Failed => "failed",
+        }
+    }
+
+    pub fn is_terminal(&self) -> bool {
+        matches!(self, SagaStatus::Completed | SagaStatus::Compensated | SagaStatus::Failed)
+    }
+}
+
+impl fmt::Display for SagaStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for SagaStatus {
+    type Err = SystemError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "running" => Ok(SagaStatus::Running),
+            "compensating" => Ok(SagaStatus::Compensating),
+            "completed" => Ok(SagaStatus::Completed),
+            "compensated" => Ok(SagaStatus::Compensated),
+            "failed" => Ok(SagaStatus::Failed),
+            other => Err(SystemError::ParseError(format!("Unknown saga status: {}", other))),
+        }
+    }
+}
+
+// One entry in a saga's history, kept for debugging
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SagaHistoryEntry {
+    pub step: String,
+    pub compensating: bool,
+    pub attempt: i32,
+    pub outcome: String, // sent, succeeded, failed, timed_out
+    pub detail: Option<String>,
+    pub at: DateTime<Utc>,
+}
+
+// A saga as persisted in `saga_instances`
+#[derive(Debug, Clone, Serialize)]
+pub struct SagaInstance {
+    pub id: Uuid,
+    pub saga_type: String,
+    pub status: SagaStatus,
+    pub current_step: i32,
+    pub attempt: i32,
+    pub data: Value,
+    pub history: Vec<SagaHistoryEntry>,
+    pub last_error: Option<String>,
+    pub correlation_id: Option<Uuid>,
+    pub step_deadline: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl SagaInstance {
+    pub(crate) fn record(&mut self, step: &str, compensating: bool, outcome: &str, detail: Option<String>) {
+        self.history.push(SagaHistoryEntry {
+            step: step.to_string(),
+            compensating,
+            attempt: self.attempt,
+            outcome: outcome.to_string(),
+            detail,
+            at: Utc::now(),
+        });
+    }
+}
+
+// Builds a command payload from the saga's data
+pub type CommandBuilder<D> = fn(&D) -> Value;
+
+// A step of a saga: the command that performs it and, optionally, the command
+// that undoes it. Both are built from the saga's data. Participants must
+// handle a command and its compensation idempotently: commands can be
+// redelivered, and a step that timed out is compensated even though it may
+// have run.
+pub struct SagaStep<D> {
+    pub name: &'static str,
+    pub routing_key: &'static str,
+    pub command: CommandBuilder<D>,
+    pub compensation: Option<(&'static str, CommandBuilder<D>)>,
+    pub on_success: Option<fn(&mut D, &Value)>,
+    pub timeout: Duration,
+}
+
+impl<D> SagaStep<D> {
+    pub fn new(name: &'static str, routing_key: &'static str, command: CommandBuilder<D>) -> Self {
+        Self {
+            name,
+            routing_key,
+            command,
+            compensation: None,
+            on_success: None,
+            timeout: Duration::from_secs(30),
+        }
+    }
+
+    pub fn compensate_with(mut self, routing_key: &'static str, compensation: CommandBuilder<D>) -> Self {
+        self.compensation = Some((routing_key, compensation));
+        self
+    }
+
+    // Merges the participant's reply into the saga data, e.g. to keep the id
+    // of a hold that a later compensation needs to release
+    pub fn on_success(mut self, on_success: fn(&mut D, &Value)) -> Self {
+        self.on_success = Some(on_success);
+        self
+    }
+
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+}
+
+// A kind of saga, e.g. booking a property. `Data` is persisted with every
+// instance and passed to each step's command builder.
+pub trait SagaDefinition: Send + Sync + 'static {
+    type Data: Serialize + DeserializeOwned + Send + Sync;
+
+    const SAGA_TYPE: &'static str;
+    // Times a timed-out compensation is resent before the saga is marked failed
+    const MAX_COMPENSATION_ATTEMPTS: i32 = 3;
+
+    fn steps() -> Vec<SagaStep<Self::Data>>;
+}
+
+// Sent by the orchestrator to a participant on the step's routing key
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SagaCommand {
+    pub saga_id: Uuid,
+    pub saga_type: String,
+    pub step: String,
+    pub step_index: i32,
+    pub compensating: bool,
+    pub attempt: i32,
+    pub reply_to: String,
+    pub payload: Value,
+}
+
+impl DomainEvent for SagaCommand {
+    const EVENT_TYPE: &'static str = "saga_command";
+    const ROUTING_KEY: &'static str = "saga.command";
+}
+
+impl SagaCommand {
+    pub fn success(&self, payload: Value) -> SagaReply {
+        self.reply(true, payload, None)
+    }
+
+    pub fn failure(&self, error: &str) -> SagaReply {
+        self.reply(false, Value::Null, Some(error.to_string()))
+    }
+
+    fn reply(&self, success: bool, payload: Value, error: Option<String>) -> SagaReply {
+        SagaReply {
+            saga_id: self.saga_id,
+            step_index: self.step_index,
+            compensating: self.compensating,
+            attempt: self.attempt,
+            success,
+            payload,
+            error,
+        }
+    }
+}
+
+// Sent by a participant back to the orchestrator on `reply_to`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SagaReply {
+    pub saga_id: Uuid,
+    pub step_index: i32,
+    pub compensating: bool,
+    pub attempt: i32,
+    pub success: bool,
+    pub payload: Value,
+    pub error: Option<String>,
+}
+
+impl DomainEvent for SagaReply {
+    const EVENT_TYPE: &'static str = "saga_reply";
+    const ROUTING_KEY: &'static str = "saga.reply";
+}
+
+pub fn reply_routing_key(saga_type: &str) -> String {
+    format!("{}.{}", SagaReply::ROUTING_KEY, saga_type)
+}
+
+// Used by participants to answer a command they received
+pub async fn send_reply(
+    broker: &MessageBroker,
+    source_service: &str,
+    command: &EventEnvelope<SagaCommand>,
+    reply: SagaReply,
+) -> SystemResult<()> {
+    let envelope = EventEnvelope::new(source_service, reply).caused_by(command);
+    broker.publish_to(&envelope, &command.payload.reply_to).await
+}
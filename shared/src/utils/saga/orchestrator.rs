@@ -0,0 +1,349 @@
+use crate::events::{EventEnvelope, ExchangeType};
+use crate::features::errors::{SystemError, SystemResult};
+use crate::utils::messaging::retry::MessageError;
+use crate::utils::messaging::{MessageBroker, Publisher};
+use crate::utils::saga::store::PostgresSagaStore;
+use crate::utils::saga::{reply_routing_key, SagaCommand, SagaDefinition, SagaInstance, SagaReply, SagaStatus, SagaStep};
+use chrono::{DateTime, Utc};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::broadcast;
+use uuid::Uuid;
+
+const TIMEOUT_CHECK_INTERVAL: Duration = Duration::from_secs(5);
+const TIMEOUT_BATCH_SIZE: i64 = 100;
+
+// Drives every instance of one saga type. State changes are committed before
+// the resulting command is published; a command that fails to publish is
+// covered by the step timeout, so an orchestrator can crash at any point
+// without losing a saga. Commands go out through `publisher`; replies are
+// consumed from the `MessageBroker` given to `spawn`.
+pub struct SagaOrchestrator<S: SagaDefinition> {
+    store: PostgresSagaStore,
+    publisher: Arc<dyn Publisher>,
+    source_service: String,
+    steps: Vec<SagaStep<S::Data>>,
+}
+
+fn deadline(timeout: Duration) -> Option<DateTime<Utc>> {
+    chrono::Duration::from_std(timeout).ok().map(|timeout| Utc::now() + timeout)
+}
+
+fn to_json<T: serde::Serialize>(value: &T) -> SystemResult<serde_json::Value> {
+    serde_json::to_value(value).map_err(|e| SystemError::SerializationError(e.to_string()))
+}
+
+impl<S: SagaDefinition> SagaOrchestrator<S> {
+    pub fn new(store: PostgresSagaStore, publisher: Arc<dyn Publisher>, source_service: &str) -> Self {
+        Self {
+            store,
+            publisher,
+            source_service: source_service.to_string(),
+            steps: S::steps(),
+        }
+    }
+
+    pub fn reply_queue(&self) -> String {
+        format!("{}.saga.{}", self.source_service, S::SAGA_TYPE)
+    }
+
+    // Declares the reply queue; must run before the first saga is started so
+    // no reply is dropped
+    pub async fn setup(&self, broker: &MessageBroker) -> SystemResult<()> {
+        broker
+            .setup_queue(&self.reply_queue(), &[&reply_routing_key(S::SAGA_TYPE)], ExchangeType::Topic)
+            .await
+    }
+
+    // Persists a new saga and sends its first command
+    pub async fn start(&self, data: S::Data, correlation_id: Option<Uuid>) -> SystemResult<Uuid> {
+        let first = self.steps.first().ok_or_else(|| {
+            SystemError::ConfigurationError(format!("Saga {} has no steps", S::SAGA_TYPE))
+        })?;
+
+        let now = Utc::now();
+        let mut instance = SagaInstance {
+            id: Uuid::new_v4(),
+            saga_type: S::SAGA_TYPE.to_string(),
+            status: SagaStatus::Running,
+            current_step: 0,
+            attempt: 1,
+            data: to_json(&data)?,
+            history: Vec::new(),
+            last_error: None,
+            correlation_id,
+            step_deadline: deadline(first.timeout),
+            created_at: now,
+            updated_at: now,
+        };
+        instance.record(first.name, false, "sent", None);
+
+        self.store.insert(&instance).await?;
+        self.dispatch(&instance).await;
+        Ok(instance.id)
+    }
+
+    pub async fn status(&self, saga_id: Uuid) -> SystemResult<Option<SagaInstance>> {
+        self.store.find(saga_id).await
+    }
+
+    // Applies a participant's reply. Replies that don't match the step the
+    // saga is waiting on (duplicates, or late replies after a timeout) are
+    // ignored.
+    pub async fn handle_reply(&self, envelope: EventEnvelope<SagaReply>) -> SystemResult<()> {
+        let reply = envelope.payload;
+        let mut tx = self.store.begin().await?;
+        let Some(mut instance) = self.store.lock(&mut tx, reply.saga_id).await? else {
+            log::warn!("Received reply for unknown saga {}", reply.saga_id);
+            return Ok(());
+        };
+
+        let expected = instance.saga_type == S::SAGA_TYPE
+            && instance.current_step == reply.step_index
+            && match instance.status {
+                SagaStatus::Running => !reply.compensating,
+                SagaStatus::Compensating => reply.compensating,
+                _ => false,
+            };
+        if !expected {
+            log::info!(
+                "Ignoring stale reply for saga {} step {} (saga is {} at step {})",
+                instance.id,
+                reply.step_index,
+                instance.status,
+                instance.current_step
+            );
+            return Ok(());
+        }
+
+        let send = self.apply_reply(&mut instance, reply)?;
+        self.store.update(&mut tx, &instance).await?;
+        tx.commit().await?;
+
+        if send {
+            self.dispatch(&instance).await;
+        }
+        Ok(())
+    }
+
+    // Returns whether the saga has a new command to send
+    fn apply_reply(&self, instance: &mut SagaInstance, reply: SagaReply) -> SystemResult<bool> {
+        let index = instance.current_step as usize;
+        let step = self.steps.get(index).ok_or_else(|| {
+            SystemError::InternalError(format!("Saga {} has no step {}", S::SAGA_TYPE, index))
+        })?;
+
+        if reply.success {
+            if !reply.compensating {
+                if let Some(on_success) = step.on_success {
+                    let mut data: S::Data = serde_json::from_value(instance.data.clone())
+                        .map_err(|e| SystemError::DeserializationError(e.to_string()))?;
+                    on_success(&mut data, &reply.payload);
+                    instance.data = to_json(&data)?;
+                }
+            }
+            instance.record(step.name, reply.compensating, "succeeded", None);
+            return Ok(if reply.compensating {
+                self.compensate_from(instance, index.checked_sub(1))
+            } else {
+                self.advance(instance)
+            });
+        }
+
+        let error = reply.error.unwrap_or_else(|| "unknown error".to_string());
+        instance.record(step.name, reply.compensating, "failed", Some(error.clone()));
+        instance.last_error = Some(error);
+
+        if reply.compensating {
+            log::error!("Compensation {} of saga {} failed; saga needs attention", step.name, instance.id);
+            instance.status = SagaStatus::Failed;
+            instance.step_deadline = None;
+            return Ok(false);
+        }
+        Ok(self.compensate_from(instance, index.checked_sub(1)))
+    }
+
+    // Moves to the next step, or completes the saga after the last one
+    fn advance(&self, instance: &mut SagaInstance) -> bool {
+        let next = instance.current_step as usize + 1;
+        let Some(step) = self.steps.get(next) else {
+            instance.status = SagaStatus::Completed;
+            instance.step_deadline = None;
+            return false;
+        };
+
+        instance.current_step = next as i32;
+        instance.attempt = 1;
+        instance.step_deadline = deadline(step.timeout);
+        instance.record(step.name, false, "sent", None);
+        true
+    }
+
+    // Compensates steps `from` down to 0, skipping steps without a
+    // compensation. `None` means nothing is left to undo.
+    fn compensate_from(&self, instance: &mut SagaInstance, from: Option<usize>) -> bool {
+        let next = from.and_then(|from| (0..=from).rev().find(|&i| self.steps[i].compensation.is_some()));
+        let Some(index) = next else {
+            instance.status = SagaStatus::Compensated;
+            instance.step_deadline = None;
+            return false;
+        };
+
+        let step = &self.steps[index];
+        instance.status = SagaStatus::Compensating;
+        instance.current_step = index as i32;
+        instance.attempt = 1;
+        instance.step_deadline = deadline(step.timeout);
+        instance.record(step.name, true, "sent", None);
+        true
+    }
+
+    // A step that misses its deadline is treated as failed and compensated,
+    // including the step itself since it may have run. A compensation that
+    // misses its deadline is resent up to `MAX_COMPENSATION_ATTEMPTS` times.
+    pub async fn check_timeouts(&self) -> SystemResult<usize> {
+        let mut tx = self.store.begin().await?;
+        let expired = self
+            .store
+            .lock_expired(&mut tx, S::SAGA_TYPE, TIMEOUT_BATCH_SIZE)
+            .await?;
+        let count = expired.len();
+        let mut to_send = Vec::new();
+
+        for mut instance in expired {
+            let index = instance.current_step as usize;
+            let Some(step) = self.steps.get(index) else {
+                instance.status = SagaStatus::Failed;
+                instance.step_deadline = None;
+                instance.last_error = Some(format!("Unknown step {}", index));
+                self.store.update(&mut tx, &instance).await?;
+                continue;
+            };
+
+            let compensating = instance.status == SagaStatus::Compensating;
+            instance.record(step.name, compensating, "timed_out", None);
+
+            let send = if !compensating {
+                log::warn!("Step {} of saga {} timed out, compensating", step.name, instance.id);
+                instance.last_error = Some(format!("Step {} timed out", step.name));
+                self.compensate_from(&mut instance, Some(index))
+            } else if instance.attempt < S::MAX_COMPENSATION_ATTEMPTS {
+                instance.attempt += 1;
+                instance.step_deadline = deadline(step.timeout);
+                instance.record(step.name, true, "sent", None);
+                true
+            } else {
+                log::error!(
+                    "Compensation {} of saga {} timed out {} times; saga needs attention",
+                    step.name,
+                    instance.id,
+                    instance.attempt
+                );
+                instance.last_error = Some(format!(
+                    "Compensation {} timed out after {} attempts",
+                    step.name, instance.attempt
+                ));
+                instance.status = SagaStatus::Failed;
+                instance.step_deadline = None;
+                false
+            };
+
+            self.store.update(&mut tx, &instance).await?;
+            if send {
+                to_send.push(instance);
+            }
+        }
+        tx.commit().await?;
+
+        for instance in &to_send {
+            self.dispatch(instance).await;
+        }
+        Ok(count)
+    }
+
+    // Publishes the command for the saga's current step (or its compensation)
+    async fn dispatch(&self, instance: &SagaInstance) {
+        let index = instance.current_step as usize;
+        let Some(step) = self.steps.get(index) else {
+            return;
+        };
+        let data: S::Data = match serde_json::from_value(instance.data.clone()) {
+            Ok(data) => data,
+            Err(e) => {
+                log::error!("Failed to decode data of saga {}: {}", instance.id, e);
+                return;
+            }
+        };
+
+        let compensating = instance.status == SagaStatus::Compensating;
+        let (routing_key, payload) = match (compensating, step.compensation) {
+            (false, _) => (step.routing_key, (step.command)(&data)),
+            (true, Some((routing_key, compensation))) => (routing_key, compensation(&data)),
+            (true, None) => return,
+        };
+
+        let command = SagaCommand {
+            saga_id: instance.id,
+            saga_type: S::SAGA_TYPE.to_string(),
+            step: step.name.to_string(),
+            step_index: instance.current_step,
+            compensating,
+            attempt: instance.attempt,
+            reply_to: reply_routing_key(S::SAGA_TYPE),
+            payload,
+        };
+        let envelope = EventEnvelope::new(&self.source_service, command)
+            .with_correlation_id(instance.correlation_id.unwrap_or(instance.id));
+
+        if let Err(e) = self.publisher.publish_to(&envelope, routing_key).await {
+            log::warn!(
+                "Failed to send {} for saga {}, it will be handled when the step times out: {}",
+                step.name,
+                instance.id,
+                e
+            );
+        }
+    }
+
+    // Consumes replies and checks step deadlines until shutdown
+    pub fn spawn(self: Arc<Self>, broker: MessageBroker, shutdown_tx: &broadcast::Sender<()>) {
+        let consumer = self.clone();
+        let shutdown_rx = shutdown_tx.subscribe();
+        tokio::spawn(async move {
+            let queue_name = consumer.reply_queue();
+            let consumer_tag = format!("{}.consumer", queue_name);
+            if let Err(e) = consumer.setup(&broker).await {
+                log::error!("Failed to declare saga reply queue {}: {}", queue_name, e);
+            }
+
+            let orchestrator = consumer.clone();
+            let result = broker
+                .consume(&queue_name, &consumer_tag, shutdown_rx, move |message| {
+                    let orchestrator = orchestrator.clone();
+                    async move {
+                        let envelope = EventEnvelope::<SagaReply>::decode(&message.data).map_err(MessageError::Poison)?;
+                        orchestrator.handle_reply(envelope).await.map_err(MessageError::from)
+                    }
+                })
+                .await;
+            if let Err(e) = result {
+                log::error!("Saga reply consumer {} stopped: {}", queue_name, e);
+            }
+        });
+
+        let mut shutdown_rx = shutdown_tx.subscribe();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(TIMEOUT_CHECK_INTERVAL);
+            loop {
+                tokio::select! {
+                    _ = shutdown_rx.recv() => break,
+                    _ = interval.tick() => {
+                        if let Err(e) = self.check_timeouts().await {
+                            log::error!("Saga {} timeout check failed: {}", S::SAGA_TYPE, e);
+                        }
+                    }
+                }
+            }
+        });
+    }
+}
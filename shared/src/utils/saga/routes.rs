@@ -0,0 +1,75 @@
+use crate::entities::enums::UserRole;
+use crate::features::errors::{
+    map_auth_error_to_response, map_success_to_response, SuccessResponse, SystemError, SystemResult,
+};
+use crate::features::security::auth::AuthenticatedUser;
+use crate::utils::saga::{PostgresSagaStore, SagaStatus};
+use actix_web::{web, HttpResponse, Result, Scope};
+use serde::Deserialize;
+use uuid::Uuid;
+
+const ALLOWED_ROLES: [UserRole; 2] = [UserRole::Admin, UserRole::SuperAdmin];
+
+#[derive(Debug, Deserialize)]
+pub struct SagaListQuery {
+    pub saga_type: Option<String>,
+    pub status: Option<String>,
+    pub limit: Option<i64>,
+}
+
+// Read-only saga status for debugging, restricted to admins signed in as
+// themselves. Needs the store
+// registered as app data:
+//
+//     .app_data(web::Data::new(PostgresSagaStore::new(pool.clone())))
+//     .service(saga_routes())
+pub fn saga_routes() -> Scope {
+    web::scope("/sagas")
+        .route("", web::get().to(list_sagas))
+        .route("/{saga_id}", web::get().to(get_saga))
+}
+
+async fn get_saga(
+    user: AuthenticatedUser,
+    store: web::Data<PostgresSagaStore>,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse> {
+    if let Err(err) = require_admin(&user) {
+        return Ok(map_auth_error_to_response(&err));
+    }
+
+    let saga_id = path.into_inner();
+    match store.find(saga_id).await {
+        Ok(Some(instance)) => Ok(map_success_to_response(SuccessResponse::Fetched, Some(instance), None)),
+        Ok(None) => Ok(map_auth_error_to_response(&SystemError::NotFound(format!("Saga {} not found", saga_id)))),
+        Err(err) => Ok(map_auth_error_to_response(&err)),
+    }
+}
+
+async fn list_sagas(
+    user: AuthenticatedUser,
+    store: web::Data<PostgresSagaStore>,
+    query: web::Query<SagaListQuery>,
+) -> Result<HttpResponse> {
+    if let Err(err) = require_admin(&user) {
+        return Ok(map_auth_error_to_response(&err));
+    }
+
+    let status = match query.status.as_deref().map(str::parse::<SagaStatus>).transpose() {
+        Ok(status) => status,
+        Err(err) => return Ok(map_auth_error_to_response(&err)),
+    };
+    let limit = query.limit.unwrap_or(50).clamp(1, 500);
+
+    match store.list(query.saga_type.as_deref(), status, limit).await {
+        Ok(instances) => Ok(map_success_to_response(SuccessResponse::Fetched, Some(instances), None)),
+        Err(err) => Ok(map_auth_error_to_response(&err)),
+    }
+}
+
+// Saga data carries other users' bookings and payments, so an admin's API
+// key or OAuth token is not enough
+fn require_admin(user: &AuthenticatedUser) -> SystemResult<()> {
+    user.require_any_role(&ALLOWED_ROLES)?;
+    user.forbid_delegation()
+}
@@ -0,0 +1,20 @@
+CREATE TABLE IF NOT EXISTS saga_instances (
+    id UUID PRIMARY KEY,
+    saga_type VARCHAR(100) NOT NULL,
+    status VARCHAR(20) NOT NULL,
+    current_step INTEGER NOT NULL DEFAULT 0,
+    attempt INTEGER NOT NULL DEFAULT 1,
+    data JSONB NOT NULL,
+    history JSONB NOT NULL DEFAULT '[]'::jsonb,
+    last_error TEXT,
+    correlation_id UUID,
+    step_deadline TIMESTAMP WITH TIME ZONE,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW() NOT NULL,
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT NOW() NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_saga_instances_deadline
+    ON saga_instances (saga_type, step_deadline)
+    WHERE status IN ('running', 'compensating');
+
+CREATE INDEX IF NOT EXISTS idx_saga_instances_status ON saga_instances (status, updated_at);
@@ -0,0 +1,151 @@
+use crate::features::errors::{SystemError, SystemResult};
+use crate::utils::saga::{SagaInstance, SagaStatus};
+use sqlx::postgres::PgRow;
+use sqlx::{PgConnection, Pool, Postgres, Row, Transaction};
+use uuid::Uuid;
+
+const COLUMNS: &str = "id, saga_type, status, current_step, attempt, data::text AS data, history::text AS history, \
+     last_error, correlation_id, step_deadline, created_at, updated_at";
+
+// Saga state lives in `saga_instances` (see `SAGA_SCHEMA_SQL`). The
+// orchestrator changes an instance only while holding its row lock, so a
+// reply and a timeout for the same saga are never applied at the same time.
+#[derive(Clone)]
+pub struct PostgresSagaStore {
+    pool: Pool<Postgres>,
+}
+
+impl PostgresSagaStore {
+    pub fn new(pool: Pool<Postgres>) -> Self {
+        Self { pool }
+    }
+
+    pub async fn begin(&self) -> SystemResult<Transaction<'static, Postgres>> {
+        Ok(self.pool.begin().await?)
+    }
+
+    #[tracing::instrument(name = "PostgresSagaStore::insert", skip_all, fields(db.system = "postgresql"))]
+    pub async fn insert(&self, instance: &SagaInstance) -> SystemResult<()> {
+        log::info!("insert() called with saga_id: {}", instance.id);
+        sqlx::query(
+            r#"
+            INSERT INTO saga_instances
+                (id, saga_type, status, current_step, attempt, data, history, last_error, correlation_id,
+                 step_deadline, created_at, updated_at)
+            VALUES ($1, $2, $3, $4, $5, $6::jsonb, $7::jsonb, $8, $9, $10, $11, $12)
+            "#
+        )
+        .bind(instance.id)
+        .bind(&instance.saga_type)
+        .bind(instance.status.as_str())
+        .bind(instance.current_step)
+        .bind(instance.attempt)
+        .bind(instance.data.to_string())
+        .bind(history_json(instance)?)
+        .bind(&instance.last_error)
+        .bind(instance.correlation_id)
+        .bind(instance.step_deadline)
+        .bind(instance.created_at)
+        .bind(instance.updated_at)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    // Locks the instance until the transaction ends
+    #[tracing::instrument(name = "PostgresSagaStore::lock", skip_all, fields(db.system = "postgresql"))]
+    pub async fn lock(&self, conn: &mut PgConnection, saga_id: Uuid) -> SystemResult<Option<SagaInstance>> {
+        let query = format!("SELECT {} FROM saga_instances WHERE id = $1 FOR UPDATE", COLUMNS);
+        let row = sqlx::query(&query).bind(saga_id).fetch_optional(conn).await?;
+        row.map(|row| from_row(&row)).transpose()
+    }
+
+    // Locks up to `limit` in-flight instances whose step deadline has passed.
+    // Instances locked by another orchestrator are skipped.
+    #[tracing::instrument(name = "PostgresSagaStore::lock_expired", skip_all, fields(db.system = "postgresql"))]
+    pub async fn lock_expired(&self, conn: &mut PgConnection, saga_type: &str, limit: i64) -> SystemResult<Vec<SagaInstance>> {
+        let query = format!(
+            "SELECT {} FROM saga_instances \
+             WHERE saga_type = $1 AND status IN ('running', 'compensating') AND step_deadline < NOW() \
+             ORDER BY step_deadline LIMIT $2 FOR UPDATE SKIP LOCKED",
+            COLUMNS
+        );
+        let rows = sqlx::query(&query).bind(saga_type).bind(limit).fetch_all(conn).await?;
+        rows.iter().map(from_row).collect()
+    }
+
+    #[tracing::instrument(name = "PostgresSagaStore::update", skip_all, fields(db.system = "postgresql"))]
+    pub async fn update(&self, conn: &mut PgConnection, instance: &SagaInstance) -> SystemResult<()> {
+        sqlx::query(
+            r#"
+            UPDATE saga_instances
+            SET status = $2, current_step = $3, attempt = $4, data = $5::jsonb, history = $6::jsonb,
+                last_error = $7, step_deadline = $8, updated_at = NOW()
+            WHERE id = $1
+            "#
+        )
+        .bind(instance.id)
+        .bind(instance.status.as_str())
+        .bind(instance.current_step)
+        .bind(instance.attempt)
+        .bind(instance.data.to_string())
+        .bind(history_json(instance)?)
+        .bind(&instance.last_error)
+        .bind(instance.step_deadline)
+        .execute(conn)
+        .await?;
+        Ok(())
+    }
+
+    #[tracing::instrument(name = "PostgresSagaStore::find", skip_all, fields(db.system = "postgresql"))]
+    pub async fn find(&self, saga_id: Uuid) -> SystemResult<Option<SagaInstance>> {
+        log::info!("find() called with saga_id: {}", saga_id);
+        let query = format!("SELECT {} FROM saga_instances WHERE id = $1", COLUMNS);
+        let row = sqlx::query(&query).bind(saga_id).fetch_optional(&self.pool).await?;
+        row.map(|row| from_row(&row)).transpose()
+    }
+
+    // Most recently updated first
+    #[tracing::instrument(name = "PostgresSagaStore::list", skip_all, fields(db.system = "postgresql"))]
+    pub async fn list(&self, saga_type: Option<&str>, status: Option<SagaStatus>, limit: i64) -> SystemResult<Vec<SagaInstance>> {
+        log::info!("list() called with saga_type: {:?}, status: {:?}", saga_type, status);
+        let query = format!(
+            "SELECT {} FROM saga_instances \
+             WHERE ($1::text IS NULL OR saga_type = $1) AND ($2::text IS NULL OR status = $2) \
+             ORDER BY updated_at DESC LIMIT $3",
+            COLUMNS
+        );
+        let rows = sqlx::query(&query)
+            .bind(saga_type)
+            .bind(status.map(|s| s.as_str()))
+            .bind(limit)
+            .fetch_all(&self.pool)
+            .await?;
+        rows.iter().map(from_row).collect()
+    }
+}
+
+fn history_json(instance: &SagaInstance) -> SystemResult<String> {
+    serde_json::to_string(&instance.history).map_err(|e| SystemError::SerializationError(e.to_string()))
+}
+
+fn from_row(row: &PgRow) -> SystemResult<SagaInstance> {
+    let status: String = row.try_get("status")?;
+    let data: String = row.try_get("data")?;
+    let history: String = row.try_get("history")?;
+
+    Ok(SagaInstance {
+        id: row.try_get("id")?,
+        saga_type: row.try_get("saga_type")?,
+        status: status.parse()?,
+        current_step: row.try_get("current_step")?,
+        attempt: row.try_get("attempt")?,
+        data: serde_json::from_str(&data).map_err(|e| SystemError::DeserializationError(e.to_string()))?,
+        history: serde_json::from_str(&history).map_err(|e| SystemError::DeserializationError(e.to_string()))?,
+        last_error: row.try_get("last_error")?,
+        correlation_id: row.try_get("correlation_id")?,
+        step_deadline: row.try_get("step_deadline")?,
+        created_at: row.try_get("created_at")?,
+        updated_at: row.try_get("updated_at")?,
+    })
+}
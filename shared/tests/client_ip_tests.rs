@@ -0,0 +1,84 @@
+// The client address `AuthMiddleware` hands to API key allowlists
+use actix_web::http::StatusCode;
+use actix_web::{test, web, App, HttpResponse};
+use async_trait::async_trait;
+use shared::config::jwt_config::JwtConfig;
+use shared::entities::enums::UserRole;
+use shared::features::errors::{SystemError, SystemResult};
+use shared::features::security::auth::{ApiKeyValidator, AuthMiddleware, TrustedProxies, API_KEY_HEADER};
+use shared::features::security::jwt::JwtClaims;
+use std::sync::Arc;
+use uuid::Uuid;
+
+// Accepts the key only from 203.0.113.7
+struct AllowlistedKey;
+
+#[async_trait]
+impl ApiKeyValidator for AllowlistedKey {
+    async fn validate(&self, _api_key: &str, ip_address: Option<&str>) -> SystemResult<JwtClaims> {
+        if ip_address != Some("203.0.113.7") {
+            return Err(SystemError::PermissionDenied(format!("Not allowed from {:?}", ip_address)));
+        }
+        let config = JwtConfig::default();
+        Ok(JwtClaims::new(
+            Uuid::new_v4(),
+            "integration@example.com".to_string(),
+            UserRole::Landlord,
+            vec![],
+            config.issuer,
+            config.audience,
+            Uuid::new_v4(),
+            60,
+        ))
+    }
+}
+
+async fn status(middleware: AuthMiddleware, peer: &str, forwarded_for: Option<&str>) -> StatusCode {
+    let app = test::init_service(
+        App::new()
+            .wrap(middleware)
+            .route("/api/v1/properties", web::get().to(HttpResponse::Ok)),
+    )
+    .await;
+    let mut request = test::TestRequest::get()
+        .uri("/api/v1/properties")
+        .peer_addr(peer.parse().unwrap())
+        .insert_header((API_KEY_HEADER, "bk_000000000000_secret"));
+    if let Some(forwarded_for) = forwarded_for {
+        request = request.insert_header(("X-Forwarded-For", forwarded_for));
+    }
+    test::call_service(&app, request.to_request()).await.status()
+}
+
+fn middleware() -> AuthMiddleware {
+    AuthMiddleware::new(JwtConfig::default()).with_api_key_validator(Arc::new(AllowlistedKey))
+}
+
+#[actix_web::test]
+async fn forwarded_headers_from_untrusted_peers_are_ignored() {
+    assert_eq!(status(middleware(), "203.0.113.7:4000", None).await, StatusCode::OK);
+
+    let spoofed = status(middleware(), "198.51.100.9:4000", Some("203.0.113.7")).await;
+
+    assert_eq!(spoofed, StatusCode::FORBIDDEN);
+}
+
+#[actix_web::test]
+async fn trusted_proxies_report_the_client_they_forwarded() {
+    let proxies = TrustedProxies::parse("10.0.0.0/8, 192.0.2.1").unwrap();
+    let through_proxies = || middleware().with_trusted_proxies(proxies.clone());
+
+    // Two trusted hops; the client wrote the leftmost entry itself
+    let forwarded = status(through_proxies(), "10.0.0.5:4000", Some("198.51.100.9, 203.0.113.7, 192.0.2.1")).await;
+    assert_eq!(forwarded, StatusCode::OK);
+
+    let spoofed = status(through_proxies(), "10.0.0.5:4000", Some("203.0.113.7, 198.51.100.9")).await;
+    assert_eq!(spoofed, StatusCode::FORBIDDEN);
+}
+
+#[actix_web::test]
+async fn trusted_proxy_lists_must_parse() {
+    assert!(TrustedProxies::parse("").is_ok());
+    assert!(TrustedProxies::parse("10.0.0.0/33").is_err());
+    assert!(TrustedProxies::parse("traefik").is_err());
+}
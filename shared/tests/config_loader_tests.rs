@@ -0,0 +1,208 @@
+// How `ConfigLoader` layers defaults, TOML, the environment and `--set`
+use serde::{Deserialize, Serialize};
+use shared::config::loader::{ConfigArgs, ConfigLoader, ConfigSection, Violations};
+use shared::features::errors::SystemError;
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use uuid::Uuid;
+
+// The environment is shared by every test in this binary
+static ENV: Mutex<()> = Mutex::new(());
+
+const VARIABLES: &[&str] = &[
+    "CONFIG_FILE",
+    "LOADER_TEST_HOST",
+    "LOADER_TEST_PORT",
+    "LOADER_TEST_API_KEY",
+    "LOADER_TEST_API_KEY_FILE",
+    "LOADER_TEST_ENABLED",
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ListingConfig {
+    host: String,
+    port: u16,
+    api_key: String,
+    enabled: bool,
+}
+
+impl Default for ListingConfig {
+    fn default() -> Self {
+        Self {
+            host: "localhost".to_string(),
+            port: 8080,
+            api_key: String::new(),
+            enabled: true,
+        }
+    }
+}
+
+impl ConfigSection for ListingConfig {
+    const ENV: &'static [(&'static str, &'static str)] = &[
+        ("LOADER_TEST_HOST", "host"),
+        ("LOADER_TEST_PORT", "port"),
+        ("LOADER_TEST_API_KEY", "api_key"),
+        ("LOADER_TEST_ENABLED", "enabled"),
+    ];
+
+    const SECRETS: &'static [&'static str] = &["api_key"];
+
+    fn validate(&self, violations: &mut Violations) {
+        violations.check(!self.host.is_empty(), "host", "must be set");
+        violations.check(self.port > 0, "port", "must be at least 1");
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct TestConfig {
+    listing: ListingConfig,
+}
+
+fn loader() -> ConfigLoader {
+    ConfigLoader::new("loader-test")
+        .section::<ListingConfig>("listing")
+        .with_default("listing.port", 8000)
+}
+
+// Holds the environment until dropped, then clears what the test set
+struct Env {
+    _guard: MutexGuard<'static, ()>,
+}
+
+impl Env {
+    fn lock() -> Self {
+        let guard = ENV.lock().unwrap_or_else(PoisonError::into_inner);
+        VARIABLES.iter().for_each(|variable| env::remove_var(variable));
+        Self { _guard: guard }
+    }
+
+    fn set(&self, variable: &str, value: &str) {
+        env::set_var(variable, value);
+    }
+}
+
+impl Drop for Env {
+    fn drop(&mut self) {
+        VARIABLES.iter().for_each(|variable| env::remove_var(variable));
+    }
+}
+
+// Removed when dropped
+struct TempFile(PathBuf);
+
+impl TempFile {
+    fn new(contents: &str) -> Self {
+        let path = env::temp_dir().join(format!("loader-test-{}", Uuid::new_v4()));
+        fs::write(&path, contents).unwrap();
+        Self(path)
+    }
+
+    fn path(&self) -> &str {
+        self.0.to_str().unwrap()
+    }
+}
+
+impl Drop for TempFile {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.0);
+    }
+}
+
+fn args(file: Option<&TempFile>, overrides: &[(&str, &str)]) -> ConfigArgs {
+    ConfigArgs {
+        file: file.map(|f| f.0.clone()),
+        overrides: overrides.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect(),
+        print_config: false,
+    }
+}
+
+fn load_error(args: &ConfigArgs) -> String {
+    match loader().load::<TestConfig>(args) {
+        Err(SystemError::ConfigurationError(message)) => message,
+        other => panic!("expected a configuration error, got {:?}", other.map(|c| c.listing)),
+    }
+}
+
+#[test]
+fn each_layer_overrides_the_one_before() {
+    let env = Env::lock();
+    let toml = TempFile::new("[listing]\nhost = \"toml-host\"\nport = 8100\n");
+
+    let config: TestConfig = loader().load(&args(None, &[])).unwrap();
+    assert_eq!((config.listing.host.as_str(), config.listing.port), ("localhost", 8000));
+
+    let config: TestConfig = loader().load(&args(Some(&toml), &[])).unwrap();
+    assert_eq!((config.listing.host.as_str(), config.listing.port), ("toml-host", 8100));
+
+    env.set("LOADER_TEST_PORT", "8200");
+    let config: TestConfig = loader().load(&args(Some(&toml), &[])).unwrap();
+    assert_eq!((config.listing.host.as_str(), config.listing.port), ("toml-host", 8200));
+
+    let config: TestConfig = loader()
+        .load(&args(Some(&toml), &[("listing.port", "8300"), ("listing.enabled", "no")]))
+        .unwrap();
+    assert_eq!((config.listing.host.as_str(), config.listing.port), ("toml-host", 8300));
+    assert!(!config.listing.enabled);
+}
+
+#[test]
+fn secrets_can_be_read_from_a_file() {
+    let env = Env::lock();
+    let secret = TempFile::new("from-the-file\n");
+    env.set("LOADER_TEST_API_KEY_FILE", secret.path());
+
+    let config: TestConfig = loader().load(&args(None, &[])).unwrap();
+    assert_eq!(config.listing.api_key, "from-the-file");
+
+    // The variable itself wins over its file
+    env.set("LOADER_TEST_API_KEY", "from-the-variable");
+    let config: TestConfig = loader().load(&args(None, &[])).unwrap();
+    assert_eq!(config.listing.api_key, "from-the-variable");
+}
+
+#[test]
+fn an_unreadable_secret_file_is_an_error() {
+    let env = Env::lock();
+    env.set("LOADER_TEST_API_KEY_FILE", "/nonexistent/loader-test-secret");
+
+    let message = load_error(&args(None, &[]));
+
+    assert!(message.contains("listing.api_key: cannot read LOADER_TEST_API_KEY_FILE"), "{}", message);
+}
+
+#[test]
+fn every_mistake_is_reported_at_once() {
+    let env = Env::lock();
+    env.set("LOADER_TEST_PORT", "eighty");
+    env.set("LOADER_TEST_ENABLED", "maybe");
+
+    let message = load_error(&args(None, &[("listing.host", ""), ("listing.colour", "blue")]));
+
+    for expected in [
+        "listing.port: expected an integer, got \"eighty\" from LOADER_TEST_PORT",
+        "listing.enabled: expected true or false, got \"maybe\" from LOADER_TEST_ENABLED",
+        "listing.colour: unknown setting (from --set)",
+        "listing.host: must be set (set LOADER_TEST_HOST)",
+    ] {
+        assert!(message.contains(expected), "missing {:?} in:\n{}", expected, message);
+    }
+}
+
+#[test]
+fn rendering_masks_secrets() {
+    let _env = Env::lock();
+    let loader = loader();
+    let mut config: TestConfig = loader.load(&args(None, &[])).unwrap();
+
+    // Unset secrets stay visibly empty
+    assert!(loader.render(&config).contains("api_key = \"\""));
+
+    config.listing.api_key = "sk_live_123".to_string();
+    let rendered = loader.render(&config);
+
+    assert!(!rendered.contains("sk_live_123"), "{}", rendered);
+    assert!(rendered.contains("api_key = \"<redacted>\""), "{}", rendered);
+    assert!(rendered.contains("host = \"localhost\""), "{}", rendered);
+}
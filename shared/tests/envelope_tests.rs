@@ -0,0 +1,27 @@
+// Decoding bodies published before envelopes existed
+use shared::events::notification_event::email_otp_requested_event::EmailOtpRequestedEvent;
+use shared::events::notification_event::sms_otp_requested_event::SmsOtpRequestedEvent;
+use shared::events::EventEnvelope;
+
+#[test]
+fn redeliveries_of_a_legacy_body_keep_their_event_id() {
+    let body = br#"{"phone":"+2348012345678","otp_code":"123456"}"#;
+
+    let first = EventEnvelope::<SmsOtpRequestedEvent>::decode(body).unwrap();
+    let redelivered = EventEnvelope::<SmsOtpRequestedEvent>::decode(body).unwrap();
+    let another = EventEnvelope::<SmsOtpRequestedEvent>::decode(br#"{"phone":"+2348012345678","otp_code":"654321"}"#).unwrap();
+
+    assert_eq!(first.event_id, redelivered.event_id);
+    assert_ne!(first.event_id, another.event_id);
+    assert_eq!(first.schema_version, 1);
+}
+
+#[test]
+fn legacy_ids_differ_between_routing_keys() {
+    let body = br#"{"email":"tenant@example.com","otp_code":"123456","phone":"+2348012345678"}"#;
+
+    let sms = EventEnvelope::<SmsOtpRequestedEvent>::decode(body).unwrap();
+    let email = EventEnvelope::<EmailOtpRequestedEvent>::decode(body).unwrap();
+
+    assert_ne!(sms.event_id, email.event_id);
+}
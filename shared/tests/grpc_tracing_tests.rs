@@ -0,0 +1,47 @@
+// gRPC calls carry the caller's W3C trace context
+use opentelemetry::global;
+use opentelemetry::trace::TraceContextExt;
+use opentelemetry_sdk::propagation::TraceContextPropagator;
+use shared::features::observability::grpc::{extract_trace_context, inject_trace_context, RemoteTraceContext};
+use tonic::Request;
+
+const TRACE_ID: &str = "4bf92f3577b34da6a3ce929d0e0e4736";
+const TRACEPARENT: &str = "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01";
+
+fn remote_trace_id(request: &Request<()>) -> Option<String> {
+    let remote = request.extensions().get::<RemoteTraceContext>()?;
+    let span = remote.0.span();
+    let context = span.span_context();
+    context.is_valid().then(|| context.trace_id().to_string())
+}
+
+#[test]
+fn the_server_interceptor_keeps_the_callers_trace() {
+    global::set_text_map_propagator(TraceContextPropagator::new());
+    let mut request = Request::new(());
+    request.metadata_mut().insert("traceparent", TRACEPARENT.parse().unwrap());
+
+    let request = extract_trace_context(request).unwrap();
+
+    assert_eq!(remote_trace_id(&request).as_deref(), Some(TRACE_ID));
+}
+
+#[test]
+fn calls_without_a_trace_have_no_remote_parent() {
+    global::set_text_map_propagator(TraceContextPropagator::new());
+
+    let request = extract_trace_context(Request::new(())).unwrap();
+
+    assert!(request.extensions().get::<RemoteTraceContext>().is_some());
+    assert_eq!(remote_trace_id(&request), None);
+}
+
+#[test]
+fn the_client_interceptor_never_fails_a_call() {
+    global::set_text_map_propagator(TraceContextPropagator::new());
+
+    // Outside any span there is nothing to send
+    let request = inject_trace_context(Request::new(())).unwrap();
+
+    assert!(request.metadata().get("traceparent").is_none());
+}
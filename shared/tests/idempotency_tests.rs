@@ -0,0 +1,152 @@
+// The `Idempotency` middleware over an in-memory store
+use actix_web::http::header::RETRY_AFTER;
+use actix_web::http::StatusCode;
+use actix_web::{test, web, App, HttpResponse};
+use serde_json::{json, Value};
+use shared::features::idempotency::{Idempotency, IDEMPOTENCY_KEY_HEADER, IDEMPOTENCY_REPLAYED_HEADER};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use test_support::InMemoryIdempotencyStore;
+use tokio::sync::Notify;
+
+// How many times each handler ran, and the gate `/slow` waits on
+#[derive(Clone, Default)]
+struct Handlers {
+    calls: Arc<AtomicUsize>,
+    release: Arc<Notify>,
+}
+
+impl Handlers {
+    fn calls(&self) -> usize {
+        self.calls.load(Ordering::SeqCst)
+    }
+}
+
+async fn create(handlers: web::Data<Handlers>, body: web::Json<Value>) -> HttpResponse {
+    let call = handlers.calls.fetch_add(1, Ordering::SeqCst) + 1;
+    HttpResponse::Created().json(json!({ "call": call, "echo": body.into_inner() }))
+}
+
+async fn slow(handlers: web::Data<Handlers>) -> HttpResponse {
+    handlers.calls.fetch_add(1, Ordering::SeqCst);
+    handlers.release.notified().await;
+    HttpResponse::Ok().finish()
+}
+
+async fn failing(handlers: web::Data<Handlers>) -> HttpResponse {
+    handlers.calls.fetch_add(1, Ordering::SeqCst);
+    HttpResponse::ServiceUnavailable().finish()
+}
+
+fn request(path: &str, key: &str, body: Value) -> test::TestRequest {
+    test::TestRequest::post()
+        .uri(path)
+        .insert_header((IDEMPOTENCY_KEY_HEADER, key))
+        .set_json(body)
+}
+
+macro_rules! app {
+    ($store:expr, $handlers:expr) => {
+        test::init_service(
+            App::new()
+                .wrap(Idempotency::new(Arc::new($store.clone())))
+                .app_data(web::Data::new($handlers.clone()))
+                .route("/bookings", web::post().to(create))
+                .route("/slow", web::post().to(slow))
+                .route("/failing", web::post().to(failing)),
+        )
+        .await
+    };
+}
+
+#[actix_web::test]
+async fn a_retry_replays_the_stored_response() {
+    let (store, handlers) = (InMemoryIdempotencyStore::new(), Handlers::default());
+    let app = app!(store, handlers);
+
+    let first = test::call_service(&app, request("/bookings", "key-1", json!({ "nights": 2 })).to_request()).await;
+    assert_eq!(first.status(), StatusCode::CREATED);
+    assert!(first.headers().get(IDEMPOTENCY_REPLAYED_HEADER).is_none());
+    let first: Value = test::read_body_json(first).await;
+
+    let retry = test::call_service(&app, request("/bookings", "key-1", json!({ "nights": 2 })).to_request()).await;
+    assert_eq!(retry.status(), StatusCode::CREATED);
+    assert_eq!(retry.headers().get(IDEMPOTENCY_REPLAYED_HEADER).unwrap(), "true");
+    let retry: Value = test::read_body_json(retry).await;
+
+    assert_eq!(retry, first);
+    assert_eq!(handlers.calls(), 1);
+}
+
+#[actix_web::test]
+async fn reusing_a_key_for_a_different_body_conflicts() {
+    let (store, handlers) = (InMemoryIdempotencyStore::new(), Handlers::default());
+    let app = app!(store, handlers);
+
+    let first = test::call_service(&app, request("/bookings", "key-1", json!({ "nights": 2 })).to_request()).await;
+    assert_eq!(first.status(), StatusCode::CREATED);
+
+    let changed = test::call_service(&app, request("/bookings", "key-1", json!({ "nights": 3 })).to_request()).await;
+
+    assert_eq!(changed.status(), StatusCode::CONFLICT);
+    assert_eq!(handlers.calls(), 1);
+}
+
+#[actix_web::test]
+async fn a_retry_while_the_first_request_runs_conflicts_with_retry_after() {
+    let (store, handlers) = (InMemoryIdempotencyStore::new(), Handlers::default());
+    let app = app!(store, handlers);
+
+    // The first request is polled until it blocks in the handler, so the
+    // second finds the key claimed
+    let (first, retry) = tokio::join!(
+        test::call_service(&app, request("/slow", "key-1", json!({})).to_request()),
+        async {
+            let retry = test::call_service(&app, request("/slow", "key-1", json!({})).to_request()).await;
+            handlers.release.notify_one();
+            retry
+        }
+    );
+
+    assert_eq!(first.status(), StatusCode::OK);
+    assert_eq!(retry.status(), StatusCode::CONFLICT);
+    assert_eq!(retry.headers().get(RETRY_AFTER).unwrap(), "1");
+    assert_eq!(handlers.calls(), 1);
+}
+
+#[actix_web::test]
+async fn server_errors_are_not_stored() {
+    let (store, handlers) = (InMemoryIdempotencyStore::new(), Handlers::default());
+    let app = app!(store, handlers);
+
+    let first = test::call_service(&app, request("/failing", "key-1", json!({})).to_request()).await;
+    assert_eq!(first.status(), StatusCode::SERVICE_UNAVAILABLE);
+    assert!(store.is_empty());
+
+    let retry = test::call_service(&app, request("/failing", "key-1", json!({})).to_request()).await;
+
+    assert_eq!(retry.status(), StatusCode::SERVICE_UNAVAILABLE);
+    assert!(retry.headers().get(IDEMPOTENCY_REPLAYED_HEADER).is_none());
+    assert_eq!(handlers.calls(), 2);
+}
+
+#[actix_web::test]
+async fn excluded_paths_run_every_time_and_store_nothing() {
+    let (store, handlers) = (InMemoryIdempotencyStore::new(), Handlers::default());
+    let app = test::init_service(
+        App::new()
+            .wrap(Idempotency::new(Arc::new(store.clone())).except_for(&["/bookings"]))
+            .app_data(web::Data::new(handlers.clone()))
+            .route("/bookings", web::post().to(create)),
+    )
+    .await;
+
+    for _ in 0..2 {
+        let response = test::call_service(&app, request("/bookings", "key-1", json!({ "nights": 2 })).to_request()).await;
+        assert_eq!(response.status(), StatusCode::CREATED);
+        assert!(response.headers().get(IDEMPOTENCY_REPLAYED_HEADER).is_none());
+    }
+
+    assert!(store.is_empty());
+    assert_eq!(handlers.calls(), 2);
+}
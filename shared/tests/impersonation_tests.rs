@@ -0,0 +1,91 @@
+// What `AuthMiddleware` lets an impersonation token reach
+use actix_web::http::StatusCode;
+use actix_web::{test, web, App, HttpResponse};
+use shared::config::jwt_config::JwtConfig;
+use shared::entities::enums::UserRole;
+use shared::features::helper::jwt_helper::JwtHelper;
+use shared::features::security::auth::AuthMiddleware;
+use shared::features::security::jwt::{ActorClaims, JwtClaims};
+use uuid::Uuid;
+
+fn jwt_config() -> JwtConfig {
+    JwtConfig {
+        secret: "impersonation-test-secret-that-is-long-enough".to_string(),
+        ..Default::default()
+    }
+}
+
+fn token(impersonated: bool) -> String {
+    let config = jwt_config();
+    let mut claims = JwtClaims::new(
+        Uuid::new_v4(),
+        "tenant@example.com".to_string(),
+        UserRole::Tenant,
+        vec![],
+        config.issuer.clone(),
+        config.audience.clone(),
+        Uuid::new_v4(),
+        config.access_token_expiry as usize,
+    );
+    if impersonated {
+        claims = claims.with_actor(ActorClaims {
+            sub: Uuid::new_v4(),
+            email: "admin@example.com".to_string(),
+        });
+    }
+    JwtHelper::sign_claims(&claims, &config.secret).unwrap()
+}
+
+fn post(path: &str, token: &str) -> test::TestRequest {
+    test::TestRequest::post()
+        .uri(path)
+        .insert_header(("Authorization", format!("Bearer {}", token)))
+}
+
+macro_rules! app {
+    ($middleware:expr) => {
+        test::init_service(
+            App::new()
+                .wrap($middleware)
+                .route("/api/v1/payments/charge", web::post().to(HttpResponse::Ok))
+                .route("/api/v1/refunds", web::post().to(HttpResponse::Ok))
+                .route("/api/v1/bookings", web::post().to(HttpResponse::Ok))
+                .route("/api/v1/auth/mfa/totp", web::post().to(HttpResponse::Ok)),
+        )
+        .await
+    };
+}
+
+#[actix_web::test]
+async fn impersonation_tokens_cannot_make_payments_in_any_service() {
+    let app = app!(AuthMiddleware::new(jwt_config()));
+    let impersonated = token(true);
+
+    for path in ["/api/v1/payments/charge", "/api/v1/refunds"] {
+        let response = test::call_service(&app, post(path, &impersonated).to_request()).await;
+        assert_eq!(response.status(), StatusCode::FORBIDDEN, "{}", path);
+    }
+
+    let response = test::call_service(&app, post("/api/v1/bookings", &impersonated).to_request()).await;
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
+#[actix_web::test]
+async fn users_acting_for_themselves_can_make_payments() {
+    let app = app!(AuthMiddleware::new(jwt_config()));
+
+    let response = test::call_service(&app, post("/api/v1/payments/charge", &token(false)).to_request()).await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
+#[actix_web::test]
+async fn blocking_more_paths_keeps_payments_blocked() {
+    let app = app!(AuthMiddleware::new(jwt_config()).block_impersonation_for(&["/api/v1/auth/mfa"]));
+    let impersonated = token(true);
+
+    for path in ["/api/v1/auth/mfa/totp", "/api/v1/payments/charge"] {
+        let response = test::call_service(&app, post(path, &impersonated).to_request()).await;
+        assert_eq!(response.status(), StatusCode::FORBIDDEN, "{}", path);
+    }
+}
@@ -0,0 +1,40 @@
+// TTL checks run before either backend is reached, so these need neither
+// Redis nor Postgres
+use deadpool_redis::{Config, Runtime};
+use shared::features::errors::SystemError;
+use shared::utils::caching::CacheKeys;
+use shared::utils::locking::{LockProvider, PgAdvisoryLock, RedisLock, MIN_LEASE_TTL};
+use sqlx::postgres::PgPoolOptions;
+use std::sync::Arc;
+use std::time::Duration;
+
+const KEYS: CacheKeys = CacheKeys::new("locking-test", 1);
+
+fn providers() -> Vec<Box<dyn LockProvider>> {
+    let redis = Config::from_url("redis://127.0.0.1:1").create_pool(Some(Runtime::Tokio1)).unwrap();
+    let postgres = PgPoolOptions::new().connect_lazy("postgres://nobody@127.0.0.1:1/none").unwrap();
+    vec![
+        Box::new(RedisLock::new(Arc::new(redis), KEYS)),
+        Box::new(PgAdvisoryLock::new(postgres, "locking-test")),
+    ]
+}
+
+#[tokio::test]
+async fn leases_shorter_than_the_minimum_ttl_are_rejected() {
+    for provider in providers() {
+        for ttl in [Duration::ZERO, Duration::from_nanos(2), MIN_LEASE_TTL - Duration::from_millis(1)] {
+            let result = provider.try_acquire("booking:42", ttl).await;
+
+            assert!(matches!(result, Err(SystemError::ValidationError(_))), "ttl {:?}", ttl);
+        }
+    }
+}
+
+#[tokio::test]
+async fn acquire_does_not_retry_an_invalid_ttl() {
+    for provider in providers() {
+        let result = provider.acquire("booking:42", Duration::ZERO, Duration::from_secs(5)).await;
+
+        assert!(matches!(result, Err(SystemError::ValidationError(_))));
+    }
+}
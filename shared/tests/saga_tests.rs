@@ -0,0 +1,292 @@
+// `SagaOrchestrator` over a real `saga_instances` table, with commands
+// recorded by an in-memory broker
+use actix_web::http::StatusCode;
+use actix_web::{test, web, App};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use shared::config::jwt_config::JwtConfig;
+use shared::entities::enums::UserRole;
+use shared::events::EventEnvelope;
+use shared::features::helper::jwt_helper::JwtHelper;
+use shared::features::security::auth::AuthMiddleware;
+use shared::features::security::jwt::JwtClaims;
+use shared::utils::saga::routes::saga_routes;
+use shared::utils::saga::{
+    PostgresSagaStore, SagaCommand, SagaDefinition, SagaOrchestrator, SagaReply, SagaStatus, SagaStep, SAGA_SCHEMA_SQL,
+};
+use std::sync::Arc;
+use std::time::Duration;
+use test_support::{InMemoryBroker, TestDatabase};
+use uuid::Uuid;
+
+#[derive(Debug, Serialize, Deserialize)]
+struct BookingData {
+    booking_id: Uuid,
+    hold_id: Option<String>,
+}
+
+// hold -> charge -> notify; notifying cannot be undone
+struct BookingSaga;
+
+impl SagaDefinition for BookingSaga {
+    type Data = BookingData;
+
+    const SAGA_TYPE: &'static str = "test_booking";
+
+    fn steps() -> Vec<SagaStep<BookingData>> {
+        vec![
+            SagaStep::new("hold", "property.hold", |d: &BookingData| json!({ "booking_id": d.booking_id }))
+                .compensate_with("property.release", |d| json!({ "hold_id": d.hold_id }))
+                .on_success(|d, reply| d.hold_id = reply["hold_id"].as_str().map(String::from)),
+            SagaStep::new("charge", "payment.charge", |d: &BookingData| json!({ "booking_id": d.booking_id }))
+                .compensate_with("payment.refund", |d| json!({ "booking_id": d.booking_id })),
+            SagaStep::new("notify", "notification.send", |d: &BookingData| json!({ "booking_id": d.booking_id })),
+        ]
+    }
+}
+
+// One step nobody answers in time
+struct SlowSaga;
+
+impl SagaDefinition for SlowSaga {
+    type Data = BookingData;
+
+    const SAGA_TYPE: &'static str = "test_slow";
+    const MAX_COMPENSATION_ATTEMPTS: i32 = 2;
+
+    fn steps() -> Vec<SagaStep<BookingData>> {
+        vec![SagaStep::new("hold", "property.hold", |d: &BookingData| json!({ "booking_id": d.booking_id }))
+            .compensate_with("property.release", |d| json!({ "booking_id": d.booking_id }))
+            .timeout(Duration::from_millis(1))]
+    }
+}
+
+struct Saga<S: SagaDefinition> {
+    orchestrator: SagaOrchestrator<S>,
+    broker: InMemoryBroker,
+    database: TestDatabase,
+}
+
+// None when Postgres is unavailable and the test should skip
+async fn saga<S: SagaDefinition>() -> Option<Saga<S>> {
+    let database = TestDatabase::start().await?;
+    sqlx::raw_sql(SAGA_SCHEMA_SQL).execute(&database.pool).await.unwrap();
+    let broker = InMemoryBroker::new();
+    let orchestrator = SagaOrchestrator::new(
+        PostgresSagaStore::new(database.pool.clone()),
+        Arc::new(broker.clone()),
+        "test-service",
+    );
+    Some(Saga {
+        orchestrator,
+        broker,
+        database,
+    })
+}
+
+impl<S: SagaDefinition<Data = BookingData>> Saga<S> {
+    async fn start(&self) -> Uuid {
+        let data = BookingData {
+            booking_id: Uuid::new_v4(),
+            hold_id: None,
+        };
+        self.orchestrator.start(data, None).await.unwrap()
+    }
+
+    // Every command sent so far, with the routing key it went to
+    fn commands(&self) -> Vec<(String, SagaCommand)> {
+        self.broker
+            .published()
+            .into_iter()
+            .map(|message| {
+                let envelope = EventEnvelope::<SagaCommand>::decode(&message.payload).unwrap();
+                (message.routing_key, envelope.payload)
+            })
+            .collect()
+    }
+
+    fn last_command(&self) -> SagaCommand {
+        self.commands().pop().expect("no command was sent").1
+    }
+
+    async fn reply(&self, reply: SagaReply) {
+        self.orchestrator
+            .handle_reply(EventEnvelope::new("participant", reply))
+            .await
+            .unwrap();
+    }
+
+    async fn status(&self, saga_id: Uuid) -> SagaStatus {
+        self.orchestrator.status(saga_id).await.unwrap().unwrap().status
+    }
+}
+
+#[tokio::test]
+async fn steps_run_in_order_until_the_saga_completes() {
+    let Some(saga) = saga::<BookingSaga>().await else { return };
+    let saga_id = saga.start().await;
+
+    saga.reply(saga.last_command().success(json!({ "hold_id": "hold-1" }))).await;
+    saga.reply(saga.last_command().success(Value::Null)).await;
+    assert_eq!(saga.status(saga_id).await, SagaStatus::Running);
+    saga.reply(saga.last_command().success(Value::Null)).await;
+
+    let sent: Vec<(String, bool)> = saga
+        .commands()
+        .into_iter()
+        .map(|(routing_key, command)| (routing_key, command.compensating))
+        .collect();
+    assert_eq!(
+        sent,
+        [
+            ("property.hold".to_string(), false),
+            ("payment.charge".to_string(), false),
+            ("notification.send".to_string(), false),
+        ]
+    );
+    let instance = saga.orchestrator.status(saga_id).await.unwrap().unwrap();
+    assert_eq!(instance.status, SagaStatus::Completed);
+    assert_eq!(instance.data["hold_id"], "hold-1");
+    assert_eq!(instance.step_deadline, None);
+}
+
+#[tokio::test]
+async fn a_failed_step_undoes_the_completed_ones_in_reverse_order() {
+    let Some(saga) = saga::<BookingSaga>().await else { return };
+    let saga_id = saga.start().await;
+
+    saga.reply(saga.last_command().success(json!({ "hold_id": "hold-1" }))).await;
+    saga.reply(saga.last_command().success(Value::Null)).await;
+    saga.reply(saga.last_command().failure("mail server down")).await;
+    assert_eq!(saga.status(saga_id).await, SagaStatus::Compensating);
+    saga.reply(saga.last_command().success(Value::Null)).await;
+    saga.reply(saga.last_command().success(Value::Null)).await;
+
+    let compensations: Vec<(String, SagaCommand)> = saga
+        .commands()
+        .into_iter()
+        .filter(|(_, command)| command.compensating)
+        .collect();
+    assert_eq!(compensations.len(), 2);
+    assert_eq!(compensations[0].0, "payment.refund");
+    assert_eq!(compensations[1].0, "property.release");
+    // Built from the data the hold step's reply left behind
+    assert_eq!(compensations[1].1.payload, json!({ "hold_id": "hold-1" }));
+
+    let instance = saga.orchestrator.status(saga_id).await.unwrap().unwrap();
+    assert_eq!(instance.status, SagaStatus::Compensated);
+    assert_eq!(instance.last_error.as_deref(), Some("mail server down"));
+}
+
+#[tokio::test]
+async fn a_failed_compensation_needs_attention() {
+    let Some(saga) = saga::<BookingSaga>().await else { return };
+    let saga_id = saga.start().await;
+
+    saga.reply(saga.last_command().success(json!({ "hold_id": "hold-1" }))).await;
+    saga.reply(saga.last_command().failure("card declined")).await;
+    saga.reply(saga.last_command().failure("hold already expired")).await;
+
+    let instance = saga.orchestrator.status(saga_id).await.unwrap().unwrap();
+    assert_eq!(instance.status, SagaStatus::Failed);
+    assert_eq!(instance.last_error.as_deref(), Some("hold already expired"));
+    assert_eq!(saga.commands().len(), 3);
+}
+
+#[tokio::test]
+async fn stale_and_duplicate_replies_are_ignored() {
+    let Some(saga) = saga::<BookingSaga>().await else { return };
+    let saga_id = saga.start().await;
+    let hold = saga.last_command();
+
+    saga.reply(hold.success(json!({ "hold_id": "hold-1" }))).await;
+    let charge = saga.last_command();
+
+    // A redelivered reply for the step already done
+    saga.reply(hold.success(json!({ "hold_id": "hold-2" }))).await;
+    // A late failure for it, which must not start compensating
+    saga.reply(hold.failure("timed out upstream")).await;
+    // A compensation reply while nothing is being compensated
+    let mut compensation = charge.success(Value::Null);
+    compensation.compensating = true;
+    saga.reply(compensation).await;
+    // A reply for a saga that does not exist
+    let mut unknown = charge.success(Value::Null);
+    unknown.saga_id = Uuid::new_v4();
+    saga.reply(unknown).await;
+
+    assert_eq!(saga.commands().len(), 2);
+    let instance = saga.orchestrator.status(saga_id).await.unwrap().unwrap();
+    assert_eq!((instance.status, instance.current_step), (SagaStatus::Running, 1));
+    assert_eq!(instance.data["hold_id"], "hold-1");
+}
+
+#[tokio::test]
+async fn the_timeout_sweep_compensates_and_then_gives_up() {
+    let Some(saga) = saga::<SlowSaga>().await else { return };
+    let saga_id = saga.start().await;
+
+    // The step itself is compensated, since it may have run
+    tokio::time::sleep(Duration::from_millis(20)).await;
+    assert_eq!(saga.orchestrator.check_timeouts().await.unwrap(), 1);
+    let (routing_key, command) = saga.commands().pop().unwrap();
+    assert_eq!(routing_key, "property.release");
+    assert_eq!((command.compensating, command.attempt), (true, 1));
+
+    // The compensation is resent until MAX_COMPENSATION_ATTEMPTS
+    tokio::time::sleep(Duration::from_millis(20)).await;
+    assert_eq!(saga.orchestrator.check_timeouts().await.unwrap(), 1);
+    assert_eq!(saga.last_command().attempt, 2);
+
+    tokio::time::sleep(Duration::from_millis(20)).await;
+    assert_eq!(saga.orchestrator.check_timeouts().await.unwrap(), 1);
+    assert_eq!(saga.commands().len(), 3);
+    let instance = saga.orchestrator.status(saga_id).await.unwrap().unwrap();
+    assert_eq!(instance.status, SagaStatus::Failed);
+    assert_eq!(instance.last_error.as_deref(), Some("Compensation hold timed out after 2 attempts"));
+
+    // Finished sagas are no longer swept
+    tokio::time::sleep(Duration::from_millis(20)).await;
+    assert_eq!(saga.orchestrator.check_timeouts().await.unwrap(), 0);
+}
+
+#[actix_web::test]
+async fn saga_routes_are_for_admins_signed_in_as_themselves() {
+    let Some(saga) = saga::<BookingSaga>().await else { return };
+    let saga_id = saga.start().await;
+    let jwt_config = JwtConfig::default();
+    let app = test::init_service(
+        App::new()
+            .wrap(AuthMiddleware::new(jwt_config.clone()))
+            .app_data(web::Data::new(PostgresSagaStore::new(saga.database.pool.clone())))
+            .service(saga_routes()),
+    )
+    .await;
+    let claims = |role: UserRole| {
+        JwtClaims::new(
+            Uuid::new_v4(),
+            "operator@example.com".to_string(),
+            role,
+            vec![],
+            jwt_config.issuer.clone(),
+            jwt_config.audience.clone(),
+            Uuid::new_v4(),
+            60,
+        )
+    };
+
+    for (claims, expected) in [
+        (claims(UserRole::Admin), StatusCode::OK),
+        (claims(UserRole::Tenant), StatusCode::FORBIDDEN),
+        (claims(UserRole::Admin).with_api_key_id(Uuid::new_v4()), StatusCode::FORBIDDEN),
+        (claims(UserRole::SuperAdmin).with_client_id("partner".to_string()), StatusCode::FORBIDDEN),
+    ] {
+        let token = JwtHelper::sign_claims(&claims, &jwt_config.secret).unwrap();
+        let request = test::TestRequest::get()
+            .uri(&format!("/sagas/{}", saga_id))
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .to_request();
+
+        assert_eq!(test::call_service(&app, request).await.status(), expected);
+    }
+}
@@ -0,0 +1,67 @@
+// `ValidatedJson` in front of a handler
+use actix_web::http::StatusCode;
+use actix_web::{test, web, App, HttpResponse};
+use serde_json::{json, Value};
+use shared::features::validation::ValidatedJson;
+use shared::user::models::dto::request::RegisterRequest;
+
+async fn register(request: ValidatedJson<RegisterRequest>) -> HttpResponse {
+    HttpResponse::Created().json(json!({ "email": request.email }))
+}
+
+fn registration(overrides: Value) -> Value {
+    let mut body = json!({
+        "email": "jane@example.com",
+        "phone_number": "+2348012345678",
+        "password": "Battery-Staple-42",
+        "first_name": "Jane",
+        "last_name": "Doe",
+    });
+    for (field, value) in overrides.as_object().unwrap() {
+        body[field] = value.clone();
+    }
+    body
+}
+
+macro_rules! app {
+    () => {
+        test::init_service(App::new().route("/register", web::post().to(register))).await
+    };
+}
+
+#[actix_web::test]
+async fn a_valid_registration_reaches_the_handler() {
+    let app = app!();
+
+    for body in [registration(json!({})), registration(json!({ "phone_number": null }))] {
+        let request = test::TestRequest::post().uri("/register").set_json(body).to_request();
+        let response = test::call_service(&app, request).await;
+
+        assert_eq!(response.status(), StatusCode::CREATED);
+    }
+}
+
+#[actix_web::test]
+async fn every_broken_registration_rule_is_reported() {
+    let app = app!();
+    let body = registration(json!({
+        "email": "not-an-email",
+        "phone_number": "08012345678",
+        "password": "short",
+        "first_name": "",
+    }));
+
+    let request = test::TestRequest::post().uri("/register").set_json(body).to_request();
+    let response = test::call_service(&app, request).await;
+
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let problem: Value = test::read_body_json(response).await;
+    assert_eq!(problem["code"], "validation_failed");
+    let fields: Vec<&str> = problem["errors"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|error| error["field"].as_str().unwrap())
+        .collect();
+    assert_eq!(fields, ["email", "first_name", "password", "phone_number"]);
+}
@@ -0,0 +1,66 @@
+use async_trait::async_trait;
+use shared::events::{DomainEvent, EventEnvelope};
+use shared::features::errors::{SystemError, SystemResult};
+use shared::utils::messaging::Publisher;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+#[derive(Debug, Clone)]
+pub struct PublishedMessage {
+    pub exchange: String,
+    pub routing_key: String,
+    pub payload: Vec<u8>,
+}
+
+// A `Publisher` that keeps everything published in memory so tests can
+// assert on it. Clones share the same record.
+#[derive(Clone, Default)]
+pub struct InMemoryBroker {
+    published: Arc<Mutex<Vec<PublishedMessage>>>,
+    unavailable: Arc<AtomicBool>,
+}
+
+impl InMemoryBroker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Fails every publish from now on, as a broker that is down would
+    pub fn go_down(&self) {
+        self.unavailable.store(true, Ordering::SeqCst);
+    }
+
+    pub fn published(&self) -> Vec<PublishedMessage> {
+        self.published.lock().unwrap().clone()
+    }
+
+    // Every `E` published so far, oldest first
+    pub fn events<E: DomainEvent>(&self) -> Vec<EventEnvelope<E>> {
+        self.published
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|message| message.routing_key == E::ROUTING_KEY)
+            .map(|message| EventEnvelope::<E>::decode(&message.payload).expect("Published an undecodable event"))
+            .collect()
+    }
+
+    pub fn clear(&self) {
+        self.published.lock().unwrap().clear();
+    }
+}
+
+#[async_trait]
+impl Publisher for InMemoryBroker {
+    async fn publish_raw(&self, exchange: &str, routing_key: &str, payload: &[u8]) -> SystemResult<()> {
+        if self.unavailable.load(Ordering::SeqCst) {
+            return Err(SystemError::MessageBrokerError("Broker is unavailable".to_string()));
+        }
+        self.published.lock().unwrap().push(PublishedMessage {
+            exchange: exchange.to_string(),
+            routing_key: routing_key.to_string(),
+            payload: payload.to_vec(),
+        });
+        Ok(())
+    }
+}
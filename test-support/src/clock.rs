@@ -0,0 +1,52 @@
+use chrono::{DateTime, Duration, Utc};
+use shared::utils::clock::{self, Clock, ClockGuard};
+use std::sync::{Arc, Mutex};
+
+// A clock that only moves when told to. Clones share the same time.
+//
+//     let clock = FakeClock::new();
+//     let _guard = clock.install();
+//     clock.advance(Duration::minutes(31));
+#[derive(Clone)]
+pub struct FakeClock {
+    now: Arc<Mutex<DateTime<Utc>>>,
+}
+
+impl FakeClock {
+    // Starts at the current time, truncated to whole seconds
+    pub fn new() -> Self {
+        let now = Utc::now();
+        Self::at(now - Duration::nanoseconds(now.timestamp_subsec_nanos() as i64))
+    }
+
+    pub fn at(now: DateTime<Utc>) -> Self {
+        Self {
+            now: Arc::new(Mutex::new(now)),
+        }
+    }
+
+    // Makes this the clock `clock::now()` reads on the current thread
+    pub fn install(&self) -> ClockGuard {
+        clock::install(Arc::new(self.clone()))
+    }
+
+    pub fn set(&self, now: DateTime<Utc>) {
+        *self.now.lock().unwrap() = now;
+    }
+
+    pub fn advance(&self, by: Duration) {
+        *self.now.lock().unwrap() += by;
+    }
+}
+
+impl Default for FakeClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clock for FakeClock {
+    fn now(&self) -> DateTime<Utc> {
+        *self.now.lock().unwrap()
+    }
+}
@@ -0,0 +1,80 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use shared::features::errors::SystemResult;
+use shared::features::idempotency::{IdempotencyState, IdempotencyStore, StoredResponse};
+use shared::utils::clock;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+struct Record {
+    fingerprint: String,
+    response: Option<StoredResponse>,
+    expires_at: DateTime<Utc>,
+}
+
+// An `IdempotencyStore` in a map. Expiry follows `shared::utils::clock`, so
+// a `FakeClock` can lapse claims and stored responses. Clones share the map.
+#[derive(Clone, Default)]
+pub struct InMemoryIdempotencyStore {
+    records: Arc<Mutex<HashMap<String, Record>>>,
+}
+
+impl InMemoryIdempotencyStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.records.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+fn expiry(ttl: Duration) -> DateTime<Utc> {
+    clock::now() + chrono::Duration::seconds(ttl.as_secs() as i64)
+}
+
+#[async_trait]
+impl IdempotencyStore for InMemoryIdempotencyStore {
+    async fn begin(&self, key: &str, fingerprint: &str, lock_ttl: Duration) -> SystemResult<IdempotencyState> {
+        let mut records = self.records.lock().unwrap();
+        if let Some(record) = records.get(key).filter(|r| r.expires_at > clock::now()) {
+            return Ok(match &record.response {
+                Some(response) => IdempotencyState::Completed(response.clone()),
+                None => IdempotencyState::InProgress {
+                    fingerprint: record.fingerprint.clone(),
+                },
+            });
+        }
+        records.insert(
+            key.to_string(),
+            Record {
+                fingerprint: fingerprint.to_string(),
+                response: None,
+                expires_at: expiry(lock_ttl),
+            },
+        );
+        Ok(IdempotencyState::Started)
+    }
+
+    async fn complete(&self, key: &str, response: &StoredResponse, ttl: Duration) -> SystemResult<()> {
+        self.records.lock().unwrap().insert(
+            key.to_string(),
+            Record {
+                fingerprint: response.fingerprint.clone(),
+                response: Some(response.clone()),
+                expires_at: expiry(ttl),
+            },
+        );
+        Ok(())
+    }
+
+    async fn abandon(&self, key: &str) -> SystemResult<()> {
+        self.records.lock().unwrap().remove(key);
+        Ok(())
+    }
+}
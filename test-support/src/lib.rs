@@ -0,0 +1,54 @@
+// Ephemeral Postgres, Redis and RabbitMQ for integration tests.
+//
+// Each piece comes from `TEST_DATABASE_URL` / `TEST_REDIS_URL` /
+// `TEST_RABBITMQ_URL` when set (a server you already run), and otherwise
+// from a throwaway Docker container. With neither available the test is
+// skipped, unless `TEST_REQUIRE_INFRA` is set, as it should be in CI.
+//
+//     let Some(infra) = TestInfra::start(&MIGRATIONS).await else { return };
+//
+// Unit tests that need none of that use `FakeClock`, `InMemoryBroker` and
+// `InMemoryIdempotencyStore`.
+mod broker;
+mod clock;
+mod idempotency;
+mod postgres;
+mod rabbitmq;
+mod redis;
+
+pub use broker::{InMemoryBroker, PublishedMessage};
+pub use clock::FakeClock;
+pub use idempotency::InMemoryIdempotencyStore;
+pub use postgres::TestDatabase;
+pub use rabbitmq::TestRabbitMq;
+pub use redis::TestRedis;
+
+use shared::utils::migrations::Migrations;
+use std::fmt::Display;
+
+// Everything a service needs, started concurrently
+pub struct TestInfra {
+    pub database: TestDatabase,
+    pub redis: TestRedis,
+    pub rabbitmq: TestRabbitMq,
+}
+
+impl TestInfra {
+    pub async fn start(migrations: &Migrations) -> Option<Self> {
+        let (database, redis, rabbitmq) =
+            tokio::join!(TestDatabase::migrated(migrations), TestRedis::start(), TestRabbitMq::start());
+        Some(Self {
+            database: database?,
+            redis: redis?,
+            rabbitmq: rabbitmq?,
+        })
+    }
+}
+
+fn unavailable<T>(what: &str, reason: impl Display) -> Option<T> {
+    if std::env::var_os("TEST_REQUIRE_INFRA").is_some() {
+        panic!("{} is required but unavailable: {}", what, reason);
+    }
+    eprintln!("skipping: {} unavailable ({})", what, reason);
+    None
+}
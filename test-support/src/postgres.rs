@@ -0,0 +1,109 @@
+use crate::unavailable;
+use shared::utils::migrations::Migrations;
+use sqlx::postgres::PgPoolOptions;
+use sqlx::{Connection, PgConnection, Pool, Postgres};
+use testcontainers_modules::postgres::Postgres as PostgresImage;
+use testcontainers_modules::testcontainers::runners::AsyncRunner;
+use testcontainers_modules::testcontainers::{ContainerAsync, ImageExt};
+use uuid::Uuid;
+
+const IMAGE_TAG: &str = "16-alpine";
+
+// A database of its own for one test. On a shared server (`TEST_DATABASE_URL`)
+// it is created under a random name and dropped afterwards.
+pub struct TestDatabase {
+    pub pool: Pool<Postgres>,
+    pub url: String,
+    shared_server: Option<(String, String)>, // (server url, database name)
+    _container: Option<ContainerAsync<PostgresImage>>,
+}
+
+impl TestDatabase {
+    pub async fn start() -> Option<Self> {
+        let (url, shared_server, container) = match std::env::var("TEST_DATABASE_URL") {
+            Ok(server_url) => {
+                let name = format!("test_{}", Uuid::new_v4().simple());
+                let mut conn = match PgConnection::connect(&server_url).await {
+                    Ok(conn) => conn,
+                    Err(e) => return unavailable("Postgres", e),
+                };
+                sqlx::query(&format!("CREATE DATABASE \"{}\"", name))
+                    .execute(&mut conn)
+                    .await
+                    .expect("Failed to create test database");
+                let _ = conn.close().await;
+                (with_database(&server_url, &name), Some((server_url, name)), None)
+            }
+            Err(_) => {
+                let container = match PostgresImage::default().with_tag(IMAGE_TAG).start().await {
+                    Ok(container) => container,
+                    Err(e) => return unavailable("Postgres", e),
+                };
+                let host = container.get_host().await.expect("Postgres container host");
+                let port = container.get_host_port_ipv4(5432).await.expect("Postgres container port");
+                let url = format!("postgres://postgres:postgres@{}:{}/postgres", host, port);
+                (url, None, Some(container))
+            }
+        };
+
+        let pool = PgPoolOptions::new()
+            .max_connections(5)
+            .connect(&url)
+            .await
+            .expect("Failed to connect to the test database");
+        Some(Self {
+            pool,
+            url,
+            shared_server,
+            _container: container,
+        })
+    }
+
+    // Starts a database with the service's migrations applied
+    pub async fn migrated(migrations: &Migrations) -> Option<Self> {
+        let database = Self::start().await?;
+        migrations.up(&database.pool).await.expect("Failed to apply migrations");
+        Some(database)
+    }
+}
+
+impl Drop for TestDatabase {
+    fn drop(&mut self) {
+        let Some((server_url, name)) = self.shared_server.take() else {
+            return;
+        };
+        // Drop runs inside the test's runtime, which cannot be blocked on
+        let dropped = std::thread::spawn(move || {
+            let runtime = tokio::runtime::Builder::new_current_thread().enable_all().build()?;
+            runtime.block_on(async {
+                let mut conn = PgConnection::connect(&server_url).await?;
+                sqlx::query(&format!("DROP DATABASE IF EXISTS \"{}\" WITH (FORCE)", name))
+                    .execute(&mut conn)
+                    .await?;
+                conn.close().await
+            })?;
+            Ok::<_, Box<dyn std::error::Error + Send + Sync>>(())
+        })
+        .join();
+        if !matches!(dropped, Ok(Ok(()))) {
+            eprintln!("Failed to drop a test database; remove test_* databases by hand");
+        }
+    }
+}
+
+// Swaps the database name in a postgres:// URL, keeping any query string
+fn with_database(url: &str, database: &str) -> String {
+    let (base, query) = match url.split_once('?') {
+        Some((base, query)) => (base, Some(query)),
+        None => (url, None),
+    };
+    let authority_end = base.find("://").map(|i| i + 3).unwrap_or(0);
+    let server = match base[authority_end..].find('/') {
+        Some(i) => &base[..authority_end + i],
+        None => base,
+    };
+    match query {
+        Some(query) => format!("{}/{}?{}", server, database, query),
+        None => format!("{}/{}", server, database),
+    }
+}
@@ -0,0 +1,30 @@
+use crate::unavailable;
+use testcontainers_modules::rabbitmq::RabbitMq;
+use testcontainers_modules::testcontainers::runners::AsyncRunner;
+use testcontainers_modules::testcontainers::ContainerAsync;
+
+const AMQP_PORT: u16 = 5672;
+
+pub struct TestRabbitMq {
+    pub url: String,
+    _container: Option<ContainerAsync<RabbitMq>>,
+}
+
+impl TestRabbitMq {
+    pub async fn start() -> Option<Self> {
+        if let Ok(url) = std::env::var("TEST_RABBITMQ_URL") {
+            return Some(Self { url, _container: None });
+        }
+
+        let container = match RabbitMq::default().start().await {
+            Ok(container) => container,
+            Err(e) => return unavailable("RabbitMQ", e),
+        };
+        let host = container.get_host().await.expect("RabbitMQ container host");
+        let port = container.get_host_port_ipv4(AMQP_PORT).await.expect("RabbitMQ container port");
+        Some(Self {
+            url: format!("amqp://guest:guest@{}:{}", host, port),
+            _container: Some(container),
+        })
+    }
+}
@@ -0,0 +1,43 @@
+use crate::unavailable;
+use deadpool_redis::{Config, Pool, Runtime};
+use std::sync::Arc;
+use testcontainers_modules::redis::{Redis, REDIS_PORT};
+use testcontainers_modules::testcontainers::runners::AsyncRunner;
+use testcontainers_modules::testcontainers::ContainerAsync;
+
+// Tests sharing a server (`TEST_REDIS_URL`) share its keyspace, so they
+// should use their own users and identifiers
+pub struct TestRedis {
+    pub pool: Arc<Pool>,
+    pub url: String,
+    _container: Option<ContainerAsync<Redis>>,
+}
+
+impl TestRedis {
+    pub async fn start() -> Option<Self> {
+        let (url, container) = match std::env::var("TEST_REDIS_URL") {
+            Ok(url) => (url, None),
+            Err(_) => {
+                let container = match Redis::default().start().await {
+                    Ok(container) => container,
+                    Err(e) => return unavailable("Redis", e),
+                };
+                let host = container.get_host().await.expect("Redis container host");
+                let port = container.get_host_port_ipv4(REDIS_PORT).await.expect("Redis container port");
+                (format!("redis://{}:{}", host, port), Some(container))
+            }
+        };
+
+        let pool = Config::from_url(&url)
+            .create_pool(Some(Runtime::Tokio1))
+            .expect("Failed to create the Redis pool");
+        if let Err(e) = pool.get().await {
+            return unavailable("Redis", e);
+        }
+        Some(Self {
+            pool: Arc::new(pool),
+            url,
+            _container: container,
+        })
+    }
+}